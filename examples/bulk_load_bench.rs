@@ -0,0 +1,29 @@
+// compares loading 1e7 bids with set_bid one by one and with set_bids
+//
+// cargo run --release --example bulk_load_bench
+use maximum_budgeted_allocation::generator::generate;
+use maximum_budgeted_allocation::primal_dual::PrimalDual;
+use std::time::Instant;
+
+fn main() {
+    let instance = generate(10_000, 100_000, 0.01, (100.0, 1000.0), (1.0, 50.0), 1);
+
+    let start = Instant::now();
+    let mut incremental = PrimalDual::new(instance.num_agents, instance.num_items, 0.01);
+    for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+        incremental.set_budget(agent_id, budget);
+    }
+    for &(agent_id, item_id, bid) in &instance.bids {
+        incremental.set_bid(agent_id, item_id, bid);
+    }
+    let incremental_time = start.elapsed();
+
+    let start = Instant::now();
+    let bulk = PrimalDual::from_instance(&instance, 0.01);
+    let bulk_time = start.elapsed();
+
+    assert_eq!(incremental.num_bids(), bulk.num_bids());
+    println!("{} bids", bulk.num_bids());
+    println!("set_bid : {:?}", incremental_time);
+    println!("set_bids: {:?}", bulk_time);
+}
@@ -0,0 +1,28 @@
+// compares the objective getters with 1 thread and with all threads on 1e7 agents
+//
+// cargo run --release --features rayon --example objective_bench
+use maximum_budgeted_allocation::primal_dual::PrimalDual;
+use std::time::Instant;
+
+fn main() {
+    let num_agents = 10_000_000;
+    let mut solver = PrimalDual::new(num_agents, 1, 0.1);
+    for agent_id in 0..num_agents {
+        solver.set_budget(agent_id, 1.0 + (agent_id % 1000) as f64);
+    }
+    solver.set_bid(0, 0, 1.0);
+    solver.solve();
+
+    let single = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let start = Instant::now();
+    let serial = single.install(|| solver.get_primal_objective_value());
+    let serial_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel = solver.get_primal_objective_value();
+    let parallel_time = start.elapsed();
+
+    assert_eq!(serial.to_bits(), parallel.to_bits());
+    println!("1 thread  : {:?}", serial_time);
+    println!("{} threads: {:?}", rayon::current_num_threads(), parallel_time);
+}
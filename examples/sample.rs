@@ -25,11 +25,13 @@ fn main() {
     println!("dual objective value:{:.3}", solver.get_dual_objective_value());
     println!("approximate rate:{:.3}", solver.get_approximation_ratio());
 
+    // the assignment is borrowed, so the bids can be read while holding it
     let assignment = solver.get_assignment();
-    for agent_id in 0..num_agents {
-        if assignment[agent_id].is_empty() {
+    for (agent_id, item_ids) in assignment.iter().enumerate() {
+        if item_ids.is_empty() {
             continue;
         }
-        println!("agent id:{}, item ids:{:?}", agent_id, assignment[agent_id]);
+        let bids: Vec<f64> = item_ids.iter().map(|&item_id| solver.get_bid(agent_id, item_id)).collect();
+        println!("agent id:{}, item ids:{:?}, bids:{:?}", agent_id, item_ids, bids);
     }
 }
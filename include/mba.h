@@ -0,0 +1,47 @@
+#ifndef MBA_H
+#define MBA_H
+
+/* generated by cbindgen from src/ffi.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define MBA_OK 0
+
+#define MBA_NULL_POINTER 1
+
+#define MBA_PANIC 2
+
+typedef struct MbaSolver MbaSolver;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+MbaSolver *mba_new(size_t num_agents, size_t num_items, double epsilon);
+
+void mba_free(MbaSolver *solver);
+
+int32_t mba_set_budget(MbaSolver *solver, size_t agent_id, double budget);
+
+int32_t mba_set_bid(MbaSolver *solver, size_t agent_id, size_t item_id, double bid);
+
+int32_t mba_solve(MbaSolver *solver);
+
+double mba_primal_value(const MbaSolver *solver);
+
+ptrdiff_t mba_assignment_len(const MbaSolver *solver, size_t agent_id);
+
+ptrdiff_t mba_assignment_items(const MbaSolver *solver,
+                               size_t agent_id,
+                               size_t *items,
+                               size_t capacity);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MBA_H */
@@ -0,0 +1,211 @@
+// items held by every agent after a solve, detached from the solver
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    items: Vec<Vec<usize>>,         // items[agent_id] = [item_id, ...]
+    bids: Vec<Vec<f64>>,            // bids[agent_id][i] = bid of agent_id on items[agent_id][i]
+    item_owner: Vec<Option<usize>>, // item_owner[item_id] = agent_id
+    budgets: Vec<f64>,              // budgets[agent_id] = budget the solve worked with, 0 for inactive agents
+}
+
+impl Assignment {
+    // items[agent_id] and bids[agent_id] must have the same length, and every item belongs to at most one agent
+    pub fn new(items: Vec<Vec<usize>>, bids: Vec<Vec<f64>>, num_items: usize, budgets: Vec<f64>) -> Self {
+        debug_assert_eq!(items.len(), bids.len());
+        debug_assert_eq!(items.len(), budgets.len());
+
+        let mut item_owner = vec![None; num_items];
+        for (agent_id, item_ids) in items.iter().enumerate() {
+            debug_assert_eq!(item_ids.len(), bids[agent_id].len());
+            for &item_id in item_ids {
+                debug_assert!(item_owner[item_id].is_none());
+                item_owner[item_id] = Some(agent_id);
+            }
+        }
+        Assignment {
+            items,
+            bids,
+            item_owner,
+            budgets,
+        }
+    }
+
+    pub fn num_agents(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn num_items(&self) -> usize {
+        self.item_owner.len()
+    }
+
+    // matrix[[agent_id, item_id]] = 1 if agent_id holds item_id, 0 otherwise
+    #[cfg(feature = "ndarray")]
+    pub fn to_indicator_matrix(&self) -> ndarray::Array2<u8> {
+        let mut matrix = ndarray::Array2::zeros((self.num_agents(), self.num_items()));
+        for (item_id, owner) in self.item_owner.iter().enumerate() {
+            if let Some(agent_id) = *owner {
+                matrix[[agent_id, item_id]] = 1;
+            }
+        }
+        matrix
+    }
+
+    // budget the solve worked with, 0 for an inactive agent
+    pub fn budget(&self, agent_id: usize) -> f64 {
+        self.budgets[agent_id]
+    }
+
+    pub fn items_of(&self, agent_id: usize) -> &[usize] {
+        &self.items[agent_id]
+    }
+
+    pub fn agent_of(&self, item_id: usize) -> Option<usize> {
+        self.item_owner[item_id]
+    }
+
+    // sum of the bids on the items of agent_id, which may exceed its budget
+    pub fn spend(&self, agent_id: usize) -> f64 {
+        self.bids[agent_id].iter().sum()
+    }
+
+    // min(spend, budget) of agent_id
+    pub fn value(&self, agent_id: usize) -> f64 {
+        self.spend(agent_id).min(self.budgets[agent_id])
+    }
+
+    // sum over the agents of min(spend, budget)
+    pub fn total_value(&self) -> f64 {
+        (0..self.num_agents()).map(|agent_id| self.value(agent_id)).sum()
+    }
+
+    // drops items of every agent whose spend exceeds its budget, cheapest first, then takes back the most valuable dropped items that still fit.
+    // afterwards total_value is the sum of the bids held, and an agent loses less than its largest bid.
+    // returns the value lost
+    pub fn make_budget_feasible(&mut self) -> f64 {
+        let before = self.total_value();
+        for agent_id in 0..self.num_agents() {
+            let budget = self.budgets[agent_id];
+            if self.spend(agent_id) <= budget {
+                continue;
+            }
+
+            let bids = &self.bids[agent_id];
+            let mut order: Vec<usize> = (0..bids.len()).collect();
+            order.sort_by(|&a, &b| bids[a].total_cmp(&bids[b]).then(a.cmp(&b)));
+            // summed in the order of spend, so that the kept items pass the same comparison spend would make
+            let kept_spend = |kept: &[bool]| -> f64 { bids.iter().zip(kept).filter(|(_, &kept)| kept).map(|(bid, _)| bid).sum() };
+
+            let mut kept = vec![true; bids.len()];
+            for &i in &order {
+                if kept_spend(&kept) <= budget {
+                    break;
+                }
+                kept[i] = false;
+            }
+            for &i in order.iter().rev() {
+                if !kept[i] {
+                    kept[i] = true;
+                    kept[i] = kept_spend(&kept) <= budget;
+                }
+            }
+
+            for (i, &item_id) in self.items[agent_id].iter().enumerate() {
+                if !kept[i] {
+                    self.item_owner[item_id] = None;
+                }
+            }
+            let mut keep = kept.iter();
+            self.items[agent_id].retain(|_| *keep.next().unwrap());
+            let mut keep = kept.iter();
+            self.bids[agent_id].retain(|_| *keep.next().unwrap());
+        }
+        before - self.total_value()
+    }
+
+    // (agent_id, item_id, bid) by agent id, and for every agent in the order it holds its items
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.items
+            .iter()
+            .zip(&self.bids)
+            .enumerate()
+            .flat_map(|(agent_id, (item_ids, bids))| item_ids.iter().zip(bids).map(move |(&item_id, &bid)| (agent_id, item_id, bid)))
+    }
+}
+
+impl<'a> IntoIterator for &'a Assignment {
+    type Item = (usize, usize, f64);
+    type IntoIter = Box<dyn Iterator<Item = (usize, usize, f64)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Assignment;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn views() {
+        let assignment = Assignment::new(vec![vec![2, 0], vec![], vec![3]], vec![vec![5.0, 7.0], vec![], vec![4.0]], 5, vec![10.0, 1.0, 3.0]);
+        assert_eq!(assignment.items_of(0), [2, 0]);
+        assert_eq!(assignment.agent_of(0), Some(0));
+        assert_eq!(assignment.agent_of(1), None);
+        assert_eq!(assignment.agent_of(3), Some(2));
+        assert_eq!(assignment.spend(0), 12.0);
+        assert_eq!(assignment.total_value(), 13.0);
+        assert_eq!(assignment.iter().collect::<Vec<_>>(), vec![(0, 2, 5.0), (0, 0, 7.0), (2, 3, 4.0)]);
+        assert_eq!((&assignment).into_iter().count(), 3);
+    }
+
+    #[test]
+    fn make_budget_feasible() {
+        let mut assignment = Assignment::new(vec![vec![0, 1, 2, 3], vec![4]], vec![vec![4.0, 1.0, 6.0, 2.0], vec![3.0]], 6, vec![10.0, 5.0]);
+        // 13 > 10: dropping items 1 and 3 gets to 10, then neither fits again
+        assert_eq!(assignment.make_budget_feasible(), 0.0);
+        assert_eq!(assignment.items_of(0), [0, 2]);
+        assert_eq!(assignment.agent_of(1), None);
+        assert_eq!(assignment.agent_of(3), None);
+        assert_eq!(assignment.items_of(1), [4]);
+
+        let mut assignment = Assignment::new(vec![vec![0, 1, 2]], vec![vec![6.0, 6.0, 5.0]], 3, vec![10.0]);
+        assert_eq!(assignment.make_budget_feasible(), 4.0);
+        assert_eq!(assignment.items_of(0), [1]);
+    }
+
+    #[test]
+    fn solved_budget_feasible() {
+        let num_agents = 12;
+        let num_items = 150;
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 15.0 + (agent_id * 7 % 20) as f64);
+        }
+        for agent_id in 0..num_agents {
+            for item_id in 0..num_items {
+                let bid = ((agent_id * 5 + item_id * 3) % 13) as f64;
+                if bid > 0.0 {
+                    solver.set_bid(agent_id, item_id, bid);
+                }
+            }
+        }
+        solver.solve();
+
+        let original = solver.assignment();
+        let mut assignment = original.clone();
+        let lost = assignment.make_budget_feasible();
+        assert!(lost > 0.0);
+        assert!((original.total_value() - assignment.total_value() - lost).abs() < 1e-9);
+
+        let mut total = 0.0;
+        for agent_id in 0..num_agents {
+            let budget = solver.get_effective_budget(agent_id);
+            assert!(assignment.spend(agent_id) <= budget);
+            total += assignment.spend(agent_id);
+
+            let largest_bid = original.iter().filter(|&(id, _, _)| id == agent_id).map(|(_, _, bid)| bid).fold(0.0, f64::max);
+            assert!(original.spend(agent_id).min(budget) - assignment.spend(agent_id) < largest_bid.max(f64::MIN_POSITIVE));
+        }
+        assert!((assignment.total_value() - total).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,297 @@
+// fixed benchmark suite with a stable json report, for tracking performance across releases
+//
+// the instances depend only on the seed and the size tier, and their hash is part of the report,
+// so two reports are comparable exactly when their hashes agree
+use crate::instance::Instance;
+use crate::primal_dual::PrimalDual;
+use crate::rng::{Rng, RngConfig};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Instant;
+
+// bumped whenever a field of BenchReport or BenchCase changes meaning
+pub const SCHEMA_VERSION: u32 = 1;
+
+// compare flags a case whose wall time grew by more than this factor
+pub const SLOWDOWN_THRESHOLD: f64 = 1.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeTier {
+    // a tenth of the standard sizes in each dimension, for tests
+    Reduced,
+    Standard,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchCase {
+    pub name: String,
+    pub num_agents: usize,
+    pub num_items: usize,
+    pub num_bids: usize,
+    pub epsilon: f64,
+    pub wall_time_ns: u64, // construction, set_budget, set_bid and solve
+    pub sweeps: usize,
+    pub reallocations: usize,
+    pub primal: f64,
+    pub dual: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub schema_version: u32,
+    pub crate_version: String,
+    pub seed: u64,
+    pub instance_set_hash: String, // fnv-1a over the sizes, budgets and bids of every instance, in hex
+    pub cases: Vec<BenchCase>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub name: String,
+    pub old_ns: u64,
+    pub new_ns: u64,
+    pub slowdown: f64, // new_ns / old_ns
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegressionSummary {
+    pub comparable: bool, // same schema and instance set; otherwise regressions is empty
+    pub regressions: Vec<Regression>,
+}
+
+impl RegressionSummary {
+    pub fn is_ok(&self) -> bool {
+        self.comparable && self.regressions.is_empty()
+    }
+}
+
+impl fmt::Display for RegressionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.comparable {
+            return writeln!(f, "reports are not comparable: different schema or instance set");
+        }
+        for regression in &self.regressions {
+            writeln!(f, "{}: {} ns -> {} ns ({:.2}x)", regression.name, regression.old_ns, regression.new_ns, regression.slowdown)?;
+        }
+        Ok(())
+    }
+}
+
+impl BenchReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(text: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(text)
+    }
+}
+
+pub fn run_standard_suite(seed: u64) -> BenchReport {
+    run_suite(seed, SizeTier::Standard)
+}
+
+pub fn run_suite(seed: u64, tier: SizeTier) -> BenchReport {
+    let instances = instances(seed, tier);
+    let mut hash = FNV_OFFSET;
+    for (_, _, instance) in &instances {
+        hash = hash_instance(hash, instance);
+    }
+
+    let cases = instances
+        .iter()
+        .map(|(name, epsilon, instance)| {
+            let start = Instant::now();
+            let mut solver = PrimalDual::from_instance(instance, *epsilon);
+            let sweeps = solver.solve_counting_sweeps();
+            let wall_time_ns = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+            BenchCase {
+                name: name.to_string(),
+                num_agents: instance.num_agents,
+                num_items: instance.num_items,
+                num_bids: instance.bids.len(),
+                epsilon: *epsilon,
+                wall_time_ns,
+                sweeps,
+                reallocations: solver.get_num_reallocations(),
+                primal: solver.get_primal_objective_value(),
+                dual: solver.get_dual_objective_value(),
+            }
+        })
+        .collect();
+
+    BenchReport {
+        schema_version: SCHEMA_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        seed,
+        instance_set_hash: format!("{:016x}", hash),
+        cases,
+    }
+}
+
+// cases of new that are more than SLOWDOWN_THRESHOLD times slower than the case of the same name in old
+pub fn compare(old: &BenchReport, new: &BenchReport) -> RegressionSummary {
+    let comparable = old.schema_version == new.schema_version && old.instance_set_hash == new.instance_set_hash;
+    let mut regressions = Vec::new();
+    if comparable {
+        for case in &new.cases {
+            let Some(old_case) = old.cases.iter().find(|old_case| old_case.name == case.name) else {
+                continue;
+            };
+            let slowdown = case.wall_time_ns as f64 / old_case.wall_time_ns.max(1) as f64;
+            if slowdown > SLOWDOWN_THRESHOLD {
+                regressions.push(Regression {
+                    name: case.name.clone(),
+                    old_ns: old_case.wall_time_ns,
+                    new_ns: case.wall_time_ns,
+                    slowdown,
+                });
+            }
+        }
+    }
+    RegressionSummary { comparable, regressions }
+}
+
+// (name, epsilon, instance) of every case
+fn instances(seed: u64, tier: SizeTier) -> Vec<(&'static str, f64, Instance)> {
+    let config = RngConfig::new(seed);
+    let scale = match tier {
+        SizeTier::Reduced => 1,
+        SizeTier::Standard => 10,
+    };
+    vec![
+        ("small_dense", 0.01, small_dense(&mut config.stream("small_dense"), 2 * scale, 5 * scale)),
+        ("large_sparse", 0.05, large_sparse(&mut config.stream("large_sparse"), 100 * scale, 500 * scale)),
+        ("beta_one", 0.1, beta_one(&mut config.stream("beta_one"), 20 * scale, 40 * scale)),
+        ("adwords_skewed", 0.05, adwords_skewed(&mut config.stream("adwords_skewed"), 50 * scale, 1000 * scale)),
+    ]
+}
+
+// every agent bids on every item
+fn small_dense(rng: &mut Rng, num_agents: usize, num_items: usize) -> Instance {
+    let mut instance = Instance::new(num_agents, num_items);
+    for agent_id in 0..num_agents {
+        instance.set_budget(agent_id, rng.range_f64(50.0, 150.0));
+        for item_id in 0..num_items {
+            instance.add_bid(agent_id, item_id, rng.range_f64(1.0, 20.0));
+        }
+    }
+    instance
+}
+
+// every item has three distinct bidders
+fn large_sparse(rng: &mut Rng, num_agents: usize, num_items: usize) -> Instance {
+    let mut instance = Instance::new(num_agents, num_items);
+    for agent_id in 0..num_agents {
+        instance.set_budget(agent_id, rng.range_f64(20.0, 60.0));
+    }
+    for item_id in 0..num_items {
+        for agent_id in distinct_agents(rng, num_agents, 3) {
+            instance.add_bid(agent_id, item_id, rng.range_f64(1.0, 10.0));
+        }
+    }
+    instance
+}
+
+// every bid equals the budget of its agent, so beta is 1 and the guarantee is at its weakest
+fn beta_one(rng: &mut Rng, num_agents: usize, num_items: usize) -> Instance {
+    let mut instance = Instance::new(num_agents, num_items);
+    for agent_id in 0..num_agents {
+        instance.set_budget(agent_id, rng.range_f64(1.0, 10.0));
+    }
+    for item_id in 0..num_items {
+        for agent_id in distinct_agents(rng, num_agents, 3) {
+            instance.add_bid(agent_id, item_id, instance.budgets[agent_id]);
+        }
+    }
+    instance
+}
+
+// budgets fall off like 1 / rank and the agents with large budgets bid on most items, as in ad auctions
+fn adwords_skewed(rng: &mut Rng, num_agents: usize, num_items: usize) -> Instance {
+    let mut instance = Instance::new(num_agents, num_items);
+    for agent_id in 0..num_agents {
+        instance.set_budget(agent_id, 100.0 / (agent_id + 1) as f64);
+    }
+    for item_id in 0..num_items {
+        for agent_id in 0..num_agents {
+            if rng.next_f64() < 1.0 / (agent_id + 1) as f64 {
+                let bid = rng.range_f64(0.1, 1.0).min(instance.budgets[agent_id]);
+                instance.add_bid(agent_id, item_id, bid);
+            }
+        }
+    }
+    instance
+}
+
+fn distinct_agents(rng: &mut Rng, num_agents: usize, count: usize) -> Vec<usize> {
+    let mut agent_ids = Vec::with_capacity(count);
+    while agent_ids.len() < count.min(num_agents) {
+        let agent_id = rng.range_usize(0, num_agents);
+        if !agent_ids.contains(&agent_id) {
+            agent_ids.push(agent_id);
+        }
+    }
+    agent_ids
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+fn hash_instance(hash: u64, instance: &Instance) -> u64 {
+    let words = [instance.num_agents as u64, instance.num_items as u64]
+        .into_iter()
+        .chain(instance.budgets.iter().map(|budget| budget.to_bits()))
+        .chain(
+            instance
+                .bids
+                .iter()
+                .flat_map(|&(agent_id, item_id, bid)| [agent_id as u64, item_id as u64, bid.to_bits()]),
+        );
+    words
+        .flat_map(u64::to_le_bytes)
+        .fold(hash, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, run_suite, BenchReport, SizeTier};
+
+    #[test]
+    fn reduced_suite() {
+        let report = run_suite(7, SizeTier::Reduced);
+        assert_eq!(report.cases.len(), 4);
+        for case in &report.cases {
+            assert!(case.sweeps > 0, "{}", case.name);
+            assert!(case.primal > 0.0 && case.primal <= case.dual + 1e-9, "{}", case.name);
+        }
+
+        let restored = BenchReport::from_json(&report.to_json()).unwrap();
+        assert_eq!(restored, report);
+
+        let again = run_suite(7, SizeTier::Reduced);
+        assert_eq!(again.instance_set_hash, report.instance_set_hash);
+        assert_eq!(
+            again.cases.iter().map(|case| (case.sweeps, case.primal)).collect::<Vec<_>>(),
+            report.cases.iter().map(|case| (case.sweeps, case.primal)).collect::<Vec<_>>()
+        );
+        assert_ne!(run_suite(8, SizeTier::Reduced).instance_set_hash, report.instance_set_hash);
+    }
+
+    #[test]
+    fn flags_slowdown() {
+        let old = run_suite(7, SizeTier::Reduced);
+        assert!(compare(&old, &old).is_ok());
+
+        let mut new = old.clone();
+        new.cases[1].wall_time_ns = 2 * old.cases[1].wall_time_ns.max(1);
+        let summary = compare(&old, &new);
+        assert!(!summary.is_ok());
+        assert_eq!(summary.regressions.len(), 1);
+        assert_eq!(summary.regressions[0].name, old.cases[1].name);
+        assert!(summary.regressions[0].slowdown >= 2.0);
+
+        new.instance_set_hash = "0".to_string();
+        let summary = compare(&old, &new);
+        assert!(!summary.comparable && summary.regressions.is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+// reference HTTP wrapper around PrimalDual
+//
+// POST /solve   : instance json -> solution json
+// POST /verify  : solution json -> verification result
+// GET  /healthz : liveness
+//
+// flags: --port <u16> (0 picks an ephemeral port), --max-body-bytes <usize>, --deadline-ms <u64>,
+//        --config <path> (SolverConfig toml; the epsilon of a request overrides the one of the file),
+//        --max-memory-bytes <usize> (memory limit of a solver whose config sets none, default 1 GiB),
+//        --inactive-warning-fraction <f64> (warn on stderr when more than this fraction of the agents has no accepted bid)
+// invalid instances are answered with 400 and the ErrorCode of the failure, instances above the memory limit with 413;
+// a solve past the deadline is cancelled and answered with 503 and the partial assignment it reached, which carries no
+// guarantee. every request runs on its own thread, so /healthz answers during a solve, while solves still run one at a
+// time so that the memory limit bounds the whole process; a request that panics is answered with 500
+// the bound address is printed to stdout on startup
+use maximum_budgeted_allocation::error_code::ErrorCode;
+use maximum_budgeted_allocation::primal_dual::{InactiveAgent, MbaError, PrimalDual, SolveOutcome, SolverConfig};
+use maximum_budgeted_allocation::solution::Solution;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+struct Config {
+    port: u16,
+    max_body_bytes: usize,
+    deadline: Duration,
+    solver: SolverConfig,
+    max_memory_bytes: usize,
+    inactive_warning_fraction: f64,
+}
+
+#[derive(Deserialize)]
+struct SolveRequest {
+    epsilon: Option<f64>,
+    num_items: usize,
+    budgets: Vec<f64>,
+    bids: Vec<(usize, usize, f64)>, // [(agent_id, item_id, bid), ...]
+}
+
+#[derive(Serialize)]
+struct SolveResponse {
+    primal: f64,
+    dual: f64,
+    ratio: f64,
+    assignment: Vec<Vec<usize>>,
+    inactive_agents: Vec<InactiveAgent>,
+}
+
+#[derive(Serialize)]
+struct VerifyResponse {
+    ok: bool,
+    error: Option<String>,
+    code: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct TimeoutResponse {
+    error: &'static str,
+    num_agents: usize,
+    num_items: usize,
+    num_bids: usize,
+    elapsed_ms: u128,
+    partial: PartialResponse,
+}
+
+// the assignment of a cancelled solve; some agent may be left unpaid for, so there is no ratio
+#[derive(Serialize)]
+struct PartialResponse {
+    primal: f64,
+    dual: f64,
+    assignment: Vec<Vec<usize>>,
+}
+
+fn parse_args() -> Config {
+    let mut config = Config {
+        port: 8080,
+        max_body_bytes: 16 << 20,
+        deadline: Duration::from_secs(10),
+        solver: SolverConfig::default(),
+        max_memory_bytes: 1 << 30,
+        inactive_warning_fraction: 0.1,
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for pair in args.chunks(2) {
+        let value = pair.get(1).unwrap_or_else(|| panic!("missing value for {}", pair[0]));
+        match pair[0].as_str() {
+            "--port" => config.port = value.parse().expect("invalid --port"),
+            "--max-body-bytes" => config.max_body_bytes = value.parse().expect("invalid --max-body-bytes"),
+            "--deadline-ms" => config.deadline = Duration::from_millis(value.parse().expect("invalid --deadline-ms")),
+            "--config" => {
+                let text = std::fs::read_to_string(value).unwrap_or_else(|e| panic!("cannot read {}: {}", value, e));
+                config.solver = toml::from_str(&text).unwrap_or_else(|e| panic!("invalid --config {}: {}", value, e));
+            }
+            "--max-memory-bytes" => config.max_memory_bytes = value.parse().expect("invalid --max-memory-bytes"),
+            "--inactive-warning-fraction" => config.inactive_warning_fraction = value.parse().expect("invalid --inactive-warning-fraction"),
+            flag => panic!("unknown flag {}", flag),
+        }
+    }
+    config
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<std::io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    Response::from_data(serde_json::to_vec(body).unwrap()).with_status_code(status).with_header(header)
+}
+
+fn error_response(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    json_response(
+        status,
+        &VerifyResponse {
+            ok: false,
+            error: Some(message.to_string()),
+            code: None,
+        },
+    )
+}
+
+fn mba_error_response(e: &MbaError) -> Response<std::io::Cursor<Vec<u8>>> {
+    let status = match e.code() {
+        ErrorCode::MemoryLimitExceeded => 413,
+        _ => 400,
+    };
+    json_response(
+        status,
+        &VerifyResponse {
+            ok: false,
+            error: Some(e.to_string()),
+            code: Some(e.code().value()),
+        },
+    )
+}
+
+fn read_body(request: &mut Request, config: &Config) -> Result<Vec<u8>, Response<std::io::Cursor<Vec<u8>>>> {
+    if request.body_length().is_some_and(|length| length > config.max_body_bytes) {
+        return Err(error_response(413, "request body too large"));
+    }
+
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(config.max_body_bytes as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| error_response(400, &e.to_string()))?;
+    if body.len() > config.max_body_bytes {
+        return Err(error_response(413, "request body too large"));
+    }
+    Ok(body)
+}
+
+fn solve(body: &[u8], config: &Config, solve_slot: &Mutex<()>) -> Response<std::io::Cursor<Vec<u8>>> {
+    let instance: SolveRequest = match serde_json::from_slice(body) {
+        Ok(instance) => instance,
+        Err(e) => return error_response(400, &e.to_string()),
+    };
+
+    let num_agents = instance.budgets.len();
+    let num_items = instance.num_items;
+    let num_bids = instance.bids.len();
+    // a request that panicked released the slot as well
+    let _slot = solve_slot.lock().unwrap_or_else(|e| e.into_inner());
+    let start = Instant::now();
+    let mut solver = match build_solver(&instance, config) {
+        Ok(solver) => solver,
+        Err(e) => return mba_error_response(&e),
+    };
+    for warning in solver.get_range_warnings() {
+        eprintln!("warning: {}", warning);
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    let solver_cancel = Arc::clone(&cancel);
+    thread::spawn(move || {
+        let outcome = solver.solve_cancellable(&solver_cancel);
+        let _ = sender.send((outcome, solver.extract_solution(), solver.get_inactive_agents()));
+    });
+
+    // the solve stops at the next check of the flag and still sends what it reached
+    let result = receiver.recv_timeout(config.deadline).or_else(|e| match e {
+        mpsc::RecvTimeoutError::Timeout => {
+            cancel.store(true, Ordering::Relaxed);
+            receiver.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+        }
+        e => Err(e),
+    });
+    match result {
+        Ok((SolveOutcome::Cancelled, solution, _)) => json_response(
+            503,
+            &TimeoutResponse {
+                error: "deadline exceeded",
+                num_agents,
+                num_items,
+                num_bids,
+                elapsed_ms: start.elapsed().as_millis(),
+                partial: PartialResponse {
+                    primal: solution.primal,
+                    dual: solution.dual,
+                    assignment: solution.assignment,
+                },
+            },
+        ),
+        Ok((SolveOutcome::Completed, solution, inactive_agents)) => {
+            if inactive_agents.len() as f64 > config.inactive_warning_fraction * num_agents as f64 {
+                eprintln!("WARNING: {} of {} agents have no accepted bid, check the units of bids and budgets: {:?}", inactive_agents.len(), num_agents, inactive_agents);
+            }
+            json_response(
+                200,
+                &SolveResponse {
+                    primal: solution.primal,
+                    dual: solution.dual,
+                    ratio: solution.get_approximation_ratio(),
+                    assignment: solution.assignment,
+                    inactive_agents,
+                },
+            )
+        }
+        Err(_) => error_response(500, "solver failed"),
+    }
+}
+
+// the solver of a request, through the try_ setters so that invalid input fails with its MbaError
+fn build_solver(instance: &SolveRequest, config: &Config) -> Result<PrimalDual, MbaError> {
+    let mut solver_config = config.solver;
+    solver_config.max_memory_bytes.get_or_insert(config.max_memory_bytes);
+    if let Some(epsilon) = instance.epsilon {
+        solver_config.epsilon = epsilon;
+    }
+
+    let mut solver = PrimalDual::try_with_config(instance.budgets.len(), instance.num_items, solver_config)?;
+    for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+        solver.try_set_budget(agent_id, budget)?;
+    }
+    for &(agent_id, item_id, bid) in &instance.bids {
+        solver.try_set_bid(agent_id, item_id, bid)?;
+    }
+    Ok(solver)
+}
+
+fn verify(body: &[u8]) -> Response<std::io::Cursor<Vec<u8>>> {
+    let solution: Solution = match serde_json::from_slice(body) {
+        Ok(solution) => solution,
+        Err(e) => return error_response(400, &e.to_string()),
+    };
+
+    match solution.verify() {
+        Ok(()) => json_response(
+            200,
+            &VerifyResponse {
+                ok: true,
+                error: None,
+                code: None,
+            },
+        ),
+        Err(e) => json_response(
+            200,
+            &VerifyResponse {
+                ok: false,
+                error: Some(e.code().description().to_string()),
+                code: Some(e.code().value()),
+            },
+        ),
+    }
+}
+
+fn handle(mut request: Request, config: &Config, solve_slot: &Mutex<()>) {
+    let response = panic::catch_unwind(AssertUnwindSafe(|| respond(&mut request, config, solve_slot))).unwrap_or_else(|_| error_response(500, "request failed"));
+    let _ = request.respond(response);
+}
+
+fn respond(request: &mut Request, config: &Config, solve_slot: &Mutex<()>) -> Response<std::io::Cursor<Vec<u8>>> {
+    match (request.method(), request.url()) {
+        (Method::Get, "/healthz") => json_response(
+            200,
+            &VerifyResponse {
+                ok: true,
+                error: None,
+                code: None,
+            },
+        ),
+        (Method::Post, "/solve") => match read_body(request, config) {
+            Ok(body) => solve(&body, config, solve_slot),
+            Err(response) => response,
+        },
+        (Method::Post, "/verify") => match read_body(request, config) {
+            Ok(body) => verify(&body),
+            Err(response) => response,
+        },
+        _ => error_response(404, "not found"),
+    }
+}
+
+fn main() {
+    let config = Arc::new(parse_args());
+    let server = Server::http(("127.0.0.1", config.port)).expect("could not bind");
+    println!("{}", server.server_addr());
+
+    let solve_slot = Arc::new(Mutex::new(()));
+    for request in server.incoming_requests() {
+        let config = Arc::clone(&config);
+        let solve_slot = Arc::clone(&solve_slot);
+        thread::spawn(move || handle(request, &config, &solve_slot));
+    }
+}
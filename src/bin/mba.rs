@@ -0,0 +1,181 @@
+// solves an instance file and prints the objectives
+//
+// usage: mba <instance file> [--epsilon <f64>] [--config <path>] [--print-assignment] [--output <path>]
+//            [--inactive-warning-fraction <f64>]
+//   <instance file>    : the csv format of io::read_instance or the binary format of io::read_instance_binary, told apart by io::detect_format
+//   --epsilon          : in [0, 1), default 0.01 or that of --config; 0 solves small instances exactly
+//   --config <path>    : SolverConfig toml, as for mba-server; needs the config feature
+//   --print-assignment : also print one "agent_id,item_id" line per assigned item
+//   --output <path>    : write the assignment in the same csv form to path
+//   --inactive-warning-fraction : warn on stderr when more than this fraction of the agents has no accepted bid, default 0.1
+// errors are printed to stderr and exit with the ErrorCode of the failure, or 2 for a malformed command line
+use maximum_budgeted_allocation::error_code::ErrorCode;
+use maximum_budgeted_allocation::instance::Instance;
+use maximum_budgeted_allocation::io::{detect_format, read_instance, read_instance_binary, FormatKind};
+use maximum_budgeted_allocation::primal_dual::{PrimalDual, SolverConfig};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: mba <instance file> [--epsilon <f64>] [--config <path>] [--print-assignment] [--output <path>] [--inactive-warning-fraction <f64>]";
+
+struct Args {
+    path: String,
+    epsilon: Option<f64>,
+    config: Option<String>,
+    print_assignment: bool,
+    output: Option<String>,
+    inactive_warning_fraction: f64,
+}
+
+// why mba failed, with the exit status it fails with
+struct Failure {
+    status: u8,
+    message: String,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Self {
+        Failure {
+            status: 2,
+            message: message.into(),
+        }
+    }
+
+    fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Failure {
+            status: code.value() as u8,
+            message: message.into(),
+        }
+    }
+}
+
+fn parse_args() -> Result<Args, Failure> {
+    let mut path = None;
+    let mut epsilon = None;
+    let mut config = None;
+    let mut print_assignment = false;
+    let mut output = None;
+    let mut inactive_warning_fraction = 0.1;
+
+    let mut args = std::env::args().skip(1);
+    let value = |flag: &str, args: &mut dyn Iterator<Item = String>| args.next().ok_or_else(|| Failure::usage(format!("missing value for {}", flag)));
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--epsilon" => {
+                let text = value("--epsilon", &mut args)?;
+                epsilon = Some(text.parse().map_err(|_| Failure::usage(format!("invalid --epsilon {}", text)))?);
+            }
+            "--config" => config = Some(value("--config", &mut args)?),
+            "--print-assignment" => print_assignment = true,
+            "--output" => output = Some(value("--output", &mut args)?),
+            "--inactive-warning-fraction" => {
+                let text = value("--inactive-warning-fraction", &mut args)?;
+                inactive_warning_fraction = text.parse().map_err(|_| Failure::usage(format!("invalid --inactive-warning-fraction {}", text)))?;
+            }
+            _ if arg.starts_with("--") => return Err(Failure::usage(format!("unknown flag {}", arg))),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(Failure::usage(format!("unexpected argument {}", arg))),
+        }
+    }
+
+    Ok(Args {
+        path: path.ok_or_else(|| Failure::usage(USAGE))?,
+        epsilon,
+        config,
+        print_assignment,
+        output,
+        inactive_warning_fraction,
+    })
+}
+
+#[cfg(feature = "config")]
+fn read_config(path: &str) -> Result<SolverConfig, Failure> {
+    let text = std::fs::read_to_string(path).map_err(|e| Failure::new(ErrorCode::Io, format!("cannot read {}: {}", path, e)))?;
+    toml::from_str(&text).map_err(|e| Failure::new(ErrorCode::MalformedInput, format!("{}: {}", path, e)))
+}
+
+#[cfg(not(feature = "config"))]
+fn read_config(_path: &str) -> Result<SolverConfig, Failure> {
+    Err(Failure::usage("--config needs mba built with the config feature"))
+}
+
+fn read_input(path: &str) -> Result<Instance, Failure> {
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| Failure::new(ErrorCode::Io, format!("cannot open {}: {}", path, e)))
+    };
+    match detect_format(path).map_err(|e| Failure::new(ErrorCode::Io, format!("cannot open {}: {}", path, e)))? {
+        FormatKind::Text => read_instance(open()?).map_err(|e| Failure::new(e.code(), format!("{}: {}", path, e))),
+        FormatKind::Instance { .. } => read_instance_binary(open()?).map_err(|e| Failure::new(e.code(), format!("{}: {}", path, e))),
+        FormatKind::Solution { .. } => Err(Failure::new(ErrorCode::InvalidFormat, format!("{} holds a solution, not an instance", path))),
+        FormatKind::Unknown => Err(Failure::new(ErrorCode::InvalidFormat, format!("{} is neither csv nor a binary instance", path))),
+    }
+}
+
+fn run() -> Result<(), Failure> {
+    let args = parse_args()?;
+    let mut config = match &args.config {
+        Some(path) => read_config(path)?,
+        None => SolverConfig::default(),
+    };
+    if let Some(epsilon) = args.epsilon {
+        config.epsilon = epsilon;
+    }
+    let instance = read_input(&args.path)?;
+
+    let mut solver = PrimalDual::try_with_config(instance.num_agents, instance.num_items, config).map_err(|e| Failure::new(e.code(), e.to_string()))?;
+    let rejected = |e: maximum_budgeted_allocation::primal_dual::MbaError| Failure::new(e.code(), format!("{}: {}", args.path, e));
+    for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+        solver.try_set_budget(agent_id, budget).map_err(rejected)?;
+    }
+    for &(agent_id, item_id, bid) in &instance.bids {
+        solver.try_set_bid(agent_id, item_id, bid).map_err(rejected)?;
+    }
+    for warning in solver.get_range_warnings() {
+        eprintln!("warning: {}", warning);
+    }
+    solver.solve();
+    let solution = solver.extract_solution();
+
+    let inactive_agents = solver.get_inactive_agents();
+    if inactive_agents.len() as f64 > args.inactive_warning_fraction * instance.num_agents as f64 {
+        eprintln!(
+            "WARNING: {} of {} agents have no accepted bid, check the units of bids and budgets: {:?}",
+            inactive_agents.len(),
+            instance.num_agents,
+            inactive_agents
+        );
+    }
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let write_error = |e: std::io::Error| Failure::new(ErrorCode::Io, e.to_string());
+    writeln!(out, "primal: {}", solution.primal).map_err(write_error)?;
+    writeln!(out, "dual: {}", solution.dual).map_err(write_error)?;
+    writeln!(out, "ratio: {}", solution.get_approximation_ratio()).map_err(write_error)?;
+    if args.print_assignment {
+        solution.write_assignment(&mut out).map_err(write_error)?;
+    }
+
+    if let Some(path) = &args.output {
+        let file = File::create(path).map_err(|e| Failure::new(ErrorCode::Io, format!("cannot create {}: {}", path, e)))?;
+        let mut writer = BufWriter::new(file);
+        solution
+            .write_assignment(&mut writer)
+            .and_then(|_| writer.flush())
+            .map_err(|e| Failure::new(ErrorCode::Io, format!("cannot write {}: {}", path, e)))?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("error: {}", failure.message);
+            ExitCode::from(failure.status)
+        }
+    }
+}
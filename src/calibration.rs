@@ -0,0 +1,177 @@
+// what epsilon costs: one solve per epsilon on the same instance, tabulated
+use crate::exact;
+use crate::instance::Instance;
+use crate::primal_dual::PrimalDual;
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationRow {
+    pub epsilon: f64,
+    pub primal: f64,
+    pub dual: f64,
+    pub ratio: f64, // primal / dual, a lower bound of the true ratio
+    pub sweeps: usize,
+    pub time: Duration,
+    pub marginal_value_per_second: Option<f64>, // primal gained per extra second over the next coarser epsilon
+    pub optimality_gap: Option<f64>,            // 1 - primal / optimum when the optimum is known
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationReport {
+    pub rows: Vec<CalibrationRow>, // by epsilon descending, coarsest first
+    pub optimum: Option<f64>,      // from exact::solve when the instance is small enough
+}
+
+// one solve per epsilon; the optimum is computed when exact::solve accepts the instance
+pub fn report(instance: &Instance, epsilons: &[f64]) -> CalibrationReport {
+    let mut epsilons = epsilons.to_vec();
+    epsilons.sort_by(|a, b| b.total_cmp(a));
+    epsilons.dedup();
+
+    let optimum = {
+        // the bids the solver accepts
+        let accepted = PrimalDual::from_instance(instance, 0.5).to_instance();
+        let mut bids = vec![vec![0.0; instance.num_items]; instance.num_agents];
+        for &(agent_id, item_id, bid) in &accepted.bids {
+            bids[agent_id][item_id] = bid;
+        }
+        exact::solve(&instance.budgets, &bids).map(|(optimum, _)| optimum)
+    };
+
+    let mut rows: Vec<CalibrationRow> = Vec::with_capacity(epsilons.len());
+    for epsilon in epsilons {
+        let mut solver = PrimalDual::from_instance(instance, epsilon);
+        let start = Instant::now();
+        let sweeps = solver.solve_counting_sweeps();
+        let time = start.elapsed();
+
+        let primal = solver.get_primal_objective_value();
+        let dual = solver.get_dual_objective_value();
+        let marginal_value_per_second = rows.last().and_then(|coarser| {
+            let extra = time.as_secs_f64() - coarser.time.as_secs_f64();
+            (extra > 0.0).then(|| (primal - coarser.primal) / extra)
+        });
+        rows.push(CalibrationRow {
+            epsilon,
+            primal,
+            dual,
+            ratio: if dual > 0.0 { primal / dual } else { 1.0 },
+            sweeps,
+            time,
+            marginal_value_per_second,
+            optimality_gap: optimum.map(|optimum| if optimum > 0.0 { 1.0 - primal / optimum } else { 0.0 }),
+        });
+    }
+
+    CalibrationReport { rows, optimum }
+}
+
+impl CalibrationReport {
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let optional = |value: Option<f64>| value.map_or(String::new(), |value| format!("{:?}", value));
+        writeln!(writer, "epsilon,primal,dual,ratio,sweeps,time_s,marginal_value_per_second,optimality_gap")?;
+        for row in &self.rows {
+            writeln!(
+                writer,
+                "{:?},{:?},{:?},{:?},{},{:?},{},{}",
+                row.epsilon,
+                row.primal,
+                row.dual,
+                row.ratio,
+                row.sweeps,
+                row.time.as_secs_f64(),
+                optional(row.marginal_value_per_second),
+                optional(row.optimality_gap)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CalibrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>10} {:>14} {:>8} {:>8} {:>10} {:>14} {:>8}", "epsilon", "primal", "ratio", "sweeps", "time_ms", "value/s", "gap")?;
+        for row in &self.rows {
+            let marginal = row.marginal_value_per_second.map_or("-".to_string(), |value| format!("{:.3}", value));
+            let gap = row.optimality_gap.map_or("-".to_string(), |gap| format!("{:.4}", gap));
+            writeln!(
+                f,
+                "{:>10} {:>14.3} {:>8.4} {:>8} {:>10.3} {:>14} {:>8}",
+                row.epsilon,
+                row.primal,
+                row.ratio,
+                row.sweeps,
+                row.time.as_secs_f64() * 1000.0,
+                marginal,
+                gap
+            )?;
+        }
+        if let Some(optimum) = self.optimum {
+            writeln!(f, "optimum {}", optimum)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::report;
+    use crate::instance::Instance;
+    use crate::primal_dual::PrimalDual;
+    use crate::rng::RngConfig;
+
+    fn seeded_instance(num_agents: usize, num_items: usize) -> Instance {
+        let mut rng = RngConfig::new(235).stream("calibration");
+        let mut instance = Instance::new(num_agents, num_items);
+        for agent_id in 0..num_agents {
+            instance.set_budget(agent_id, rng.range_f64(20.0, 60.0));
+        }
+        for agent_id in 0..num_agents {
+            for item_id in 0..num_items {
+                if rng.next_f64() < 0.5 {
+                    instance.add_bid(agent_id, item_id, rng.range_f64(1.0, 10.0));
+                }
+            }
+        }
+        instance
+    }
+
+    #[test]
+    fn monotone_work() {
+        let instance = seeded_instance(20, 200);
+        let report = report(&instance, &[0.01, 0.2, 0.05, 0.1, 0.02]);
+
+        let epsilons: Vec<f64> = report.rows.iter().map(|row| row.epsilon).collect();
+        assert_eq!(epsilons, vec![0.2, 0.1, 0.05, 0.02, 0.01]);
+        assert!(report.rows.windows(2).all(|pair| pair[0].sweeps <= pair[1].sweeps));
+        assert!(report.rows[0].marginal_value_per_second.is_none());
+        assert!(report.optimum.is_none());
+
+        for row in &report.rows {
+            let mut solver = PrimalDual::from_instance(&instance, row.epsilon);
+            solver.solve();
+            assert_eq!(solver.get_primal_objective_value(), row.primal);
+            assert_eq!(solver.get_dual_objective_value(), row.dual);
+        }
+
+        let mut buffer = Vec::new();
+        report.write_csv(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 1 + 5);
+        assert_eq!(report.to_string().lines().count(), 1 + 5);
+    }
+
+    #[test]
+    fn optimality_gap() {
+        let instance = seeded_instance(3, 6);
+        let report = report(&instance, &[0.1, 0.01]);
+        let optimum = report.optimum.unwrap();
+        for row in &report.rows {
+            let gap = row.optimality_gap.unwrap();
+            assert!((0.0..=1.0).contains(&gap));
+            assert!(row.primal <= optimum + 1e-9);
+        }
+    }
+}
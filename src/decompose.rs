@@ -0,0 +1,155 @@
+// an instance split into its independent sub-markets: the connected components of the graph with an edge from every
+// agent to every item it bids on. no bid connects two components, so solving each one on its own and merging the
+// assignments solves the instance, e.g. on several threads
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+
+// a component as an instance of its own; agent_ids[i] and item_ids[i] are the ids in the whole instance of agent and item
+// i of the component
+#[derive(Clone, Debug, PartialEq)]
+pub struct Component {
+    pub instance: Instance,
+    pub agent_ids: Vec<usize>,
+    pub item_ids: Vec<usize>,
+}
+
+fn find(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
+}
+
+// components in the order of their smallest agent id, then of their smallest item id for those without agents, with ids in
+// increasing order and bids in the order of instance.bids. an agent or item without bids is a component of its own
+pub fn decompose(instance: &Instance) -> Vec<Component> {
+    let num_agents = instance.num_agents;
+    // nodes 0..num_agents are the agents, the items follow
+    let mut parent: Vec<usize> = (0..num_agents + instance.num_items).collect();
+    for &(agent_id, item_id, _) in &instance.bids {
+        let (a, b) = (find(&mut parent, agent_id), find(&mut parent, num_agents + item_id));
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut component_of = vec![usize::MAX; parent.len()]; // component of each root
+    let mut local_ids = vec![0; parent.len()];
+    let mut components: Vec<Component> = Vec::new();
+    for (node, local_id) in local_ids.iter_mut().enumerate() {
+        let root = find(&mut parent, node);
+        if component_of[root] == usize::MAX {
+            component_of[root] = components.len();
+            components.push(Component {
+                instance: Instance::default(),
+                agent_ids: Vec::new(),
+                item_ids: Vec::new(),
+            });
+        }
+        let component = &mut components[component_of[root]];
+        if node < num_agents {
+            *local_id = component.agent_ids.len();
+            component.agent_ids.push(node);
+            component.instance.budgets.push(instance.budgets[node]);
+        } else {
+            *local_id = component.item_ids.len();
+            component.item_ids.push(node - num_agents);
+        }
+    }
+    for component in &mut components {
+        component.instance.num_agents = component.agent_ids.len();
+        component.instance.num_items = component.item_ids.len();
+    }
+    for &(agent_id, item_id, bid) in &instance.bids {
+        let component = &mut components[component_of[find(&mut parent, agent_id)]];
+        component.instance.bids.push((local_ids[agent_id], local_ids[num_agents + item_id], bid));
+    }
+    components
+}
+
+// the assignment of the whole instance from assignments[i], an assignment of components[i].instance
+pub fn merge(instance: &Instance, components: &[Component], assignments: &[Assignment]) -> Assignment {
+    debug_assert_eq!(components.len(), assignments.len());
+    let mut items = vec![Vec::new(); instance.num_agents];
+    let mut bids = vec![Vec::new(); instance.num_agents];
+    let mut budgets = instance.budgets.clone();
+    for (component, assignment) in components.iter().zip(assignments) {
+        for (agent_id, item_id, bid) in assignment {
+            items[component.agent_ids[agent_id]].push(component.item_ids[item_id]);
+            bids[component.agent_ids[agent_id]].push(bid);
+        }
+        for (local_id, &agent_id) in component.agent_ids.iter().enumerate() {
+            budgets[agent_id] = assignment.budget(local_id);
+        }
+    }
+    Assignment::new(items, bids, instance.num_items, budgets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decompose, merge};
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::lp;
+    use crate::primal_dual::PrimalDual;
+
+    // agents 0 and 2 share items 0 and 3, agent 1 bids alone on item 1, item 2 has no bidder
+    fn three_markets() -> Instance {
+        let mut instance = Instance::new(3, 4);
+        instance.budgets = vec![5.0, 3.0, 4.0];
+        for (agent_id, item_id, bid) in [(0, 0, 2.0), (2, 0, 3.0), (1, 1, 1.0), (2, 3, 2.5), (0, 3, 1.5)] {
+            instance.add_bid(agent_id, item_id, bid);
+        }
+        instance
+    }
+
+    #[test]
+    fn components() {
+        let components = decompose(&three_markets());
+        assert_eq!(components.len(), 3);
+        assert_eq!((components[0].agent_ids.clone(), components[0].item_ids.clone()), (vec![0, 2], vec![0, 3]));
+        assert_eq!(components[0].instance.budgets, vec![5.0, 4.0]);
+        assert_eq!(components[0].instance.bids, vec![(0, 0, 2.0), (1, 0, 3.0), (1, 1, 2.5), (0, 1, 1.5)]);
+        assert_eq!((components[1].agent_ids.clone(), components[1].item_ids.clone()), (vec![1], vec![1]));
+        assert_eq!((components[2].agent_ids.clone(), components[2].item_ids.clone()), (vec![], vec![2]));
+        assert_eq!(components[2].instance, Instance::new(0, 1));
+    }
+
+    #[test]
+    fn solved_separately() {
+        // sparse enough to fall apart
+        for seed in 0..5 {
+            let instance = generate(8, 60, 0.03, (5.0, 20.0), (1.0, 6.0), 301 + seed);
+            let components = decompose(&instance);
+            assert!(components.len() > 1);
+            assert_eq!(components.iter().map(|component| component.instance.bids.len()).sum::<usize>(), instance.bids.len());
+
+            // the optimum of the relaxation is the sum of those of the components
+            let bound: f64 = components.iter().map(|component| lp::upper_bound(&component.instance)).sum();
+            assert!((bound - lp::upper_bound(&instance)).abs() <= 1e-6 * bound.max(1.0));
+
+            let assignments: Vec<_> = components
+                .iter()
+                .map(|component| {
+                    let mut solver = PrimalDual::from_instance(&component.instance, 0.01);
+                    solver.solve();
+                    solver.assignment()
+                })
+                .collect();
+            let merged = merge(&instance, &components, &assignments);
+            let separate: f64 = assignments.iter().map(|assignment| assignment.total_value()).sum();
+            assert!((merged.total_value() - separate).abs() <= 1e-9);
+
+            let mut whole = PrimalDual::from_instance(&instance, 0.01);
+            whole.solve();
+            assert!(
+                (merged.total_value() - whole.get_primal_objective_value()).abs() <= 1e-6 * separate,
+                "{} {}",
+                merged.total_value(),
+                whole.get_primal_objective_value()
+            );
+            for (agent_id, item_id, _) in &merged {
+                assert!(instance.bids.iter().any(|&(a, i, _)| (a, i) == (agent_id, item_id)));
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// stable numeric codes for every error of the crate
+//
+// codes and descriptions are part of the public interface: never renumber or reword them, only append
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ErrorCode {
+    InvalidEpsilon = 10,
+    InvalidInitialAlpha = 11,
+    InvalidSafeRange = 12,
+    InvalidRoundBudgetFraction = 13,
+    AgentIndexOutOfRange = 20,
+    ItemIndexOutOfRange = 21,
+    InvalidBudget = 30,
+    InvalidBid = 31,
+    BidExceedsBudget = 32,
+    ValueOutOfRange = 33,
+    InvalidCapacity = 34,
+    InvalidReservePrice = 35,
+    ShapeMismatch = 36,
+    InvalidIndptr = 37,
+    ConflictingOptions = 38,
+    NotAPermutation = 39,
+    ItemAssignedTwice = 40,
+    ItemOwnerMismatch = 41,
+    NegativeSpend = 42,
+    PrimalExceedsDual = 43,
+    CapacityExceeded = 44,
+    MissingBid = 45,
+    TooManyItems = 46,
+    OverCapacity = 47,
+    ConsumptionDrift = 48,
+    PrimalMismatch = 49,
+    MemoryLimitExceeded = 50,
+    TooLargeForExact = 51,
+    UnsupportedByExact = 52,
+    InvalidChunkSize = 53,
+    AlreadySolved = 60,
+    InvalidSize = 70,
+    InvalidAgentCapacity = 71,
+    InvalidRate = 72,
+    Io = 80,
+    MalformedInput = 81,
+    DuplicateBid = 82,
+    BadMagic = 83,
+    UnsupportedFormatVersion = 84,
+    InvalidFormat = 85,
+    UnknownSolver = 90,
+}
+
+// every code in ascending order. a new code needs an arm in tests::position, a match without wildcard, and all_in_order
+// checks that it sits here at the position of that arm
+const ALL: [ErrorCode; 41] = [
+    ErrorCode::InvalidEpsilon,
+    ErrorCode::InvalidInitialAlpha,
+    ErrorCode::InvalidSafeRange,
+    ErrorCode::InvalidRoundBudgetFraction,
+    ErrorCode::AgentIndexOutOfRange,
+    ErrorCode::ItemIndexOutOfRange,
+    ErrorCode::InvalidBudget,
+    ErrorCode::InvalidBid,
+    ErrorCode::BidExceedsBudget,
+    ErrorCode::ValueOutOfRange,
+    ErrorCode::InvalidCapacity,
+    ErrorCode::InvalidReservePrice,
+    ErrorCode::ShapeMismatch,
+    ErrorCode::InvalidIndptr,
+    ErrorCode::ConflictingOptions,
+    ErrorCode::NotAPermutation,
+    ErrorCode::ItemAssignedTwice,
+    ErrorCode::ItemOwnerMismatch,
+    ErrorCode::NegativeSpend,
+    ErrorCode::PrimalExceedsDual,
+    ErrorCode::CapacityExceeded,
+    ErrorCode::MissingBid,
+    ErrorCode::TooManyItems,
+    ErrorCode::OverCapacity,
+    ErrorCode::ConsumptionDrift,
+    ErrorCode::PrimalMismatch,
+    ErrorCode::MemoryLimitExceeded,
+    ErrorCode::TooLargeForExact,
+    ErrorCode::UnsupportedByExact,
+    ErrorCode::InvalidChunkSize,
+    ErrorCode::AlreadySolved,
+    ErrorCode::InvalidSize,
+    ErrorCode::InvalidAgentCapacity,
+    ErrorCode::InvalidRate,
+    ErrorCode::Io,
+    ErrorCode::MalformedInput,
+    ErrorCode::DuplicateBid,
+    ErrorCode::BadMagic,
+    ErrorCode::UnsupportedFormatVersion,
+    ErrorCode::InvalidFormat,
+    ErrorCode::UnknownSolver,
+];
+
+impl ErrorCode {
+    pub fn value(self) -> i32 {
+        self as i32
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidEpsilon => "epsilon is not in [0, 1)",
+            ErrorCode::InvalidInitialAlpha => "initial alpha of an agent is not in (0, epsilon]",
+            ErrorCode::InvalidSafeRange => "safe range is empty or its lower bound is not positive",
+            ErrorCode::InvalidRoundBudgetFraction => "round budget fraction is not in [0, 1]",
+            ErrorCode::AgentIndexOutOfRange => "agent id is out of range",
+            ErrorCode::ItemIndexOutOfRange => "item id is out of range",
+            ErrorCode::InvalidBudget => "budget is negative or not finite",
+            ErrorCode::InvalidBid => "bid is not positive or not finite",
+            ErrorCode::BidExceedsBudget => "bid exceeds the budget of the agent",
+            ErrorCode::ValueOutOfRange => "value is outside the safe range",
+            ErrorCode::InvalidCapacity => "item capacity is 0, or above 1 for the exact solver",
+            ErrorCode::InvalidReservePrice => "reserve price of an item is negative, NaN or infinite",
+            ErrorCode::ShapeMismatch => "budgets and bid matrix disagree on the number of agents",
+            ErrorCode::InvalidIndptr => "row pointers of a sparse bid matrix are not monotone or do not match its entries",
+            ErrorCode::ConflictingOptions => "solve options contradict each other or the solver",
+            ErrorCode::NotAPermutation => "agent order does not hold every agent exactly once",
+            ErrorCode::ItemAssignedTwice => "item is assigned to more than one agent",
+            ErrorCode::ItemOwnerMismatch => "item owner disagrees with the assignment",
+            ErrorCode::NegativeSpend => "spend of an agent is negative",
+            ErrorCode::PrimalExceedsDual => "primal objective exceeds the dual objective",
+            ErrorCode::CapacityExceeded => "item is held by more agents than its capacity",
+            ErrorCode::MissingBid => "agent holds an item without a positive bid on it",
+            ErrorCode::TooManyItems => "agent holds more items than its cap",
+            ErrorCode::OverCapacity => "sizes of the items of an agent exceed its capacity",
+            ErrorCode::ConsumptionDrift => "consumption of an agent disagrees with the bids on its items",
+            ErrorCode::PrimalMismatch => "primal objective disagrees with the spends",
+            ErrorCode::MemoryLimitExceeded => "solver would exceed the memory limit",
+            ErrorCode::TooLargeForExact => "instance is too large for the exact solver",
+            ErrorCode::UnsupportedByExact => "limit is not supported by the exact solver",
+            ErrorCode::InvalidChunkSize => "reduction chunk size is 0",
+            ErrorCode::AlreadySolved => "operation is only allowed before the first solve",
+            ErrorCode::InvalidSize => "size of an item for an agent is negative or not finite",
+            ErrorCode::InvalidAgentCapacity => "capacity of an agent is negative or not finite",
+            ErrorCode::InvalidRate => "currency rate of an agent is not positive or not finite",
+            ErrorCode::Io => "reading or writing the input failed",
+            ErrorCode::MalformedInput => "input line is not well-formed",
+            ErrorCode::DuplicateBid => "input bids twice for the same agent and item",
+            ErrorCode::BadMagic => "binary input does not start with the magic bytes",
+            ErrorCode::UnsupportedFormatVersion => "binary input is of a newer format version",
+            ErrorCode::InvalidFormat => "binary input is inconsistent",
+            ErrorCode::UnknownSolver => "solver name is unknown",
+        }
+    }
+}
+
+// every code with its canonical description, in ascending order of code
+pub fn catalog() -> Vec<(i32, &'static str)> {
+    ALL.iter().map(|&code| (code.value(), code.description())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{catalog, ErrorCode, ALL};
+    use crate::io::{CsvError, FormatError, ParseError};
+    use crate::primal_dual::{BuildError, MbaError, VerifyError};
+    use crate::solution::SolutionError;
+    use crate::solver::SpecError;
+    use std::collections::HashSet;
+
+    // no wildcard: a new code does not compile until it has an arm here, which places it in ALL; all_in_order checks
+    // that every code of ALL sits at the position of its arm
+    fn position(code: ErrorCode) -> usize {
+        match code {
+            ErrorCode::InvalidEpsilon => 0,
+            ErrorCode::InvalidInitialAlpha => 1,
+            ErrorCode::InvalidSafeRange => 2,
+            ErrorCode::InvalidRoundBudgetFraction => 3,
+            ErrorCode::AgentIndexOutOfRange => 4,
+            ErrorCode::ItemIndexOutOfRange => 5,
+            ErrorCode::InvalidBudget => 6,
+            ErrorCode::InvalidBid => 7,
+            ErrorCode::BidExceedsBudget => 8,
+            ErrorCode::ValueOutOfRange => 9,
+            ErrorCode::InvalidCapacity => 10,
+            ErrorCode::InvalidReservePrice => 11,
+            ErrorCode::ShapeMismatch => 12,
+            ErrorCode::InvalidIndptr => 13,
+            ErrorCode::ConflictingOptions => 14,
+            ErrorCode::NotAPermutation => 15,
+            ErrorCode::ItemAssignedTwice => 16,
+            ErrorCode::ItemOwnerMismatch => 17,
+            ErrorCode::NegativeSpend => 18,
+            ErrorCode::PrimalExceedsDual => 19,
+            ErrorCode::CapacityExceeded => 20,
+            ErrorCode::MissingBid => 21,
+            ErrorCode::TooManyItems => 22,
+            ErrorCode::OverCapacity => 23,
+            ErrorCode::ConsumptionDrift => 24,
+            ErrorCode::PrimalMismatch => 25,
+            ErrorCode::MemoryLimitExceeded => 26,
+            ErrorCode::TooLargeForExact => 27,
+            ErrorCode::UnsupportedByExact => 28,
+            ErrorCode::InvalidChunkSize => 29,
+            ErrorCode::AlreadySolved => 30,
+            ErrorCode::InvalidSize => 31,
+            ErrorCode::InvalidAgentCapacity => 32,
+            ErrorCode::InvalidRate => 33,
+            ErrorCode::Io => 34,
+            ErrorCode::MalformedInput => 35,
+            ErrorCode::DuplicateBid => 36,
+            ErrorCode::BadMagic => 37,
+            ErrorCode::UnsupportedFormatVersion => 38,
+            ErrorCode::InvalidFormat => 39,
+            ErrorCode::UnknownSolver => 40,
+        }
+    }
+
+    #[test]
+    fn all_in_order() {
+        for (position_in_all, &code) in ALL.iter().enumerate() {
+            assert_eq!(position(code), position_in_all, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn unique() {
+        let codes: HashSet<i32> = ALL.iter().map(|code| code.value()).collect();
+        assert_eq!(codes.len(), ALL.len());
+
+        let descriptions: HashSet<&str> = ALL.iter().map(|code| code.description()).collect();
+        assert_eq!(descriptions.len(), ALL.len());
+
+        let catalog = catalog();
+        assert!(catalog.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn stable() {
+        assert_eq!(ErrorCode::InvalidEpsilon.value(), 10);
+        // the description names the same range as the error it catalogs
+        let message = BuildError::InvalidEpsilon { epsilon: 2.0 }.to_string();
+        assert!(message.ends_with(ErrorCode::InvalidEpsilon.description().trim_start_matches("epsilon")), "{}", message);
+        assert_eq!(ErrorCode::AgentIndexOutOfRange.value(), 20);
+        assert_eq!(SolutionError::ItemAssignedTwice { item_id: 0 }.code(), ErrorCode::ItemAssignedTwice);
+        assert_eq!(SolutionError::PrimalExceedsDual.code().value(), 43);
+    }
+
+    #[test]
+    fn every_error() {
+        assert_eq!(VerifyError::MissingBid { agent_id: 0, item_id: 1 }.code().value(), 45);
+        assert_eq!(VerifyError::DuplicateItem { item_id: 0, agent_ids: (0, 1) }.code(), ErrorCode::ItemAssignedTwice);
+        let csv = CsvError {
+            line: 3,
+            message: String::from("expected 3 fields"),
+        };
+        assert_eq!(csv.code().value(), 81);
+        assert_eq!(ParseError::Malformed(csv).code(), ErrorCode::MalformedInput);
+        let error = MbaError::InvalidBid {
+            agent_id: 0,
+            item_id: 0,
+            bid: -1.0,
+        };
+        assert_eq!(ParseError::Rejected { line: 2, error }.code(), ErrorCode::InvalidBid);
+        assert_eq!(FormatError::Invalid(String::new()).code().value(), 85);
+        assert_eq!(SpecError::UnknownSolver { name: String::from("simplex") }.code().value(), 90);
+        assert_eq!(SpecError::Build(BuildError::InvalidEpsilon { epsilon: 2.0 }).code(), ErrorCode::InvalidEpsilon);
+    }
+}
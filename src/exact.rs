@@ -0,0 +1,178 @@
+// exact maximum budgeted allocation by branch and bound, for small instances
+//
+// items with a single bidder go to it up front; the others are branched on one by one,
+// each subtree cut when its value plus the largest bids of the remaining items cannot beat the best assignment so far
+
+// solve gives up when the product over items of the number of bidders exceeds this
+pub const MAX_ASSIGNMENTS: u64 = 1 << 22;
+
+// (optimum, item_owner) for bids[agent_id][item_id] (0 for no bid), None above MAX_ASSIGNMENTS;
+// every item with a bid is assigned
+pub fn solve(budgets: &[f64], bids: &[Vec<f64>]) -> Option<(f64, Vec<Option<usize>>)> {
+    solve_with_limit(budgets, bids, MAX_ASSIGNMENTS)
+}
+
+// solve giving up above max_assignments instead of MAX_ASSIGNMENTS
+pub fn solve_with_limit(budgets: &[f64], bids: &[Vec<f64>], max_assignments: u64) -> Option<(f64, Vec<Option<usize>>)> {
+    let num_items = bids.first().map_or(0, |row| row.len());
+    let bidders: Vec<Vec<usize>> = (0..num_items)
+        .map(|item_id| {
+            let mut agent_ids: Vec<usize> = (0..budgets.len()).filter(|&agent_id| bids[agent_id][item_id] > 0.0).collect();
+            agent_ids.sort_by(|&a, &b| bids[b][item_id].total_cmp(&bids[a][item_id]));
+            agent_ids
+        })
+        .collect();
+    if !fits_within(&bidders, max_assignments) {
+        return None;
+    }
+
+    let mut search = Search {
+        budgets,
+        bids,
+        order: (0..num_items).filter(|&item_id| bidders[item_id].len() > 1).collect(),
+        remaining: Vec::new(),
+        spends: vec![0.0; budgets.len()],
+        item_owner: vec![None; num_items],
+        best: None,
+        bidders,
+    };
+
+    let mut value = 0.0;
+    for item_id in 0..num_items {
+        if let [agent_id] = search.bidders[item_id][..] {
+            value += search.assign(item_id, agent_id);
+        }
+    }
+
+    search.remaining = vec![0.0; search.order.len() + 1];
+    for k in (0..search.order.len()).rev() {
+        let item_id = search.order[k];
+        let largest = search.bidders[item_id]
+            .iter()
+            .map(|&agent_id| bids[agent_id][item_id].min(budgets[agent_id]))
+            .fold(0.0, f64::max);
+        search.remaining[k] = search.remaining[k + 1] + largest;
+    }
+
+    search.branch(0, value);
+    search.best
+}
+
+// whether the product over items of the number of bidders is at most MAX_ASSIGNMENTS
+pub fn fits(bidders: &[Vec<usize>]) -> bool {
+    fits_within(bidders, MAX_ASSIGNMENTS)
+}
+
+// fits for num_items items that every one of num_agents agents bids on, without building the bidders
+pub fn fits_shape(num_agents: usize, num_items: usize) -> bool {
+    if num_agents <= 1 {
+        return true;
+    }
+    let mut num_assignments: u64 = 1;
+    for _ in 0..num_items {
+        match num_assignments.checked_mul(num_agents as u64) {
+            Some(n) if n <= MAX_ASSIGNMENTS => num_assignments = n,
+            _ => return false,
+        }
+    }
+    true
+}
+
+pub fn fits_within(bidders: &[Vec<usize>], max_assignments: u64) -> bool {
+    let mut num_assignments: u64 = 1;
+    for agent_ids in bidders.iter().filter(|agent_ids| agent_ids.len() > 1) {
+        match num_assignments.checked_mul(agent_ids.len() as u64) {
+            Some(n) if n <= max_assignments => num_assignments = n,
+            _ => return false,
+        }
+    }
+    true
+}
+
+struct Search<'a> {
+    budgets: &'a [f64],
+    bids: &'a [Vec<f64>],
+    bidders: Vec<Vec<usize>>, // bidders[item_id] = [agent_id, ...] by bid descending
+    order: Vec<usize>,        // items with more than one bidder, in branching order
+    remaining: Vec<f64>,      // remaining[k] = sum over order[k..] of the largest bid capped by the budget
+    spends: Vec<f64>,
+    item_owner: Vec<Option<usize>>,
+    best: Option<(f64, Vec<Option<usize>>)>,
+}
+
+impl Search<'_> {
+    // gives item_id to agent_id, returns the increase of the objective
+    fn assign(&mut self, item_id: usize, agent_id: usize) -> f64 {
+        let budget = self.budgets[agent_id];
+        let before = self.spends[agent_id].min(budget);
+        self.spends[agent_id] += self.bids[agent_id][item_id];
+        self.item_owner[item_id] = Some(agent_id);
+        self.spends[agent_id].min(budget) - before
+    }
+
+    fn branch(&mut self, k: usize, value: f64) {
+        if self.best.as_ref().is_some_and(|(best, _)| value + self.remaining[k] <= *best) {
+            return;
+        }
+        if k == self.order.len() {
+            self.best = Some((value, self.item_owner.clone()));
+            return;
+        }
+
+        let item_id = self.order[k];
+        for i in 0..self.bidders[item_id].len() {
+            let agent_id = self.bidders[item_id][i];
+            let gain = self.assign(item_id, agent_id);
+            self.branch(k + 1, value + gain);
+            self.spends[agent_id] -= self.bids[agent_id][item_id];
+        }
+        self.item_owner[item_id] = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fits, fits_shape, solve, solve_with_limit, MAX_ASSIGNMENTS};
+
+    #[test]
+    fn sample() {
+        let budgets = [300.0, 400.0];
+        let bids = vec![vec![200.0, 200.0, 100.0, 0.0], vec![100.0, 100.0, 200.0, 0.0]];
+        let (value, item_owner) = solve(&budgets, &bids).unwrap();
+        assert_eq!(value, 500.0);
+        assert_eq!(item_owner[3], None);
+        assert_eq!(item_owner[2], Some(1));
+    }
+
+    #[test]
+    fn budget_binds() {
+        // giving both items to agent 0 wastes 5 of the bids
+        let budgets = [10.0, 4.0];
+        let bids = vec![vec![8.0, 7.0], vec![4.0, 4.0]];
+        let (value, item_owner) = solve(&budgets, &bids).unwrap();
+        assert_eq!(value, 12.0);
+        assert_eq!(item_owner, vec![Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn too_large() {
+        let num_items = (MAX_ASSIGNMENTS.ilog2() + 1) as usize;
+        let budgets = [1.0, 1.0];
+        let bids = vec![vec![1.0; num_items], vec![1.0; num_items]];
+        assert!(solve(&budgets, &bids).is_none());
+
+        // 2^3 assignments
+        let bids = vec![vec![1.0; 3], vec![1.0; 3]];
+        assert!(solve_with_limit(&budgets, &bids, 7).is_none());
+        assert_eq!(solve_with_limit(&budgets, &bids, 8).unwrap().0, 2.0);
+    }
+
+    #[test]
+    fn shape() {
+        let num_items = MAX_ASSIGNMENTS.ilog2() as usize;
+        for (num_agents, num_items) in [(2, num_items), (2, num_items + 1), (3, 13), (3, 14), (1, 1000), (1000, 0)] {
+            assert_eq!(fits_shape(num_agents, num_items), fits(&vec![(0..num_agents).collect(); num_items]), "{} {}", num_agents, num_items);
+        }
+        assert!(!fits_shape(usize::MAX, usize::MAX));
+    }
+}
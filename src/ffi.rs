@@ -0,0 +1,213 @@
+// c abi for linking the solver into other languages, declared in include/mba.h (regenerate with
+// `cbindgen --config cbindgen.toml --output include/mba.h`):
+//
+//   MbaSolver *solver = mba_new(2, 3, 0.01);
+//   mba_set_budget(solver, 0, 10.0);
+//   mba_set_bid(solver, 0, 1, 4.0);
+//   mba_solve(solver);
+//   size_t items[8];
+//   ptrdiff_t len = mba_assignment_items(solver, 0, items, 8);
+//   mba_free(solver);
+//
+// functions returning int32_t return MBA_OK or a status: MBA_NULL_POINTER, MBA_PANIC or the ErrorCode of an MbaError.
+// functions returning ptrdiff_t return a count, or minus a status. no panic unwinds into the caller.
+// every solver pointer must be null or come from mba_new and not be freed yet. functions taking a const solver only read
+// it and may run at the same time; one taking a mutable solver must not overlap any other call on it
+#![allow(clippy::missing_safety_doc)]
+
+use crate::primal_dual::{MbaError, PrimalDual};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+pub const MBA_OK: i32 = 0;
+pub const MBA_NULL_POINTER: i32 = 1;
+pub const MBA_PANIC: i32 = 2;
+
+// a solver owned by the caller, opaque in c
+pub struct MbaSolver {
+    solver: PrimalDual,
+}
+
+// runs f on the solver, failing with MBA_NULL_POINTER for a null solver, MBA_PANIC for a panic and the code of an error
+unsafe fn with_solver<R>(solver: *mut MbaSolver, f: impl FnOnce(&mut PrimalDual) -> Result<R, MbaError>) -> Result<R, i32> {
+    let Some(solver) = solver.as_mut() else {
+        return Err(MBA_NULL_POINTER);
+    };
+    match catch_unwind(AssertUnwindSafe(|| f(&mut solver.solver))) {
+        Ok(result) => result.map_err(|e| e.code().value()),
+        Err(_) => Err(MBA_PANIC),
+    }
+}
+
+// with_solver for the functions taking a const solver, which only read it
+unsafe fn with_solver_ref<R>(solver: *const MbaSolver, f: impl FnOnce(&PrimalDual) -> Result<R, MbaError>) -> Result<R, i32> {
+    let Some(solver) = solver.as_ref() else {
+        return Err(MBA_NULL_POINTER);
+    };
+    match catch_unwind(AssertUnwindSafe(|| f(&solver.solver))) {
+        Ok(result) => result.map_err(|e| e.code().value()),
+        Err(_) => Err(MBA_PANIC),
+    }
+}
+
+fn status(result: Result<(), i32>) -> i32 {
+    result.err().unwrap_or(MBA_OK)
+}
+
+fn count(result: Result<usize, i32>) -> isize {
+    match result {
+        Ok(count) => count as isize,
+        Err(status) => -(status as isize),
+    }
+}
+
+fn check_agent(solver: &PrimalDual, agent_id: usize) -> Result<(), MbaError> {
+    if agent_id >= solver.num_agents() {
+        return Err(MbaError::AgentIndexOutOfRange {
+            agent_id,
+            num_agents: solver.num_agents(),
+        });
+    }
+    Ok(())
+}
+
+// null for an invalid epsilon or a solver too large to build
+#[no_mangle]
+pub extern "C" fn mba_new(num_agents: usize, num_items: usize, epsilon: f64) -> *mut MbaSolver {
+    match catch_unwind(|| PrimalDual::try_new(num_agents, num_items, epsilon)) {
+        Ok(Ok(solver)) => Box::into_raw(Box::new(MbaSolver { solver })),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+// frees a solver of mba_new; null is ignored
+#[no_mangle]
+pub unsafe extern "C" fn mba_free(solver: *mut MbaSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mba_set_budget(solver: *mut MbaSolver, agent_id: usize, budget: f64) -> i32 {
+    status(with_solver(solver, |solver| solver.try_set_budget(agent_id, budget)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mba_set_bid(solver: *mut MbaSolver, agent_id: usize, item_id: usize, bid: f64) -> i32 {
+    status(with_solver(solver, |solver| solver.try_set_bid(agent_id, item_id, bid)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mba_solve(solver: *mut MbaSolver) -> i32 {
+    status(with_solver(solver, |solver| {
+        solver.solve();
+        Ok(())
+    }))
+}
+
+// NaN for a null solver
+#[no_mangle]
+pub unsafe extern "C" fn mba_primal_value(solver: *const MbaSolver) -> f64 {
+    with_solver_ref(solver, |solver| Ok(solver.get_primal_objective_value())).unwrap_or(f64::NAN)
+}
+
+// number of items assigned to agent_id
+#[no_mangle]
+pub unsafe extern "C" fn mba_assignment_len(solver: *const MbaSolver, agent_id: usize) -> isize {
+    count(with_solver_ref(solver, |solver| {
+        check_agent(solver, agent_id)?;
+        Ok(solver.get_assignment()[agent_id].len())
+    }))
+}
+
+// writes the first capacity items of agent_id to items and returns the number of items of agent_id, which is more than
+// capacity when they did not all fit. items may be null when capacity is 0
+#[no_mangle]
+pub unsafe extern "C" fn mba_assignment_items(solver: *const MbaSolver, agent_id: usize, items: *mut usize, capacity: usize) -> isize {
+    if items.is_null() && capacity > 0 {
+        return -(MBA_NULL_POINTER as isize);
+    }
+    count(with_solver_ref(solver, |solver| {
+        check_agent(solver, agent_id)?;
+        let item_ids = &solver.get_assignment()[agent_id];
+        for (i, &item_id) in item_ids.iter().take(capacity).enumerate() {
+            *items.add(i) = item_id;
+        }
+        Ok(item_ids.len())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_code::ErrorCode;
+    use std::ptr;
+
+    #[test]
+    fn solve() {
+        unsafe {
+            let solver = mba_new(2, 3, 0.01);
+            assert!(!solver.is_null());
+            assert_eq!(mba_set_budget(solver, 0, 10.0), MBA_OK);
+            assert_eq!(mba_set_budget(solver, 1, 10.0), MBA_OK);
+            assert_eq!(mba_set_bid(solver, 0, 0, 4.0), MBA_OK);
+            assert_eq!(mba_set_bid(solver, 0, 2, 3.0), MBA_OK);
+            assert_eq!(mba_set_bid(solver, 1, 1, 2.0), MBA_OK);
+            assert_eq!(mba_solve(solver), MBA_OK);
+            assert_eq!(mba_primal_value(solver), 9.0);
+
+            assert_eq!(mba_assignment_len(solver, 0), 2);
+            let mut items = [usize::MAX; 4];
+            assert_eq!(mba_assignment_items(solver, 0, items.as_mut_ptr(), items.len()), 2);
+            assert_eq!(items, [0, 2, usize::MAX, usize::MAX]);
+            // truncated to the capacity
+            let mut items = [usize::MAX; 1];
+            assert_eq!(mba_assignment_items(solver, 0, items.as_mut_ptr(), 1), 2);
+            assert_eq!(items, [0]);
+            assert_eq!(mba_assignment_items(solver, 1, ptr::null_mut(), 0), 1);
+            mba_free(solver);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        unsafe {
+            assert!(mba_new(1, 1, 1.5).is_null());
+            let solver = mba_new(1, 2, 0.1);
+            assert_eq!(mba_set_budget(solver, 1, 1.0), ErrorCode::AgentIndexOutOfRange.value());
+            assert_eq!(mba_set_budget(solver, 0, -1.0), ErrorCode::InvalidBudget.value());
+            assert_eq!(mba_set_budget(solver, 0, 5.0), MBA_OK);
+            assert_eq!(mba_set_bid(solver, 0, 2, 1.0), ErrorCode::ItemIndexOutOfRange.value());
+            assert_eq!(mba_set_bid(solver, 0, 0, f64::NAN), ErrorCode::InvalidBid.value());
+            assert_eq!(mba_assignment_len(solver, 1), -(ErrorCode::AgentIndexOutOfRange.value() as isize));
+            assert_eq!(mba_assignment_items(solver, 0, ptr::null_mut(), 1), -(MBA_NULL_POINTER as isize));
+            mba_free(solver);
+        }
+    }
+
+    #[test]
+    fn null_solver() {
+        unsafe {
+            let solver = ptr::null_mut();
+            assert_eq!(mba_set_budget(solver, 0, 1.0), MBA_NULL_POINTER);
+            assert_eq!(mba_set_bid(solver, 0, 0, 1.0), MBA_NULL_POINTER);
+            assert_eq!(mba_solve(solver), MBA_NULL_POINTER);
+            assert!(mba_primal_value(solver).is_nan());
+            assert_eq!(mba_assignment_len(solver, 0), -(MBA_NULL_POINTER as isize));
+            assert_eq!(mba_assignment_items(solver, 0, ptr::null_mut(), 0), -(MBA_NULL_POINTER as isize));
+            mba_free(solver);
+        }
+    }
+
+    #[test]
+    fn panic() {
+        unsafe {
+            let solver = mba_new(1, 1, 0.1);
+            let result = with_solver(solver, |_| -> Result<(), MbaError> { panic!("inside the solver") });
+            assert_eq!(result, Err(MBA_PANIC));
+            // the solver is still usable
+            assert_eq!(mba_set_budget(solver, 0, 1.0), MBA_OK);
+            mba_free(solver);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+// seeded random instances for benchmarks and property tests
+//
+// every pair (agent, item) carries a bid with probability density. the bids of an agent are drawn by skipping ahead
+// a geometric number of items, so a sparse instance costs O(num_agents + bids) rather than O(num_agents * num_items)
+use crate::instance::Instance;
+use crate::rng::{Rng, RngConfig};
+
+// how the value of a bid is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BidModel {
+    // uniform in bid_range
+    #[default]
+    Uniform,
+    // every item has a quality uniform in [0, 1); a bid is low + (high - low) * ((1 - noise) * quality + noise * u)
+    // with u uniform in [0, 1) per bid, so noise 0 gives every bidder the same bid on an item and noise 1 is Uniform
+    Correlated {
+        noise: f64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeneratorConfig {
+    pub num_agents: usize,
+    pub num_items: usize,
+    pub density: f64,             // probability that an agent bids on an item, in [0, 1]
+    pub budget_range: (f64, f64), // budgets are uniform in [low, high)
+    pub bid_range: (f64, f64),    // low must be positive
+    pub seed: u64,
+    pub bid_model: BidModel,
+    pub max_beta: Option<f64>, // caps every bid at max_beta * budget, so beta of the instance is at most max_beta
+}
+
+impl GeneratorConfig {
+    pub fn new(num_agents: usize, num_items: usize, density: f64, budget_range: (f64, f64), bid_range: (f64, f64), seed: u64) -> Self {
+        GeneratorConfig {
+            num_agents,
+            num_items,
+            density,
+            budget_range,
+            bid_range,
+            seed,
+            bid_model: BidModel::default(),
+            max_beta: None,
+        }
+    }
+}
+
+// uniform bids without a beta cap
+pub fn generate(num_agents: usize, num_items: usize, density: f64, budget_range: (f64, f64), bid_range: (f64, f64), seed: u64) -> Instance {
+    generate_with(&GeneratorConfig::new(num_agents, num_items, density, budget_range, bid_range, seed))
+}
+
+// bids come agent by agent in increasing item order; a bid capped to 0 by a zero budget is left out
+pub fn generate_with(config: &GeneratorConfig) -> Instance {
+    debug_assert!((0.0..=1.0).contains(&config.density));
+    debug_assert!(0.0 <= config.budget_range.0 && config.budget_range.0 <= config.budget_range.1);
+    debug_assert!(0.0 < config.bid_range.0 && config.bid_range.0 <= config.bid_range.1);
+    debug_assert!(config.max_beta.is_none_or(|max_beta| max_beta > 0.0));
+
+    let rng_config = RngConfig::new(config.seed);
+    let mut budget_rng = rng_config.stream("generator/budgets");
+    let mut quality_rng = rng_config.stream("generator/qualities");
+    let mut item_rng = rng_config.stream("generator/items");
+    let mut bid_rng = rng_config.stream("generator/bids");
+
+    let mut instance = Instance::new(config.num_agents, config.num_items);
+    for budget in &mut instance.budgets {
+        *budget = budget_rng.range_f64(config.budget_range.0, config.budget_range.1);
+    }
+    let qualities: Vec<f64> = match config.bid_model {
+        BidModel::Uniform => Vec::new(),
+        BidModel::Correlated { .. } => (0..config.num_items).map(|_| quality_rng.next_f64()).collect(),
+    };
+
+    let (low, high) = config.bid_range;
+    for agent_id in 0..config.num_agents {
+        let budget = instance.budgets[agent_id];
+        let mut item_id = next_item(&mut item_rng, config.density, 0);
+        while item_id < config.num_items {
+            let share = match config.bid_model {
+                BidModel::Uniform => bid_rng.next_f64(),
+                BidModel::Correlated { noise } => (1.0 - noise) * qualities[item_id] + noise * bid_rng.next_f64(),
+            };
+            let mut bid = low + (high - low) * share;
+            if let Some(max_beta) = config.max_beta {
+                bid = bid.min(max_beta * budget);
+            }
+            if bid > 0.0 {
+                instance.add_bid(agent_id, item_id, bid);
+            }
+            item_id = next_item(&mut item_rng, config.density, item_id + 1);
+        }
+    }
+    instance
+}
+
+// first item at or after from that gets a bid; the gap is geometric with success probability density
+fn next_item(rng: &mut Rng, density: f64, from: usize) -> usize {
+    if density >= 1.0 {
+        return from;
+    }
+    if density <= 0.0 {
+        return usize::MAX;
+    }
+    let gap = ((1.0 - rng.next_f64()).ln() / (1.0 - density).ln()).floor();
+    if gap >= usize::MAX as f64 {
+        return usize::MAX;
+    }
+    from.saturating_add(gap as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, generate_with, BidModel, GeneratorConfig};
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn reproducible() {
+        let first = generate(20, 100, 0.3, (50.0, 100.0), (1.0, 10.0), 7);
+        assert_eq!(first, generate(20, 100, 0.3, (50.0, 100.0), (1.0, 10.0), 7));
+        assert_ne!(first, generate(20, 100, 0.3, (50.0, 100.0), (1.0, 10.0), 8));
+    }
+
+    #[test]
+    fn ranges_and_density() {
+        let instance = generate(200, 500, 0.05, (50.0, 100.0), (1.0, 10.0), 1);
+        assert!(instance.budgets.iter().all(|budget| (50.0..100.0).contains(budget)));
+        assert!(instance.bids.iter().all(|(_, _, bid)| (1.0..10.0).contains(bid)));
+
+        let expected = 200.0 * 500.0 * 0.05;
+        assert!((instance.bids.len() as f64 - expected).abs() < 0.05 * expected, "{}", instance.bids.len());
+        // at most one bid per pair
+        let mut pairs: Vec<_> = instance.bids.iter().map(|&(agent_id, item_id, _)| (agent_id, item_id)).collect();
+        pairs.dedup();
+        assert_eq!(pairs.len(), instance.bids.len());
+
+        assert_eq!(generate(3, 4, 1.0, (1.0, 1.0), (1.0, 2.0), 1).bids.len(), 12);
+        assert!(generate(3, 4, 0.0, (1.0, 1.0), (1.0, 2.0), 1).bids.is_empty());
+    }
+
+    #[test]
+    fn large_sparse() {
+        let instance = generate(10_000, 1_000_000, 1e-5, (100.0, 1000.0), (1.0, 50.0), 3);
+        let expected = 10_000.0 * 1_000_000.0 * 1e-5;
+        assert!((instance.bids.len() as f64 - expected).abs() < 0.05 * expected, "{}", instance.bids.len());
+    }
+
+    #[test]
+    fn max_beta() {
+        let config = GeneratorConfig {
+            max_beta: Some(0.2),
+            ..GeneratorConfig::new(30, 60, 0.5, (10.0, 20.0), (1.0, 15.0), 5)
+        };
+        let instance = generate_with(&config);
+        let beta = instance.bids.iter().map(|&(agent_id, _, bid)| bid / instance.budgets[agent_id]).fold(0.0, f64::max);
+        assert!(beta <= 0.2 && beta > 0.19, "{}", beta);
+    }
+
+    #[test]
+    fn correlated_bids() {
+        let config = GeneratorConfig {
+            bid_model: BidModel::Correlated { noise: 0.0 },
+            ..GeneratorConfig::new(10, 50, 1.0, (100.0, 200.0), (1.0, 11.0), 9)
+        };
+        let instance = generate_with(&config);
+        // without noise every agent bids the same on an item
+        for item_id in 0..50 {
+            let bids: Vec<f64> = instance.bids.iter().filter(|bid| bid.1 == item_id).map(|bid| bid.2).collect();
+            assert!(bids.iter().all(|&bid| bid == bids[0]));
+        }
+
+        let noisy = generate_with(&GeneratorConfig {
+            bid_model: BidModel::Correlated { noise: 0.3 },
+            ..config
+        });
+        for &(agent_id, item_id, bid) in &noisy.bids {
+            let base = instance.bids[agent_id * 50 + item_id].2;
+            assert!((bid - base).abs() <= 0.3 * 10.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn primal_bounded_by_dual() {
+        for seed in 0..1000 {
+            let config = GeneratorConfig {
+                max_beta: Some([0.1, 0.5, 1.0][seed as usize % 3]),
+                bid_model: if seed % 2 == 0 { BidModel::Uniform } else { BidModel::Correlated { noise: 0.5 } },
+                ..GeneratorConfig::new(1 + seed as usize % 7, 1 + seed as usize % 13, 0.6, (5.0, 50.0), (1.0, 20.0), seed)
+            };
+            let mut solver = PrimalDual::from_instance(&generate_with(&config), 0.05);
+            solver.solve();
+            let (primal, dual) = (solver.get_primal_objective_value(), solver.get_dual_objective_value());
+            assert!(primal >= dual * solver.get_approximation_ratio() - 1e-9, "seed {}: {} {}", seed, primal, dual);
+        }
+    }
+}
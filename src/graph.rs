@@ -0,0 +1,87 @@
+// bipartite graphs for petgraph: node agent_id is the agent, node num_agents + item_id the item, and every edge joins an
+// agent to an item with the bid as weight, e.g. petgraph::algo::connected_components counts the sub-markets of decompose
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+use petgraph::graph::{NodeIndex, UnGraph};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Node {
+    Agent { agent_id: usize, budget: f64 },
+    Item { item_id: usize },
+}
+
+fn nodes(budgets: impl Iterator<Item = f64>, num_items: usize, num_edges: usize) -> UnGraph<Node, f64> {
+    let budgets: Vec<f64> = budgets.collect();
+    let mut graph = UnGraph::with_capacity(budgets.len() + num_items, num_edges);
+    for (agent_id, budget) in budgets.into_iter().enumerate() {
+        graph.add_node(Node::Agent { agent_id, budget });
+    }
+    for item_id in 0..num_items {
+        graph.add_node(Node::Item { item_id });
+    }
+    graph
+}
+
+impl Instance {
+    // an edge per bid, in the order of bids
+    pub fn to_graph(&self) -> UnGraph<Node, f64> {
+        let mut graph = nodes(self.budgets.iter().copied(), self.num_items, self.bids.len());
+        for &(agent_id, item_id, bid) in &self.bids {
+            graph.add_edge(NodeIndex::new(agent_id), NodeIndex::new(self.num_agents + item_id), bid);
+        }
+        graph
+    }
+}
+
+impl Assignment {
+    // an edge per assigned item, in the order of iter; the budgets are those of the solve
+    pub fn to_graph(&self) -> UnGraph<Node, f64> {
+        let num_agents = self.num_agents();
+        let mut graph = nodes((0..num_agents).map(|agent_id| self.budget(agent_id)), self.num_items(), self.iter().count());
+        for (agent_id, item_id, bid) in self {
+            graph.add_edge(NodeIndex::new(agent_id), NodeIndex::new(num_agents + item_id), bid);
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Node;
+    use crate::decompose::decompose;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+    use petgraph::algo::connected_components;
+    use petgraph::graph::NodeIndex;
+
+    #[test]
+    fn instance_and_assignment() {
+        let instance = generate(8, 60, 0.03, (5.0, 20.0), (1.0, 6.0), 301);
+        let graph = instance.to_graph();
+        assert_eq!(graph.node_count(), 68);
+        assert_eq!(graph.edge_count(), instance.bids.len());
+        assert_eq!(
+            graph[NodeIndex::new(3)],
+            Node::Agent {
+                agent_id: 3,
+                budget: instance.budgets[3]
+            }
+        );
+        assert_eq!(graph[NodeIndex::new(8)], Node::Item { item_id: 0 });
+        assert_eq!(connected_components(&graph), decompose(&instance).len());
+
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        solver.solve();
+        let assignment = solver.assignment();
+        let graph = assignment.to_graph();
+        assert_eq!(graph.edge_count(), assignment.iter().count());
+        let total: f64 = graph.edge_weights().sum();
+        assert!((total - (0..8).map(|agent_id| assignment.spend(agent_id)).sum::<f64>()).abs() <= 1e-9);
+        for edge in graph.raw_edges() {
+            let (Node::Agent { agent_id, .. }, Node::Item { item_id }) = (graph[edge.source()], graph[edge.target()]) else {
+                panic!("edge from an item");
+            };
+            assert_eq!(assignment.agent_of(item_id), Some(agent_id));
+        }
+    }
+}
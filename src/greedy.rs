@@ -0,0 +1,236 @@
+// greedy baseline for maximum budgeted allocation
+//
+// items in decreasing order of their largest bid, each to the bidder with the largest marginal gain min(bid, remaining budget),
+// ties to the larger bid and then to the lower agent id.
+// the objective is submodular, so this is a 1/2-approximation whatever the item order; the order only helps in practice.
+// a bid above the budget of its agent is treated as PrimalDual treats it under the same OverbidPolicy, so that both solve
+// the same instance
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+use crate::primal_dual::{MbaError, OverbidPolicy};
+use crate::solution::Solution;
+
+pub struct Greedy {
+    num_agents: usize,
+    num_items: usize,
+    budgets: Vec<f64>,
+    item_bids: Vec<Vec<(usize, f64)>>, // item_bids[item_id] = [(agent_id, bid), ...] in insertion order
+    accepted: Vec<Vec<(usize, f64)>>,  // item_bids after the overbid policy, as of the last solve
+    overbid_policy: OverbidPolicy,
+    item_owner: Vec<Option<usize>>,
+    spends: Vec<f64>,
+}
+
+impl Greedy {
+    pub fn new(num_agents: usize, num_items: usize) -> Self {
+        Greedy {
+            num_agents,
+            num_items,
+            budgets: vec![0.0; num_agents],
+            item_bids: vec![Vec::new(); num_items],
+            accepted: vec![Vec::new(); num_items],
+            overbid_policy: OverbidPolicy::default(),
+            item_owner: vec![None; num_items],
+            spends: vec![0.0; num_agents],
+        }
+    }
+
+    // budgets and bids of instance, in the order of instance.bids
+    pub fn from_instance(instance: &Instance) -> Self {
+        let mut greedy = Greedy::new(instance.num_agents, instance.num_items);
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            greedy.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &instance.bids {
+            greedy.set_bid(agent_id, item_id, bid);
+        }
+        greedy
+    }
+
+    pub fn set_budget(&mut self, agent_id: usize, budget: f64) {
+        debug_assert!(budget >= 0.0);
+        self.budgets[agent_id] = budget;
+    }
+
+    // a later bid on the same pair replaces the earlier one
+    pub fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: f64) {
+        debug_assert!(agent_id < self.num_agents);
+        debug_assert!(bid > 0.0);
+        match self.item_bids[item_id].iter_mut().find(|(id, _)| *id == agent_id) {
+            Some((_, old)) => *old = bid,
+            None => self.item_bids[item_id].push((agent_id, bid)),
+        }
+    }
+
+    // applied when solve runs, to the budgets at that time; OverbidPolicy::Error makes solve panic
+    pub fn set_overbid_policy(&mut self, policy: OverbidPolicy) {
+        self.overbid_policy = policy;
+    }
+
+    pub fn solve(&mut self) {
+        self.item_owner.fill(None);
+        self.spends.fill(0.0);
+        self.accepted = (0..self.num_items).map(|item_id| self.accepted_bids(item_id)).collect();
+
+        let max_bid = |bids: &[(usize, f64)]| bids.iter().map(|&(_, bid)| bid).fold(0.0, f64::max);
+        let mut item_ids: Vec<usize> = (0..self.num_items).filter(|&item_id| !self.accepted[item_id].is_empty()).collect();
+        item_ids.sort_by(|&a, &b| max_bid(&self.accepted[b]).total_cmp(&max_bid(&self.accepted[a])).then(a.cmp(&b)));
+
+        for item_id in item_ids {
+            let gain = |agent_id: usize, bid: f64| bid.min((self.budgets[agent_id] - self.spends[agent_id]).max(0.0));
+            let &(agent_id, bid) = self.accepted[item_id]
+                .iter()
+                .max_by(|&&(a, a_bid), &&(b, b_bid)| gain(a, a_bid).total_cmp(&gain(b, b_bid)).then(a_bid.total_cmp(&b_bid)).then(b.cmp(&a)))
+                .unwrap();
+            self.item_owner[item_id] = Some(agent_id);
+            self.spends[agent_id] += bid;
+        }
+    }
+
+    // the bids on item_id above the budget of their agent rejected or clamped
+    fn accepted_bids(&self, item_id: usize) -> Vec<(usize, f64)> {
+        let mut bids = Vec::with_capacity(self.item_bids[item_id].len());
+        for &(agent_id, bid) in &self.item_bids[item_id] {
+            let budget = self.budgets[agent_id];
+            match self.overbid_policy {
+                _ if bid <= budget => bids.push((agent_id, bid)),
+                OverbidPolicy::Reject => {}
+                OverbidPolicy::Clamp if budget > 0.0 => bids.push((agent_id, budget)),
+                OverbidPolicy::Clamp => {}
+                OverbidPolicy::Error => panic!("{}", MbaError::BidExceedsBudget { agent_id, item_id, bid, budget }),
+            }
+        }
+        bids
+    }
+
+    pub fn get_primal_objective_value(&self) -> f64 {
+        self.spends.iter().zip(&self.budgets).map(|(spend, budget)| spend.min(*budget)).sum()
+    }
+
+    pub fn assignment(&self) -> Assignment {
+        let mut items = vec![Vec::new(); self.num_agents];
+        let mut bids = vec![Vec::new(); self.num_agents];
+        for (item_id, owner) in self.item_owner.iter().enumerate() {
+            if let Some(agent_id) = *owner {
+                let &(_, bid) = self.accepted[item_id].iter().find(|&&(id, _)| id == agent_id).unwrap();
+                items[agent_id].push(item_id);
+                bids[agent_id].push(bid);
+            }
+        }
+        Assignment::new(items, bids, self.num_items, self.budgets.clone())
+    }
+
+    // greedy has no dual, so dual is the trivial bound sum_a min(budget_a, sum of the accepted bids of a). epsilon 1/2 and
+    // beta 0 make get_approximation_ratio the 1/2 of greedy, which bounds the primal against the optimum, not the dual
+    pub fn extract_solution(&self) -> Solution {
+        let mut assignment = vec![Vec::new(); self.num_agents];
+        let mut volumes = vec![0.0; self.num_agents];
+        for (item_id, bids) in self.accepted.iter().enumerate() {
+            for &(agent_id, bid) in bids {
+                volumes[agent_id] += bid;
+            }
+            if let Some(agent_id) = self.item_owner[item_id] {
+                assignment[agent_id].push(item_id);
+            }
+        }
+
+        Solution {
+            assignment,
+            item_owner: self.item_owner.clone(),
+            spends: self.spends.clone(),
+            primal: self.get_primal_objective_value(),
+            dual: volumes.iter().zip(&self.budgets).map(|(volume, budget)| volume.min(*budget)).sum(),
+            epsilon: 0.5,
+            beta: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Greedy;
+    use crate::exact;
+    use crate::instance::Instance;
+    use crate::primal_dual::{OverbidPolicy, PrimalDual};
+    use crate::rng::RngConfig;
+
+    fn sample() -> Instance {
+        let mut instance = Instance::new(2, 3);
+        instance.budgets = vec![300.0, 400.0];
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            instance.add_bid(agent_id, item_id, bid);
+        }
+        instance
+    }
+
+    #[test]
+    fn same_instance_api() {
+        let instance = sample();
+        let mut greedy = Greedy::from_instance(&instance);
+        greedy.solve();
+        let mut primal_dual = PrimalDual::from_instance(&instance, 0.01);
+        primal_dual.solve();
+
+        assert_eq!(greedy.get_primal_objective_value(), 500.0);
+        assert_eq!(greedy.get_primal_objective_value(), primal_dual.get_primal_objective_value());
+        let solution = greedy.extract_solution();
+        assert!(solution.verify().is_ok());
+        assert_eq!(solution.item_owner, vec![Some(0), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn half_of_exact() {
+        let mut rng = RngConfig::new(257).stream("half_of_exact");
+        for _ in 0..200 {
+            let num_agents = rng.range_usize(1, 5);
+            let num_items = rng.range_usize(1, 10);
+            let mut instance = Instance::new(num_agents, num_items);
+            for agent_id in 0..num_agents {
+                instance.set_budget(agent_id, rng.range_f64(5.0, 50.0));
+                for item_id in 0..num_items {
+                    if rng.next_f64() < 0.6 {
+                        instance.add_bid(agent_id, item_id, rng.range_f64(1.0, 40.0));
+                    }
+                }
+            }
+            let mut bids = vec![vec![0.0; num_items]; num_agents];
+            for &(agent_id, item_id, bid) in &instance.bids {
+                bids[agent_id][item_id] = bid;
+            }
+
+            let (optimum, _) = exact::solve(&instance.budgets, &bids).unwrap();
+            // exact::solve caps the bids above the budget, as Clamp does
+            let mut greedy = Greedy::from_instance(&instance);
+            greedy.set_overbid_policy(OverbidPolicy::Clamp);
+            greedy.solve();
+            let value = greedy.get_primal_objective_value();
+            assert!(value <= optimum + 1e-9, "{:?}", instance);
+            assert!(value >= optimum / 2.0 - 1e-9, "{:?}", instance);
+            assert!(greedy.extract_solution().verify().is_ok());
+        }
+    }
+
+    #[test]
+    fn overbid_policy() {
+        let mut instance = Instance::new(2, 2);
+        instance.set_budget(0, 5.0);
+        instance.set_budget(1, 10.0);
+        instance.add_bid(0, 0, 8.0);
+        instance.add_bid(1, 0, 4.0);
+        instance.add_bid(1, 1, 3.0);
+
+        // the bid of 8 above the budget of 5 is ignored, as PrimalDual does by default
+        let mut greedy = Greedy::from_instance(&instance);
+        greedy.solve();
+        assert_eq!(greedy.extract_solution().item_owner, vec![Some(1), Some(1)]);
+        assert_eq!(greedy.get_primal_objective_value(), 7.0);
+
+        greedy.set_overbid_policy(OverbidPolicy::Clamp);
+        greedy.solve();
+        let solution = greedy.extract_solution();
+        assert_eq!(solution.item_owner, vec![Some(0), Some(1)]);
+        assert_eq!(greedy.get_primal_objective_value(), 8.0);
+        assert_eq!(solution.epsilon, 0.5);
+        assert_eq!(solution.get_approximation_ratio(), 0.5);
+    }
+}
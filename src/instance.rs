@@ -0,0 +1,275 @@
+use crate::greedy::Greedy;
+use std::collections::BTreeMap;
+
+// budgets and bids of a maximum budgeted allocation problem, independent of any solver
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Instance {
+    pub num_agents: usize,
+    pub num_items: usize,
+    pub budgets: Vec<f64>,              // budgets[agent_id] = budget
+    pub bids: Vec<(usize, usize, f64)>, // [(agent_id, item_id, bid), ...]
+}
+
+impl Instance {
+    pub fn new(num_agents: usize, num_items: usize) -> Self {
+        Instance {
+            num_agents,
+            num_items,
+            budgets: vec![0.0; num_agents],
+            bids: Vec::new(),
+        }
+    }
+
+    pub fn set_budget(&mut self, agent_id: usize, budget: f64) {
+        self.budgets[agent_id] = budget;
+    }
+
+    pub fn add_bid(&mut self, agent_id: usize, item_id: usize, bid: f64) {
+        debug_assert!(agent_id < self.num_agents && item_id < self.num_items);
+        self.bids.push((agent_id, item_id, bid));
+    }
+}
+
+// fix for a problem of the instance; impact is the change of the greedy objective once applied
+#[derive(Clone, Debug, PartialEq)]
+pub enum Repair {
+    // smallest budget that accepts every bid of agent
+    RaiseBudget { agent: usize, to: f64, impact: f64 },
+    LowerBid { agent: usize, item: usize, to: f64, impact: f64 },
+    // removes the item; the ids of later items shift down by one, so several repairs go through apply_all
+    DropItem { item: usize, impact: f64 },
+}
+
+impl Repair {
+    pub fn impact(&self) -> f64 {
+        match *self {
+            Repair::RaiseBudget { impact, .. } | Repair::LowerBid { impact, .. } | Repair::DropItem { impact, .. } => impact,
+        }
+    }
+}
+
+impl Instance {
+    // repairs for the bids the solver would reject:
+    // an item whose every bid exceeds its bidder's budget gets a budget raise for each of its bidders,
+    // a lowered bid for its largest bidder and a drop; an item without any bid gets a drop.
+    // the item ids are those of self, which apply_all keeps valid while applying several repairs
+    pub fn suggest_repairs(&self) -> Vec<Repair> {
+        let before = self.greedy_value();
+        let with_impact = |mut repair: Repair| {
+            let mut instance = self.clone();
+            apply(&mut instance, &repair);
+            let delta = instance.greedy_value() - before;
+            match &mut repair {
+                Repair::RaiseBudget { impact, .. } | Repair::LowerBid { impact, .. } | Repair::DropItem { impact, .. } => *impact = delta,
+            }
+            repair
+        };
+
+        let bid = self.bid_map();
+        let mut bidders = vec![Vec::new(); self.num_items];
+        for (&(item_id, agent_id), &value) in &bid {
+            bidders[item_id].push((agent_id, value));
+        }
+
+        let mut raised = vec![false; self.num_agents];
+        let mut repairs = Vec::new();
+        for (item_id, bids) in bidders.iter().enumerate() {
+            if bids.iter().any(|&(agent_id, value)| value <= self.budgets[agent_id]) {
+                continue;
+            }
+
+            for &(agent_id, _) in bids {
+                if !raised[agent_id] {
+                    raised[agent_id] = true;
+                    let to = bid.iter().filter(|(&(_, id), _)| id == agent_id).map(|(_, &value)| value).fold(0.0, f64::max);
+                    repairs.push(with_impact(Repair::RaiseBudget {
+                        agent: agent_id,
+                        to,
+                        impact: 0.0,
+                    }));
+                }
+            }
+            if let Some(&(agent_id, _)) = bids.iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+                repairs.push(with_impact(Repair::LowerBid {
+                    agent: agent_id,
+                    item: item_id,
+                    to: self.budgets[agent_id],
+                    impact: 0.0,
+                }));
+            }
+            repairs.push(with_impact(Repair::DropItem { item: item_id, impact: 0.0 }));
+        }
+        repairs
+    }
+
+    // bid_map[(item_id, agent_id)] = bid, a later bid on the same pair replaces the earlier one
+    fn bid_map(&self) -> BTreeMap<(usize, usize), f64> {
+        self.bids.iter().map(|&(agent_id, item_id, bid)| ((item_id, agent_id), bid)).collect()
+    }
+
+    // objective of greedy, which ignores bids above the budget as OverbidPolicy::Reject does
+    fn greedy_value(&self) -> f64 {
+        let mut greedy = Greedy::from_instance(self);
+        greedy.solve();
+        greedy.get_primal_objective_value()
+    }
+}
+
+pub fn apply(instance: &mut Instance, repair: &Repair) {
+    match *repair {
+        Repair::RaiseBudget { agent, to, .. } => instance.budgets[agent] = instance.budgets[agent].max(to),
+        Repair::LowerBid { agent, item, to, .. } => {
+            for bid in instance.bids.iter_mut().filter(|bid| bid.0 == agent && bid.1 == item) {
+                bid.2 = bid.2.min(to);
+            }
+        }
+        Repair::DropItem { item, .. } => {
+            instance.bids.retain(|bid| bid.1 != item);
+            for bid in instance.bids.iter_mut().filter(|bid| bid.1 > item) {
+                bid.1 -= 1;
+            }
+            instance.num_items -= 1;
+        }
+    }
+}
+
+// applies every repair of repairs, e.g. all of suggest_repairs, with the item ids they were suggested for: the drops go
+// last and from the highest item down, so that no drop shifts the item of another repair
+pub fn apply_all(instance: &mut Instance, repairs: &[Repair]) {
+    let mut dropped = Vec::new();
+    for repair in repairs {
+        match *repair {
+            Repair::DropItem { item, .. } => dropped.push(item),
+            _ => apply(instance, repair),
+        }
+    }
+    dropped.sort_unstable_by(|a, b| b.cmp(a));
+    dropped.dedup();
+    for item in dropped {
+        apply(instance, &Repair::DropItem { item, impact: 0.0 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, apply_all, Instance, Repair};
+    use crate::primal_dual::PrimalDual;
+
+    fn solve(instance: &Instance) -> f64 {
+        let mut solver = PrimalDual::new(instance.num_agents, instance.num_items, 0.01);
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &instance.bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+        solver.get_primal_objective_value()
+    }
+
+    // item 2 is only bid on above the budget of agent 1, item 3 has no bids
+    fn over_budget_instance() -> Instance {
+        let mut instance = Instance::new(2, 4);
+        instance.set_budget(0, 100.0);
+        instance.set_budget(1, 50.0);
+        instance.add_bid(0, 0, 40.0);
+        instance.add_bid(1, 1, 30.0);
+        instance.add_bid(1, 2, 80.0);
+        instance
+    }
+
+    #[test]
+    fn raise_budget() {
+        let instance = over_budget_instance();
+        let repairs = instance.suggest_repairs();
+        assert_eq!(repairs.len(), 4);
+
+        let raise = repairs.iter().find(|repair| matches!(repair, Repair::RaiseBudget { .. })).unwrap();
+        assert_eq!(
+            raise,
+            &Repair::RaiseBudget {
+                agent: 1,
+                to: 80.0,
+                impact: raise.impact()
+            }
+        );
+
+        let mut repaired = instance.clone();
+        apply(&mut repaired, raise);
+        let before = solve(&instance);
+        let after = solve(&repaired);
+        assert!(after > before);
+        assert!((after - before - raise.impact()).abs() < 1e-9);
+        assert_eq!(repaired.suggest_repairs(), vec![Repair::DropItem { item: 3, impact: 0.0 }]);
+    }
+
+    #[test]
+    fn lower_bid_and_drop() {
+        let instance = over_budget_instance();
+        let repairs = instance.suggest_repairs();
+
+        assert!(repairs.contains(&Repair::LowerBid {
+            agent: 1,
+            item: 2,
+            to: 50.0,
+            impact: 20.0
+        }));
+        assert!(repairs.contains(&Repair::DropItem { item: 2, impact: 0.0 }));
+
+        let mut dropped = instance.clone();
+        apply(&mut dropped, &Repair::DropItem { item: 2, impact: 0.0 });
+        assert_eq!(dropped.num_items, 3);
+        assert!(dropped.bids.iter().all(|&(_, item_id, _)| item_id < 3));
+        assert_eq!(solve(&dropped), solve(&instance));
+    }
+
+    #[test]
+    fn drop_several() {
+        // items 1 and 3 are only bid on above the budget of agent 1
+        let mut instance = Instance::new(2, 4);
+        instance.set_budget(0, 100.0);
+        instance.set_budget(1, 50.0);
+        instance.add_bid(0, 0, 40.0);
+        instance.add_bid(1, 1, 80.0);
+        instance.add_bid(0, 2, 30.0);
+        instance.add_bid(1, 3, 90.0);
+
+        let drops: Vec<Repair> = instance
+            .suggest_repairs()
+            .into_iter()
+            .filter(|repair| matches!(repair, Repair::DropItem { .. }))
+            .collect();
+        assert_eq!(drops.len(), 2);
+        let mut dropped = instance.clone();
+        apply_all(&mut dropped, &drops);
+        assert_eq!(dropped.num_items, 2);
+        assert_eq!(dropped.bids, vec![(0, 0, 40.0), (0, 1, 30.0)]);
+        assert!(dropped.suggest_repairs().is_empty());
+
+        // every suggestion at once: the budget raise makes the drops moot, but they still remove the right items
+        let mut repaired = instance.clone();
+        apply_all(&mut repaired, &instance.suggest_repairs());
+        let mut expected = dropped;
+        expected.set_budget(1, 90.0);
+        assert_eq!(repaired, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use crate::solution::Solution;
+
+        let instance = over_budget_instance();
+        let json = serde_json::to_string(&instance).unwrap();
+        let restored: Instance = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, instance);
+
+        let mut solver = PrimalDual::from_instance(&restored, 0.01);
+        solver.solve();
+        let solution = solver.extract_solution();
+        let restored: Solution = serde_json::from_str(&serde_json::to_string(&solution).unwrap()).unwrap();
+        assert_eq!(restored.primal, solve(&instance));
+        assert_eq!(restored.get_approximation_ratio(), solver.get_approximation_ratio());
+    }
+}
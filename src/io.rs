@@ -0,0 +1,195 @@
+// reading and writing the crate's file formats
+use crate::error_code::ErrorCode;
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+mod binary;
+mod export;
+mod load;
+mod text;
+
+pub use binary::{
+    detect_format, read_instance_binary, read_solution_binary, write_instance_binary, write_solution_binary, FormatError, FormatKind, INSTANCE_MAGIC, INSTANCE_VERSION,
+    SOLUTION_MAGIC, SOLUTION_VERSION,
+};
+pub use export::{write_assignment_csv, write_assignment_csv_with, ExportOptions};
+#[cfg(feature = "serde")]
+pub use export::{write_assignment_json, write_assignment_json_with};
+pub use load::{load_into, load_into_with, LoadOptions};
+pub use text::{read_instance, write_instance, ParseError};
+
+// the largest num_agents and num_items a reader accepts from the header of a file. the header comes before any data
+// backs it, so a corrupt or hostile one is rejected here instead of allocating for the counts it claims
+pub const MAX_COUNT: usize = 1 << 26;
+
+// delimiter and decimal separator of a csv file
+//
+// floats are always written in the shortest form that parses back to the same bits,
+// independent of the locale of the machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub decimal_comma: bool, // "1,5" instead of "1.5"; requires a delimiter other than ','
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            decimal_comma: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvError {
+    pub line: usize, // 1-based
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl CsvError {
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::MalformedInput
+    }
+}
+
+impl CsvOptions {
+    // ';' and decimal comma, as written by most european spreadsheets
+    pub fn european() -> Self {
+        CsvOptions {
+            delimiter: ';',
+            decimal_comma: true,
+        }
+    }
+
+    // european() if the line contains a ';', default() otherwise
+    pub fn detect(line: &str) -> Self {
+        if line.contains(';') {
+            CsvOptions::european()
+        } else {
+            CsvOptions::default()
+        }
+    }
+
+    // fields of line; with expected_fields, any other count is an error,
+    // which is what catches "1,5" read as two fields in a file that mixes the conventions
+    pub fn split<'a>(&self, line: &'a str, line_number: usize, expected_fields: Option<usize>) -> Result<Vec<&'a str>, CsvError> {
+        debug_assert!(!(self.decimal_comma && self.delimiter == ','));
+
+        let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split(self.delimiter).map(str::trim).collect();
+        match expected_fields {
+            Some(expected) if fields.len() != expected => Err(CsvError {
+                line: line_number,
+                message: format!("{} fields separated by '{}', expected {}", fields.len(), self.delimiter, expected),
+            }),
+            _ => Ok(fields),
+        }
+    }
+
+    pub fn parse_float(&self, field: &str, line_number: usize) -> Result<f64, CsvError> {
+        let error = |message: String| CsvError { line: line_number, message };
+
+        let normalized = if self.decimal_comma {
+            if field.contains('.') {
+                return Err(error(format!("'{}' uses a decimal point in a file with decimal commas", field)));
+            }
+            field.replace(',', ".")
+        } else {
+            if field.contains(',') {
+                return Err(error(format!("'{}' uses a decimal comma in a file with decimal points", field)));
+            }
+            field.to_string()
+        };
+        normalized.parse().map_err(|_| error(format!("'{}' is not a number", field)))
+    }
+
+    pub fn parse_index(&self, field: &str, line_number: usize) -> Result<usize, CsvError> {
+        field.parse().map_err(|_| CsvError {
+            line: line_number,
+            message: format!("'{}' is not an index", field),
+        })
+    }
+
+    pub fn format_float(&self, value: f64) -> String {
+        let text = format!("{:?}", value);
+        if self.decimal_comma {
+            text.replace('.', ",")
+        } else {
+            text
+        }
+    }
+
+    pub fn write_record<W: Write>(&self, writer: &mut W, values: &[f64]) -> io::Result<()> {
+        for (i, &value) in values.iter().enumerate() {
+            if i > 0 {
+                write!(writer, "{}", self.delimiter)?;
+            }
+            write!(writer, "{}", self.format_float(value))?;
+        }
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CsvOptions;
+
+    const VALUES: [f64; 8] = [0.1, 1.0 / 3.0, 1e-300, 123456789.125, 5e-324, 1.7976931348623157e308, 0.0, 2.5];
+
+    fn round_trip(options: CsvOptions) {
+        let mut buffer = Vec::new();
+        for row in VALUES.chunks(3) {
+            options.write_record(&mut buffer, row).unwrap();
+        }
+        let text = String::from_utf8(buffer).unwrap();
+
+        let detected = CsvOptions::detect(text.lines().next().unwrap());
+        assert_eq!(detected, options);
+
+        let mut parsed = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            for field in detected.split(line, i + 1, None).unwrap() {
+                parsed.push(detected.parse_float(field, i + 1).unwrap());
+            }
+        }
+        assert_eq!(parsed.iter().map(|value| value.to_bits()).collect::<Vec<u64>>(), VALUES.iter().map(|value| value.to_bits()).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn round_trip_default() {
+        round_trip(CsvOptions::default());
+    }
+
+    #[test]
+    fn round_trip_european() {
+        round_trip(CsvOptions::european());
+        assert_eq!(CsvOptions::european().format_float(1.5), "1,5");
+    }
+
+    #[test]
+    fn mixed_conventions() {
+        let text = "0,1,2.5\n1,0,1,5\n";
+        let options = CsvOptions::detect(text.lines().next().unwrap());
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(options.split(lines[0], 1, Some(3)).is_ok());
+        let e = options.split(lines[1], 2, Some(3)).unwrap_err();
+        assert_eq!(e.line, 2);
+        assert_eq!(e.to_string(), "line 2: 4 fields separated by ',', expected 3");
+
+        let text = "0;1;2,5\n1;0;1.5\n";
+        let options = CsvOptions::detect(text.lines().next().unwrap());
+        let fields = options.split(text.lines().nth(1).unwrap(), 2, Some(3)).unwrap();
+        let e = options.parse_float(fields[2], 2).unwrap_err();
+        assert_eq!(e.line, 2);
+        assert!(e.message.contains("decimal point"));
+    }
+}
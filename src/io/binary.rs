@@ -0,0 +1,329 @@
+// binary instance and solution files
+//
+// every file starts with 4 magic bytes and a little-endian u16 version. readers accept every version up to
+// the current one; a newer version is a FormatError::FormatVersion instead of a misread.
+// a format change appends a version: the writer only writes the newest, the reader keeps a branch per version,
+// and tests/fixtures gets a frozen file of the new version
+//
+// instance v1: num_agents u64, num_items u64, budgets f64 * num_agents, num_bids u64, (agent_id u64, item_id u64, bid f64) * num_bids
+// solution v1: num_agents u64, num_items u64, per agent (num_assigned u64, item_id u64 * num_assigned), spends f64 * num_agents,
+//              primal f64, dual f64, epsilon f64, beta f64
+// all integers and floats are little-endian
+use super::MAX_COUNT;
+use crate::error_code::ErrorCode;
+use crate::instance::Instance;
+use crate::solution::Solution;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::path::Path;
+
+pub const INSTANCE_MAGIC: [u8; 4] = *b"MBAI";
+pub const SOLUTION_MAGIC: [u8; 4] = *b"MBAS";
+pub const INSTANCE_VERSION: u16 = 1;
+pub const SOLUTION_VERSION: u16 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatKind {
+    Instance { version: u16 },
+    Solution { version: u16 },
+    // no known magic; a text format such as csv
+    Text,
+    Unknown,
+}
+
+#[derive(Debug)]
+pub enum FormatError {
+    Io(io::Error),
+    BadMagic { expected: [u8; 4], found: [u8; 4] },
+    FormatVersion { found: u16, supported: u16 },
+    Invalid(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Io(e) => write!(f, "{}", e),
+            FormatError::BadMagic { expected, found } => {
+                write!(f, "magic bytes {:?} instead of {:?}", String::from_utf8_lossy(found), String::from_utf8_lossy(expected))
+            }
+            FormatError::FormatVersion { found, supported } => write!(f, "format version {} is newer than the supported version {}", found, supported),
+            FormatError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl FormatError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FormatError::Io(_) => ErrorCode::Io,
+            FormatError::BadMagic { .. } => ErrorCode::BadMagic,
+            FormatError::FormatVersion { .. } => ErrorCode::UnsupportedFormatVersion,
+            FormatError::Invalid(_) => ErrorCode::InvalidFormat,
+        }
+    }
+}
+
+impl From<io::Error> for FormatError {
+    fn from(e: io::Error) -> Self {
+        FormatError::Io(e)
+    }
+}
+
+// kind of the file at path, from its first bytes
+pub fn detect_format(path: impl AsRef<Path>) -> io::Result<FormatKind> {
+    let mut head = Vec::with_capacity(64);
+    File::open(path)?.take(64).read_to_end(&mut head)?;
+
+    if head.len() >= 6 {
+        let version = u16::from_le_bytes([head[4], head[5]]);
+        if head[..4] == INSTANCE_MAGIC {
+            return Ok(FormatKind::Instance { version });
+        }
+        if head[..4] == SOLUTION_MAGIC {
+            return Ok(FormatKind::Solution { version });
+        }
+    }
+    // the head may end inside a multi-byte character
+    let text = match std::str::from_utf8(&head) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap(),
+    };
+    if text.len() + 3 >= head.len() && !text.contains('\0') {
+        Ok(FormatKind::Text)
+    } else {
+        Ok(FormatKind::Unknown)
+    }
+}
+
+pub fn write_instance_binary<W: Write>(instance: &Instance, mut writer: W) -> io::Result<()> {
+    writer.write_all(&INSTANCE_MAGIC)?;
+    writer.write_all(&INSTANCE_VERSION.to_le_bytes())?;
+    write_u64(&mut writer, instance.num_agents)?;
+    write_u64(&mut writer, instance.num_items)?;
+    for &budget in &instance.budgets {
+        writer.write_all(&budget.to_le_bytes())?;
+    }
+    write_u64(&mut writer, instance.bids.len())?;
+    for &(agent_id, item_id, bid) in &instance.bids {
+        write_u64(&mut writer, agent_id)?;
+        write_u64(&mut writer, item_id)?;
+        writer.write_all(&bid.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn read_instance_binary<R: Read>(mut reader: R) -> Result<Instance, FormatError> {
+    match read_header(&mut reader, INSTANCE_MAGIC, INSTANCE_VERSION)? {
+        1 => {
+            let num_agents = read_count(&mut reader, "num_agents")?;
+            let num_items = read_count(&mut reader, "num_items")?;
+            // grown as the budgets are read, so that a truncated file fails before allocating for all of them
+            let mut budgets = Vec::with_capacity(num_agents.min(1 << 20));
+            for _ in 0..num_agents {
+                budgets.push(read_f64(&mut reader)?);
+            }
+            let mut instance = Instance {
+                num_agents,
+                num_items,
+                budgets,
+                bids: Vec::new(),
+            };
+            let num_bids = read_u64(&mut reader)?;
+            for _ in 0..num_bids {
+                let agent_id = read_u64(&mut reader)?;
+                let item_id = read_u64(&mut reader)?;
+                let bid = read_f64(&mut reader)?;
+                if agent_id >= num_agents || item_id >= num_items {
+                    return Err(FormatError::Invalid(format!("bid ({}, {}) is out of range", agent_id, item_id)));
+                }
+                instance.bids.push((agent_id, item_id, bid));
+            }
+            Ok(instance)
+        }
+        version => unreachable!("read_header admits version {}", version),
+    }
+}
+
+pub fn write_solution_binary<W: Write>(solution: &Solution, mut writer: W) -> io::Result<()> {
+    writer.write_all(&SOLUTION_MAGIC)?;
+    writer.write_all(&SOLUTION_VERSION.to_le_bytes())?;
+    write_u64(&mut writer, solution.num_agents())?;
+    write_u64(&mut writer, solution.num_items())?;
+    for item_ids in &solution.assignment {
+        write_u64(&mut writer, item_ids.len())?;
+        for &item_id in item_ids {
+            write_u64(&mut writer, item_id)?;
+        }
+    }
+    for &value in solution.spends.iter().chain([&solution.primal, &solution.dual, &solution.epsilon, &solution.beta]) {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn read_solution_binary<R: Read>(mut reader: R) -> Result<Solution, FormatError> {
+    match read_header(&mut reader, SOLUTION_MAGIC, SOLUTION_VERSION)? {
+        1 => {
+            let num_agents = read_count(&mut reader, "num_agents")?;
+            let num_items = read_count(&mut reader, "num_items")?;
+            let mut assignment = Vec::with_capacity(num_agents.min(1 << 20));
+            let mut item_owner = vec![None; num_items];
+            for agent_id in 0..num_agents {
+                let num_assigned = read_u64(&mut reader)?;
+                let mut item_ids = Vec::with_capacity(num_assigned.min(num_items));
+                for _ in 0..num_assigned {
+                    let item_id = read_u64(&mut reader)?;
+                    if item_id >= num_items {
+                        return Err(FormatError::Invalid(format!("item {} is out of range", item_id)));
+                    }
+                    item_owner[item_id] = Some(agent_id);
+                    item_ids.push(item_id);
+                }
+                assignment.push(item_ids);
+            }
+            let spends = (0..num_agents).map(|_| read_f64(&mut reader)).collect::<Result<Vec<f64>, FormatError>>()?;
+            Ok(Solution {
+                assignment,
+                item_owner,
+                spends,
+                primal: read_f64(&mut reader)?,
+                dual: read_f64(&mut reader)?,
+                epsilon: read_f64(&mut reader)?,
+                beta: read_f64(&mut reader)?,
+            })
+        }
+        version => unreachable!("read_header admits version {}", version),
+    }
+}
+
+// checks the magic bytes and returns the version, which is in 1..=supported
+fn read_header<R: Read>(reader: &mut R, expected: [u8; 4], supported: u16) -> Result<u16, FormatError> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != expected {
+        return Err(FormatError::BadMagic { expected, found: magic });
+    }
+
+    let mut version = [0; 2];
+    reader.read_exact(&mut version)?;
+    let version = u16::from_le_bytes(version);
+    if version == 0 || version > supported {
+        return Err(FormatError::FormatVersion { found: version, supported });
+    }
+    Ok(version)
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<usize, FormatError> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| FormatError::Invalid("count does not fit in usize".to_string()))
+}
+
+// a count of the header, at most MAX_COUNT
+fn read_count<R: Read>(reader: &mut R, name: &str) -> Result<usize, FormatError> {
+    let count = read_u64(reader)?;
+    if count > MAX_COUNT {
+        return Err(FormatError::Invalid(format!("{} {} exceeds the limit {}", name, count, MAX_COUNT)));
+    }
+    Ok(count)
+}
+
+fn read_f64<R: Read>(reader: &mut R) -> Result<f64, FormatError> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_instance_binary, read_solution_binary, write_instance_binary, write_solution_binary, FormatError, INSTANCE_MAGIC, SOLUTION_MAGIC};
+    use crate::instance::Instance;
+    use crate::io::MAX_COUNT;
+    use crate::primal_dual::PrimalDual;
+
+    fn sample() -> Instance {
+        let mut instance = Instance::new(2, 4);
+        instance.set_budget(0, 300.0);
+        instance.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            instance.add_bid(agent_id, item_id, bid);
+        }
+        instance
+    }
+
+    #[test]
+    fn instance_round_trip() {
+        let instance = sample();
+        let mut buffer = Vec::new();
+        write_instance_binary(&instance, &mut buffer).unwrap();
+        assert_eq!(read_instance_binary(buffer.as_slice()).unwrap(), instance);
+    }
+
+    #[test]
+    fn solution_round_trip() {
+        let instance = sample();
+        let mut solver = PrimalDual::new(2, 4, 0.01);
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &instance.bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+        let solution = solver.extract_solution();
+
+        let mut buffer = Vec::new();
+        write_solution_binary(&solution, &mut buffer).unwrap();
+        assert_eq!(read_solution_binary(buffer.as_slice()).unwrap(), solution);
+    }
+
+    #[test]
+    fn future_version() {
+        let mut buffer = Vec::new();
+        write_instance_binary(&sample(), &mut buffer).unwrap();
+        buffer[4..6].copy_from_slice(&7u16.to_le_bytes());
+        assert!(matches!(read_instance_binary(buffer.as_slice()), Err(FormatError::FormatVersion { found: 7, supported: 1 })));
+
+        assert!(matches!(
+            read_solution_binary(buffer.as_slice()),
+            Err(FormatError::BadMagic {
+                expected: _,
+                found: INSTANCE_MAGIC
+            })
+        ));
+
+        buffer[4..6].copy_from_slice(&1u16.to_le_bytes());
+        assert!(matches!(read_instance_binary(&buffer[..10]), Err(FormatError::Io(_))));
+    }
+
+    #[test]
+    fn oversized_header() {
+        // 22 bytes claiming 2^40 agents
+        let mut buffer = INSTANCE_MAGIC.to_vec();
+        buffer.extend(1u16.to_le_bytes());
+        buffer.extend((1u64 << 40).to_le_bytes());
+        buffer.extend(4u64.to_le_bytes());
+        assert!(matches!(read_instance_binary(buffer.as_slice()), Err(FormatError::Invalid(_))));
+
+        let mut buffer = SOLUTION_MAGIC.to_vec();
+        buffer.extend(1u16.to_le_bytes());
+        buffer.extend(2u64.to_le_bytes());
+        buffer.extend((MAX_COUNT as u64 + 1).to_le_bytes());
+        assert!(matches!(read_solution_binary(buffer.as_slice()), Err(FormatError::Invalid(_))));
+
+        // a count within the limit that the data does not back is a read error, not an allocation
+        let mut buffer = INSTANCE_MAGIC.to_vec();
+        buffer.extend(1u16.to_le_bytes());
+        buffer.extend((MAX_COUNT as u64).to_le_bytes());
+        buffer.extend(4u64.to_le_bytes());
+        assert!(matches!(read_instance_binary(buffer.as_slice()), Err(FormatError::Io(_))));
+    }
+}
@@ -0,0 +1,239 @@
+// an assignment with its bids, the per-agent spend and the objective, written row by row so that nothing is
+// materialized whatever the size of the assignment
+//
+// csv, three sections separated by a blank line:
+//   agent_id,item_id,bid
+//   ...                      one row per assigned item, by agent; ",item_id," per unassigned item with include_unassigned
+//
+//   agent_id,budget,spend,value
+//   ...                      one row per agent; value = min(spend, budget of the assignment)
+//
+//   objective
+//   total value
+use super::CsvOptions;
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+use std::io;
+use std::io::Write;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub include_unassigned: bool, // also list the items nobody holds
+    pub csv: CsvOptions,          // ignored by the json writer
+}
+
+// write_assignment_csv_with with the default options
+pub fn write_assignment_csv<W: Write>(assignment: &Assignment, instance: &Instance, writer: W) -> io::Result<()> {
+    write_assignment_csv_with(assignment, instance, &ExportOptions::default(), writer)
+}
+
+// the budget column holds the budgets of instance, which describes the same agents and items as assignment
+pub fn write_assignment_csv_with<W: Write>(assignment: &Assignment, instance: &Instance, options: &ExportOptions, mut writer: W) -> io::Result<()> {
+    debug_assert_eq!((assignment.num_agents(), assignment.num_items()), (instance.num_agents, instance.num_items));
+    let csv = &options.csv;
+    let d = csv.delimiter;
+
+    writeln!(writer, "agent_id{d}item_id{d}bid")?;
+    for (agent_id, item_id, bid) in assignment {
+        writeln!(writer, "{}{d}{}{d}{}", agent_id, item_id, csv.format_float(bid))?;
+    }
+    if options.include_unassigned {
+        for item_id in (0..assignment.num_items()).filter(|&item_id| assignment.agent_of(item_id).is_none()) {
+            writeln!(writer, "{d}{}{d}", item_id)?;
+        }
+    }
+
+    writeln!(writer)?;
+    writeln!(writer, "agent_id{d}budget{d}spend{d}value")?;
+    for agent_id in 0..assignment.num_agents() {
+        let (budget, spend, value) = (instance.budgets[agent_id], assignment.spend(agent_id), assignment.value(agent_id));
+        writeln!(writer, "{}{d}{}{d}{}{d}{}", agent_id, csv.format_float(budget), csv.format_float(spend), csv.format_float(value))?;
+    }
+
+    writeln!(writer)?;
+    writeln!(writer, "objective")?;
+    writeln!(writer, "{}", csv.format_float(assignment.total_value()))
+}
+
+// write_assignment_json_with with the default options
+#[cfg(feature = "serde")]
+pub fn write_assignment_json<W: Write>(assignment: &Assignment, instance: &Instance, writer: W) -> io::Result<()> {
+    write_assignment_json_with(assignment, instance, &ExportOptions::default(), writer)
+}
+
+// {"assignment": [{"agent_id", "item_id", "bid"}, ...], "unassigned": [item_id, ...] with include_unassigned,
+//  "agents": [{"agent_id", "budget", "spend", "value"}, ...], "objective": total value}
+#[cfg(feature = "serde")]
+pub fn write_assignment_json_with<W: Write>(assignment: &Assignment, instance: &Instance, options: &ExportOptions, writer: W) -> io::Result<()> {
+    debug_assert_eq!((assignment.num_agents(), assignment.num_items()), (instance.num_agents, instance.num_items));
+    let export = json::Export {
+        assignment: json::Rows(assignment),
+        unassigned: options.include_unassigned.then_some(json::Unassigned(assignment)),
+        agents: json::Agents(assignment, instance),
+        objective: assignment.total_value(),
+    };
+    serde_json::to_writer(writer, &export).map_err(io::Error::from)
+}
+
+// serialized through iterators, so that serde_json streams the rows
+#[cfg(feature = "serde")]
+mod json {
+    use crate::assignment::Assignment;
+    use crate::instance::Instance;
+    use serde::{Serialize, Serializer};
+
+    #[derive(Serialize)]
+    pub(super) struct Export<'a> {
+        pub(super) assignment: Rows<'a>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub(super) unassigned: Option<Unassigned<'a>>,
+        pub(super) agents: Agents<'a>,
+        pub(super) objective: f64,
+    }
+
+    #[derive(Serialize)]
+    struct Row {
+        agent_id: usize,
+        item_id: usize,
+        bid: f64,
+    }
+
+    #[derive(Serialize)]
+    struct Agent {
+        agent_id: usize,
+        budget: f64,
+        spend: f64,
+        value: f64,
+    }
+
+    pub(super) struct Rows<'a>(pub(super) &'a Assignment);
+    pub(super) struct Unassigned<'a>(pub(super) &'a Assignment);
+    pub(super) struct Agents<'a>(pub(super) &'a Assignment, pub(super) &'a Instance);
+
+    impl Serialize for Rows<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.iter().map(|(agent_id, item_id, bid)| Row { agent_id, item_id, bid }))
+        }
+    }
+
+    impl Serialize for Unassigned<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq((0..self.0.num_items()).filter(|&item_id| self.0.agent_of(item_id).is_none()))
+        }
+    }
+
+    impl Serialize for Agents<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let Agents(assignment, instance) = *self;
+            serializer.collect_seq((0..assignment.num_agents()).map(|agent_id| Agent {
+                agent_id,
+                budget: instance.budgets[agent_id],
+                spend: assignment.spend(agent_id),
+                value: assignment.value(agent_id),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_assignment_csv, write_assignment_csv_with, ExportOptions};
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::io::CsvOptions;
+    use crate::primal_dual::PrimalDual;
+
+    fn solved() -> (Instance, crate::assignment::Assignment) {
+        let instance = generate(6, 50, 0.3, (10.0, 40.0), (0.5, 12.0), 294);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        solver.solve();
+        (instance, solver.assignment())
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let (instance, assignment) = solved();
+        for csv in [CsvOptions::default(), CsvOptions::european()] {
+            let options = ExportOptions { include_unassigned: true, csv };
+            let mut bytes = Vec::new();
+            write_assignment_csv_with(&assignment, &instance, &options, &mut bytes).unwrap();
+            let text = String::from_utf8(bytes).unwrap();
+
+            let sections: Vec<Vec<&str>> = text.split("\n\n").map(|section| section.lines().collect()).collect();
+            assert_eq!(sections.len(), 3);
+
+            let mut spends = vec![0.0; instance.num_agents];
+            let mut num_unassigned = 0;
+            for (i, line) in sections[0].iter().enumerate().skip(1) {
+                let fields = csv.split(line, i + 1, Some(3)).unwrap();
+                if fields[0].is_empty() {
+                    let item_id = csv.parse_index(fields[1], i + 1).unwrap();
+                    assert_eq!(assignment.agent_of(item_id), None);
+                    num_unassigned += 1;
+                    continue;
+                }
+                let agent_id = csv.parse_index(fields[0], i + 1).unwrap();
+                let item_id = csv.parse_index(fields[1], i + 1).unwrap();
+                assert_eq!(assignment.agent_of(item_id), Some(agent_id));
+                spends[agent_id] += csv.parse_float(fields[2], i + 1).unwrap();
+            }
+            assert_eq!(num_unassigned, (0..instance.num_items).filter(|&item_id| assignment.agent_of(item_id).is_none()).count());
+
+            let mut objective = 0.0;
+            for (i, line) in sections[1].iter().enumerate().skip(1) {
+                let fields = csv.split(line, i + 1, Some(4)).unwrap();
+                let agent_id = csv.parse_index(fields[0], i + 1).unwrap();
+                let budget = csv.parse_float(fields[1], i + 1).unwrap();
+                assert_eq!(budget, instance.budgets[agent_id]);
+                assert_eq!(csv.parse_float(fields[2], i + 1).unwrap(), spends[agent_id]);
+                objective += spends[agent_id].min(budget);
+            }
+            assert_eq!(sections[2][0], "objective");
+            assert_eq!(csv.parse_float(sections[2][1], 1).unwrap(), assignment.total_value());
+            assert!((objective - assignment.total_value()).abs() <= 1e-9);
+        }
+    }
+
+    #[test]
+    fn without_unassigned() {
+        let mut instance = Instance::new(1, 2);
+        instance.set_budget(0, 4.0);
+        instance.add_bid(0, 1, 2.5);
+        let mut solver = PrimalDual::from_instance(&instance, 0.1);
+        solver.solve();
+
+        let mut bytes = Vec::new();
+        write_assignment_csv(&solver.assignment(), &instance, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "agent_id,item_id,bid\n0,1,2.5\n\nagent_id,budget,spend,value\n0,4.0,2.5,2.5\n\nobjective\n2.5\n");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let (instance, assignment) = solved();
+        let options = ExportOptions {
+            include_unassigned: true,
+            ..ExportOptions::default()
+        };
+        let mut bytes = Vec::new();
+        super::write_assignment_json_with(&assignment, &instance, &options, &mut bytes).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        let rows = json["assignment"].as_array().unwrap();
+        assert_eq!(rows.len(), assignment.iter().count());
+        let mut spends = vec![0.0; instance.num_agents];
+        for row in rows {
+            spends[row["agent_id"].as_u64().unwrap() as usize] += row["bid"].as_f64().unwrap();
+        }
+        let objective: f64 = spends.iter().zip(&instance.budgets).map(|(spend, budget)| spend.min(*budget)).sum();
+        assert!((objective - json["objective"].as_f64().unwrap()).abs() <= 1e-9);
+        assert_eq!(json["objective"].as_f64().unwrap(), assignment.total_value());
+        assert_eq!(json["agents"].as_array().unwrap().len(), instance.num_agents);
+        assert!(json["unassigned"].as_array().is_some());
+
+        let mut bytes = Vec::new();
+        super::write_assignment_json(&assignment, &instance, &mut bytes).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(json.get("unassigned").is_none());
+    }
+}
@@ -0,0 +1,207 @@
+// bids streamed from "agent_id,item_id,bid" lines straight into a solver, one line in memory at a time:
+//
+//   agent_id,item_id,bid       optional header lines: lines before the first bid that do not start with a digit
+//   0,17,2.5
+//   ...                        blank lines are skipped
+//
+// the solver is sized and budgeted by the caller, so any number of bids loads in the memory of the solver alone
+use super::{CsvOptions, ParseError};
+use crate::primal_dual::{MbaError, PrimalDual, Scalar};
+use std::io::BufRead;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub csv: Option<CsvOptions>, // None detects them from the first line, see CsvOptions::detect
+    pub progress_every: usize,   // lines between two calls of on_progress, 0 for none
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            csv: None,
+            progress_every: 1 << 20,
+        }
+    }
+}
+
+// load_into_with with the default options and no progress
+pub fn load_into<T: Scalar, R: BufRead>(solver: &mut PrimalDual<T>, reader: R) -> Result<usize, ParseError> {
+    load_into_with(solver, reader, &LoadOptions::default(), |_| {})
+}
+
+// sets the bid of every line through try_set_bids and returns the number of bids. on_progress gets the number of lines
+// read so far every options.progress_every lines.
+// an error names its line, and the bids of the lines before it stay set. a bid replaces an earlier bid on the same
+// pair, so loading the corrected file again into the same solver gives the solver of a single load
+pub fn load_into_with<T: Scalar, R: BufRead, F: FnMut(usize)>(
+    solver: &mut PrimalDual<T>,
+    mut reader: R,
+    options: &LoadOptions,
+    mut on_progress: F,
+) -> Result<usize, ParseError> {
+    let mut csv = options.csv;
+    let mut line = String::new();
+    let mut line_number = 0;
+    let mut bid_line = 0; // line of the last bid handed to the solver
+    let mut num_bids = 0;
+    let mut error = None;
+
+    let mut next_bid = || -> Result<Option<(usize, usize, f64)>, ParseError> {
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            line_number += 1;
+            if options.progress_every > 0 && line_number % options.progress_every == 0 {
+                on_progress(line_number);
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let csv = *csv.get_or_insert_with(|| CsvOptions::detect(&line));
+            if num_bids == 0 && !line.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
+                continue; // a header
+            }
+            let fields = csv.split(&line, line_number, Some(3))?;
+            let agent_id = csv.parse_index(fields[0], line_number)?;
+            let item_id = csv.parse_index(fields[1], line_number)?;
+            let bid = csv.parse_float(fields[2], line_number)?;
+            bid_line = line_number;
+            num_bids += 1;
+            return Ok(Some((agent_id, item_id, bid)));
+        }
+    };
+    let bids = std::iter::from_fn(|| {
+        next_bid().unwrap_or_else(|e| {
+            error = Some(e);
+            None
+        })
+    });
+    let result = solver.try_set_bids(bids.map(|(agent_id, item_id, bid)| (agent_id, item_id, T::from_f64(bid))));
+
+    if let Err(e) = result {
+        return Err(match e {
+            MbaError::AgentIndexOutOfRange { agent_id, num_agents } => ParseError::AgentOutOfRange {
+                line: bid_line,
+                agent_id,
+                num_agents,
+            },
+            MbaError::ItemIndexOutOfRange { item_id, num_items } => ParseError::ItemOutOfRange {
+                line: bid_line,
+                item_id,
+                num_items,
+            },
+            error => ParseError::Rejected { line: bid_line, error },
+        });
+    }
+    match error {
+        Some(e) => Err(e),
+        None => Ok(num_bids),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_into, load_into_with, LoadOptions};
+    use crate::generator::generate;
+    use crate::io::{CsvOptions, ParseError};
+    use crate::primal_dual::{MbaError, PrimalDual};
+
+    #[test]
+    fn same_as_set_bids() {
+        let instance = generate(5, 40, 0.4, (10.0, 30.0), (0.5, 8.0), 298);
+        let mut text = String::from("agent_id,item_id,bid\n\n");
+        for &(agent_id, item_id, bid) in &instance.bids {
+            text.push_str(&format!("{},{},{:?}\n", agent_id, item_id, bid));
+        }
+        text.push('\n');
+
+        let mut loaded = PrimalDual::new(5, 40, 0.01);
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            loaded.set_budget(agent_id, budget);
+        }
+        let mut progress = Vec::new();
+        let options = LoadOptions {
+            progress_every: 10,
+            ..LoadOptions::default()
+        };
+        let num_bids = load_into_with(&mut loaded, text.as_bytes(), &options, |lines| progress.push(lines)).unwrap();
+        assert_eq!(num_bids, instance.bids.len());
+        assert_eq!(progress, (1..=text.lines().count() / 10).map(|i| i * 10).collect::<Vec<usize>>());
+
+        let mut expected = PrimalDual::from_instance(&instance, 0.01);
+        loaded.solve();
+        expected.solve();
+        assert_eq!(loaded.extract_solution(), expected.extract_solution());
+    }
+
+    #[test]
+    fn european() {
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        assert_eq!(load_into(&mut solver, "agent;item;bid\n0;1;2,5\n".as_bytes()).unwrap(), 1);
+        assert_eq!(solver.get_max_bidder(1), Some(0));
+
+        let options = LoadOptions {
+            csv: Some(CsvOptions::default()),
+            ..LoadOptions::default()
+        };
+        let e = load_into_with(&mut solver, "0;1;2,5\n".as_bytes(), &options, |_| {}).unwrap_err();
+        assert_eq!(e.to_string(), "line 1: 2 fields separated by ',', expected 3");
+    }
+
+    #[test]
+    fn errors() {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+
+        let e = load_into(&mut solver, "0,0,1\n\n0,1,2\n1,x,1\n1,2,1\n".as_bytes()).unwrap_err();
+        assert_eq!(e.line(), Some(4));
+        assert_eq!(e.to_string(), "line 4: 'x' is not an index");
+        // the lines before the error are set
+        assert_eq!(solver.num_bids(), 2);
+
+        let e = load_into(&mut solver, "0,0,1\n2,0,1\n".as_bytes()).unwrap_err();
+        assert!(matches!(
+            e,
+            ParseError::AgentOutOfRange {
+                line: 2,
+                agent_id: 2,
+                num_agents: 2
+            }
+        ));
+        let e = load_into(&mut solver, "1,3,1\n".as_bytes()).unwrap_err();
+        assert!(matches!(
+            e,
+            ParseError::ItemOutOfRange {
+                line: 1,
+                item_id: 3,
+                num_items: 3
+            }
+        ));
+        let e = load_into(&mut solver, "header\n1,2,-1\n".as_bytes()).unwrap_err();
+        assert!(matches!(
+            e,
+            ParseError::Rejected {
+                line: 2,
+                error: MbaError::InvalidBid { .. }
+            }
+        ));
+        let e = load_into(&mut solver, "0,0,1\nheader\n".as_bytes()).unwrap_err();
+        assert_eq!(e.line(), Some(2));
+
+        // loading the corrected file again gives the same bids as a single load
+        let text = "0,0,1\n0,1,2\n1,1,1\n1,2,1\n";
+        load_into(&mut solver, text.as_bytes()).unwrap();
+        let mut fresh = PrimalDual::new(2, 3, 0.01);
+        fresh.set_budget(0, 10.0);
+        fresh.set_budget(1, 10.0);
+        load_into(&mut fresh, text.as_bytes()).unwrap();
+        assert_eq!(solver.num_bids(), fresh.num_bids());
+        solver.solve();
+        fresh.solve();
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+    }
+}
@@ -0,0 +1,275 @@
+// the csv instance format of the test data
+//
+//   num_agents,num_items
+//   budget_0,budget_1,...
+//   agent_id,item_id,bid
+//   ...
+//
+// files with ';' in the first line are read with decimal commas, see CsvOptions::detect
+use super::{CsvError, CsvOptions, MAX_COUNT};
+use crate::error_code::ErrorCode;
+use crate::instance::Instance;
+use crate::primal_dual::MbaError;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::io::{BufRead, Write};
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    Malformed(CsvError),
+    AgentOutOfRange {
+        line: usize,
+        agent_id: usize,
+        num_agents: usize,
+    },
+    ItemOutOfRange {
+        line: usize,
+        item_id: usize,
+        num_items: usize,
+    },
+    DuplicateBid {
+        line: usize,
+        agent_id: usize,
+        item_id: usize,
+        first_line: usize,
+    },
+    Rejected {
+        line: usize,
+        error: MbaError, // a bid the solver does not take, see load_into
+    },
+}
+
+impl ParseError {
+    // the code of the rejecting MbaError for ParseError::Rejected
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ParseError::Io(_) => ErrorCode::Io,
+            ParseError::Malformed(e) => e.code(),
+            ParseError::AgentOutOfRange { .. } => ErrorCode::AgentIndexOutOfRange,
+            ParseError::ItemOutOfRange { .. } => ErrorCode::ItemIndexOutOfRange,
+            ParseError::DuplicateBid { .. } => ErrorCode::DuplicateBid,
+            ParseError::Rejected { error, .. } => error.code(),
+        }
+    }
+
+    // 1-based line of the error, None for io errors
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::Malformed(e) => Some(e.line),
+            ParseError::AgentOutOfRange { line, .. }
+            | ParseError::ItemOutOfRange { line, .. }
+            | ParseError::DuplicateBid { line, .. }
+            | ParseError::Rejected { line, .. } => Some(*line),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "{}", e),
+            ParseError::Malformed(e) => write!(f, "{}", e),
+            ParseError::AgentOutOfRange { line, agent_id, num_agents } => write!(f, "line {}: agent {} is out of range for {} agents", line, agent_id, num_agents),
+            ParseError::ItemOutOfRange { line, item_id, num_items } => write!(f, "line {}: item {} is out of range for {} items", line, item_id, num_items),
+            ParseError::DuplicateBid {
+                line,
+                agent_id,
+                item_id,
+                first_line,
+            } => {
+                write!(f, "line {}: agent {} already bids on item {} in line {}", line, agent_id, item_id, first_line)
+            }
+            ParseError::Rejected { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        ParseError::Io(e)
+    }
+}
+
+impl From<CsvError> for ParseError {
+    fn from(e: CsvError) -> Self {
+        ParseError::Malformed(e)
+    }
+}
+
+// blank lines are skipped
+pub fn read_instance<R: BufRead>(reader: R) -> Result<Instance, ParseError> {
+    let mut lines = reader.lines().enumerate().map(|(i, line)| line.map(|line| (i + 1, line)));
+    let mut next_line = || -> io::Result<Option<(usize, String)>> {
+        for line in lines.by_ref() {
+            let (line_number, line) = line?;
+            if !line.trim().is_empty() {
+                return Ok(Some((line_number, line)));
+            }
+        }
+        Ok(None)
+    };
+    let missing = |line: usize, what: &str| {
+        ParseError::Malformed(CsvError {
+            line,
+            message: format!("missing {}", what),
+        })
+    };
+
+    let (line_number, line) = next_line()?.ok_or_else(|| missing(1, "num_agents,num_items line"))?;
+    let options = CsvOptions::detect(&line);
+    let fields = options.split(&line, line_number, Some(2))?;
+    let num_agents = options.parse_index(fields[0], line_number)?;
+    let num_items = options.parse_index(fields[1], line_number)?;
+    for (name, count) in [("num_agents", num_agents), ("num_items", num_items)] {
+        if count > MAX_COUNT {
+            return Err(ParseError::Malformed(CsvError {
+                line: line_number,
+                message: format!("{} {} exceeds the limit {}", name, count, MAX_COUNT),
+            }));
+        }
+    }
+
+    // the budgets come from the fields of their line, which backs num_agents
+    let mut budgets = Vec::new();
+    if num_agents > 0 {
+        let (line_number, line) = next_line()?.ok_or_else(|| missing(line_number + 1, "budget line"))?;
+        for field in options.split(&line, line_number, Some(num_agents))? {
+            budgets.push(options.parse_float(field, line_number)?);
+        }
+    }
+    let mut instance = Instance {
+        num_agents,
+        num_items,
+        budgets,
+        bids: Vec::new(),
+    };
+
+    let mut first_lines: HashMap<(usize, usize), usize> = HashMap::new();
+    while let Some((line_number, line)) = next_line()? {
+        let fields = options.split(&line, line_number, Some(3))?;
+        let agent_id = options.parse_index(fields[0], line_number)?;
+        let item_id = options.parse_index(fields[1], line_number)?;
+        let bid = options.parse_float(fields[2], line_number)?;
+        if agent_id >= num_agents {
+            return Err(ParseError::AgentOutOfRange {
+                line: line_number,
+                agent_id,
+                num_agents,
+            });
+        }
+        if item_id >= num_items {
+            return Err(ParseError::ItemOutOfRange {
+                line: line_number,
+                item_id,
+                num_items,
+            });
+        }
+        if let Some(&first_line) = first_lines.get(&(agent_id, item_id)) {
+            return Err(ParseError::DuplicateBid {
+                line: line_number,
+                agent_id,
+                item_id,
+                first_line,
+            });
+        }
+        first_lines.insert((agent_id, item_id), line_number);
+        instance.bids.push((agent_id, item_id, bid));
+    }
+    Ok(instance)
+}
+
+// with the default CsvOptions, so that read_instance gets back the same bits
+pub fn write_instance<W: Write>(instance: &Instance, mut writer: W) -> io::Result<()> {
+    let options = CsvOptions::default();
+    writeln!(writer, "{},{}", instance.num_agents, instance.num_items)?;
+    if instance.num_agents > 0 {
+        options.write_record(&mut writer, &instance.budgets)?;
+    }
+    for &(agent_id, item_id, bid) in &instance.bids {
+        writeln!(writer, "{},{},{}", agent_id, item_id, options.format_float(bid))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_instance, write_instance, ParseError, MAX_COUNT};
+    use crate::instance::Instance;
+
+    fn parse(text: &str) -> Result<Instance, ParseError> {
+        read_instance(text.as_bytes())
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut instance = Instance::new(3, 4);
+        instance.budgets = vec![300.0, 0.1, 1.0 / 3.0];
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (2, 3, 1e-7), (1, 1, 0.1), (0, 2, 2.0 / 3.0)] {
+            instance.add_bid(agent_id, item_id, bid);
+        }
+
+        let mut buffer = Vec::new();
+        write_instance(&instance, &mut buffer).unwrap();
+        assert_eq!(parse(std::str::from_utf8(&buffer).unwrap()).unwrap(), instance);
+    }
+
+    #[test]
+    fn sample() {
+        let instance = parse("2,3\n300,400\n0,0,200\n1,2,200.5\n\n").unwrap();
+        assert_eq!(instance.budgets, vec![300.0, 400.0]);
+        assert_eq!(instance.bids, vec![(0, 0, 200.0), (1, 2, 200.5)]);
+
+        let instance = parse("2;3\n300,5;400\n1;2;200,5\n").unwrap();
+        assert_eq!(instance.budgets, vec![300.5, 400.0]);
+        assert_eq!(instance.bids, vec![(1, 2, 200.5)]);
+    }
+
+    #[test]
+    fn errors() {
+        let e = parse("2,3\n300,400\n0,0,200\n0,x,1\n").unwrap_err();
+        assert_eq!(e.line(), Some(4));
+        assert_eq!(e.to_string(), "line 4: 'x' is not an index");
+
+        assert_eq!(parse("2,3\n300\n").unwrap_err().line(), Some(2));
+        assert_eq!(parse("2,3\n300,400\n0,0\n").unwrap_err().line(), Some(3));
+        assert_eq!(parse("2,3\n").unwrap_err().to_string(), "line 2: missing budget line");
+        assert!(matches!(
+            parse("2,3\n300,400\n2,0,1\n").unwrap_err(),
+            ParseError::AgentOutOfRange {
+                line: 3,
+                agent_id: 2,
+                num_agents: 2
+            }
+        ));
+        assert!(matches!(
+            parse("2,3\n300,400\n0,3,1\n").unwrap_err(),
+            ParseError::ItemOutOfRange {
+                line: 3,
+                item_id: 3,
+                num_items: 3
+            }
+        ));
+        let e = parse("2,3\n300,400\n0,1,1\n1,1,1\n\n0,1,2\n").unwrap_err();
+        assert!(matches!(
+            e,
+            ParseError::DuplicateBid {
+                line: 6,
+                agent_id: 0,
+                item_id: 1,
+                first_line: 3
+            }
+        ));
+        assert_eq!(e.to_string(), "line 6: agent 0 already bids on item 1 in line 3");
+
+        let e = parse("\n1099511627776,3\n").unwrap_err();
+        assert_eq!(e.line(), Some(2));
+        assert_eq!(e.to_string(), format!("line 2: num_agents 1099511627776 exceeds the limit {}", MAX_COUNT));
+        assert_eq!(parse(&format!("2,{}\n", MAX_COUNT + 1)).unwrap_err().line(), Some(1));
+        assert_eq!(parse(&format!("{},3\n300,400\n", MAX_COUNT)).unwrap_err().line(), Some(2));
+    }
+}
@@ -1 +1,27 @@
+pub mod assignment;
+#[cfg(feature = "bench-report")]
+pub mod bench;
+pub mod calibration;
+pub mod decompose;
+pub mod error_code;
+pub mod exact;
+#[cfg(feature = "capi")]
+pub mod ffi;
+pub mod generator;
+#[cfg(feature = "petgraph")]
+pub mod graph;
+pub mod greedy;
+pub mod instance;
+pub mod io;
+pub mod lp;
+pub mod online;
+pub mod preprocess;
 pub mod primal_dual;
+#[cfg(feature = "python")]
+pub mod python;
+mod reduce;
+pub mod rng;
+pub mod solution;
+pub mod solver;
+pub mod sweep;
+pub mod testing;
@@ -0,0 +1,271 @@
+// LP relaxation of maximum budgeted allocation
+//   max  sum_a min(budget_a, sum_j bid_aj * x_aj)
+//   s.t. sum_a x_aj <= 1  for every item
+//        0 <= x_aj
+//
+// solved in the equivalent linear form, max sum bid_aj * x_aj s.t. sum_j bid_aj * x_aj <= budget_a for every agent,
+// by a dense primal simplex. the origin is feasible, so no first phase is needed. meant for instances of up to a few
+// thousand bids, to bound the solvers from above: LpDuals::objective >= upper_bound >= the integral optimum
+use crate::instance::Instance;
+use std::collections::BTreeMap;
+
+// reduced costs and pivots at most this large are zero
+const TOLERANCE: f64 = 1e-9;
+
+// pivots by largest reduced cost up to this many times the tableau height plus width, then by Bland's rule,
+// which cannot cycle
+const DANTZIG_PIVOTS_PER_DIMENSION: usize = 50;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FractionalAssignment {
+    pub values: Vec<(usize, usize, f64)>, // [(agent_id, item_id, x), ...] for every x > 0, by agent then item
+    pub objective: f64,
+}
+
+impl FractionalAssignment {
+    pub fn value(&self, agent_id: usize, item_id: usize) -> f64 {
+        self.values
+            .binary_search_by(|&(a, j, _)| (a, j).cmp(&(agent_id, item_id)))
+            .map_or(0.0, |index| self.values[index].2)
+    }
+
+    // sum over agents of x_aj, at most 1
+    pub fn item_fractions(&self, num_items: usize) -> Vec<f64> {
+        let mut fractions = vec![0.0; num_items];
+        for &(_, item_id, x) in &self.values {
+            fractions[item_id] += x;
+        }
+        fractions
+    }
+}
+
+// the LP optimum, an upper bound on the optimum of instance
+pub fn upper_bound(instance: &Instance) -> f64 {
+    solve(instance).objective
+}
+
+// an optimal fractional assignment; of several bids of an agent on an item the last counts, like in PrimalDual::set_bids.
+// bids of at most 0 and agents of budget at most 0 are left out
+pub fn solve(instance: &Instance) -> FractionalAssignment {
+    let mut bids = BTreeMap::new();
+    for &(agent_id, item_id, bid) in &instance.bids {
+        bids.insert((agent_id, item_id), bid);
+    }
+    let columns: Vec<(usize, usize, f64)> = bids
+        .into_iter()
+        .filter(|&((agent_id, _), bid)| bid > 0.0 && instance.budgets[agent_id] > 0.0)
+        .map(|((agent_id, item_id), bid)| (agent_id, item_id, bid))
+        .collect();
+
+    // one row per agent and item with a column; agent rows are divided by the budget to keep the entries near 1
+    let mut agent_row = vec![None; instance.num_agents];
+    let mut item_row = vec![None; instance.num_items];
+    let mut num_rows = 0;
+    for &(agent_id, item_id, _) in &columns {
+        for row in [&mut agent_row[agent_id], &mut item_row[item_id]] {
+            if row.is_none() {
+                *row = Some(num_rows);
+                num_rows += 1;
+            }
+        }
+    }
+
+    let mut tableau = Tableau::new(num_rows, columns.len());
+    for (column, &(agent_id, item_id, bid)) in columns.iter().enumerate() {
+        tableau.set(agent_row[agent_id].unwrap(), column, bid / instance.budgets[agent_id]);
+        tableau.set(item_row[item_id].unwrap(), column, 1.0);
+        tableau.objective[column] = bid;
+    }
+    tableau.optimize();
+
+    let x = tableau.solution();
+    let values = columns
+        .iter()
+        .zip(x)
+        .filter(|&(_, x)| x > TOLERANCE)
+        .map(|(&(agent_id, item_id, _), x)| (agent_id, item_id, x.min(1.0)))
+        .collect::<Vec<_>>();
+    let mut spends = vec![0.0; instance.num_agents];
+    for (&(agent_id, _, bid), x) in columns.iter().zip(tableau.solution()) {
+        spends[agent_id] += bid * x;
+    }
+    let objective = spends.iter().zip(&instance.budgets).map(|(spend, &budget)| spend.min(budget.max(0.0))).sum();
+    FractionalAssignment { values, objective }
+}
+
+// max objective * x s.t. rows * x + slack = 1, x, slack >= 0, with the slacks as the starting basis
+struct Tableau {
+    num_rows: usize,
+    num_columns: usize,  // structural columns, followed by one slack column per row and the right-hand side
+    entries: Vec<f64>,   // row-major, num_rows * (num_columns + num_rows + 1)
+    objective: Vec<f64>, // reduced costs, followed by minus the objective value
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn new(num_rows: usize, num_columns: usize) -> Self {
+        let width = num_columns + num_rows + 1;
+        let mut entries = vec![0.0; num_rows * width];
+        for row in 0..num_rows {
+            entries[row * width + num_columns + row] = 1.0;
+            entries[row * width + width - 1] = 1.0;
+        }
+        Tableau {
+            num_rows,
+            num_columns,
+            entries,
+            objective: vec![0.0; width],
+            basis: (num_columns..num_columns + num_rows).collect(),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.num_columns + self.num_rows + 1
+    }
+
+    fn set(&mut self, row: usize, column: usize, value: f64) {
+        let width = self.width();
+        self.entries[row * width + column] = value;
+    }
+
+    fn optimize(&mut self) {
+        let width = self.width();
+        let dantzig_pivots = DANTZIG_PIVOTS_PER_DIMENSION * (self.num_rows + width);
+        for pivots in 0.. {
+            let Some(column) = self.entering(pivots < dantzig_pivots) else {
+                return;
+            };
+            // the problem is bounded, as every column has an item row entry of 1
+            let row = self.leaving(column).expect("lp relaxation is bounded");
+            self.pivot(row, column);
+        }
+    }
+
+    fn entering(&self, dantzig: bool) -> Option<usize> {
+        let candidates = (0..self.width() - 1).filter(|&column| self.objective[column] > TOLERANCE);
+        if dantzig {
+            candidates.max_by(|&a, &b| self.objective[a].total_cmp(&self.objective[b]).then(b.cmp(&a)))
+        } else {
+            candidates.min()
+        }
+    }
+
+    // minimum ratio test, ties to the smallest basic column
+    fn leaving(&self, column: usize) -> Option<usize> {
+        let width = self.width();
+        let mut best: Option<(f64, usize)> = None;
+        for row in 0..self.num_rows {
+            let entry = self.entries[row * width + column];
+            if entry <= TOLERANCE {
+                continue;
+            }
+            let ratio = self.entries[row * width + width - 1] / entry;
+            let better =
+                best.is_none_or(|(best_ratio, best_row)| ratio < best_ratio - TOLERANCE || (ratio <= best_ratio + TOLERANCE && self.basis[row] < self.basis[best_row]));
+            if better {
+                best = Some((ratio, row));
+            }
+        }
+        best.map(|(_, row)| row)
+    }
+
+    fn pivot(&mut self, row: usize, column: usize) {
+        let width = self.width();
+        let start = row * width;
+        let scale = self.entries[start + column];
+        self.entries[start..start + width].iter_mut().for_each(|entry| *entry /= scale);
+        let pivot_row = self.entries[start..start + width].to_vec();
+
+        for other in (0..self.num_rows).filter(|&other| other != row) {
+            let factor = self.entries[other * width + column];
+            if factor != 0.0 {
+                for (entry, &pivot) in self.entries[other * width..(other + 1) * width].iter_mut().zip(&pivot_row) {
+                    *entry -= factor * pivot;
+                }
+            }
+        }
+        let factor = self.objective[column];
+        for (entry, &pivot) in self.objective.iter_mut().zip(&pivot_row) {
+            *entry -= factor * pivot;
+        }
+        self.basis[row] = column;
+    }
+
+    // values of the structural columns
+    fn solution(&self) -> Vec<f64> {
+        let width = self.width();
+        let mut x = vec![0.0; self.num_columns];
+        for (row, &column) in self.basis.iter().enumerate() {
+            if column < self.num_columns {
+                x[column] = self.entries[row * width + width - 1].max(0.0);
+            }
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, upper_bound};
+    use crate::exact;
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn splits_an_item() {
+        // either agent alone spends 5 of the bid of 10, half the item each spends 10
+        let mut instance = Instance::new(2, 1);
+        instance.set_budget(0, 5.0);
+        instance.set_budget(1, 5.0);
+        instance.add_bid(0, 0, 10.0);
+        instance.add_bid(1, 0, 10.0);
+
+        let lp = solve(&instance);
+        assert!((lp.objective - 10.0).abs() < 1e-9);
+        assert!((lp.value(0, 0) + lp.value(1, 0) - 1.0).abs() < 1e-9);
+        assert!((lp.item_fractions(1)[0] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn skips_unusable_bids() {
+        let mut instance = Instance::new(2, 3);
+        instance.set_budget(0, 0.0);
+        instance.set_budget(1, 4.0);
+        instance.add_bid(0, 0, 3.0);
+        instance.add_bid(1, 1, 0.0);
+        instance.add_bid(1, 2, 1.0);
+        instance.add_bid(1, 2, 2.0);
+
+        let lp = solve(&instance);
+        assert_eq!(lp.values, vec![(1, 2, 1.0)]);
+        assert_eq!(lp.objective, 2.0);
+        assert_eq!(upper_bound(&Instance::new(3, 3)), 0.0);
+    }
+
+    #[test]
+    fn between_dual_and_optimum() {
+        for seed in 0..30 {
+            let instance = generate(4, 9, 0.6, (5.0, 30.0), (1.0, 12.0), seed);
+            let lp = solve(&instance);
+            let fractions = lp.item_fractions(instance.num_items);
+            assert!(fractions.iter().all(|&fraction| fraction <= 1.0 + 1e-9));
+
+            // the solver rejects bids above the budget, so its dual bounds the LP of the bids it accepted
+            let mut solver = PrimalDual::from_instance(&instance, 0.05);
+            solver.solve();
+            let accepted = upper_bound(&solver.to_instance());
+            let tolerance = 1e-7 * lp.objective.max(1.0);
+            assert!(solver.get_dual_objective_value() >= accepted - tolerance);
+            assert!(accepted >= solver.get_primal_objective_value() - tolerance);
+            assert!(lp.objective >= accepted - tolerance);
+
+            let mut bids = vec![vec![0.0; instance.num_items]; instance.num_agents];
+            for &(agent_id, item_id, bid) in &instance.bids {
+                bids[agent_id][item_id] = bid;
+            }
+            let (optimum, _) = exact::solve(&instance.budgets, &bids).unwrap();
+            assert!(lp.objective >= optimum - tolerance);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// online maximum budgeted allocation: items arrive one at a time and are assigned on arrival, irrevocably
+//
+// each item goes to the bidder of the largest scaled bid min(bid, remaining budget) * (1 - e^(x - 1)), where x is the
+// fraction of its budget the agent has spent, ties to the lower agent id (Mehta, Saberi, Vazirani, Vazirani).
+// this is a (1 - 1/e)-approximation of the optimum as the bids become small against the budgets.
+// an agent that has spent more than 1 - epsilon of its budget is treated as exhausted and gets no further items
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+
+pub struct OnlineAllocator {
+    epsilon: f64,
+    budgets: Vec<f64>,
+    spends: Vec<f64>,
+    item_owner: Vec<Option<usize>>, // item_owner[item_id] = agent_id, item_id counting the calls of assign
+    item_bids: Vec<f64>,            // item_bids[item_id] = bid of its owner, 0 for unassigned items
+}
+
+impl OnlineAllocator {
+    pub fn new(budgets: Vec<f64>, epsilon: f64) -> Self {
+        assert!((0.0..1.0).contains(&epsilon), "epsilon must be in [0, 1), got {}", epsilon);
+        debug_assert!(budgets.iter().all(|&budget| budget >= 0.0));
+        OnlineAllocator {
+            epsilon,
+            spends: vec![0.0; budgets.len()],
+            budgets,
+            item_owner: Vec::new(),
+            item_bids: Vec::new(),
+        }
+    }
+
+    // assigns the next item, bid on by [(agent_id, bid), ...], and returns its owner; None when no bidder has budget left.
+    // of several bids of an agent the last counts
+    pub fn assign(&mut self, bids_for_item: &[(usize, f64)]) -> Option<usize> {
+        let mut best: Option<(f64, usize, f64)> = None;
+        for (index, &(agent_id, bid)) in bids_for_item.iter().enumerate() {
+            if bids_for_item[index + 1..].iter().any(|&(later, _)| later == agent_id) {
+                continue;
+            }
+            let score = self.scaled_bid(agent_id, bid);
+            if score > 0.0 && best.is_none_or(|(best_score, best_id, _)| score > best_score || (score == best_score && agent_id < best_id)) {
+                best = Some((score, agent_id, bid));
+            }
+        }
+
+        let owner = best.map(|(_, agent_id, bid)| {
+            self.spends[agent_id] += bid;
+            self.item_bids.push(bid);
+            agent_id
+        });
+        if owner.is_none() {
+            self.item_bids.push(0.0);
+        }
+        self.item_owner.push(owner);
+        owner
+    }
+
+    fn scaled_bid(&self, agent_id: usize, bid: f64) -> f64 {
+        let budget = self.budgets[agent_id];
+        let spend = self.spends[agent_id];
+        if bid <= 0.0 || budget <= 0.0 || spend > (1.0 - self.epsilon) * budget {
+            return 0.0;
+        }
+        let fraction = spend / budget;
+        bid.min(budget - spend) * (1.0 - (fraction - 1.0).exp())
+    }
+
+    pub fn num_items(&self) -> usize {
+        self.item_owner.len()
+    }
+
+    // sum of the bids on the items of agent_id, which may exceed its budget
+    pub fn spend(&self, agent_id: usize) -> f64 {
+        self.spends[agent_id]
+    }
+
+    pub fn spends(&self) -> &[f64] {
+        &self.spends
+    }
+
+    pub fn item_owner(&self) -> &[Option<usize>] {
+        &self.item_owner
+    }
+
+    // sum over the agents of min(spend, budget)
+    pub fn objective(&self) -> f64 {
+        self.spends.iter().zip(&self.budgets).map(|(spend, budget)| spend.min(*budget)).sum()
+    }
+
+    pub fn assignment(&self) -> Assignment {
+        let mut items = vec![Vec::new(); self.budgets.len()];
+        let mut bids = vec![Vec::new(); self.budgets.len()];
+        for (item_id, owner) in self.item_owner.iter().enumerate() {
+            if let Some(agent_id) = *owner {
+                items[agent_id].push(item_id);
+                bids[agent_id].push(self.item_bids[item_id]);
+            }
+        }
+        Assignment::new(items, bids, self.num_items(), self.budgets.clone())
+    }
+}
+
+// the items of instance arrive in the order of their ids
+pub fn replay(instance: &Instance, epsilon: f64) -> OnlineAllocator {
+    let mut item_bids = vec![Vec::new(); instance.num_items];
+    for &(agent_id, item_id, bid) in &instance.bids {
+        item_bids[item_id].push((agent_id, bid));
+    }
+    let mut allocator = OnlineAllocator::new(instance.budgets.clone(), epsilon);
+    for bids in &item_bids {
+        allocator.assign(bids);
+    }
+    allocator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, OnlineAllocator};
+    use crate::generator::generate;
+    use crate::lp;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn balances_spend() {
+        // equal bids go to the agent that has spent the smaller fraction of its budget
+        let mut allocator = OnlineAllocator::new(vec![10.0, 10.0], 0.0);
+        assert_eq!(allocator.assign(&[(0, 4.0), (1, 4.0)]), Some(0));
+        assert_eq!(allocator.assign(&[(0, 4.0), (1, 4.0)]), Some(1));
+        assert_eq!(allocator.assign(&[(0, 4.0), (1, 3.0)]), Some(0));
+        assert_eq!(allocator.assign(&[(1, 1.0), (0, 9.0), (0, 0.0)]), Some(1));
+        assert_eq!(allocator.assign(&[]), None);
+        assert_eq!(allocator.spends(), &[8.0, 5.0]);
+        assert_eq!(allocator.objective(), 13.0);
+        assert_eq!(allocator.item_owner(), &[Some(0), Some(1), Some(0), Some(1), None]);
+
+        let assignment = allocator.assignment();
+        assert_eq!(assignment.items_of(0), &[0, 2]);
+        assert_eq!(assignment.total_value(), 13.0);
+    }
+
+    #[test]
+    fn exhausted_agents() {
+        let mut allocator = OnlineAllocator::new(vec![10.0, 0.0], 0.1);
+        assert_eq!(allocator.assign(&[(0, 9.5), (1, 5.0)]), Some(0));
+        // 9.5 of 10 is within epsilon of the budget
+        assert_eq!(allocator.assign(&[(0, 0.5)]), None);
+        assert_eq!(allocator.objective(), 9.5);
+    }
+
+    #[test]
+    fn against_offline() {
+        for seed in 0..10 {
+            // bids small against the budgets, where the 1 - 1/e bound applies
+            let instance = generate(5, 200, 0.5, (50.0, 100.0), (0.5, 2.0), seed);
+            let online = replay(&instance, 0.0);
+            assert_eq!(online.num_items(), instance.num_items);
+            assert_eq!(online.assignment().total_value(), online.objective());
+
+            let mut offline = PrimalDual::from_instance(&instance, 0.01);
+            offline.solve();
+            let upper_bound = lp::upper_bound(&instance);
+            assert!(online.objective() <= upper_bound + 1e-9);
+            assert!(online.objective() >= (1.0 - 1.0 / std::f64::consts::E) * (1.0 - 2.0 / 50.0) * upper_bound);
+            assert!(online.objective() <= offline.get_dual_objective_value() + 1e-9);
+        }
+    }
+}
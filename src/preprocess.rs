@@ -0,0 +1,139 @@
+// canonical form of an instance with the parts that cannot change the objective removed:
+// non-positive and NaN bids, all but the last bid on a pair, agents with a budget of 0 or without bids and items without
+// bids. the remaining agents and items keep their relative order, so a solver sees the same instance up to the ids
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+use std::collections::HashMap;
+
+// ids of the preprocessed instance in the original one
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdMaps {
+    pub agent_ids: Vec<usize>, // agent_ids[agent_id] = original id of agent_id
+    pub item_ids: Vec<usize>,  // item_ids[item_id] = original id of item_id
+    pub budgets: Vec<f64>,     // budgets of the original agents
+    pub num_items: usize,      // items of the original instance
+}
+
+impl IdMaps {
+    // assignment of the preprocessed instance in the original ids; a removed agent holds nothing
+    pub fn restore(&self, assignment: &Assignment) -> Assignment {
+        debug_assert_eq!((assignment.num_agents(), assignment.num_items()), (self.agent_ids.len(), self.item_ids.len()));
+        let num_agents = self.budgets.len();
+        let mut items = vec![Vec::new(); num_agents];
+        let mut bids = vec![Vec::new(); num_agents];
+        let mut budgets = self.budgets.clone();
+        for (agent_id, item_id, bid) in assignment {
+            items[self.agent_ids[agent_id]].push(self.item_ids[item_id]);
+            bids[self.agent_ids[agent_id]].push(bid);
+        }
+        for (agent_id, &original) in self.agent_ids.iter().enumerate() {
+            budgets[original] = assignment.budget(agent_id);
+        }
+        Assignment::new(items, bids, self.num_items, budgets)
+    }
+}
+
+impl Instance {
+    // the preprocessed instance and the ids to restore its assignments with. a pair bid on twice keeps the place of its
+    // first bid and the value of its last, as set_bid does
+    pub fn preprocess(&self) -> (Instance, IdMaps) {
+        let mut bids: Vec<(usize, usize, f64)> = Vec::with_capacity(self.bids.len());
+        let mut positions: HashMap<(usize, usize), usize> = HashMap::new();
+        for &(agent_id, item_id, bid) in &self.bids {
+            match positions.get(&(agent_id, item_id)) {
+                Some(&position) => bids[position].2 = bid,
+                None => {
+                    positions.insert((agent_id, item_id), bids.len());
+                    bids.push((agent_id, item_id, bid));
+                }
+            }
+        }
+        bids.retain(|&(agent_id, _, bid)| bid > 0.0 && self.budgets[agent_id] > 0.0);
+
+        let mut agent_kept = vec![false; self.num_agents];
+        let mut item_kept = vec![false; self.num_items];
+        for &(agent_id, item_id, _) in &bids {
+            agent_kept[agent_id] = true;
+            item_kept[item_id] = true;
+        }
+        let renumber = |kept: &[bool]| -> (Vec<usize>, Vec<usize>) {
+            let mut new_ids = vec![usize::MAX; kept.len()];
+            let mut original_ids = Vec::new();
+            for (id, _) in kept.iter().enumerate().filter(|&(_, &kept)| kept) {
+                new_ids[id] = original_ids.len();
+                original_ids.push(id);
+            }
+            (new_ids, original_ids)
+        };
+        let (new_agent_ids, agent_ids) = renumber(&agent_kept);
+        let (new_item_ids, item_ids) = renumber(&item_kept);
+
+        let instance = Instance {
+            num_agents: agent_ids.len(),
+            num_items: item_ids.len(),
+            budgets: agent_ids.iter().map(|&agent_id| self.budgets[agent_id]).collect(),
+            bids: bids
+                .into_iter()
+                .map(|(agent_id, item_id, bid)| (new_agent_ids[agent_id], new_item_ids[item_id], bid))
+                .collect(),
+        };
+        let maps = IdMaps {
+            agent_ids,
+            item_ids,
+            budgets: self.budgets.clone(),
+            num_items: self.num_items,
+        };
+        (instance, maps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn removed() {
+        let mut instance = Instance::new(4, 5);
+        instance.budgets = vec![10.0, 0.0, 8.0, 6.0];
+        // agent 1 has no budget, agent 3 only a zero bid, items 1 and 4 no usable bid
+        for (agent_id, item_id, bid) in [(0, 0, 3.0), (1, 1, 2.0), (2, 2, 1.0), (0, 3, 4.0), (2, 2, 5.0), (3, 4, 0.0), (0, 0, 2.0)] {
+            instance.add_bid(agent_id, item_id, bid);
+        }
+        let (preprocessed, maps) = instance.preprocess();
+        assert_eq!(maps.agent_ids, vec![0, 2]);
+        assert_eq!(maps.item_ids, vec![0, 2, 3]);
+        assert_eq!(preprocessed.budgets, vec![10.0, 8.0]);
+        assert_eq!(preprocessed.bids, vec![(0, 0, 2.0), (1, 1, 5.0), (0, 2, 4.0)]);
+
+        let mut solver = PrimalDual::from_instance(&preprocessed, 0.01);
+        solver.solve();
+        let restored = maps.restore(&solver.assignment());
+        assert_eq!(restored.num_agents(), 4);
+        assert_eq!(restored.num_items(), 5);
+        assert_eq!(restored.items_of(2), &[2]);
+        assert_eq!(restored.agent_of(3), Some(0));
+        assert_eq!(restored.agent_of(1), None);
+        assert_eq!(restored.total_value(), 11.0);
+    }
+
+    #[test]
+    fn same_objective() {
+        for seed in 0..10 {
+            let mut instance = generate(10, 80, 0.1, (0.0, 30.0), (0.5, 8.0), 302 + seed);
+            instance.budgets[seed as usize] = 0.0;
+            let (preprocessed, maps) = instance.preprocess();
+            assert!(preprocessed.num_items <= instance.num_items);
+
+            let mut solver = PrimalDual::from_instance(&instance, 0.01);
+            solver.solve();
+            let mut reduced = PrimalDual::from_instance(&preprocessed, 0.01);
+            reduced.solve();
+            let restored = maps.restore(&reduced.assignment());
+            assert_eq!(restored.total_value(), solver.get_primal_objective_value());
+            assert_eq!(reduced.get_dual_objective_value(), solver.get_dual_objective_value());
+            assert_eq!(restored, solver.assignment());
+        }
+    }
+}
@@ -1,318 +1,3198 @@
+use crate::assignment::Assignment;
+use crate::exact;
+use crate::instance::Instance;
+use crate::reduce;
+use crate::rng::RngConfig;
+use crate::solution::Solution;
+use num_traits::Float;
 use ordered_float::NotNan;
+use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
-pub struct PrimalDual {
+mod agent_capacity;
+mod agent_epsilon;
+#[cfg(feature = "ndarray")]
+mod arrays;
+mod auto;
+mod cancel;
+mod capacity;
+mod config;
+mod csr;
+mod dry_run;
+mod error;
+mod frozen;
+mod gap;
+mod inactive;
+mod integer;
+mod landscape;
+mod limit;
+mod local_search;
+mod lp_duals;
+mod max_items;
+mod observer;
+mod options;
+mod parallel;
+mod reallocation;
+mod removal;
+mod reserve;
+mod reset;
+mod restarts;
+mod scalar;
+mod sensitivity;
+mod stats;
+mod step;
+mod unassigned;
+mod uniform;
+mod utilization;
+mod verify;
+mod warm_start;
+mod watchdog;
+
+pub use auto::AutoSolveReport;
+pub use cancel::SolveOutcome;
+pub use config::{BuildError, SolverConfig};
+pub use dry_run::DryRunReport;
+pub use error::MbaError;
+pub use frozen::FrozenSolution;
+pub use gap::{AgentGap, GapBreakdown};
+pub use inactive::{InactiveAgent, InactiveReason};
+pub use integer::{IntPrimalDual, MAX_EXACT};
+pub use landscape::{landscape_to_csv, Bidder, ItemLandscape};
+pub use limit::{SolveLimit, SolveStatus};
+pub use local_search::{LocalSearch, LocalSearchReport};
+pub use lp_duals::LpDuals;
+use observer::Observer;
+pub use observer::SolveEvent;
+pub use options::{SolveOptions, SolveResult};
+pub use restarts::RestartReport;
+pub use scalar::Scalar;
+pub use sensitivity::AgentSensitivity;
+pub use stats::SolveStats;
+pub use step::StepStatus;
+use step::{Progress, Sweep};
+pub use unassigned::UnassignedReason;
+pub use utilization::{AgentSpend, UtilizationAttempt, UtilizationOutcome, UtilizationStatus};
+pub use verify::{SolutionReport, VerifyError};
+pub use watchdog::WatchdogConfig;
+
+// how set_bid treats a bid larger than the agent's budget
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum OverbidPolicy {
+    // drop the bid
+    #[default]
+    Reject,
+    // store min(bid, budget); the agent can be charged at most its budget
+    Clamp,
+    // panic
+    Error,
+}
+
+// what solve does with an item whose bids were all rejected by OverbidPolicy::Reject
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum UnassignablePolicy {
+    // leave the item unassigned
+    #[default]
+    Ignore,
+    // admit the largest rejected bid clamped to its agent's budget, which makes beta 1
+    ClampBestBid,
+    // panic listing the items
+    Error,
+}
+
+// value alpha takes on the first update of an agent
+//
+// the approximation proof only needs alpha to grow by at most a (1 + epsilon) factor per update,
+// so starting below epsilon keeps the guarantee; a smaller alpha keeps more items with the agent
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum InitialAlpha {
+    #[default]
+    Epsilon,
+    // must be in (0, epsilon]. an epsilon lowered below it afterwards, globally or for one agent, lowers it to that
+    // epsilon, since a larger start would break the guarantee
+    Fixed(f64),
+    // smallest epsilon / (1 + epsilon)^k that makes the agent paid for, computed from its overspend
+    Adaptive,
+}
+
+// what set_budget and set_bid do with a nonzero value whose magnitude is outside the safe range
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum RangePolicy {
+    // accept the value and record a RangeWarning, see get_range_warnings
+    #[default]
+    Warn,
+    // panic
+    Error,
+}
+
+// a value accepted under RangePolicy::Warn although its magnitude is outside the safe range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeWarning {
+    pub name: &'static str, // "budget" or "bid"
+    pub value: f64,
+    pub safe_range: (f64, f64),
+}
+
+impl std::fmt::Display for RangeWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} is outside the safe range [{}, {}]", self.name, self.value, self.safe_range.0, self.safe_range.1)
+    }
+}
+
+// order in which a sweep visits the agents
+//
+// every order gives the approximation guarantee, but the final assignment may differ between orders
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum AgentOrder {
+    #[default]
+    IndexOrder,
+    // by consumption - U * budget descending, recomputed every sweep
+    LargestOverspendFirst,
+    // by number of accepted bids descending
+    MostBidsFirst,
+    // by effective budget descending
+    LargestBudgetFirst,
+    // a permutation drawn from the seed, the same in every sweep
+    Random(u64),
+    // the permutation of set_agent_permutation, index order while there is none for the current number of agents
+    Custom,
+}
+
+// inside this band sums over any realistic number of items can neither overflow nor lose the bids to underflow
+pub const DEFAULT_SAFE_RANGE: (f64, f64) = (1e-150, 1e150);
+
+// alpha never exceeds 1 minus this many machine epsilons of the scalar type
+const ALPHA_MARGIN_EPSILONS: f64 = 16.0;
+
+// (price, agent_id, num_update); equal prices go to the lower agent id
+type PriceEntry<T> = (NotNan<T>, Reverse<usize>, u32);
+
+// budgets, bids, alphas, consumptions and heap prices are stored as T; every getter and objective reports f64.
+// a clone is independent of the original, heaps and update counters included, so it can be solved on another thread
+#[derive(Clone)]
+pub struct PrimalDual<T: Scalar = f64> {
     num_agents: usize,
     num_items: usize,
     epsilon: f64,
-    budgets: Vec<f64>,
-    bid: Vec<Vec<f64>>,
+    budgets: Vec<T>,
+    bid: Vec<HashMap<usize, T>>,  // bid[agent_id][item_id] = accepted bid; absent when the agent does not bid on the item
+    agent_items: Vec<Vec<usize>>, // agent_items[agent_id] = [item_id, ...] with an accepted bid, in insertion order
+    item_agents: Vec<Vec<usize>>, // item_agents[item_id] = [agent_id, ...] with an accepted bid, in insertion order
 
-    alpha: Vec<f64>,
+    alpha: Vec<T>,
     beta: f64,
-    consumptions: Vec<f64>,
-    gamma: Vec<VecDeque<usize>>,                            // gamma[agent_id] = [item_id, ...]
-    item_agent: Vec<BinaryHeap<(NotNan<f64>, u32, usize)>>, // item_agent[item_id] = [(price, num_update, agent_id), ...]
+    consumptions: Vec<T>,
+    gamma: Vec<VecDeque<usize>>,                // gamma[agent_id] = [item_id, ...]
+    item_agent: Vec<BinaryHeap<PriceEntry<T>>>, // item_agent[item_id] = [(price, agent_id, num_update), ...]
     num_update: Vec<u32>,
-    max_bid_agent: Vec<usize>,
+    max_bid_agent: Vec<Option<usize>>, // max_bid_agent[item_id] = agent_id with the largest accepted bid, None if nobody bids
+    item_capacity: Vec<usize>,         // item_capacity[item_id] = number of distinct agents that may hold item_id, see capacity
+    max_items: Vec<Option<usize>>,     // max_items[agent_id] = most items agent_id may hold, None for no cap, see max_items
+    sizes: Vec<HashMap<usize, f64>>,   // sizes[agent_id][item_id] = capacity item_id uses when agent_id holds it, absent for 0, see agent_capacity
+    agent_capacity: Vec<Option<f64>>,  // agent_capacity[agent_id] = total size agent_id may hold, None for no capacity
+    reserve_price: Vec<f64>,           // reserve_price[item_id] = smallest bid accepted on item_id, see reserve
+    overbid_policy: OverbidPolicy,
+    initial_alpha: InitialAlpha,
+    agent_epsilon: Vec<Option<f64>>,       // agent_epsilon[agent_id] = epsilon of agent_id instead of the global one, see agent_epsilon
+    agent_initial_alpha: Vec<Option<f64>>, // agent_initial_alpha[agent_id] = first alpha of agent_id instead of initial_alpha
+    unassignable_policy: UnassignablePolicy,
+    rejected_bids: Vec<(usize, usize, f64)>,      // [(agent_id, item_id, bid), ...] dropped by OverbidPolicy::Reject
+    below_reserve_bids: Vec<(usize, usize, f64)>, // [(agent_id, item_id, bid), ...] disabled by a reserve price
+
+    rates: Vec<f64>,                       // rates[agent_id] = rate from the agent's currency to the base currency
+    original_budgets: Vec<Option<f64>>,    // original_budgets[agent_id] = budget in the agent's currency
+    original_bids: Vec<Vec<(usize, f64)>>, // original_bids[agent_id] = [(item_id, bid in the agent's currency), ...]
+
+    reduction_chunk_size: usize,
+
+    previous_owner: Vec<Option<usize>>, // previous_owner[item_id] = agent_id that held item_id in the previous solution
+    switch_penalty: f64,
+
+    safe_range: (f64, f64),
+    range_policy: RangePolicy,
+    range_warnings: Vec<RangeWarning>, // see get_range_warnings
+
+    active: Vec<bool>,
+
+    round_budget_fraction: f64,
+    carried_spend: Vec<f64>, // carried_spend[agent_id] = spend of agent_id in earlier rounds
+
+    max_memory_bytes: Option<usize>,
+    num_accepted_bids: usize, // sum of the sizes of the bid maps, which max_memory_bytes bounds with the rest, see config
+
+    owners_scratch: Vec<Option<usize>>, // buffer of reallocation::classify and initialize
+    #[cfg(feature = "rayon")]
+    parallel_min_items: usize, // fewest items a parallel pass of parallel.rs handles; fewer are done in sequence
+
+    agent_order: AgentOrder,
+    agent_permutation: Vec<usize>, // visit order of AgentOrder::Custom
+    visit_order: Vec<usize>,       // agents in the order of the current sweep
+    sweep: Option<Sweep>,          // sweep in progress between two calls of step or advance, see step
+    stepping: bool,                // step has initialized, so the next step continues the sweeps
+
+    observer: Observer, // see set_observer
+
+    initialized: bool,         // gamma and consumptions hold the assignment of an earlier initialize, which the next solve starts from
+    new_bid_items: Vec<usize>, // items bid on since then, see warm_start
+    changed: bool,             // since then something other than a bid changed, so even solve_warm starts from scratch
+
+    num_reallocations: usize, // rounds of reallocation::reallocate since the last reset
+    num_alpha_updates: usize, // calls of update_alpha since the last reset
+    num_item_moves: usize,    // items handed over or dropped by reallocation::apply_transfers since the last reset
+    num_unique_raises: usize, // rounds of reallocation::raise_alpha whose items had no other bidder, since the last reset
+    num_forced_drops: usize,  // items dropped by reallocation::force_drop since the last reset
 }
 
 // On the Approximability of Budgeted Allocations and Improved Lower Bounds for Submodular Welfare Maximization and GAP
 // (1 - beta / 4)(1 - epsilon)-approximation algorithm for maximum budgeted allocation
 // n : num of agents, m : num of items
 // Õ(nm / epsilon)
-#[allow(dead_code)]
 impl PrimalDual {
+    // panics where try_new fails
     pub fn new(num_agents: usize, num_items: usize, epsilon: f64) -> Self {
+        Self::new_scalar(num_agents, num_items, epsilon)
+    }
+
+    // epsilon must be in [0, 1); 0 asks for the exact optimum, which is computed by exact::solve and
+    // therefore needs num_agents^num_items <= exact::MAX_ASSIGNMENTS.
+    // a positive epsilon trades the guarantee (1 - epsilon) for about 1 / epsilon alpha updates per agent: 0.5 still
+    // guarantees (1 - beta / 4) / 2, and 1e-6 takes on the order of a million updates per agent, so smaller values are
+    // only practical on small instances
+    pub fn try_new(num_agents: usize, num_items: usize, epsilon: f64) -> Result<Self, BuildError> {
+        Self::try_new_scalar(num_agents, num_items, epsilon)
+    }
+
+    // budgets and bids of instance, in the order of instance.bids
+    pub fn from_instance(instance: &Instance, epsilon: f64) -> Self {
+        Self::from_instance_scalar(instance, epsilon)
+    }
+
+    // bid_matrix[agent_id][item_id] = bid, 0 for no bid; every row has num_items entries
+    pub fn from_dense(budgets: &[f64], bid_matrix: &[Vec<f64>], epsilon: f64) -> Self {
+        Self::from_dense_scalar(budgets, bid_matrix, epsilon)
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Scalar> PrimalDual<T> {
+    // new with the scalar type spelled out, e.g. PrimalDual::<f32>::new_scalar
+    pub fn new_scalar(num_agents: usize, num_items: usize, epsilon: f64) -> Self {
+        Self::try_new_scalar(num_agents, num_items, epsilon).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new_scalar(num_agents: usize, num_items: usize, epsilon: f64) -> Result<Self, BuildError> {
+        if !(0.0..1.0).contains(&epsilon) {
+            return Err(BuildError::InvalidEpsilon { epsilon });
+        }
+        if epsilon == 0.0 && !exact::fits_shape(num_agents, num_items) {
+            return Err(BuildError::TooLargeForExact { num_agents, num_items });
+        }
+        Ok(Self::build(num_agents, num_items, epsilon))
+    }
+
+    // budgets and bids of instance rounded to T
+    pub fn from_instance_scalar(instance: &Instance, epsilon: f64) -> Self {
+        let mut solver = Self::new_scalar(instance.num_agents, instance.num_items, epsilon);
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            solver.set_budget(agent_id, T::from_f64(budget));
+        }
+        solver.set_bids(instance.bids.iter().map(|&(agent_id, item_id, bid)| (agent_id, item_id, T::from_f64(bid))));
+        solver
+    }
+
+    // from_dense rounded to T
+    pub fn from_dense_scalar(budgets: &[f64], bid_matrix: &[Vec<f64>], epsilon: f64) -> Self {
+        let num_items = bid_matrix.first().map_or(0, |row| row.len());
+        let mut solver = Self::new_scalar(budgets.len(), num_items, epsilon);
+        solver.load_dense(budgets, bid_matrix).unwrap_or_else(|e| panic!("{}", e));
+        solver
+    }
+
+    // the budgets and the nonzero bids of bid_matrix, with the bid maps reserved up front
+    fn load_dense(&mut self, budgets: &[f64], bid_matrix: &[Vec<f64>]) -> Result<(), MbaError> {
+        debug_assert_eq!(budgets.len(), bid_matrix.len());
+        debug_assert!(bid_matrix.iter().all(|row| row.len() == self.num_items));
+
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            self.try_set_budget(agent_id, T::from_f64(budget))?;
+        }
+        for (agent_id, row) in bid_matrix.iter().enumerate() {
+            let num_bids = row.iter().filter(|&&bid| bid != 0.0).count();
+            self.bid[agent_id].reserve(num_bids);
+            self.agent_items[agent_id].reserve(num_bids);
+        }
+        for (item_id, item_agents) in self.item_agents.iter_mut().enumerate() {
+            item_agents.reserve(bid_matrix.iter().filter(|row| row[item_id] != 0.0).count());
+        }
+
+        let bids = bid_matrix.iter().enumerate().flat_map(|(agent_id, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|&(_, &bid)| bid != 0.0)
+                .map(move |(item_id, &bid)| (agent_id, item_id, T::from_f64(bid)))
+        });
+        self.try_set_bids(bids)
+    }
+
+    fn build(num_agents: usize, num_items: usize, epsilon: f64) -> Self {
         PrimalDual {
             num_agents,
             num_items,
             epsilon,
-            budgets: vec![0.0; num_agents],
-            bid: vec![vec![0.0; num_items]; num_agents],
-            alpha: vec![0.0; num_agents],
+            budgets: vec![T::zero(); num_agents],
+            bid: vec![HashMap::new(); num_agents],
+            agent_items: vec![Vec::new(); num_agents],
+            item_agents: vec![Vec::new(); num_items],
+            alpha: vec![T::zero(); num_agents],
             beta: 0.0,
-            consumptions: vec![0.0; num_agents],
+            consumptions: vec![T::zero(); num_agents],
             gamma: vec![VecDeque::new(); num_agents],
             item_agent: vec![BinaryHeap::new(); num_items],
             num_update: vec![0; num_agents],
-            max_bid_agent: vec![num_agents; num_items],
+            max_bid_agent: vec![None; num_items],
+            item_capacity: vec![1; num_items],
+            max_items: vec![None; num_agents],
+            sizes: vec![HashMap::new(); num_agents],
+            agent_capacity: vec![None; num_agents],
+            reserve_price: vec![0.0; num_items],
+            overbid_policy: OverbidPolicy::default(),
+            initial_alpha: InitialAlpha::default(),
+            agent_epsilon: vec![None; num_agents],
+            agent_initial_alpha: vec![None; num_agents],
+            unassignable_policy: UnassignablePolicy::default(),
+            rejected_bids: Vec::new(),
+            below_reserve_bids: Vec::new(),
+            rates: vec![1.0; num_agents],
+            original_budgets: vec![None; num_agents],
+            original_bids: vec![Vec::new(); num_agents],
+            reduction_chunk_size: reduce::DEFAULT_CHUNK_SIZE,
+            previous_owner: vec![None; num_items],
+            switch_penalty: 0.0,
+            safe_range: DEFAULT_SAFE_RANGE,
+            range_policy: RangePolicy::default(),
+            range_warnings: Vec::new(),
+            active: vec![true; num_agents],
+            round_budget_fraction: 1.0,
+            carried_spend: vec![0.0; num_agents],
+            max_memory_bytes: None,
+            num_accepted_bids: 0,
+            owners_scratch: Vec::new(),
+            #[cfg(feature = "rayon")]
+            parallel_min_items: parallel::MIN_ITEMS,
+            agent_order: AgentOrder::default(),
+            agent_permutation: Vec::new(),
+            visit_order: Vec::new(),
+            sweep: None,
+            stepping: false,
+            observer: Observer::default(),
+            initialized: false,
+            new_bid_items: Vec::new(),
+            changed: false,
+            num_reallocations: 0,
+            num_alpha_updates: 0,
+            num_item_moves: 0,
+            num_unique_raises: 0,
+            num_forced_drops: 0,
         }
     }
 
-    pub fn set_budget(&mut self, agent_id: usize, budget: f64) {
-        debug_assert!(budget >= 0.0);
+    // panics where try_set_budget fails
+    pub fn set_budget(&mut self, agent_id: usize, budget: T) {
+        self.try_set_budget(agent_id, budget).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // a budget in base currency; the amount of an earlier set_budget_in no longer applies, so update_rate keeps it
+    pub fn try_set_budget(&mut self, agent_id: usize, budget: T) -> Result<(), MbaError> {
+        self.store_budget(agent_id, budget)?;
+        self.original_budgets[agent_id] = None;
+        Ok(())
+    }
+
+    // try_set_budget without touching the amount of set_budget_in
+    fn store_budget(&mut self, agent_id: usize, budget: T) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        let value = budget.as_f64();
+        if !(value >= 0.0 && value.is_finite()) {
+            return Err(MbaError::InvalidBudget { agent_id, budget: value });
+        }
+        self.check_range("budget", value)?;
+        let previous = self.budget(agent_id);
         self.budgets[agent_id] = budget;
+        self.mark_changed();
+        self.update_beta_for_budget(agent_id, previous);
+        self.readmit_rejected_bids(agent_id)
+    }
+
+    // beta as if the bids of agent_id had been set after its budget changed from previous
+    fn update_beta_for_budget(&mut self, agent_id: usize, previous: f64) {
+        if !self.active[agent_id] || self.bid[agent_id].is_empty() {
+            return;
+        }
+        let max_bid = self.bid[agent_id].values().fold(0.0f64, |max_bid, &bid| max_bid.max(bid.as_f64()));
+        // a bid above a lowered budget counts as the budget in the primal
+        let ratio = (max_bid / self.budget(agent_id)).min(1.0);
+        if ratio >= self.beta {
+            self.beta = ratio;
+        } else if (max_bid / previous).min(1.0) >= self.beta {
+            // agent_id may have been the one defining beta
+            self.recompute_beta();
+        }
+    }
+
+    // the last rejected bid of agent_id on every item without an accepted bid is offered again,
+    // so that bids set before the budget are not lost to a budget that was still 0
+    fn readmit_rejected_bids(&mut self, agent_id: usize) -> Result<(), MbaError> {
+        let mut offers: Vec<(usize, f64)> = Vec::new();
+        for &(id, item_id, bid) in self.rejected_bids.iter().rev() {
+            if id == agent_id && !self.bid[agent_id].contains_key(&item_id) && offers.iter().all(|&(item, _)| item != item_id) {
+                offers.push((item_id, bid));
+            }
+        }
+        let budget = self.budget(agent_id);
+        offers.retain(|&(_, bid)| bid <= budget);
+        if offers.is_empty() {
+            return Ok(());
+        }
+
+        self.rejected_bids
+            .retain(|&(id, item_id, _)| id != agent_id || offers.iter().all(|&(item, _)| item != item_id));
+        for (item_id, bid) in offers.into_iter().rev() {
+            self.accept_bid(agent_id, item_id, T::from_f64(bid), true)?;
+        }
+        Ok(())
     }
 
     pub fn get_budget(&self, agent_id: usize) -> f64 {
-        self.budgets[agent_id]
+        self.budget(agent_id)
     }
 
-    pub fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: f64) {
-        debug_assert!(bid > 0.0);
+    // panics where try_set_safe_range fails
+    pub fn set_safe_range(&mut self, min_abs: f64, max_abs: f64) {
+        self.try_set_safe_range(min_abs, max_abs).unwrap_or_else(|e| panic!("{}", e));
+    }
 
-        if bid > self.budgets[agent_id] {
-            return;
+    // magnitudes accepted silently by set_budget and set_bid; needs 0 < min_abs <= max_abs
+    pub fn try_set_safe_range(&mut self, min_abs: f64, max_abs: f64) -> Result<(), MbaError> {
+        if !(0.0 < min_abs && min_abs <= max_abs) {
+            return Err(MbaError::InvalidSafeRange { min_abs, max_abs });
         }
+        self.safe_range = (min_abs, max_abs);
+        Ok(())
+    }
 
-        self.bid[agent_id][item_id] = bid;
-        let price = self.price(agent_id, item_id);
-        self.item_agent[item_id].push((NotNan::new(price).unwrap(), 0, agent_id));
+    pub fn set_range_policy(&mut self, policy: RangePolicy) {
+        self.range_policy = policy;
+    }
 
-        let max_bid_agent_id = self.max_bid_agent[item_id];
-        if max_bid_agent_id >= self.num_agents {
-            self.max_bid_agent[item_id] = agent_id;
-        } else {
-            let max_bid = self.bid[max_bid_agent_id][item_id];
-            if bid > max_bid {
-                self.max_bid_agent[item_id] = agent_id;
+    // the values accepted under RangePolicy::Warn since the last clear_range_warnings, in the order they were set
+    pub fn get_range_warnings(&self) -> &[RangeWarning] {
+        &self.range_warnings
+    }
+
+    pub fn clear_range_warnings(&mut self) {
+        self.range_warnings.clear();
+    }
+
+    // panics where try_set_epsilon fails
+    pub fn set_epsilon(&mut self, epsilon: f64) {
+        self.try_set_epsilon(epsilon).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // epsilon under the conditions of try_new, and 0 only while every item has capacity 1 and no agent has an item cap
+    // or a capacity, which fail with UnsupportedByExact. the next solve starts from scratch, as alphas raised in steps of
+    // another epsilon carry no guarantee
+    pub fn try_set_epsilon(&mut self, epsilon: f64) -> Result<(), MbaError> {
+        if !(0.0..1.0).contains(&epsilon) {
+            return Err(BuildError::InvalidEpsilon { epsilon }.into());
+        }
+        if epsilon == 0.0 {
+            if self.item_capacity.iter().any(|&capacity| capacity > 1) {
+                return Err(MbaError::UnsupportedByExact { setting: "item_capacity" });
+            }
+            if self.max_items.iter().any(Option::is_some) {
+                return Err(MbaError::UnsupportedByExact { setting: "max_items" });
+            }
+            if self.agent_capacity.iter().any(Option::is_some) {
+                return Err(MbaError::UnsupportedByExact { setting: "capacity" });
+            }
+            if !exact::fits_shape(self.num_agents, self.num_items) {
+                return Err(BuildError::TooLargeForExact {
+                    num_agents: self.num_agents,
+                    num_items: self.num_items,
+                }
+                .into());
             }
         }
+        self.epsilon = epsilon;
+        self.reset_solve_state();
+        Ok(())
+    }
 
-        self.beta = self.beta.max(bid / self.budgets[agent_id]);
+    pub fn set_agent_order(&mut self, order: AgentOrder) {
+        self.agent_order = order;
+        self.mark_changed();
     }
 
-    pub fn get_bid(&self, agent_id: usize, item_id: usize) -> f64 {
-        self.bid[agent_id][item_id]
+    // panics where try_set_agent_permutation fails
+    pub fn set_agent_permutation(&mut self, permutation: Vec<usize>) {
+        self.try_set_agent_permutation(permutation).unwrap_or_else(|e| panic!("{}", e));
     }
 
-    pub fn get_approximation_ratio(&self) -> f64 {
-        (1.0 - self.beta / 4.0) * (1.0 - self.epsilon)
+    // sweeps visit the agents in the order of permutation, which must hold every agent id once; sets AgentOrder::Custom
+    pub fn try_set_agent_permutation(&mut self, permutation: Vec<usize>) -> Result<(), MbaError> {
+        check_permutation(&permutation, self.num_agents)?;
+        self.agent_permutation = permutation;
+        self.set_agent_order(AgentOrder::Custom);
+        Ok(())
     }
 
-    pub fn solve(&mut self) {
-        self.initialize();
+    // applies to the bids set afterwards, the ones already set keep the policy they were set under
+    pub fn set_overbid_policy(&mut self, policy: OverbidPolicy) {
+        self.overbid_policy = policy;
+    }
+
+    pub fn get_overbid_policy(&self) -> OverbidPolicy {
+        self.overbid_policy
+    }
 
-        let mut all_agents_are_paid_for = false;
+    pub fn set_unassignable_policy(&mut self, policy: UnassignablePolicy) {
+        self.unassignable_policy = policy;
+        self.mark_changed();
+    }
 
-        while !all_agents_are_paid_for {
-            all_agents_are_paid_for = true;
+    pub fn set_initial_alpha(&mut self, value: f64) {
+        self.set_initial_alpha_mode(InitialAlpha::Fixed(value));
+    }
 
-            for agent_id in 0..self.num_agents {
-                while !self.is_paid_for(agent_id) {
-                    all_agents_are_paid_for = false;
+    // panics where try_set_initial_alpha_mode fails
+    pub fn set_initial_alpha_mode(&mut self, mode: InitialAlpha) {
+        self.try_set_initial_alpha_mode(mode).unwrap_or_else(|e| panic!("{}", e));
+    }
 
-                    let mut num_unique = 0;
-                    let num = self.gamma[agent_id].len();
-                    // erase wrongly allocated items
-                    for _ in 0..num {
-                        let item_id = self.gamma[agent_id].pop_front().unwrap();
-                        let max_agent_id = self.max_price_agent(item_id);
+    // InitialAlpha::Fixed must be in (0, epsilon]
+    pub fn try_set_initial_alpha_mode(&mut self, mode: InitialAlpha) -> Result<(), MbaError> {
+        if let InitialAlpha::Fixed(alpha) = mode {
+            if !(alpha > 0.0 && alpha <= self.epsilon) {
+                return Err(MbaError::InvalidInitialAlpha { agent_id: None, alpha });
+            }
+        }
+        self.initial_alpha = mode;
+        self.mark_changed();
+        Ok(())
+    }
 
-                        // item_id is rightly allocated
-                        if max_agent_id == agent_id {
-                            num_unique += if self.item_agent.len() == 1 { 1 } else { 0 };
-                            self.gamma[agent_id].push_back(item_id);
-                        }
-                        // item_id is wrongly allocated
-                        else {
-                            // erase item_id from agent_id
-                            self.consumptions[agent_id] -= self.bid[agent_id][item_id];
-
-                            // insert item_id to max_agent_id
-                            self.gamma[max_agent_id].push_back(item_id);
-                            self.consumptions[max_agent_id] += self.bid[max_agent_id][item_id];
-
-                            if self.is_paid_for(agent_id) {
-                                break;
-                            }
-                        }
-                    }
+    // panics where try_set_bid fails
+    pub fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: T) {
+        self.try_set_bid(agent_id, item_id, bid).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // a bid above the budget is not an error under OverbidPolicy::Reject; it is recorded for get_inactive_agents instead.
+    // like try_set_budget, it replaces the amount an earlier set_bid_in gave for the pair
+    pub fn try_set_bid(&mut self, agent_id: usize, item_id: usize, bid: T) -> Result<(), MbaError> {
+        self.accept_bid(agent_id, item_id, bid, true)?;
+        self.original_bids[agent_id].retain(|&(id, _)| id != item_id);
+        Ok(())
+    }
+
+    // panics where try_set_bids fails
+    pub fn set_bids<I: IntoIterator<Item = (usize, usize, T)>>(&mut self, bids: I) {
+        self.try_set_bids(bids).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // try_set_bid for every (agent_id, item_id, bid) in order, with the heaps, max_bid_agent and beta built once at the end
+    // instead of after every bid. stops at the first error; the bids before it stay set
+    pub fn try_set_bids<I: IntoIterator<Item = (usize, usize, T)>>(&mut self, bids: I) -> Result<(), MbaError> {
+        let mut result = Ok(());
+        for (agent_id, item_id, bid) in bids {
+            if let Err(e) = self.accept_bid(agent_id, item_id, bid, false) {
+                result = Err(e);
+                break;
+            }
+            self.original_bids[agent_id].retain(|&(id, _)| id != item_id);
+        }
+        self.rebuild_bid_index();
+        result
+    }
+
+    // try_set_bid; without index the caller rebuilds item_agent, max_bid_agent and beta afterwards
+    fn accept_bid(&mut self, agent_id: usize, item_id: usize, bid: T, index: bool) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if item_id >= self.num_items {
+            return Err(MbaError::ItemIndexOutOfRange {
+                item_id,
+                num_items: self.num_items,
+            });
+        }
+        let value = bid.as_f64();
+        if !(value > 0.0 && value.is_finite()) {
+            return Err(MbaError::InvalidBid { agent_id, item_id, bid: value });
+        }
+        self.check_range("bid", value)?;
+        self.below_reserve_bids.retain(|&(id, item, _)| (id, item) != (agent_id, item_id));
 
-                    if num_unique == num {
-                        for _ in 0..self.num_items {
-                            if self.is_paid_for(agent_id) {
-                                break;
-                            }
-                            self.update_alpha(agent_id);
+        let budget = self.budget(agent_id);
+        let bid = if value > budget {
+            match self.overbid_policy {
+                OverbidPolicy::Reject => {
+                    // the last bid on a pair wins, even when it is rejected
+                    if self.bid[agent_id].contains_key(&item_id) {
+                        self.drop_accepted_bid(agent_id, item_id);
+                        if self.initialized {
+                            self.new_bid_items.push(item_id);
                         }
                     }
+                    self.rejected_bids.push((agent_id, item_id, value));
+                    return Ok(());
+                }
+                OverbidPolicy::Clamp => self.budgets[agent_id],
+                OverbidPolicy::Error => {
+                    return Err(MbaError::BidExceedsBudget {
+                        agent_id,
+                        item_id,
+                        bid: value,
+                        budget,
+                    })
+                }
+            }
+        } else {
+            bid
+        };
+        if bid.as_f64() < self.reserve_price[item_id] {
+            if self.bid[agent_id].contains_key(&item_id) {
+                self.drop_accepted_bid(agent_id, item_id);
+                if self.initialized {
+                    self.new_bid_items.push(item_id);
+                }
+            }
+            self.below_reserve_bids.push((agent_id, item_id, value));
+            return Ok(());
+        }
 
-                    // update alpha
-                    if !self.is_paid_for(agent_id) {
-                        self.update_alpha(agent_id);
-                    }
+        // the last bid on a pair wins: the entries of an earlier one leave the heap, and beta and the max bid agent are recomputed without it
+        let previous = self.bid[agent_id].get(&item_id).map(|&previous| previous.as_f64());
+        if previous.is_none() {
+            self.check_memory(self.num_accepted_bids + 1)?;
+        }
+        self.store_bid(agent_id, item_id, bid);
+        if index {
+            if let Some(previous) = previous {
+                self.unindex_bid(agent_id, item_id);
+                if previous / budget >= self.beta {
+                    self.recompute_beta();
                 }
             }
+            self.index_bid(agent_id, item_id);
         }
+        if self.initialized {
+            self.new_bid_items.push(item_id);
+        }
+        Ok(())
     }
 
-    pub fn get_dual_objective_value(&self) -> f64 {
-        (0..self.num_agents)
-            .map(|agent_id| self.budgets[agent_id] * self.alpha[agent_id] + self.consumptions[agent_id] * (1.0 - self.alpha[agent_id]))
-            .sum()
+    // panics where try_set_budget_in fails
+    pub fn set_budget_in(&mut self, agent_id: usize, amount: f64, rate_to_base: f64) {
+        self.try_set_budget_in(agent_id, amount, rate_to_base).unwrap_or_else(|e| panic!("{}", e));
     }
 
-    pub fn get_primal_objective_value(&self) -> f64 {
-        (0..self.num_agents).map(|agent_id| self.consumptions[agent_id].min(self.budgets[agent_id])).sum()
+    // budget given in the agent's own currency; rate_to_base converts it to the base currency and must be positive and
+    // finite. the bids already set keep their base values until update_rate
+    pub fn try_set_budget_in(&mut self, agent_id: usize, amount: f64, rate_to_base: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if !(rate_to_base > 0.0 && rate_to_base.is_finite()) {
+            return Err(MbaError::InvalidRate { agent_id, rate: rate_to_base });
+        }
+        self.store_budget(agent_id, T::from_f64(amount * rate_to_base))?;
+        self.rates[agent_id] = rate_to_base;
+        self.original_budgets[agent_id] = Some(amount);
+        Ok(())
     }
 
-    pub fn get_assignment(&mut self) -> &Vec<VecDeque<usize>> {
-        self.gamma.as_mut()
+    // panics where try_set_bid_in fails
+    pub fn set_bid_in(&mut self, agent_id: usize, item_id: usize, amount: f64) {
+        self.try_set_bid_in(agent_id, item_id, amount).unwrap_or_else(|e| panic!("{}", e));
     }
 
-    fn initialize(&mut self) {
-        for (item_id, &agent_id) in self.max_bid_agent.iter().enumerate() {
-            // no agent can assign item id
-            if agent_id == self.num_agents {
-                continue;
+    // bid given in the agent's own currency, converted with the rate of set_budget_in
+    pub fn try_set_bid_in(&mut self, agent_id: usize, item_id: usize, amount: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        self.accept_bid(agent_id, item_id, T::from_f64(amount * self.rates[agent_id]), true)?;
+        self.original_bids[agent_id].retain(|&(id, _)| id != item_id);
+        self.original_bids[agent_id].push((item_id, amount));
+        Ok(())
+    }
+
+    // panics where try_update_rate fails
+    pub fn update_rate(&mut self, agent_id: usize, new_rate: f64) {
+        self.try_update_rate(agent_id, new_rate).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // corrects the rate of an agent; budget and bids are set again from the original amounts, so that they go through
+    // the checks and the overbid policy of set_budget and set_bid. they are all checked before the first is set, so a
+    // failure leaves the solver as it was. the next solve starts from scratch
+    pub fn try_update_rate(&mut self, agent_id: usize, new_rate: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if !(new_rate > 0.0 && new_rate.is_finite()) {
+            return Err(MbaError::InvalidRate { agent_id, rate: new_rate });
+        }
+        self.check_rate(agent_id, new_rate)?;
+
+        self.rates[agent_id] = new_rate;
+        self.mark_changed();
+        if let Some(amount) = self.original_budgets[agent_id] {
+            self.store_budget(agent_id, T::from_f64(amount * new_rate))?;
+        }
+        for (item_id, amount) in self.original_bids[agent_id].clone() {
+            self.accept_bid(agent_id, item_id, T::from_f64(amount * new_rate), true)?;
+        }
+        Ok(())
+    }
+
+    // the errors store_budget and accept_bid would return when try_update_rate sets the amounts of agent_id at new_rate,
+    // including a memory limit reached by bids the new budget readmits
+    fn check_rate(&self, agent_id: usize, new_rate: f64) -> Result<(), MbaError> {
+        let budget = match self.original_budgets[agent_id] {
+            Some(amount) => {
+                let budget = T::from_f64(amount * new_rate).as_f64();
+                if !(budget >= 0.0 && budget.is_finite()) {
+                    return Err(MbaError::InvalidBudget { agent_id, budget });
+                }
+                self.check_range_quietly("budget", budget)?;
+                budget
             }
+            None => self.budget(agent_id),
+        };
+        // whether a bid of value on item_id is accepted at budget, as accept_bid decides it under any policy but Error
+        let accepted = |item_id: usize, value: f64| {
+            let bid = if value > budget && self.overbid_policy == OverbidPolicy::Clamp {
+                budget
+            } else {
+                value
+            };
+            bid <= budget && bid >= self.reserve_price[item_id]
+        };
 
-            self.gamma[agent_id].push_back(item_id);
-            self.consumptions[agent_id] += self.bid[agent_id][item_id];
+        // the last rejected bid on every item without an accepted bid, as readmit_rejected_bids offers them again
+        let mut held: Vec<usize> = Vec::new();
+        if self.original_budgets[agent_id].is_some() {
+            let mut offered: Vec<usize> = Vec::new();
+            for &(id, item_id, bid) in self.rejected_bids.iter().rev() {
+                if id == agent_id && !self.bid[agent_id].contains_key(&item_id) && !offered.contains(&item_id) {
+                    offered.push(item_id);
+                    if accepted(item_id, bid) {
+                        held.push(item_id);
+                    }
+                }
+            }
+        }
+        let mut num_accepted_bids = self.num_accepted_bids + held.len();
+        self.check_memory(num_accepted_bids)?;
+
+        for &(item_id, amount) in &self.original_bids[agent_id] {
+            let value = T::from_f64(amount * new_rate).as_f64();
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(MbaError::InvalidBid { agent_id, item_id, bid: value });
+            }
+            self.check_range_quietly("bid", value)?;
+            if value > budget && self.overbid_policy == OverbidPolicy::Error {
+                return Err(MbaError::BidExceedsBudget {
+                    agent_id,
+                    item_id,
+                    bid: value,
+                    budget,
+                });
+            }
+            match (self.bid[agent_id].contains_key(&item_id) || held.contains(&item_id), accepted(item_id, value)) {
+                (false, true) => {
+                    num_accepted_bids += 1;
+                    self.check_memory(num_accepted_bids)?;
+                }
+                (true, false) => num_accepted_bids -= 1,
+                _ => {}
+            }
         }
+        Ok(())
     }
 
-    fn price(&self, agent_id: usize, item_id: usize) -> f64 {
-        (1.0 - self.alpha[agent_id]) * self.bid[agent_id][item_id]
+    pub fn get_rate(&self, agent_id: usize) -> f64 {
+        self.rates[agent_id]
     }
 
-    fn update_alpha(&mut self, agent_id: usize) {
-        self.alpha[agent_id] = match self.num_update[agent_id] {
-            0 => self.epsilon,
-            _ => self.alpha[agent_id] * (1.0 + self.calc_epsilon(agent_id)),
-        };
-        self.num_update[agent_id] += 1;
+    // budget as it was given to set_budget_in
+    pub fn get_budget_in(&self, agent_id: usize) -> f64 {
+        self.original_budgets[agent_id].unwrap_or(self.budget(agent_id) / self.rates[agent_id])
     }
 
-    fn calc_epsilon(&self, agent_id: usize) -> f64 {
-        let a = self.alpha[agent_id];
-        self.epsilon * ((1.0 - a) / a)
+    // converts a base-currency value of agent_id back to its own currency, rounded to the given number of decimals
+    pub fn to_agent_currency(&self, agent_id: usize, value: f64, decimals: i32) -> f64 {
+        let scale = 10f64.powi(decimals);
+        (value / self.rates[agent_id] * scale).round() / scale
     }
 
-    fn max_price_agent(&mut self, item_id: usize) -> usize {
-        loop {
-            let (price, num, agent_id) = *self.item_agent[item_id].peek().unwrap();
+    // inactive agents keep their budget and bids but take no part in the solve or the objectives
+    pub fn set_agent_active(&mut self, agent_id: usize, active: bool) {
+        if self.active[agent_id] != active {
+            self.active[agent_id] = active;
+            self.mark_changed();
+            self.rebuild_bid_index();
+        }
+    }
 
-            // price is up-to-date
-            if num == self.num_update[agent_id] {
-                return agent_id;
-            }
+    pub fn is_agent_active(&self, agent_id: usize) -> bool {
+        self.active[agent_id]
+    }
 
-            let updated_price = NotNan::new(self.price(agent_id, item_id)).unwrap();
-            debug_assert!(updated_price < price); // price is monotone decreasing
+    // panics where try_set_round_budget_fraction fails
+    pub fn set_round_budget_fraction(&mut self, fraction: f64) {
+        self.try_set_round_budget_fraction(fraction).unwrap_or_else(|e| panic!("{}", e));
+    }
 
-            let mut v = self.item_agent[item_id].peek_mut().unwrap();
-            *v = (updated_price, self.num_update[agent_id], agent_id);
+    // share of the full budget an agent may spend in this round, in [0, 1]
+    // beta and the overbid check still use the full budget
+    pub fn try_set_round_budget_fraction(&mut self, fraction: f64) -> Result<(), MbaError> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(MbaError::InvalidRoundBudgetFraction { fraction });
         }
+        self.round_budget_fraction = fraction;
+        self.mark_changed();
+        Ok(())
     }
 
-    fn is_paid_for(&self, agent_id: usize) -> bool {
-        self.consumptions[agent_id] <= self.U(agent_id) * self.budgets[agent_id]
+    // spend of earlier rounds; the effective budget never exceeds what is left of the full budget
+    pub fn carry_over(&mut self, previous_spend: &[f64]) {
+        debug_assert_eq!(previous_spend.len(), self.num_agents);
+        for (carried, &spend) in self.carried_spend.iter_mut().zip(previous_spend) {
+            *carried += spend;
+        }
+        self.mark_changed();
     }
 
-    // ((1.0 - a) * (4.0 - self.beta) + self.beta) / ((1.0 - a) * (4.0 - self.beta))
-    #[allow(non_snake_case)]
-    fn U(&self, agent_id: usize) -> f64 {
-        let denominator = (self.alpha[agent_id] - 1.0) * (self.beta - 4.0);
-        self.beta / denominator + 1.0
+    // budget the solve works with: min(fraction * budget, budget - carried spend), at least 0
+    pub fn get_effective_budget(&self, agent_id: usize) -> f64 {
+        self.effective_budget(agent_id)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::PrimalDual;
-    use std::fs;
-    use std::fs::read_to_string;
-    use std::path::Path;
+    // amount charged to every agent, min(consumption, effective budget)
+    pub fn get_spends(&self) -> Vec<f64> {
+        (0..self.num_agents)
+            .map(|agent_id| self.consumption(agent_id).min(self.effective_budget(agent_id)))
+            .collect()
+    }
 
-    #[test]
-    fn sample() {
-        let num_agents = 2;
-        let num_items = 3;
+    // accepted bids as (agent_id, item_id, bid), agent by agent; O(bids)
+    pub fn bids_iter(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.num_agents).flat_map(move |agent_id| self.bids_of_agent(agent_id))
+    }
 
-        let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+    pub fn bids_of_agent(&self, agent_id: usize) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.agent_items[agent_id]
+            .iter()
+            .map(move |&item_id| (agent_id, item_id, self.bid(agent_id, item_id)))
+    }
 
-        solver.set_budget(0, 300.0);
-        solver.set_budget(1, 400.0);
+    pub fn bids_on_item(&self, item_id: usize) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.item_agents[item_id]
+            .iter()
+            .map(move |&agent_id| (agent_id, item_id, self.bid(agent_id, item_id)))
+    }
 
-        solver.set_bid(0, 0, 200.0);
-        solver.set_bid(0, 1, 200.0);
-        solver.set_bid(0, 2, 100.0);
+    pub fn num_agents(&self) -> usize {
+        self.num_agents
+    }
 
-        solver.set_bid(1, 0, 100.0);
-        solver.set_bid(1, 1, 100.0);
-        solver.set_bid(1, 2, 200.0);
+    pub fn num_items(&self) -> usize {
+        self.num_items
+    }
 
-        solver.solve();
+    // number of accepted bids
+    pub fn num_bids(&self) -> usize {
+        self.agent_items.iter().map(|item_ids| item_ids.len()).sum()
+    }
 
-        let primal_objective_value = solver.get_primal_objective_value();
-        let dual_objective_value = solver.get_dual_objective_value();
-        let approximate_rate = solver.get_approximation_ratio();
-        assert_eq!(primal_objective_value, 500.0);
-        assert!(primal_objective_value >= dual_objective_value * approximate_rate);
+    // agent with the largest accepted bid on item_id, None if nobody bids on it
+    pub fn get_max_bidder(&self, item_id: usize) -> Option<usize> {
+        self.max_bid_agent[item_id]
     }
 
-    #[test]
-    fn random() {
-        let directory_path = Path::new("tests/random");
-        let epsilon = 0.01;
+    pub fn get_bid(&self, agent_id: usize, item_id: usize) -> f64 {
+        self.bid(agent_id, item_id)
+    }
 
-        match fs::read_dir(directory_path) {
-            Ok(entries) => {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if let Some(file_name) = entry.file_name().to_str() {
-                            if file_name.ends_with(".in") {
-                                let file_name = file_name.replace(".in", "");
-                                let input_file_path = format!("{}.in", file_name);
-                                let output_file_path = format!("{}.out", file_name);
-
-                                println!("{}/{}", input_file_path, output_file_path);
-
-                                let (primal, ratio) = get_result(&(directory_path.join(Path::new(&input_file_path))), epsilon);
-                                let opt = get_ans(&directory_path.join(Path::new(&output_file_path)));
-                                println!("{:}/{:}", primal, opt);
-                                assert!(primal >= opt * ratio);
-                                println!("");
-                            }
-                        }
-                    }
-                }
-            }
-            Err(err) => {
-                eprintln!("Error reading directory: {}", err);
-            }
-        }
+    pub fn get_alpha(&self, agent_id: usize) -> f64 {
+        self.alpha(agent_id)
     }
 
-    fn get_result(file_path: &Path, epsilon: f64) -> (f64, f64) {
-        let data = read_to_string(file_path);
-        let xy = match data {
-            Ok(content) => content,
-            Err(error) => {
-                panic!("Could not open or find file:{} {}", file_path.to_str().unwrap(), error);
-            }
-        };
-        let xy_pairs: Vec<&str> = xy.trim().split("\n").collect();
+    // recomputed from the current alphas, not read from the possibly stale heaps
+    pub fn get_item_price(&self, item_id: usize) -> f64 {
+        self.dual_item_price(item_id)
+    }
 
-        let v: Vec<&str> = xy_pairs[0].trim().split(",").collect();
-        let num_agents = v[0].parse().unwrap();
-        let num_items: usize = v[1].parse().unwrap();
+    // (alpha of every agent, price of every item)
+    pub fn get_duals(&self) -> (Vec<f64>, Vec<f64>) {
+        let prices = (0..self.num_items).map(|item_id| self.dual_item_price(item_id)).collect();
+        (self.alphas(), prices)
+    }
 
-        let mut solver = PrimalDual::new(num_agents, num_items, epsilon);
+    // rounds in which an unpaid agent handed items over or raised its alpha, since construction or the last reset
+    pub fn get_num_reallocations(&self) -> usize {
+        self.num_reallocations
+    }
 
-        let v: Vec<&str> = xy_pairs[1].trim().split(",").collect();
-        for agent_id in 0..num_agents {
-            let budget = v[agent_id].parse().unwrap();
-            solver.set_budget(agent_id, budget);
+    // largest accepted bid relative to its agent's budget over the active agents, at most 1. bids that were rejected,
+    // overwritten, removed or disabled by a reserve price do not count, and every solve recomputes it from the bids it solves
+    pub fn get_beta(&self) -> f64 {
+        self.beta
+    }
+
+    // (1 - beta / 4)(1 - epsilon), the a priori guarantee of a solve. before a solve it is the guarantee a solve of the
+    // current bids gives; without any accepted bid that is 1 - epsilon, as every assignment of such an instance is optimal
+    pub fn get_approximation_ratio(&self) -> f64 {
+        if self.epsilon == 0.0 {
+            return 1.0;
         }
+        (1.0 - self.beta / 4.0) * (1.0 - self.max_epsilon())
+    }
 
-        for (i, _pair) in xy_pairs.iter().enumerate() {
-            if i <= 1 {
-                continue;
-            }
-            let vv: Vec<&str> = xy_pairs[i].trim().split(",").collect();
-            let agent_id: usize = vv[0].parse().unwrap();
-            let item_id: usize = vv[1].parse().unwrap();
-            let bid: f64 = vv[2].parse().unwrap();
-            solver.set_bid(agent_id, item_id, bid);
+    // primal / dual, the a posteriori guarantee of the current assignment: the dual is feasible whatever the state of the
+    // solve, so it bounds the optimum and primal >= ratio * optimum. usually well above get_approximation_ratio after a
+    // solve. rounded down so that primal >= ratio * dual holds in floating point as well; 1 without any value to assign
+    pub fn get_certified_ratio(&self) -> f64 {
+        let dual = self.get_dual_objective_value();
+        if dual <= 0.0 {
+            return 1.0;
+        }
+        let ratio = (self.get_primal_objective_value() / dual).min(1.0);
+        ratio - 2.0 * f64::EPSILON * ratio
+    }
+
+    // the result equals that of a freshly built solver of the current instance: a solve after an earlier one restarts from
+    // scratch when any setter changed the instance in between, and otherwise keeps the earlier result, see initialize.
+    // solve_with the default options solves the same way and also builds a SolveResult, which allocates
+    pub fn solve(&mut self) {
+        self.solve_counting_sweeps();
+    }
+
+    // solve continuing from the alphas and assignment of an earlier solve when only set_bid was called in between,
+    // see warm_start; usually much faster than solve, but the result may differ from that of a fresh solver.
+    // after any other change it starts from scratch like solve
+    pub fn solve_warm(&mut self) {
+        if self.changed {
+            self.reset_solve_state();
         }
+        self.apply_unassignable_policy();
+        self.initialize_warm();
+        self.run(None);
+    }
 
-        solver.solve();
-        (solver.get_primal_objective_value(), solver.get_approximation_ratio())
+    // solve returning the number of sweeps
+    pub(crate) fn solve_counting_sweeps(&mut self) -> usize {
+        self.apply_unassignable_policy();
+        self.initialize();
+        self.run(None).0
+    }
+
+    // solves while favoring the owners in previous: their price for an item is raised by switch_penalty,
+    // so an item only moves when another agent's price exceeds it by more than switch_penalty.
+    // each retained item costs at most switch_penalty of objective compared to solve().
+    // returns the number of items whose owner differs from previous
+    pub fn solve_with_stability(&mut self, previous: &Solution, switch_penalty: f64) -> usize {
+        self.solve_with_stability_stats(previous, switch_penalty).num_switches
+    }
+
+    // solve_with_stability returning what the algorithm did. the favoring ends with the call, so the next solve starts
+    // from scratch without it
+    pub fn solve_with_stability_stats(&mut self, previous: &Solution, switch_penalty: f64) -> SolveStats {
+        debug_assert!(switch_penalty >= 0.0);
+        debug_assert_eq!(previous.num_items(), self.num_items);
+
+        self.previous_owner.clone_from(&previous.item_owner);
+        self.switch_penalty = switch_penalty;
+        self.mark_changed();
+        self.rebuild_bid_index();
+        let stats = self.solve_with_stats();
+
+        self.previous_owner.fill(None);
+        self.switch_penalty = 0.0;
+        self.mark_changed();
+        stats
+    }
+
+    // panics where try_set_reduction_chunk_size fails
+    pub fn set_reduction_chunk_size(&mut self, chunk_size: usize) {
+        self.try_set_reduction_chunk_size(chunk_size).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // number of agents summed per task by the objective getters; the sums are bit-identical for a fixed chunk size.
+    // chunk_size must be at least 1
+    pub fn try_set_reduction_chunk_size(&mut self, chunk_size: usize) -> Result<(), BuildError> {
+        if chunk_size == 0 {
+            return Err(BuildError::InvalidChunkSize);
+        }
+        self.reduction_chunk_size = chunk_size;
+        Ok(())
+    }
+
+    // sum_a budget_a * alpha_a + sum_j p_j with p_j the largest (1 - alpha_a) * bid_aj over the active bidders of item j (0 if none).
+    // alpha in [0, 1] and these p_j are feasible for the dual of the LP relaxation, so this bounds the primal
+    // whoever holds the items; saturates at f64::MAX instead of overflowing
+    pub fn get_dual_objective_value(&self) -> f64 {
+        let scale = self.objective_scale();
+        let agent_sum = reduce::sum(self.num_agents, self.reduction_chunk_size, |agent_id| {
+            if !self.active[agent_id] {
+                return 0.0;
+            }
+            (self.effective_budget(agent_id) * scale) * self.alpha(agent_id)
+        });
+        let item_sum = reduce::sum(self.num_items, self.reduction_chunk_size, |item_id| self.dual_item_price(item_id) * scale);
+        saturate((agent_sum + item_sum) / scale)
+    }
+
+    // sum_a budget_a * alpha_a + consumption_a * (1 - alpha_a), the accounting of the paper.
+    // it equals get_dual_objective_value only while every item with a bid is held by a bidder of maximum price;
+    // otherwise it can understate the dual bound. kept for comparison
+    pub fn get_legacy_dual_objective_value(&self) -> f64 {
+        let scale = self.objective_scale();
+        let sum = reduce::sum(self.num_agents, self.reduction_chunk_size, |agent_id| {
+            if !self.active[agent_id] {
+                return 0.0;
+            }
+            (self.effective_budget(agent_id) * scale) * self.alpha(agent_id) + (self.consumption(agent_id) * scale) * (1.0 - self.alpha(agent_id))
+        });
+        saturate(sum / scale)
+    }
+
+    // saturates at f64::MAX instead of overflowing
+    pub fn get_primal_objective_value(&self) -> f64 {
+        let scale = self.objective_scale();
+        let sum = reduce::sum(self.num_agents, self.reduction_chunk_size, |agent_id| {
+            if !self.active[agent_id] {
+                return 0.0;
+            }
+            self.consumption(agent_id).min(self.effective_budget(agent_id)) * scale
+        });
+        saturate(sum / scale)
+    }
+
+    // get_assignment()[agent_id] = items agent_id holds; see assignment for an owned copy with the bids
+    pub fn get_assignment(&self) -> &[VecDeque<usize>] {
+        &self.gamma
+    }
+
+    // get_item_assignment()[item_id] = agent holding item_id, None if nobody does, e.g. because it has no accepted bid.
+    // an item of capacity above 1 reports the highest of its holders
+    pub fn get_item_assignment(&self) -> Vec<Option<usize>> {
+        self.item_owner()
+    }
+
+    pub fn assignment(&self) -> Assignment {
+        Assignment::new(
+            self.gamma.iter().map(|item_ids| item_ids.iter().copied().collect()).collect(),
+            self.gamma
+                .iter()
+                .enumerate()
+                .map(|(agent_id, item_ids)| item_ids.iter().map(|&item_id| self.bid(agent_id, item_id)).collect())
+                .collect(),
+            self.num_items,
+            (0..self.num_agents)
+                .map(|agent_id| if self.active[agent_id] { self.effective_budget(agent_id) } else { 0.0 })
+                .collect(),
+        )
+    }
+
+    pub fn extract_solution(&self) -> Solution {
+        Solution {
+            assignment: self.gamma.iter().map(|item_ids| item_ids.iter().copied().collect()).collect(),
+            item_owner: self.item_owner(),
+            spends: self.consumptions.iter().map(|&consumption| consumption.as_f64()).collect(),
+            primal: self.get_primal_objective_value(),
+            dual: self.get_dual_objective_value(),
+            // epsilon 0 solves exactly whatever the epsilon of an agent
+            epsilon: if self.epsilon == 0.0 { 0.0 } else { self.max_epsilon() },
+            beta: self.beta,
+        }
+    }
+
+    // sweeps over the agents until all of them are paid for or the deadline passes between two rounds
+    // returns (number of sweeps, whether all agents are paid for)
+    fn run(&mut self, deadline: Option<Instant>) -> (usize, bool) {
+        self.run_with(Stop::at(deadline), |_, _| {})
+    }
+
+    // run calling on_sweep(self, number of sweeps so far) after every sweep.
+    // stop is checked between two rounds of reallocation, where gamma and consumptions agree
+    fn run_with<F: FnMut(&Self, usize)>(&mut self, stop: Stop<'_>, mut on_sweep: F) -> (usize, bool) {
+        // alpha would never grow
+        if self.epsilon == 0.0 {
+            self.assign_exact();
+            return (0, true);
+        }
+
+        let mut num_sweeps = 0;
+        self.sweep = None;
+        loop {
+            if stop.reached(self.num_alpha_updates) {
+                return (num_sweeps, false);
+            }
+            if self.sweep.is_none() {
+                num_sweeps += 1;
+            }
+            match self.advance() {
+                Progress::Reallocated => {}
+                Progress::SweepEnded => on_sweep(self, num_sweeps),
+                Progress::Done => {
+                    on_sweep(self, num_sweeps);
+                    self.enforce_agent_limits();
+                    return (num_sweeps, true);
+                }
+            }
+        }
+    }
+
+    // sort_unstable_by does not allocate, and ties go to the lower agent id
+    fn update_visit_order(&mut self) {
+        let mut order = std::mem::take(&mut self.visit_order);
+        order.clear();
+        order.extend(0..self.num_agents);
+        match self.agent_order {
+            AgentOrder::IndexOrder => {}
+            AgentOrder::LargestOverspendFirst => {
+                let overspend = |agent_id: usize| self.consumption(agent_id) - self.U(agent_id) * self.effective_budget(agent_id);
+                order.sort_unstable_by(|&a, &b| overspend(b).total_cmp(&overspend(a)).then(a.cmp(&b)));
+            }
+            AgentOrder::MostBidsFirst => order.sort_unstable_by(|&a, &b| self.agent_items[b].len().cmp(&self.agent_items[a].len()).then(a.cmp(&b))),
+            AgentOrder::LargestBudgetFirst => order.sort_unstable_by(|&a, &b| self.effective_budget(b).total_cmp(&self.effective_budget(a)).then(a.cmp(&b))),
+            AgentOrder::Random(seed) => RngConfig::new(seed).stream("agent_order").shuffle(&mut order),
+            AgentOrder::Custom if self.agent_permutation.len() == self.num_agents => order.copy_from_slice(&self.agent_permutation),
+            AgentOrder::Custom => {}
+        }
+        self.visit_order = order;
+    }
+
+    // replaces the assignment with an optimal one
+    fn assign_exact(&mut self) {
+        let budgets: Vec<f64> = (0..self.num_agents).map(|agent_id| self.effective_budget(agent_id)).collect();
+        let bids: Vec<Vec<f64>> = (0..self.num_agents)
+            .map(|agent_id| {
+                let mut row = vec![0.0; self.num_items];
+                if self.active[agent_id] {
+                    for (&item_id, &bid) in &self.bid[agent_id] {
+                        row[item_id] = bid.as_f64();
+                    }
+                }
+                row
+            })
+            .collect();
+        let (_, item_owner) = exact::solve(&budgets, &bids).expect("try_new admits epsilon 0 only for instances exact::solve accepts");
+
+        self.emit_release_all();
+        self.gamma.iter_mut().for_each(|item_ids| item_ids.clear());
+        self.consumptions.fill(T::zero());
+        for (item_id, owner) in item_owner.into_iter().enumerate() {
+            if let Some(agent_id) = owner {
+                self.gamma[agent_id].push_back(item_id);
+                let bid = T::from_f64(self.bid(agent_id, item_id));
+                self.consumptions[agent_id] += bid;
+                self.emit_move(item_id, None, Some(agent_id));
+            }
+        }
+    }
+
+    fn effective_budget(&self, agent_id: usize) -> f64 {
+        let budget = self.budget(agent_id);
+        (self.round_budget_fraction * budget).min(budget - self.carried_spend[agent_id]).max(0.0)
+    }
+
+    fn check_agent(&self, agent_id: usize) -> Result<(), MbaError> {
+        if agent_id >= self.num_agents {
+            return Err(MbaError::AgentIndexOutOfRange {
+                agent_id,
+                num_agents: self.num_agents,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_range(&mut self, name: &'static str, value: f64) -> Result<(), MbaError> {
+        let (min_abs, max_abs) = self.safe_range;
+        if value == 0.0 || (min_abs..=max_abs).contains(&value.abs()) {
+            return Ok(());
+        }
+        match self.range_policy {
+            RangePolicy::Warn => {
+                self.range_warnings.push(RangeWarning {
+                    name,
+                    value,
+                    safe_range: self.safe_range,
+                });
+                Ok(())
+            }
+            RangePolicy::Error => Err(MbaError::ValueOutOfRange {
+                name,
+                value,
+                safe_range: self.safe_range,
+            }),
+        }
+    }
+
+    // check_range without recording a warning
+    fn check_range_quietly(&self, name: &'static str, value: f64) -> Result<(), MbaError> {
+        let (min_abs, max_abs) = self.safe_range;
+        if value == 0.0 || (min_abs..=max_abs).contains(&value.abs()) || self.range_policy == RangePolicy::Warn {
+            return Ok(());
+        }
+        Err(MbaError::ValueOutOfRange {
+            name,
+            value,
+            safe_range: self.safe_range,
+        })
+    }
+
+    // power of two bringing the largest budget or consumption to at most 1, so that objective sums do not overflow
+    fn objective_scale(&self) -> f64 {
+        let max_value = self
+            .budgets
+            .iter()
+            .chain(self.consumptions.iter())
+            .fold(1.0f64, |max_value, &value| max_value.max(value.as_f64().abs()));
+        if max_value.is_finite() {
+            2f64.powi(-(max_value.log2().ceil() as i32))
+        } else {
+            2f64.powi(f64::MIN_EXP)
+        }
+    }
+
+    // largest (1 - alpha) * bid over the active bidders of item_id, 0 if there is none; the sum of the largest ones for a capacity above 1
+    fn dual_item_price(&self, item_id: usize) -> f64 {
+        if self.item_capacity[item_id] > 1 {
+            return self.capacity_item_price(item_id);
+        }
+        self.item_agents[item_id]
+            .iter()
+            .filter(|&&agent_id| self.active[agent_id])
+            .map(|&agent_id| (1.0 - self.alpha(agent_id)) * self.bid(agent_id, item_id))
+            .fold(0.0, f64::max)
+    }
+
+    // items that have rejected bids but no accepted bid
+    fn unassignable_items(&self) -> Vec<usize> {
+        let mut item_ids: Vec<usize> = self
+            .rejected_bids
+            .iter()
+            .map(|&(_, item_id, _)| item_id)
+            .filter(|&item_id| self.max_bid_agent[item_id].is_none())
+            .collect();
+        item_ids.sort_unstable();
+        item_ids.dedup();
+        item_ids
+    }
+
+    fn apply_unassignable_policy(&mut self) {
+        match self.unassignable_policy {
+            UnassignablePolicy::Ignore => {}
+            UnassignablePolicy::ClampBestBid => {
+                for item_id in self.unassignable_items() {
+                    let best = self
+                        .rejected_bids
+                        .iter()
+                        .filter(|&&(agent_id, id, _)| id == item_id && self.active[agent_id] && self.budget(agent_id) > 0.0)
+                        .max_by(|a, b| a.2.total_cmp(&b.2).then(b.0.cmp(&a.0)));
+                    if let Some(&(agent_id, _, _)) = best {
+                        self.store_bid(agent_id, item_id, self.budgets[agent_id]);
+                        self.index_bid(agent_id, item_id);
+                    }
+                }
+            }
+            UnassignablePolicy::Error => {
+                let item_ids = self.unassignable_items();
+                if !item_ids.is_empty() {
+                    panic!("items {:?} have no bid within budget", item_ids);
+                }
+            }
+        }
+    }
+
+    // item_owner[item_id] = agent_id holding item_id in gamma; the highest such agent_id for an item of capacity above 1
+    fn item_owner(&self) -> Vec<Option<usize>> {
+        let mut item_owner = vec![None; self.num_items];
+        for (agent_id, item_ids) in self.gamma.iter().enumerate() {
+            for &item_id in item_ids {
+                item_owner[item_id] = Some(agent_id);
+            }
+        }
+        item_owner
+    }
+
+    // forgets the result of a previous solve so that the instance can be solved again from scratch
+    fn reset_solve_state(&mut self) {
+        self.emit_release_all();
+        self.alpha.fill(T::zero());
+        self.consumptions.fill(T::zero());
+        self.gamma.iter_mut().for_each(|item_ids| item_ids.clear());
+        self.num_update.fill(0);
+        self.initialized = false;
+        self.new_bid_items.clear();
+        self.changed = false;
+        self.sweep = None;
+        self.stepping = false;
+        self.num_reallocations = 0;
+        self.num_alpha_updates = 0;
+        self.num_item_moves = 0;
+        self.num_unique_raises = 0;
+        self.num_forced_drops = 0;
+        self.rebuild_bid_index();
+    }
+
+    // the starting point of every solve but solve_warm: the assignment of an earlier solve when nothing changed since,
+    // so that a solve stopped by a limit resumes and a repeated solve returns the same result, otherwise a cold restart
+    fn initialize(&mut self) {
+        if self.initialized && (self.changed || !self.new_bid_items.is_empty()) {
+            self.reset_solve_state();
+        }
+        self.initialize_warm();
+    }
+
+    // marks a change to the instance other than a bid, after which the next solve starts from scratch
+    fn mark_changed(&mut self) {
+        if self.initialized {
+            self.changed = true;
+        }
+    }
+
+    // gamma[agent_id] never holds more than the items agent_id bids on, so reserving them here keeps the sweeps allocation-free
+    fn initialize_warm(&mut self) {
+        self.recompute_beta();
+        if self.initialized {
+            self.reinitialize();
+            return;
+        }
+        self.initialized = true;
+
+        for (item_ids, bid_item_ids) in self.gamma.iter_mut().zip(&self.agent_items) {
+            item_ids.reserve(bid_item_ids.len());
+        }
+
+        let mut owners = std::mem::take(&mut self.owners_scratch);
+        self.initial_owners(&mut owners);
+        for (item_id, &owner) in owners.iter().enumerate() {
+            if self.item_capacity[item_id] > 1 {
+                for agent_id in self.top_price_agents(item_id, self.item_capacity[item_id]) {
+                    if !self.fits(agent_id, item_id, None) {
+                        continue;
+                    }
+                    self.gamma[agent_id].push_back(item_id);
+                    let bid = T::from_f64(self.bid(agent_id, item_id));
+                    self.consumptions[agent_id] += bid;
+                    self.emit_move(item_id, None, Some(agent_id));
+                }
+                continue;
+            }
+
+            // no agent can assign item id
+            let Some(agent_id) = self.owner_with_room(None, item_id, owner) else {
+                continue;
+            };
+
+            self.gamma[agent_id].push_back(item_id);
+            let bid = T::from_f64(self.bid(agent_id, item_id));
+            self.consumptions[agent_id] += bid;
+            self.emit_move(item_id, None, Some(agent_id));
+        }
+        self.owners_scratch = owners;
+    }
+
+    // the max bid agent, unless the previous owner keeps the item because it is not outbid by more than switch_penalty
+    fn initial_owner(&self, item_id: usize) -> Option<usize> {
+        let agent_id = self.max_bid_agent[item_id]?;
+        match self.previous_owner[item_id] {
+            Some(owner) if self.bid(owner, item_id) > 0.0 && self.price(owner, item_id) > self.price(agent_id, item_id) => Some(owner),
+            _ => Some(agent_id),
+        }
+    }
+
+    // 0 for no bid
+    fn bid(&self, agent_id: usize, item_id: usize) -> f64 {
+        self.bid[agent_id].get(&item_id).map_or(0.0, |&bid| bid.as_f64())
+    }
+
+    fn budget(&self, agent_id: usize) -> f64 {
+        self.budgets[agent_id].as_f64()
+    }
+
+    fn alpha(&self, agent_id: usize) -> f64 {
+        self.alpha[agent_id].as_f64()
+    }
+
+    fn consumption(&self, agent_id: usize) -> f64 {
+        self.consumptions[agent_id].as_f64()
+    }
+
+    // the sum of the bids on gamma[agent_id] in its order. appending an item to gamma adds exactly its bid to this sum,
+    // so recomputing after every pass that removes items keeps consumptions free of the drift of += and -= cycles
+    fn recompute_consumption(&mut self, agent_id: usize) {
+        let consumption = self.gamma[agent_id]
+            .iter()
+            .fold(T::zero(), |sum, &item_id| sum + T::from_f64(self.bid(agent_id, item_id)));
+        self.consumptions[agent_id] = consumption;
+    }
+
+    fn alphas(&self) -> Vec<f64> {
+        self.alpha.iter().map(|&alpha| alpha.as_f64()).collect()
+    }
+
+    fn store_bid(&mut self, agent_id: usize, item_id: usize, bid: T) {
+        if self.bid[agent_id].insert(item_id, bid).is_none() {
+            self.num_accepted_bids += 1;
+            self.agent_items[agent_id].push(item_id);
+            self.item_agents[item_id].push(agent_id);
+        }
+    }
+
+    // registers bid[agent_id][item_id] in item_agent, max_bid_agent and beta
+    fn index_bid(&mut self, agent_id: usize, item_id: usize) {
+        if !self.active[agent_id] {
+            return;
+        }
+
+        let bid = self.bid(agent_id, item_id);
+        let price = self.price(agent_id, item_id);
+        self.item_agent[item_id].push((NotNan::new(T::from_f64(price)).unwrap(), Reverse(agent_id), self.num_update[agent_id]));
+
+        // equal bids go to the lower agent id, so the initial owner does not depend on the order of set_bid calls
+        match self.max_bid_agent[item_id] {
+            Some(max_bid_agent_id) if (self.bid(max_bid_agent_id, item_id), Reverse(max_bid_agent_id)) >= (bid, Reverse(agent_id)) => {}
+            _ => self.max_bid_agent[item_id] = Some(agent_id),
+        }
+
+        self.beta = self.beta.max(bid / self.budget(agent_id));
+    }
+
+    // index_bid for every accepted bid of the active agents, in one pass over the bid maps: the heaps are built from
+    // their entries at once, which is linear rather than a push per bid, and keep their allocations
+    fn rebuild_bid_index(&mut self) {
+        let mut entries: Vec<Vec<PriceEntry<T>>> = self
+            .item_agent
+            .iter_mut()
+            .map(|heap| {
+                let mut entries = std::mem::take(heap).into_vec();
+                entries.clear();
+                entries
+            })
+            .collect();
+        let mut max_bids = vec![0.0; self.num_items]; // max_bids[item_id] = bid of max_bid_agent[item_id]
+        self.max_bid_agent.fill(None);
+        self.beta = 0.0;
+
+        for agent_id in (0..self.num_agents).filter(|&agent_id| self.active[agent_id]) {
+            let (alpha, budget, num) = (self.alpha(agent_id), self.budget(agent_id), self.num_update[agent_id]);
+            for (&item_id, &bid) in &self.bid[agent_id] {
+                let bid = bid.as_f64();
+                // as price computes it
+                let mut price = (1.0 - alpha) * bid;
+                if self.previous_owner[item_id] == Some(agent_id) {
+                    price += self.switch_penalty;
+                }
+                entries[item_id].push((NotNan::new(T::from_f64(price)).unwrap(), Reverse(agent_id), num));
+
+                // agents come in increasing id, so equal bids stay with the lower agent id as in index_bid
+                if self.max_bid_agent[item_id].is_none() || bid > max_bids[item_id] {
+                    self.max_bid_agent[item_id] = Some(agent_id);
+                    max_bids[item_id] = bid;
+                }
+                self.beta = self.beta.max((bid / budget).min(1.0));
+            }
+        }
+
+        for (heap, entries) in self.item_agent.iter_mut().zip(entries) {
+            *heap = BinaryHeap::from(entries);
+        }
+    }
+
+    fn price(&self, agent_id: usize, item_id: usize) -> f64 {
+        let price = (1.0 - self.alpha(agent_id)) * self.bid(agent_id, item_id);
+        if self.previous_owner[item_id] == Some(agent_id) {
+            price + self.switch_penalty
+        } else {
+            price
+        }
+    }
+
+    // alpha stops at max_alpha
+    fn update_alpha(&mut self, agent_id: usize) {
+        let alpha = match self.num_update[agent_id] {
+            0 => self.first_alpha(agent_id),
+            _ => self.alpha(agent_id) * (1.0 + self.calc_epsilon(agent_id)),
+        };
+        let old = self.alpha(agent_id);
+        self.alpha[agent_id] = T::from_f64(alpha.min(self.max_alpha()));
+        self.num_update[agent_id] += 1;
+        self.num_alpha_updates += 1;
+        self.emit(|solver| SolveEvent::AlphaUpdated {
+            agent: agent_id,
+            old,
+            new: solver.alpha(agent_id),
+        });
+    }
+
+    fn first_alpha(&self, agent_id: usize) -> f64 {
+        let epsilon = self.get_agent_epsilon(agent_id);
+        if let Some(alpha) = self.agent_initial_alpha[agent_id] {
+            return alpha.min(epsilon);
+        }
+        match self.initial_alpha {
+            InitialAlpha::Epsilon => epsilon,
+            InitialAlpha::Fixed(value) => value.min(epsilon),
+            InitialAlpha::Adaptive => {
+                // consumption <= U(alpha) * budget  <=>  alpha >= 1 - beta / ((4 - beta) * (consumption / budget - 1))
+                let overspend = self.consumption(agent_id) / self.effective_budget(agent_id) - 1.0;
+                let required = 1.0 - self.beta / ((4.0 - self.beta) * overspend);
+
+                // epsilon / (1 + epsilon)^k for the largest k that stays at or above required, and above f64::EPSILON
+                let floor = required.max(f64::EPSILON);
+                if floor >= epsilon {
+                    return epsilon;
+                }
+                let k = ((epsilon / floor).ln() / epsilon.ln_1p()).floor();
+                let alpha = epsilon / (1.0 + epsilon).powf(k);
+                // the rounding of ln and powf may land one step below
+                if alpha < floor {
+                    alpha * (1.0 + epsilon)
+                } else {
+                    alpha
+                }
+            }
+        }
+    }
+
+    // a few machine epsilons of T below 1, so that 1 - alpha, and with it U, stays finite
+    fn max_alpha(&self) -> f64 {
+        1.0 - ALPHA_MARGIN_EPSILONS * <T as Float>::epsilon().as_f64()
+    }
+
+    fn calc_epsilon(&self, agent_id: usize) -> f64 {
+        let a = self.alpha(agent_id);
+        self.get_agent_epsilon(agent_id) * ((1.0 - a) / a)
+    }
+
+    // None if no accepted bid on item_id is left
+    fn max_price_agent(&mut self, item_id: usize) -> Option<usize> {
+        let mut heap = std::mem::take(&mut self.item_agent[item_id]);
+        let agent_id = self.refresh_top(item_id, &mut heap);
+        self.item_agent[item_id] = heap;
+        agent_id
+    }
+
+    // max_price_agent on heap, the heap of item_id taken out of item_agent so that several items can be refreshed at once
+    fn refresh_top(&self, item_id: usize, heap: &mut BinaryHeap<PriceEntry<T>>) -> Option<usize> {
+        loop {
+            let &(price, Reverse(agent_id), num) = heap.peek()?;
+
+            // price is up-to-date
+            if num == self.num_update[agent_id] {
+                return Some(agent_id);
+            }
+
+            let updated_price = NotNan::new(T::from_f64(self.price(agent_id, item_id))).unwrap();
+            debug_assert!(updated_price <= price); // price is monotone decreasing, up to rounding to T
+
+            let mut v = heap.peek_mut().unwrap();
+            *v = (updated_price, Reverse(agent_id), self.num_update[agent_id]);
+        }
+    }
+
+    fn is_paid_for(&self, agent_id: usize) -> bool {
+        // alpha reaches 1 only through rounding; the agent is then charged nothing and U is unbounded
+        if self.alpha(agent_id) >= 1.0 {
+            return true;
+        }
+        self.consumption(agent_id) <= self.U(agent_id) * self.effective_budget(agent_id)
+    }
+
+    // ((1.0 - a) * (4.0 - self.beta) + self.beta) / ((1.0 - a) * (4.0 - self.beta))
+    #[allow(non_snake_case)]
+    fn U(&self, agent_id: usize) -> f64 {
+        let denominator = (self.alpha(agent_id) - 1.0) * (self.beta - 4.0);
+        self.beta / denominator + 1.0
+    }
+}
+
+// when run gives up before every agent is paid for
+#[derive(Clone, Copy, Debug, Default)]
+struct Stop<'a> {
+    deadline: Option<Instant>,
+    max_alpha_updates: Option<usize>, // compared with num_alpha_updates
+    cancel: Option<&'a AtomicBool>,   // set by another thread, see solve_cancellable
+}
+
+impl Stop<'_> {
+    fn at(deadline: Option<Instant>) -> Self {
+        Stop { deadline, ..Stop::default() }
+    }
+
+    fn reached(&self, num_alpha_updates: usize) -> bool {
+        self.max_alpha_updates.is_some_and(|max| num_alpha_updates >= max)
+            || self.cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+// every agent id below num_agents exactly once
+fn check_permutation(permutation: &[usize], num_agents: usize) -> Result<(), MbaError> {
+    if permutation.len() != num_agents {
+        return Err(MbaError::NotAPermutation { num_agents, agent_id: None });
+    }
+    let mut seen = vec![false; num_agents];
+    for &agent_id in permutation {
+        if agent_id >= num_agents || std::mem::replace(&mut seen[agent_id], true) {
+            return Err(MbaError::NotAPermutation {
+                num_agents,
+                agent_id: Some(agent_id),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn saturate(value: f64) -> f64 {
+    debug_assert!(!value.is_nan());
+    value.clamp(-f64::MAX, f64::MAX)
+}
+
+// sizes and state only; the bids and heaps can run to millions of entries
+impl<T: Scalar> std::fmt::Debug for PrimalDual<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrimalDual")
+            .field("num_agents", &self.num_agents)
+            .field("num_items", &self.num_items)
+            .field("num_bids", &self.num_bids())
+            .field("epsilon", &self.epsilon)
+            .field("beta", &self.beta)
+            .field("solved", &self.initialized)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AgentOrder, BuildError, InitialAlpha, MbaError, OverbidPolicy, PrimalDual, RangePolicy, RangeWarning, Scalar, SolveEvent, SolverConfig, StepStatus, Stop,
+        UnassignablePolicy, DEFAULT_SAFE_RANGE,
+    };
+    use crate::error_code::ErrorCode;
+    use crate::exact;
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::rng::RngConfig;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::fs;
+    use std::fs::read_to_string;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn sample() {
+        solve_sample::<f64>();
+    }
+
+    #[test]
+    fn sample_f32() {
+        solve_sample::<f32>();
+    }
+
+    fn solve_sample<T: Scalar>() {
+        let num_agents = 2;
+        let num_items = 3;
+
+        let mut solver = PrimalDual::<T>::new_scalar(num_agents, num_items, 0.01);
+
+        solver.set_budget(0, T::from_f64(300.0));
+        solver.set_budget(1, T::from_f64(400.0));
+
+        solver.set_bid(0, 0, T::from_f64(200.0));
+        solver.set_bid(0, 1, T::from_f64(200.0));
+        solver.set_bid(0, 2, T::from_f64(100.0));
+
+        solver.set_bid(1, 0, T::from_f64(100.0));
+        solver.set_bid(1, 1, T::from_f64(100.0));
+        solver.set_bid(1, 2, T::from_f64(200.0));
+
+        solver.solve();
+
+        let primal_objective_value = solver.get_primal_objective_value();
+        let dual_objective_value = solver.get_dual_objective_value();
+        let approximate_rate = solver.get_approximation_ratio();
+        assert_eq!(primal_objective_value, 500.0);
+        assert!(primal_objective_value >= dual_objective_value * approximate_rate);
+    }
+
+    // agent 0 overbids on item 0, agent 1 bids within its budget
+    fn overbid_instance(policy: OverbidPolicy) -> PrimalDual {
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_overbid_policy(policy);
+
+        solver.set_budget(0, 50.0);
+        solver.set_budget(1, 100.0);
+
+        solver.set_bid(0, 0, 80.0);
+        solver.set_bid(0, 1, 10.0);
+        solver.set_bid(1, 0, 30.0);
+
+        solver.solve();
+        solver
+    }
+
+    #[test]
+    fn overbid_reject() {
+        let solver = overbid_instance(OverbidPolicy::Reject);
+        assert_eq!(solver.get_bid(0, 0), 0.0);
+        assert_eq!(solver.get_primal_objective_value(), 40.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 0.3 / 4.0) * 0.99);
+        assert_eq!(solver.get_assignment()[1], [0]);
+    }
+
+    #[test]
+    fn overbid_clamp() {
+        let solver = overbid_instance(OverbidPolicy::Clamp);
+        assert_eq!(solver.get_bid(0, 0), 50.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 1.0 / 4.0) * 0.99);
+        assert!(solver.get_primal_objective_value() >= 40.0);
+        assert!(solver.get_assignment()[0].contains(&0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn overbid_error() {
+        overbid_instance(OverbidPolicy::Error);
+    }
+
+    #[test]
+    fn currency() {
+        // agent 0 pays in base currency, agent 1 in a currency worth 0.5 base
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget_in(0, 100.0, 1.0);
+        solver.set_budget_in(1, 200.0, 0.5);
+        solver.set_bid_in(0, 0, 40.0);
+        solver.set_bid_in(0, 1, 20.0);
+        solver.set_bid_in(1, 0, 60.0);
+        solver.set_bid_in(1, 1, 60.0);
+        assert_eq!(solver.get_budget(1), 100.0);
+        assert_eq!(solver.get_bid(1, 0), 30.0);
+        let ratio = solver.get_approximation_ratio();
+
+        // the rate of agent 1 was wrong; its bid on item 0 now beats agent 0
+        assert_eq!(solver.try_update_rate(1, 0.0), Err(MbaError::InvalidRate { agent_id: 1, rate: 0.0 }));
+        assert!(solver.try_update_rate(1, f64::NAN).is_err());
+        solver.update_rate(1, 0.8);
+        assert_eq!(solver.get_budget(1), 160.0);
+        assert_eq!(solver.get_bid(1, 0), 48.0);
+        assert_eq!(solver.get_budget_in(1), 200.0);
+        // scaling budget and bids of an agent together leaves its bid-to-budget ratio unchanged
+        assert_eq!(solver.get_approximation_ratio(), ratio);
+
+        solver.solve();
+        assert_eq!(solver.get_primal_objective_value(), 96.0);
+        assert_eq!(solver.to_agent_currency(1, 96.0, 2), 120.0);
+    }
+
+    #[test]
+    fn currency_base_setters() {
+        // a base-currency budget or bid replaces the amount given in the agent's currency
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget_in(0, 100.0, 1.0);
+        solver.set_bid_in(0, 0, 40.0);
+        solver.set_bid_in(0, 1, 20.0);
+        solver.set_budget(0, 70.0);
+        solver.set_bid(0, 0, 30.0);
+        solver.update_rate(0, 2.0);
+        assert_eq!(solver.get_budget(0), 70.0);
+        assert_eq!(solver.get_bid(0, 0), 30.0);
+        assert_eq!(solver.get_bid(0, 1), 40.0);
+        assert_eq!(solver.get_budget_in(0), 35.0);
+
+        assert_eq!(solver.try_set_budget_in(1, 50.0, 0.0), Err(MbaError::InvalidRate { agent_id: 1, rate: 0.0 }));
+        assert!(solver.try_set_budget_in(1, 50.0, f64::INFINITY).is_err());
+        assert_eq!(solver.get_budget(1), 0.0);
+        assert_eq!(solver.get_rate(1), 1.0);
+        assert_eq!(solver.try_set_bid_in(2, 0, 1.0), Err(MbaError::AgentIndexOutOfRange { agent_id: 2, num_agents: 2 }));
+    }
+
+    #[test]
+    fn update_rate_checks_first() {
+        let unchanged = |solver: &PrimalDual, budget: f64, bids: &[f64]| {
+            assert_eq!(solver.get_rate(0), 1.0);
+            assert_eq!(solver.get_budget(0), budget);
+            for (item_id, &bid) in bids.iter().enumerate() {
+                assert_eq!(solver.get_bid(0, item_id), bid);
+            }
+        };
+
+        // the budget is in base currency, so the bids grow past it
+        let mut solver = PrimalDual::new(1, 2, 0.01);
+        solver.set_overbid_policy(OverbidPolicy::Error);
+        solver.set_budget_in(0, 100.0, 1.0);
+        solver.set_bid_in(0, 0, 20.0);
+        solver.set_bid_in(0, 1, 40.0);
+        solver.set_budget(0, 100.0);
+        let e = solver.try_update_rate(0, 3.0).unwrap_err();
+        assert_eq!(e.code(), ErrorCode::BidExceedsBudget);
+        unchanged(&solver, 100.0, &[20.0, 40.0]);
+
+        // the budget fits the safe range at the new rate but the bid does not
+        let mut solver = PrimalDual::new(1, 2, 0.01);
+        solver.set_range_policy(RangePolicy::Error);
+        solver.set_safe_range(1e-3, 1e3);
+        solver.set_budget_in(0, 100.0, 1.0);
+        solver.set_bid_in(0, 0, 50.0);
+        assert!(matches!(solver.try_update_rate(0, 1e-5), Err(MbaError::ValueOutOfRange { name: "bid", .. })));
+        unchanged(&solver, 100.0, &[50.0]);
+
+        // the raised budget readmits a rejected base-currency bid, which does not fit the memory limit
+        let config = SolverConfig {
+            max_memory_bytes: Some(PrimalDual::<f64>::construction_bytes(1, 2) + PrimalDual::<f64>::bid_bytes()),
+            ..SolverConfig::default()
+        };
+        let mut solver = PrimalDual::with_config(1, 2, config);
+        solver.set_budget_in(0, 10.0, 1.0);
+        solver.set_bid_in(0, 0, 5.0);
+        solver.set_bid(0, 1, 20.0);
+        assert_eq!(solver.try_update_rate(0, 3.0).unwrap_err().code(), ErrorCode::MemoryLimitExceeded);
+        unchanged(&solver, 10.0, &[5.0, 0.0]);
+        solver.update_rate(0, 1.5);
+        assert_eq!(solver.get_budget(0), 15.0);
+        assert_eq!(solver.get_bid(0, 1), 0.0);
+    }
+
+    // agent 0 outbids agent 1 on items 0 and 1 at rate 1 but not at rate 0.01; its bid on item 2 exceeds its budget
+    fn currency_solver(rate: f64) -> PrimalDual {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget_in(0, 100.0, rate);
+        solver.set_bid_in(0, 0, 40.0);
+        solver.set_bid_in(0, 1, 40.0);
+        solver.set_bid_in(0, 2, 150.0);
+        solver.set_budget(1, 20.0);
+        solver.set_bids([(1, 0, 10.0), (1, 1, 10.0)]);
+        solver
+    }
+
+    #[test]
+    fn update_rate_after_solve() {
+        let mut solver = currency_solver(1.0);
+        solver.solve();
+        assert_eq!(solver.get_item_assignment(), vec![Some(0), Some(0), None]);
+
+        solver.update_rate(0, 0.01);
+        solver.solve();
+        let mut fresh = currency_solver(0.01);
+        fresh.solve();
+        assert_eq!(solver.get_item_assignment(), vec![Some(1), Some(1), None]);
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+
+        // the bid above the budget stays rejected under OverbidPolicy::Reject instead of being clamped
+        assert_eq!(solver.get_bid(0, 2), 0.0);
+        let mut clamped = currency_solver(1.0);
+        clamped.set_overbid_policy(OverbidPolicy::Clamp);
+        clamped.set_bid_in(0, 2, 150.0);
+        clamped.update_rate(0, 0.5);
+        assert_eq!(clamped.get_bid(0, 2), 50.0);
+    }
+
+    // agent 0 is 1% over its budget, agent 2 only sets beta
+    fn barely_over_budget_instance(mode: InitialAlpha) -> PrimalDual {
+        let mut solver = PrimalDual::new(3, 101, 0.01);
+        solver.set_initial_alpha_mode(mode);
+
+        solver.set_budget(0, 100.0);
+        solver.set_budget(1, 1000.0);
+        solver.set_budget(2, 1000.0);
+        for item_id in 0..100 {
+            solver.set_bid(0, item_id, 1.01);
+            solver.set_bid(1, item_id, 1.0);
+        }
+        solver.set_bid(2, 100, 39.592);
+
+        solver.solve();
+        solver
+    }
+
+    #[test]
+    fn adaptive_initial_alpha() {
+        let default = barely_over_budget_instance(InitialAlpha::Epsilon);
+        let adaptive = barely_over_budget_instance(InitialAlpha::Adaptive);
+
+        assert_eq!(default.alpha[0], 0.01);
+        assert!(adaptive.alpha[0] > 0.0);
+        assert!(adaptive.alpha[0] * 10.0 <= default.alpha[0]);
+        assert!(adaptive.is_paid_for(0));
+        // the smallest power of 1 + epsilon below epsilon that pays for agent 0
+        let required = 1.0 - adaptive.beta / ((4.0 - adaptive.beta) * 0.01);
+        assert!(adaptive.alpha[0] >= required - 1e-12 && adaptive.alpha[0] * 1.01 > required);
+        assert!(adaptive.get_primal_objective_value() >= adaptive.get_dual_objective_value() * adaptive.get_approximation_ratio());
+
+        // agent 0 is 1% over budget here too. with its small alpha it outprices agent 2 on the items agent 1 sheds, and then
+        // hands its own items to agent 3, which values them more than agent 2 values the shed ones
+        let contested = |mode| {
+            let mut solver = PrimalDual::new(5, 206, 0.01);
+            solver.set_initial_alpha_mode(mode);
+            for (agent_id, budget) in [100.0, 100.0, 1000.0, 1000.0, 1000.0].into_iter().enumerate() {
+                solver.set_budget(agent_id, budget);
+            }
+            for item_id in 0..100 {
+                solver.set_bid(0, item_id, 1.01);
+                solver.set_bid(3, item_id, 1.006);
+            }
+            for item_id in 100..105 {
+                solver.set_bid(1, item_id, 1.05);
+                solver.set_bid(0, item_id, 1.0);
+                solver.set_bid(2, item_id, 0.995);
+            }
+            for item_id in 105..205 {
+                solver.set_bid(1, item_id, 1.0);
+            }
+            solver.set_bid(4, 205, 39.592);
+            solver.solve();
+            solver
+        };
+        let default = contested(InitialAlpha::Epsilon);
+        let adaptive = contested(InitialAlpha::Adaptive);
+        assert!(adaptive.get_primal_objective_value() > default.get_primal_objective_value());
+        assert_eq!(default.get_consumption(3), 0.0);
+        assert!(adaptive.get_consumption(3) > 0.0);
+        assert!(adaptive.get_primal_objective_value() >= adaptive.get_dual_objective_value() * adaptive.get_approximation_ratio());
+    }
+
+    #[test]
+    fn fixed_initial_alpha() {
+        let mut solver = PrimalDual::new(3, 101, 0.01);
+        solver.set_initial_alpha(0.001);
+        assert_eq!(solver.initial_alpha, InitialAlpha::Fixed(0.001));
+        for alpha in [0.0, -0.5, 0.02, f64::NAN] {
+            let e = solver.try_set_initial_alpha_mode(InitialAlpha::Fixed(alpha)).unwrap_err();
+            assert!(matches!(e, MbaError::InvalidInitialAlpha { agent_id: None, .. }), "{}", alpha);
+            assert_eq!(e.code(), ErrorCode::InvalidInitialAlpha);
+        }
+        assert_eq!(solver.initial_alpha, InitialAlpha::Fixed(0.001));
+        assert_eq!(solver.try_set_initial_alpha_mode(InitialAlpha::Fixed(0.01)), Ok(()));
+        let solver = barely_over_budget_instance(InitialAlpha::Fixed(0.001));
+        assert!(solver.alpha[0] >= 0.001);
+
+        // a smaller epsilon set afterwards lowers the start to it
+        let first_alpha = |epsilon: f64, agent_epsilon: Option<f64>| {
+            let mut solver = PrimalDual::new(1, 2, 0.01);
+            solver.set_initial_alpha(0.005);
+            solver.set_epsilon(epsilon);
+            if let Some(agent_epsilon) = agent_epsilon {
+                solver.set_agent_epsilon(0, agent_epsilon);
+            }
+            solver.set_budget(0, 1.0);
+            solver.set_bids([(0, 0, 1.0), (0, 1, 1.0)]);
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let sink = Arc::clone(&events);
+            solver.set_observer(Box::new(move |event| sink.lock().unwrap().push(event)));
+            solver.solve();
+            let events = events.lock().unwrap();
+            events.iter().find_map(|event| match *event {
+                SolveEvent::AlphaUpdated { new, .. } => Some(new),
+                _ => None,
+            })
+        };
+        assert_eq!(first_alpha(0.01, None), Some(0.005));
+        assert_eq!(first_alpha(0.002, None), Some(0.002));
+        assert_eq!(first_alpha(0.01, Some(0.001)), Some(0.001));
+    }
+
+    fn stability_instance(bid: f64) -> PrimalDual {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        solver.set_bid(0, 0, 200.0);
+        solver.set_bid(0, 1, 200.0);
+        solver.set_bid(0, 2, 100.0);
+        solver.set_bid(1, 0, 100.0);
+        solver.set_bid(1, 1, bid);
+        solver.set_bid(1, 2, 200.0);
+        solver
+    }
+
+    #[test]
+    fn stability() {
+        let mut solver = stability_instance(100.0);
+        solver.solve();
+        let previous = solver.extract_solution();
+
+        // agent 1 now outbids agent 0 on item 1
+        let mut unconstrained = stability_instance(250.0);
+        unconstrained.solve();
+        let unconstrained = unconstrained.extract_solution();
+        assert_ne!(unconstrained.item_owner, previous.item_owner);
+
+        let mut solver = stability_instance(250.0);
+        let num_switches = solver.solve_with_stability(&previous, 1000.0);
+        assert_eq!(num_switches, 0);
+        assert_eq!(solver.extract_solution().item_owner, previous.item_owner);
+
+        let mut solver = stability_instance(250.0);
+        let num_switches = solver.solve_with_stability(&previous, 0.0);
+        let solution = solver.extract_solution();
+        assert_eq!(solution.item_owner, unconstrained.item_owner);
+        assert_eq!(solution.primal, unconstrained.primal);
+        assert!(num_switches > 0);
+
+        // the favoring does not outlive the call
+        let mut solver = stability_instance(250.0);
+        let stats = solver.solve_with_stability_stats(&previous, 1000.0);
+        assert_eq!(stats.num_switches, 0);
+        assert_eq!(solver.extract_solution().item_owner, previous.item_owner);
+        assert!(solver.previous_owner.iter().all(Option::is_none));
+        assert_eq!(solver.switch_penalty, 0.0);
+        let stats = solver.solve_with_stats();
+        assert_eq!(stats.num_switches, 0);
+        assert_eq!(solver.extract_solution(), unconstrained);
+    }
+
+    #[test]
+    fn certified_ratio() {
+        // a certificate of every instance, during the solve as well as after it
+        for seed in 0..10 {
+            let instance = crate::generator::generate(8, 60, 0.5, (5.0, 40.0), (0.5, 20.0), seed);
+            let mut solver = PrimalDual::from_instance(&instance, 0.05);
+            while solver.step() == StepStatus::InProgress {
+                let ratio = solver.get_certified_ratio();
+                assert!(ratio <= 1.0);
+                assert!(solver.get_primal_objective_value() >= ratio * solver.get_dual_objective_value(), "seed {}", seed);
+            }
+            let ratio = solver.get_certified_ratio();
+            assert!(ratio >= solver.get_approximation_ratio(), "seed {}", seed);
+            assert!(solver.get_primal_objective_value() >= ratio * solver.get_dual_objective_value(), "seed {}", seed);
+        }
+        assert_eq!(PrimalDual::new(2, 3, 0.1).get_certified_ratio(), 1.0);
+    }
+
+    #[test]
+    fn dual_bounds_primal() {
+        let mut solver = stability_instance(100.0);
+        solver.solve();
+        let previous = solver.extract_solution();
+
+        // the penalty keeps item 1 with agent 0 although agent 1 now has the larger price on it,
+        // so the accounting over held items understates the bound
+        let mut solver = stability_instance(250.0);
+        solver.solve_with_stability(&previous, 1000.0);
+        let primal = solver.get_primal_objective_value();
+        let dual = solver.get_dual_objective_value();
+        assert!(primal <= dual + 1e-9);
+        assert!(solver.get_legacy_dual_objective_value() < dual);
+
+        let mut solver = stability_instance(250.0);
+        solver.solve();
+        assert!(solver.get_primal_objective_value() <= solver.get_dual_objective_value() + 1e-9);
+        assert_eq!(solver.get_legacy_dual_objective_value().to_bits(), solver.get_dual_objective_value().to_bits());
+
+        let mut solver = stability_instance(250.0);
+        solver.set_round_budget_fraction(0.5);
+        solver.solve();
+        assert!(solver.get_primal_objective_value() <= solver.get_dual_objective_value() + 1e-9);
+
+        let mut solver = stability_instance(250.0);
+        solver.set_agent_active(1, false);
+        solver.solve();
+        assert!(solver.get_primal_objective_value() <= solver.get_dual_objective_value() + 1e-9);
+
+        let mut solver = PrimalDual::new(2, 3, 0.0);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        solver.set_bid(0, 0, 200.0);
+        solver.set_bid(1, 1, 250.0);
+        solver.set_bid(1, 2, 200.0);
+        solver.solve();
+        assert!(solver.get_primal_objective_value() <= solver.get_dual_objective_value() + 1e-9);
+    }
+
+    #[test]
+    fn extreme_magnitudes() {
+        let mut state: u64 = 2463534242;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..50 {
+            let num_agents = (next() % 5 + 1) as usize;
+            let num_items = (next() % 20 + 1) as usize;
+            let mut solver = PrimalDual::new(num_agents, num_items, 0.1);
+            for agent_id in 0..num_agents {
+                solver.set_budget(agent_id, 10f64.powi((next() % 600) as i32 - 300));
+            }
+            for agent_id in 0..num_agents {
+                for item_id in 0..num_items {
+                    solver.set_bid(agent_id, item_id, 10f64.powi((next() % 616) as i32 - 307));
+                }
+            }
+            solver.solve();
+
+            assert!(solver.get_primal_objective_value().is_finite());
+            assert!(solver.get_dual_objective_value().is_finite());
+            assert!(solver.get_approximation_ratio().is_finite());
+        }
+    }
+
+    #[test]
+    fn overflowing_consumption() {
+        let mut solver = PrimalDual::new(1, 4, 0.1);
+        solver.set_budget(0, 1e308);
+        for item_id in 0..4 {
+            solver.set_bid(0, item_id, 1e308);
+        }
+        solver.solve();
+        assert_eq!(solver.get_primal_objective_value(), 1e308);
+        assert!(solver.get_dual_objective_value().is_finite());
+    }
+
+    #[test]
+    fn out_of_range_warning() {
+        let mut solver = PrimalDual::new(1, 2, 0.1);
+        solver.set_budget(0, 1e300);
+        solver.set_bid(0, 0, 1.0);
+        solver.set_bid(0, 1, 1e-200);
+        let warnings = solver.get_range_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            warnings[0],
+            RangeWarning {
+                name: "budget",
+                value: 1e300,
+                safe_range: DEFAULT_SAFE_RANGE
+            }
+        );
+        assert_eq!(warnings[1].name, "bid");
+        assert_eq!(warnings[1].to_string(), format!("bid {} is outside the safe range [{}, {}]", 1e-200, DEFAULT_SAFE_RANGE.0, DEFAULT_SAFE_RANGE.1));
+        assert_eq!(solver.get_budget(0), 1e300);
+
+        solver.clear_range_warnings();
+        assert!(solver.get_range_warnings().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range_error() {
+        let mut solver = PrimalDual::new(1, 1, 0.1);
+        solver.set_range_policy(RangePolicy::Error);
+        solver.set_budget(0, 1e300);
+    }
+
+    // every agent bidding on most items
+    fn generated_instance(num_agents: usize, num_items: usize) -> (Vec<f64>, Vec<(usize, usize, f64)>) {
+        let instance = generate(num_agents, num_items, 0.95, (50.0, 150.0), (1.0, 22.0), 306);
+        (instance.budgets, instance.bids)
+    }
+
+    #[test]
+    fn inactive_agents() {
+        let num_agents = 6;
+        let num_items = 40;
+        let (budgets, bids) = generated_instance(num_agents, num_items);
+        let inactive = [1, 4];
+
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        for agent_id in inactive {
+            solver.set_agent_active(agent_id, false);
+        }
+        solver.solve();
+
+        let active: Vec<usize> = (0..num_agents).filter(|agent_id| !inactive.contains(agent_id)).collect();
+        let mut fresh = PrimalDual::new(active.len(), num_items, 0.01);
+        for (new_id, &agent_id) in active.iter().enumerate() {
+            fresh.set_budget(new_id, budgets[agent_id]);
+        }
+        for &(agent_id, item_id, bid) in &bids {
+            if let Some(new_id) = active.iter().position(|&id| id == agent_id) {
+                fresh.set_bid(new_id, item_id, bid);
+            }
+        }
+        fresh.solve();
+
+        assert_eq!(solver.get_primal_objective_value(), fresh.get_primal_objective_value());
+        assert_eq!(solver.get_dual_objective_value(), fresh.get_dual_objective_value());
+        assert_eq!(solver.get_approximation_ratio(), fresh.get_approximation_ratio());
+        for (new_id, &agent_id) in active.iter().enumerate() {
+            assert_eq!(solver.get_assignment()[agent_id], fresh.get_assignment()[new_id]);
+        }
+        for agent_id in inactive {
+            assert!(solver.get_assignment()[agent_id].is_empty());
+            assert!(solver.get_bid(agent_id, 0) > 0.0 || solver.get_bid(agent_id, 1) > 0.0);
+        }
+    }
+
+    // items 1 and 2 only receive bids above budget
+    fn unassignable_instance(policy: UnassignablePolicy) -> PrimalDual {
+        let mut solver = PrimalDual::new(3, 3, 0.01);
+        solver.set_unassignable_policy(policy);
+        solver.set_budget(0, 100.0);
+        solver.set_budget(1, 50.0);
+        solver.set_budget(2, 80.0);
+
+        solver.set_bid(0, 0, 40.0);
+        solver.set_bid(0, 1, 150.0);
+        solver.set_bid(1, 1, 200.0);
+        solver.set_bid(2, 2, 90.0);
+        solver.solve();
+        solver
+    }
+
+    #[test]
+    fn unassignable_ignore() {
+        let solver = unassignable_instance(UnassignablePolicy::Ignore);
+        assert_eq!(solver.get_primal_objective_value(), 40.0);
+        assert_eq!(solver.get_assignment()[0], [0]);
+    }
+
+    #[test]
+    fn unassignable_clamp_best_bid() {
+        let solver = unassignable_instance(UnassignablePolicy::ClampBestBid);
+        // agent 1 has the largest bid on item 1, agent 2 the only bid on item 2
+        assert_eq!(solver.get_bid(1, 1), 50.0);
+        assert_eq!(solver.get_bid(0, 1), 0.0);
+        assert_eq!(solver.get_bid(2, 2), 80.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 1.0 / 4.0) * 0.99);
+        assert_eq!(solver.get_primal_objective_value(), 40.0 + 50.0 + 80.0);
+        assert_eq!(solver.get_assignment()[1], [1]);
+        assert_eq!(solver.get_assignment()[2], [2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "items [1, 2]")]
+    fn unassignable_error() {
+        unassignable_instance(UnassignablePolicy::Error);
+    }
+
+    // the items of one round; every round gets an identical copy
+    fn paced_solver(num_rounds: usize, fraction: f64) -> PrimalDual {
+        let num_agents = 4;
+        let num_items = 30;
+        let mut solver = PrimalDual::new(num_agents, num_items * num_rounds, 0.01);
+        solver.set_round_budget_fraction(fraction);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 60.0 + 20.0 * agent_id as f64);
+        }
+        for round in 0..num_rounds {
+            for agent_id in 0..num_agents {
+                for item_id in 0..num_items {
+                    let bid = ((agent_id * 7 + item_id * 3) % 9) as f64;
+                    if bid > 0.0 {
+                        solver.set_bid(agent_id, round * num_items + item_id, bid);
+                    }
+                }
+            }
+        }
+        solver
+    }
+
+    #[test]
+    fn round_budget_fraction() {
+        let mut full = paced_solver(2, 1.0);
+        full.solve();
+        let full_spend: f64 = full.get_spends().iter().sum();
+
+        let mut first = paced_solver(1, 0.5);
+        first.solve();
+        let first_spends = first.get_spends();
+        assert!((0..4).all(|agent_id| first.get_effective_budget(agent_id) == first.get_budget(agent_id) * 0.5));
+        assert!((0..4).all(|agent_id| first_spends[agent_id] <= first.get_effective_budget(agent_id)));
+
+        let mut second = paced_solver(1, 0.5);
+        second.carry_over(&first_spends);
+        second.solve();
+        let second_spends = second.get_spends();
+        assert!((0..4).all(|agent_id| first_spends[agent_id] + second_spends[agent_id] <= second.get_budget(agent_id)));
+
+        let paced_spend: f64 = first_spends.iter().chain(second_spends.iter()).sum();
+        let ratio = full.get_approximation_ratio();
+        assert!(paced_spend >= full_spend * ratio);
+        assert!(full_spend >= paced_spend * ratio);
+    }
+
+    #[test]
+    fn carry_over_limits_budget() {
+        let mut solver = PrimalDual::new(1, 1, 0.01);
+        solver.set_budget(0, 100.0);
+        solver.set_round_budget_fraction(0.5);
+        solver.carry_over(&[70.0]);
+        assert_eq!(solver.get_effective_budget(0), 30.0);
+        solver.carry_over(&[40.0]);
+        assert_eq!(solver.get_effective_budget(0), 0.0);
+        assert_eq!(solver.get_budget(0), 100.0);
+    }
+
+    #[test]
+    fn max_bidder() {
+        let mut solver = PrimalDual::new(3, 3, 0.01);
+        for agent_id in 0..3 {
+            solver.set_budget(agent_id, 100.0);
+        }
+        // item 0 has no bidder, item 1 one, item 2 three
+        solver.set_bid(2, 1, 10.0);
+        solver.set_bid(0, 2, 10.0);
+        solver.set_bid(1, 2, 30.0);
+        solver.set_bid(2, 2, 20.0);
+
+        assert_eq!(solver.get_max_bidder(0), None);
+        assert_eq!(solver.get_max_bidder(1), Some(2));
+        assert_eq!(solver.get_max_bidder(2), Some(1));
+
+        solver.set_agent_active(1, false);
+        assert_eq!(solver.get_max_bidder(2), Some(2));
+        solver.set_agent_active(2, false);
+        assert_eq!(solver.get_max_bidder(1), None);
+
+        solver.solve();
+        assert!(solver.get_assignment().iter().flatten().all(|&item_id| item_id == 2));
+    }
+
+    #[test]
+    fn exact_epsilon() {
+        let mut solver = PrimalDual::try_new(2, 4, 0.0).unwrap();
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 4.0);
+        for (agent_id, item_id, bid) in [(0, 0, 8.0), (0, 1, 7.0), (1, 0, 4.0), (1, 1, 4.0), (0, 2, 1.0)] {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+
+        assert_eq!(solver.get_primal_objective_value(), 13.0);
+        assert_eq!(solver.get_approximation_ratio(), 1.0);
+        let solution = solver.extract_solution();
+        assert_eq!(solution.item_owner, vec![Some(0), Some(1), Some(0), None]);
+        assert_eq!(solution.verify(), Ok(()));
+    }
+
+    #[test]
+    fn exact_epsilon_too_large() {
+        assert!(matches!(PrimalDual::try_new(10, 100, 0.0), Err(BuildError::TooLargeForExact { num_agents: 10, num_items: 100 })));
+        assert!(matches!(PrimalDual::try_new(2, 2, -0.1), Err(BuildError::InvalidEpsilon { .. })));
+        assert!(matches!(PrimalDual::try_new(2, 2, 1.0), Err(BuildError::InvalidEpsilon { .. })));
+        assert!(matches!(PrimalDual::try_new(2, 2, f64::NAN), Err(BuildError::InvalidEpsilon { .. })));
+        let e = PrimalDual::try_new(10, 100, 0.0).err().unwrap();
+        assert!(e.to_string().contains("positive epsilon"));
+        // refused before anything of that size is allocated
+        assert!(matches!(
+            PrimalDual::try_new(30_000, 30_000, 0.0),
+            Err(BuildError::TooLargeForExact {
+                num_agents: 30_000,
+                num_items: 30_000
+            })
+        ));
+    }
+
+    // counts the allocations of the threads that set COUNTING
+    struct CountingAllocator;
+
+    thread_local! {
+        static COUNTING: Cell<bool> = const { Cell::new(false) };
+        static NUM_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+        static NUM_BYTES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            if COUNTING.with(|counting| counting.get()) {
+                NUM_ALLOCATIONS.with(|num| num.set(num.get() + 1));
+                NUM_BYTES.with(|num| num.set(num.get() + layout.size()));
+            }
+            System.alloc(layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            if COUNTING.with(|counting| counting.get()) {
+                NUM_ALLOCATIONS.with(|num| num.set(num.get() + 1));
+                NUM_BYTES.with(|num| num.set(num.get() + new_size));
+            }
+            System.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn allocation_free_sweeps() {
+        let (budgets, bids) = generated_instance(30, 300);
+        let mut solver = PrimalDual::new(30, 300, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+
+        // warm-up sizes the scratch buffer
+        solver.solve();
+        let expected = solver.extract_solution();
+        solver.reset_solve_state();
+
+        COUNTING.with(|counting| counting.set(true));
+        solver.solve();
+        COUNTING.with(|counting| counting.set(false));
+
+        assert_eq!(NUM_ALLOCATIONS.with(|num| num.get()), 0);
+        assert_eq!(solver.extract_solution(), expected);
+    }
+
+    #[test]
+    fn assignment() {
+        let (budgets, bids) = generated_instance(30, 300);
+        let mut solver = PrimalDual::new(30, 300, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.set_agent_active(3, false);
+        solver.solve();
+
+        let assignment = solver.assignment();
+        let primal = solver.get_primal_objective_value();
+        assert!((assignment.total_value() - primal).abs() <= 1e-9 * primal);
+        for agent_id in 0..30 {
+            assert!(assignment.items_of(agent_id).iter().all(|&item_id| assignment.agent_of(item_id) == Some(agent_id)));
+            assert!((assignment.spend(agent_id) - solver.consumptions[agent_id]).abs() <= 1e-9);
+        }
+        assert_eq!((0..300).filter(|&item_id| assignment.agent_of(item_id).is_some()).count(), assignment.iter().count());
+        assert!(assignment.iter().all(|(agent_id, item_id, bid)| bid == solver.get_bid(agent_id, item_id)));
+    }
+
+    #[test]
+    fn read_while_holding_assignment() {
+        let (budgets, bids) = generated_instance(5, 30);
+        let mut solver = PrimalDual::new(5, 30, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        solver.set_bids(bids);
+        solver.solve();
+
+        let assignment = solver.get_assignment();
+        let spend: f64 = assignment
+            .iter()
+            .enumerate()
+            .map(|(agent_id, item_ids)| {
+                item_ids
+                    .iter()
+                    .map(|&item_id| solver.get_bid(agent_id, item_id))
+                    .sum::<f64>()
+                    .min(solver.get_budget(agent_id))
+            })
+            .sum();
+        assert!((spend - solver.get_primal_objective_value()).abs() <= 1e-9 * spend);
+    }
+
+    #[test]
+    fn item_assignment() {
+        let (budgets, bids) = generated_instance(8, 50);
+        let mut solver = PrimalDual::new(8, 51, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        solver.set_bids(bids);
+        // item 50 only has a bid above the budget, which is rejected
+        solver.set_bid(0, 50, 1000.0);
+        solver.solve();
+
+        let item_assignment = solver.get_item_assignment();
+        assert_eq!(item_assignment.len(), 51);
+        assert_eq!(item_assignment[50], None);
+        for (item_id, &owner) in item_assignment.iter().enumerate() {
+            match owner {
+                Some(agent_id) => assert!(solver.get_assignment()[agent_id].contains(&item_id)),
+                None => assert!(solver.get_assignment().iter().all(|item_ids| !item_ids.contains(&item_id))),
+            }
+        }
+        let num_held: usize = solver.get_assignment().iter().map(|item_ids| item_ids.len()).sum();
+        assert_eq!(item_assignment.iter().flatten().count(), num_held);
+    }
+
+    #[test]
+    fn duals() {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        assert_eq!(solver.get_duals(), (vec![0.0, 0.0], vec![200.0, 200.0, 200.0]));
+
+        solver.solve();
+        let (alphas, prices) = solver.get_duals();
+        for (agent_id, &alpha) in alphas.iter().enumerate() {
+            assert_eq!(alpha, solver.get_alpha(agent_id));
+        }
+        for (item_id, &price) in prices.iter().enumerate() {
+            assert_eq!(price, solver.get_item_price(item_id));
+            let max_price = (0..2)
+                .map(|agent_id| (1.0 - alphas[agent_id]) * solver.get_bid(agent_id, item_id))
+                .fold(0.0, f64::max);
+            assert_eq!(price, max_price);
+        }
+        let dual: f64 = (0..2).map(|agent_id| solver.get_budget(agent_id) * alphas[agent_id]).sum::<f64>() + prices.iter().sum::<f64>();
+        assert!((dual - solver.get_dual_objective_value()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn last_bid_wins() {
+        for (first, last, winner, primal) in [(10.0, 200.0, 0, 200.0), (200.0, 10.0, 1, 100.0)] {
+            let mut solver = PrimalDual::new(2, 1, 0.01);
+            solver.set_budget(0, 1000.0);
+            solver.set_budget(1, 1000.0);
+            solver.set_bid(0, 0, first);
+            solver.set_bid(1, 0, 100.0);
+            solver.set_bid(0, 0, last);
+            assert_eq!(solver.get_bid(0, 0), last);
+            assert_eq!(solver.beta, 0.1f64.max(last / 1000.0));
+            assert_eq!(solver.num_bids(), 2);
+
+            solver.solve();
+            assert_eq!(solver.extract_solution().item_owner, [Some(winner)]);
+            assert_eq!(solver.get_primal_objective_value(), primal);
+        }
+
+        // a rejected bid replaces an accepted one
+        let mut solver = PrimalDual::new(1, 1, 0.01);
+        solver.set_budget(0, 50.0);
+        solver.set_bid(0, 0, 10.0);
+        solver.set_bid(0, 0, 60.0);
+        assert_eq!(solver.get_bid(0, 0), 0.0);
+        assert_eq!(solver.get_max_bidder(0), None);
+        solver.solve();
+        assert_eq!(solver.get_primal_objective_value(), 0.0);
+    }
+
+    #[test]
+    fn equal_prices_go_to_lower_agent() {
+        // both agents bid the same on every item, and together they are over budget
+        let solve = |agent_ids: [usize; 2]| {
+            let mut solver = PrimalDual::new(2, 8, 0.01);
+            solver.set_budget(0, 20.0);
+            solver.set_budget(1, 20.0);
+            for item_id in 0..8 {
+                for agent_id in agent_ids {
+                    solver.set_bid(agent_id, item_id, 1.0 + (item_id % 3) as f64);
+                }
+            }
+            solver.solve();
+            format!("{:?}", solver.extract_solution())
+        };
+
+        let expected = solve([0, 1]);
+        for _ in 0..5 {
+            assert_eq!(solve([0, 1]), expected);
+            assert_eq!(solve([1, 0]), expected);
+        }
+    }
+
+    #[test]
+    fn item_with_only_rejected_bids() {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 20.0);
+        solver.set_bid(0, 0, 8.0);
+        solver.set_bid(1, 0, 9.0);
+        solver.set_bid(0, 1, 11.0);
+        solver.set_bid(1, 1, 25.0);
+        solver.set_bid(1, 2, 15.0);
+        solver.solve();
+
+        let solution = solver.extract_solution();
+        assert!(solution.verify().is_ok());
+        assert_eq!(solution.item_owner[1], None);
+        assert!(solution.item_owner[0].is_some() && solution.item_owner[2].is_some());
+    }
+
+    #[test]
+    fn sparse_bids() {
+        // a dense matrix would take 80 GB
+        let num_agents = 100_000;
+        let num_items = 100_000;
+        COUNTING.with(|counting| counting.set(true));
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.1);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 20.0);
+            for k in 0..10 {
+                let item_id = (agent_id * 7919 + k * 10007) % num_items;
+                solver.set_bid(agent_id, item_id, 1.0 + ((agent_id + k) % 5) as f64);
+            }
+        }
+        solver.solve();
+        COUNTING.with(|counting| counting.set(false));
+
+        // every allocation ever made, including the ones freed again
+        assert!(NUM_BYTES.with(|num| num.get()) < 1 << 30);
+        assert_eq!(solver.num_bids(), 1_000_000);
+        assert_eq!(solver.get_bid(0, 1), 0.0);
+        assert_eq!(solver.get_bid(0, 0), 1.0);
+        let solution = solver.extract_solution();
+        assert!(solution.verify().is_ok());
+        assert!(solution.primal <= solution.dual + 1e-6);
+    }
+
+    // one large agent bidding on every item and many small agents on a few items each, as in ad auctions
+    fn adwords_solver(order: AgentOrder) -> PrimalDual {
+        let num_agents = 60;
+        let num_items = 600;
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+        solver.set_agent_order(order);
+        solver.set_budget(num_agents - 1, 2000.0);
+        for item_id in 0..num_items {
+            solver.set_bid(num_agents - 1, item_id, 4.0 + (item_id % 5) as f64);
+        }
+        for agent_id in 0..num_agents - 1 {
+            solver.set_budget(agent_id, 30.0);
+            for k in 0..20 {
+                let item_id = (agent_id * 37 + k * 11) % num_items;
+                solver.set_bid(agent_id, item_id, 3.0 + ((agent_id + k) % 4) as f64);
+            }
+        }
+        solver
+    }
+
+    #[test]
+    fn agent_orders() {
+        let mut sweeps = Vec::new();
+        for order in [AgentOrder::IndexOrder, AgentOrder::LargestOverspendFirst, AgentOrder::MostBidsFirst] {
+            let mut solver = adwords_solver(order);
+            solver.initialize();
+            let (num_sweeps, completed) = solver.run(None);
+            assert!(completed);
+            sweeps.push(num_sweeps);
+
+            let solution = solver.extract_solution();
+            assert_eq!(solution.verify(), Ok(()));
+            assert!(solution.primal >= solution.dual * solution.get_approximation_ratio());
+
+            let mut again = adwords_solver(order);
+            again.solve();
+            assert_eq!(again.extract_solution(), solution);
+        }
+        assert!(sweeps[1] < sweeps[0], "{:?}", sweeps);
+    }
+
+    #[test]
+    fn bid_order_independent() {
+        let num_agents = 4;
+        let num_items = 12;
+        let budgets = [30.0, 30.0, 20.0, 20.0];
+        // agents 0 and 1, and agents 2 and 3, bid identically
+        let bids: Vec<(usize, usize, f64)> = (0..num_agents)
+            .flat_map(|agent_id| (0..num_items).map(move |item_id| (agent_id, item_id, 1.0 + ((agent_id / 2 * 3 + item_id) % 5) as f64)))
+            .collect();
+
+        let solve = |bids: &mut dyn Iterator<Item = &(usize, usize, f64)>| {
+            let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+            for (agent_id, &budget) in budgets.iter().enumerate() {
+                solver.set_budget(agent_id, budget);
+            }
+            for &(agent_id, item_id, bid) in bids {
+                solver.set_bid(agent_id, item_id, bid);
+            }
+            let max_bidders: Vec<Option<usize>> = (0..num_items).map(|item_id| solver.get_max_bidder(item_id)).collect();
+            solver.solve();
+            (max_bidders, solver.extract_solution())
+        };
+
+        let (forward_max_bidders, forward) = solve(&mut bids.iter());
+        let (reverse_max_bidders, reverse) = solve(&mut bids.iter().rev());
+        assert_eq!(forward_max_bidders, reverse_max_bidders);
+        assert!(forward_max_bidders.iter().all(|&agent_id| agent_id == Some(0) || agent_id == Some(2)));
+        assert_eq!(forward, reverse);
+    }
+
+    #[test]
+    fn bids_round_trip() {
+        let (budgets, bids) = generated_instance(5, 30);
+        let mut solver = PrimalDual::new(5, 30, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        // rejected, and drops the bid agent 0 had on item 0
+        solver.set_bid(0, 0, 1000.0);
+
+        assert_eq!(solver.bids_iter().count(), solver.num_bids());
+        let accepted = |&&(agent_id, item_id, bid): &&(usize, usize, f64)| bid <= budgets[agent_id] && (agent_id, item_id) != (0, 0);
+        assert_eq!(solver.num_bids(), bids.iter().filter(accepted).count());
+        assert!(solver.bids_of_agent(2).all(|(agent_id, _, bid)| agent_id == 2 && bid > 0.0));
+        assert_eq!(solver.bids_on_item(3).count(), (0..5).filter(|&agent_id| solver.get_bid(agent_id, 3) > 0.0).count());
+
+        let mut fresh = PrimalDual::new(5, 30, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            fresh.set_budget(agent_id, budget);
+        }
+        for (agent_id, item_id, bid) in solver.bids_iter() {
+            fresh.set_bid(agent_id, item_id, bid);
+        }
+
+        solver.solve();
+        fresh.solve();
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+    }
+
+    #[test]
+    fn budget_after_bids() {
+        let (budgets, bids) = generated_instance(6, 40);
+        let mut fresh = PrimalDual::new(6, 40, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            fresh.set_budget(agent_id, budget);
+        }
+        fresh.set_bids(bids.iter().copied());
+
+        // every budget is first too small, then 0 for agent 0, which rejects its bids
+        let mut corrected = PrimalDual::new(6, 40, 0.01);
+        for (agent_id, &budget) in budgets.iter().enumerate().skip(1) {
+            corrected.set_budget(agent_id, budget / 2.0);
+        }
+        corrected.set_bids(bids.iter().copied());
+        assert!(corrected.num_bids() < fresh.num_bids());
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            corrected.set_budget(agent_id, budget);
+        }
+        assert_eq!(corrected.num_bids(), fresh.num_bids());
+        assert_eq!(corrected.get_approximation_ratio(), fresh.get_approximation_ratio());
+
+        fresh.solve();
+        corrected.solve();
+        assert_eq!(corrected.get_primal_objective_value(), fresh.get_primal_objective_value());
+        assert_eq!(corrected.get_dual_objective_value(), fresh.get_dual_objective_value());
+    }
+
+    #[test]
+    fn raised_budget_lowers_beta() {
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bid(0, 0, 8.0);
+        solver.set_bid(1, 1, 4.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 0.8 / 4.0) * 0.99);
+
+        solver.set_budget(0, 40.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 0.4 / 4.0) * 0.99);
+        solver.set_budget(1, 5.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 0.8 / 4.0) * 0.99);
+        // bids above a lowered budget count as the budget
+        solver.set_budget(1, 0.0);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 1.0 / 4.0) * 0.99);
+    }
+
+    #[test]
+    fn overwritten_bid_leaves_beta() {
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 20.0);
+        assert_eq!(solver.get_beta(), 0.0);
+        assert_eq!(solver.get_approximation_ratio(), 0.99);
+
+        solver.set_bid(0, 0, 9.0);
+        solver.set_bid(1, 1, 2.0);
+        assert_eq!(solver.get_beta(), 0.9);
+        solver.set_bid(0, 0, 1.0);
+        assert_eq!(solver.get_beta(), 0.1);
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - 0.1 / 4.0) * 0.99);
+
+        // the same through set_bids, and for a bid rejected above the budget
+        let mut bulk = PrimalDual::new(2, 2, 0.01);
+        bulk.set_budget(0, 10.0);
+        bulk.set_budget(1, 20.0);
+        bulk.set_bids([(0, 0, 9.0), (1, 1, 2.0), (0, 0, 1.0), (1, 0, 30.0)]);
+        assert_eq!(bulk.get_beta(), 0.1);
+
+        solver.solve();
+        bulk.solve();
+        assert_eq!(solver.get_beta(), 0.1);
+        assert_eq!(bulk.get_beta(), 0.1);
+        assert_eq!(solver.extract_solution().beta, 0.1);
+        assert!(solver.get_approximation_ratio() >= (1.0 - 0.1 / 4.0) * 0.99);
+    }
+
+    #[test]
+    fn bulk_matches_incremental() {
+        let mut instance = crate::generator::generate(30, 200, 0.3, (20.0, 80.0), (1.0, 25.0), 275);
+        // a replaced bid and a rejected one
+        instance.bids.push((0, 1, 2.5));
+        instance.bids.push((1, 2, 1000.0));
+
+        let mut incremental = PrimalDual::new(instance.num_agents, instance.num_items, 0.01);
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            incremental.set_budget(agent_id, budget);
+        }
+        for &(agent_id, item_id, bid) in &instance.bids {
+            incremental.set_bid(agent_id, item_id, bid);
+        }
+        let bulk = PrimalDual::from_instance(&instance, 0.01);
+
+        let mut bid_matrix = vec![vec![0.0; instance.num_items]; instance.num_agents];
+        for &(agent_id, item_id, bid) in &instance.bids {
+            bid_matrix[agent_id][item_id] = bid;
+        }
+        let dense = PrimalDual::from_dense(&instance.budgets, &bid_matrix, 0.01);
+
+        let mut solutions = Vec::new();
+        for mut solver in [incremental, bulk, dense] {
+            let max_bidders: Vec<Option<usize>> = (0..instance.num_items).map(|item_id| solver.get_max_bidder(item_id)).collect();
+            let beta = solver.beta;
+            solver.solve();
+            let alphas: Vec<u64> = (0..instance.num_agents).map(|agent_id| solver.get_alpha(agent_id).to_bits()).collect();
+            solutions.push((max_bidders, beta.to_bits(), alphas, solver.num_alpha_updates, solver.extract_solution()));
+        }
+        assert_eq!(solutions[0], solutions[1]);
+        assert_eq!(solutions[0], solutions[2]);
+    }
+
+    #[test]
+    fn bulk_stops_at_first_error() {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        let result = solver.try_set_bids([(0, 0, 4.0), (1, 0, 5.0), (0, 3, 1.0), (1, 1, 1.0)]);
+        assert_eq!(result, Err(MbaError::ItemIndexOutOfRange { item_id: 3, num_items: 3 }));
+        assert_eq!(solver.num_bids(), 2);
+        assert_eq!(solver.get_max_bidder(0), Some(1));
+        assert_eq!(solver.get_bid(1, 1), 0.0);
+    }
+
+    #[test]
+    fn random() {
+        solve_random_files::<f64>(0.0);
+    }
+
+    // f32 rounds the bids and sums, so the guarantee only holds up to a relative tolerance
+    #[test]
+    fn random_f32() {
+        solve_random_files::<f32>(1e-4);
+    }
+
+    fn solve_random_files<T: Scalar>(tolerance: f64) {
+        let directory_path = Path::new("tests/random");
+        let epsilon = 0.01;
+
+        match fs::read_dir(directory_path) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    if let Some(file_name) = entry.file_name().to_str() {
+                        if file_name.ends_with(".in") {
+                            let file_name = file_name.replace(".in", "");
+                            let input_file_path = format!("{}.in", file_name);
+                            let output_file_path = format!("{}.out", file_name);
+
+                            println!("{}/{}", input_file_path, output_file_path);
+
+                            let instance = read_instance(&directory_path.join(Path::new(&input_file_path)));
+                            let (primal, ratio) = get_result::<T>(&instance, epsilon);
+                            let opt = exact_optimum(&instance).unwrap_or_else(|| get_ans(&directory_path.join(Path::new(&output_file_path))));
+                            println!("{:}/{:}", primal, opt);
+                            assert!(primal >= opt * ratio * (1.0 - tolerance));
+                            println!();
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("Error reading directory: {}", err);
+            }
+        }
+    }
+
+    fn read_instance(file_path: &Path) -> Instance {
+        let file = fs::File::open(file_path).unwrap_or_else(|e| panic!("Could not open or find file:{} {}", file_path.to_str().unwrap(), e));
+        crate::io::read_instance(std::io::BufReader::new(file)).unwrap_or_else(|e| panic!("{}: {}", file_path.to_str().unwrap(), e))
+    }
+
+    fn get_result<T: Scalar>(instance: &Instance, epsilon: f64) -> (f64, f64) {
+        let mut solver = PrimalDual::<T>::from_instance_scalar(instance, epsilon);
+        solver.solve();
+        (solver.get_primal_objective_value(), solver.get_approximation_ratio())
+    }
+
+    // None above exact::MAX_ASSIGNMENTS
+    fn exact_optimum(instance: &Instance) -> Option<f64> {
+        let mut bids = vec![vec![0.0; instance.num_items]; instance.num_agents];
+        for &(agent_id, item_id, bid) in &instance.bids {
+            bids[agent_id][item_id] = bid;
+        }
+        exact::solve(&instance.budgets, &bids).map(|(optimum, _)| optimum)
+    }
+
+    #[test]
+    fn random_against_exact() {
+        solve_against_exact::<f64>(0.0);
+    }
+
+    #[test]
+    fn random_against_exact_f32() {
+        solve_against_exact::<f32>(1e-4);
+    }
+
+    fn solve_against_exact<T: Scalar>(tolerance: f64) {
+        let mut rng = RngConfig::new(256).stream("random_against_exact");
+        for _ in 0..100 {
+            let num_agents = rng.range_usize(1, 5);
+            let num_items = rng.range_usize(1, 11);
+            let mut instance = Instance::new(num_agents, num_items);
+            for agent_id in 0..num_agents {
+                instance.set_budget(agent_id, rng.range_f64(5.0, 50.0));
+                for item_id in 0..num_items {
+                    if rng.next_f64() < 0.6 {
+                        instance.add_bid(agent_id, item_id, rng.range_f64(1.0, 20.0).min(instance.budgets[agent_id]));
+                    }
+                }
+            }
+
+            let opt = exact_optimum(&instance).unwrap();
+            let (primal, ratio) = get_result::<T>(&instance, 0.01);
+            assert!(primal <= opt * (1.0 + tolerance) + 1e-9, "{:?}", instance);
+            assert!(primal >= opt * ratio * (1.0 - tolerance) - 1e-9, "{:?}", instance);
+        }
+    }
+
+    #[test]
+    fn extreme_epsilons() {
+        let mut rng = RngConfig::new(281).stream("extreme_epsilons");
+        // 1e-6 makes about a million alpha updates per solve
+        for (epsilon, num_instances) in [(0.5, 20), (1e-6, 3)] {
+            for _ in 0..num_instances {
+                let num_agents = rng.range_usize(1, 4);
+                let num_items = rng.range_usize(1, 8);
+                let mut instance = Instance::new(num_agents, num_items);
+                for agent_id in 0..num_agents {
+                    instance.set_budget(agent_id, rng.range_f64(5.0, 30.0));
+                    for item_id in 0..num_items {
+                        if rng.next_f64() < 0.7 {
+                            instance.add_bid(agent_id, item_id, rng.range_f64(1.0, 20.0).min(instance.budgets[agent_id]));
+                        }
+                    }
+                }
+
+                let opt = exact_optimum(&instance).unwrap();
+                let (primal, ratio) = get_result::<f64>(&instance, epsilon);
+                assert!(ratio >= 0.75 * (1.0 - epsilon) && ratio < 1.0, "{}", ratio);
+                assert!(primal <= opt + 1e-9 && primal >= opt * ratio - 1e-9, "{} {:?}", epsilon, instance);
+            }
+        }
+    }
+
+    // clones move into threads
+    const _: fn() = || {
+        fn assert_send<S: Send>() {}
+        assert_send::<PrimalDual>();
+        assert_send::<PrimalDual<f32>>();
+    };
+
+    #[test]
+    fn clones_solve_on_threads() {
+        let instance = crate::generator::generate(8, 60, 0.5, (20.0, 80.0), (1.0, 20.0), 287);
+        let built = PrimalDual::from_instance(&instance, 0.1);
+        assert_eq!((built.num_agents(), built.num_items()), (8, 60));
+
+        let handles: Vec<_> = [0.2, 0.05, 0.01]
+            .into_iter()
+            .map(|epsilon| {
+                let mut solver = built.clone();
+                std::thread::spawn(move || {
+                    solver.set_epsilon(epsilon);
+                    solver.solve();
+                    (epsilon, solver.get_assignment().to_vec(), solver.get_lp_duals())
+                })
+            })
+            .collect();
+        for handle in handles {
+            let (epsilon, assignment, duals) = handle.join().unwrap();
+            let mut fresh = PrimalDual::from_instance(&instance, epsilon);
+            fresh.solve();
+            assert_eq!(assignment, fresh.get_assignment());
+            assert_eq!(duals, fresh.get_lp_duals());
+        }
+        // the clones left the original unsolved
+        assert_eq!(built.get_primal_objective_value(), 0.0);
+
+        let mut solved = built.clone();
+        solved.solve();
+        let mut copy = solved.clone();
+        solved.set_bid(0, 0, 19.5);
+        copy.set_bid(0, 0, 19.5);
+        solved.solve();
+        copy.solve();
+        assert_eq!(solved.get_assignment(), copy.get_assignment());
+        assert_eq!(solved.get_lp_duals(), copy.get_lp_duals());
+    }
+
+    #[test]
+    fn set_epsilon() {
+        let mut solver = PrimalDual::new(2, 3, 0.1);
+        assert!(matches!(solver.try_set_epsilon(1.0), Err(MbaError::Build(BuildError::InvalidEpsilon { .. }))));
+        assert!(solver.try_set_epsilon(0.0).is_ok());
+        assert_eq!(solver.get_approximation_ratio(), 1.0);
+
+        let mut large = PrimalDual::new(10, 100, 0.1);
+        assert!(matches!(large.try_set_epsilon(0.0), Err(MbaError::Build(BuildError::TooLargeForExact { num_agents: 10, num_items: 100 }))));
+        large.set_item_capacity(0, 2);
+        let e = large.try_set_epsilon(0.0).unwrap_err();
+        assert_eq!(e, MbaError::UnsupportedByExact { setting: "item_capacity" });
+        assert_eq!(e.code(), ErrorCode::UnsupportedByExact);
+    }
+
+    #[test]
+    fn debug_summary() {
+        let mut solver = PrimalDual::new(2, 3, 0.1);
+        solver.set_budget(0, 10.0);
+        solver.set_bid(0, 1, 4.0);
+        assert_eq!(format!("{:?}", solver), "PrimalDual { num_agents: 2, num_items: 3, num_bids: 1, epsilon: 0.1, beta: 0.4, solved: false, .. }");
+    }
+
+    // items pass back and forth between agents of nearly equal prices, with bids spread over five orders of magnitude
+    // so that every += and -= rounds
+    fn consumption_stays_exact(epsilon: f64) -> usize {
+        let mut rng = RngConfig::new(291).stream("consumption_drift");
+        let num_agents = 12;
+        let num_items = 400;
+        let mut solver = PrimalDual::new(num_agents, num_items, epsilon);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, rng.range_f64(50.0, 200.0));
+        }
+        for item_id in 0..num_items {
+            let base = 10f64.powf(rng.range_f64(-2.0, 3.0)) / 3.0;
+            for agent_id in 0..num_agents {
+                solver.set_bid(agent_id, item_id, base * rng.range_f64(0.9, 1.1));
+            }
+        }
+
+        let check = |solver: &PrimalDual| {
+            for agent_id in 0..num_agents {
+                let recomputed: f64 = solver.gamma[agent_id].iter().map(|&item_id| solver.bid(agent_id, item_id)).sum();
+                let consumption = solver.consumption(agent_id);
+                assert!((consumption - recomputed).abs() <= 1e-9 * recomputed.max(1.0), "{} {}", consumption, recomputed);
+            }
+        };
+        solver.apply_unassignable_policy();
+        solver.initialize();
+        let (_, completed) = solver.run_with(Stop::default(), |solver, _| check(solver));
+        assert!(completed);
+        check(&solver);
+        solver.verify_solution().unwrap();
+        solver.get_num_reallocations()
+    }
+
+    #[test]
+    fn consumption_drift() {
+        assert!(consumption_stays_exact(0.001) > 20_000);
+    }
+
+    #[test]
+    fn solve_twice() {
+        let instance = crate::generator::generate(8, 60, 0.5, (20.0, 80.0), (1.0, 20.0), 293);
+        let fresh = |instance: &Instance| {
+            let mut solver = PrimalDual::from_instance(instance, 0.05);
+            solver.solve();
+            solver.extract_solution()
+        };
+
+        let mut solver = PrimalDual::from_instance(&instance, 0.05);
+        solver.solve();
+        solver.solve();
+        assert_eq!(solver.extract_solution(), fresh(&instance));
+
+        let mut changed = instance.clone();
+        changed.add_bid(3, 7, 19.0);
+        solver.set_bid(3, 7, 19.0);
+        solver.solve();
+        assert_eq!(solver.extract_solution(), fresh(&changed));
+
+        changed.set_budget(2, 35.0);
+        solver.set_budget(2, 35.0);
+        solver.solve();
+        assert_eq!(solver.extract_solution(), fresh(&changed));
+
+        // solve_warm restarts from scratch after anything but a bid
+        changed.set_budget(5, 90.0);
+        solver.set_budget(5, 90.0);
+        solver.solve_warm();
+        assert_eq!(solver.extract_solution(), fresh(&changed));
+        changed.add_bid(0, 1, 12.0);
+        solver.set_bid(0, 1, 12.0);
+        solver.solve_warm();
+        solver.verify_solution().unwrap();
+        assert!(solver.get_primal_objective_value() >= solver.get_approximation_ratio() * solver.get_dual_objective_value() - 1e-9);
+    }
+
+    #[test]
+    fn setters_restart_solve() {
+        type Setter = fn(&mut PrimalDual);
+        let setters: [(&str, Setter); 21] = [
+            ("set_budget", |solver| solver.set_budget(2, 35.0)),
+            ("set_epsilon", |solver| solver.set_epsilon(0.02)),
+            ("set_agent_order", |solver| solver.set_agent_order(AgentOrder::MostBidsFirst)),
+            ("set_agent_permutation", |solver| {
+                solver.set_agent_order(AgentOrder::Custom);
+                solver.set_agent_permutation(vec![7, 6, 5, 4, 3, 2, 1, 0]);
+            }),
+            ("set_unassignable_policy", |solver| solver.set_unassignable_policy(UnassignablePolicy::ClampBestBid)),
+            ("set_initial_alpha", |solver| solver.set_initial_alpha(1e-4)),
+            ("set_initial_alpha_mode", |solver| solver.set_initial_alpha_mode(InitialAlpha::Adaptive)),
+            ("set_bid", |solver| solver.set_bid(3, 7, 19.0)),
+            ("update_rate", |solver| solver.update_rate(1, 0.5)),
+            ("set_agent_active", |solver| solver.set_agent_active(4, false)),
+            ("set_round_budget_fraction", |solver| solver.set_round_budget_fraction(0.5)),
+            ("carry_over", |solver| solver.carry_over(&[5.0; 8])),
+            ("set_size", |solver| {
+                solver.set_capacity(0, 10.0);
+                solver.set_size(0, 3, 4.0);
+            }),
+            ("set_capacity", |solver| solver.set_capacity(1, 2.0)),
+            ("set_max_items", |solver| solver.set_max_items(2, 3)),
+            ("set_agent_epsilon", |solver| solver.set_agent_epsilon(3, 0.01)),
+            ("set_agent_initial_alpha", |solver| solver.set_agent_initial_alpha(5, 1e-3)),
+            ("clear_agent_epsilon", |solver| solver.clear_agent_epsilon(6)),
+            ("clear_agent_initial_alpha", |solver| solver.clear_agent_initial_alpha(6)),
+            ("clear_capacity", |solver| solver.clear_capacity(7)),
+            ("clear_max_items", |solver| solver.clear_max_items(7)),
+        ];
+
+        let instance = crate::generator::generate(8, 60, 0.5, (20.0, 80.0), (1.0, 20.0), 7);
+        let mut base = PrimalDual::from_instance(&instance, 0.05);
+        base.set_budget_in(1, 60.0, 1.0);
+        base.set_bid_in(1, 0, 12.0);
+        base.set_agent_epsilon(6, 0.2);
+        base.set_agent_initial_alpha(6, 0.1);
+        base.set_capacity(7, 30.0);
+        base.set_max_items(7, 4);
+        for (name, setter) in setters {
+            let mut fresh = base.clone();
+            setter(&mut fresh);
+            fresh.solve();
+
+            let mut solver = base.clone();
+            solver.solve();
+            setter(&mut solver);
+            solver.solve();
+            assert_eq!(solver.extract_solution(), fresh.extract_solution(), "{}", name);
+            assert_eq!(solver.get_num_reallocations(), fresh.get_num_reallocations(), "{}", name);
+        }
     }
 
     fn get_ans(file_path: &Path) -> f64 {
@@ -0,0 +1,245 @@
+use super::{MbaError, PrimalDual, Scalar};
+use ordered_float::NotNan;
+use std::cmp::Reverse;
+
+// generalized assignment on top of the budgets: holding item_id uses size(agent_id, item_id) of the capacity of
+// agent_id, 0 unless set, while the bid still counts toward the budget. the allocation never takes an agent above its
+// capacity: initialize and every transfer of reallocation give an item to the agent of maximum price that has room for
+// it, the holder keeps it when no other agent of a larger price has, and an item nobody has room for stays unassigned.
+// every step of a solve is therefore capacity-feasible. the dual does not
+// account for capacities, so get_approximation_ratio is only proven for pure budgeted allocation.
+// exact::solve does not model capacities, so they are rejected while epsilon is 0
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_set_size fails
+    pub fn set_size(&mut self, agent_id: usize, item_id: usize, size: f64) {
+        self.try_set_size(agent_id, item_id, size).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // size must be non-negative and finite; the next solve starts from scratch
+    pub fn try_set_size(&mut self, agent_id: usize, item_id: usize, size: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if item_id >= self.num_items {
+            return Err(MbaError::ItemIndexOutOfRange {
+                item_id,
+                num_items: self.num_items,
+            });
+        }
+        if !(size >= 0.0 && size.is_finite()) {
+            return Err(MbaError::InvalidSize { agent_id, item_id, size });
+        }
+        if size == 0.0 {
+            self.sizes[agent_id].remove(&item_id);
+        } else {
+            self.sizes[agent_id].insert(item_id, size);
+        }
+        self.mark_changed();
+        Ok(())
+    }
+
+    pub fn get_size(&self, agent_id: usize, item_id: usize) -> f64 {
+        self.sizes[agent_id].get(&item_id).copied().unwrap_or(0.0)
+    }
+
+    // panics where try_set_capacity fails
+    pub fn set_capacity(&mut self, agent_id: usize, capacity: f64) {
+        self.try_set_capacity(agent_id, capacity).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // capacity must be non-negative and finite, and epsilon must not be 0; the next solve starts from scratch
+    pub fn try_set_capacity(&mut self, agent_id: usize, capacity: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if !(capacity >= 0.0 && capacity.is_finite()) {
+            return Err(MbaError::InvalidAgentCapacity { agent_id, capacity });
+        }
+        if self.epsilon == 0.0 {
+            return Err(MbaError::UnsupportedByExact { setting: "capacity" });
+        }
+        self.agent_capacity[agent_id] = Some(capacity);
+        self.mark_changed();
+        Ok(())
+    }
+
+    // None for no capacity, the default
+    pub fn get_capacity(&self, agent_id: usize) -> Option<f64> {
+        self.agent_capacity[agent_id]
+    }
+
+    pub fn clear_capacity(&mut self, agent_id: usize) {
+        self.agent_capacity[agent_id] = None;
+        self.mark_changed();
+    }
+
+    // sum of the sizes of the items agent_id holds, in the order of gamma
+    pub fn get_used_capacity(&self, agent_id: usize) -> f64 {
+        self.gamma[agent_id].iter().map(|&item_id| self.get_size(agent_id, item_id)).sum()
+    }
+
+    // whether agent_id stays within its capacity when it takes item_id after giving up released. summed in the order
+    // gamma has afterwards, so that verify_solution recomputes exactly the value compared here
+    pub(super) fn fits(&self, agent_id: usize, item_id: usize, released: Option<usize>) -> bool {
+        let Some(capacity) = self.agent_capacity[agent_id] else {
+            return true;
+        };
+        let used: f64 = self.gamma[agent_id]
+            .iter()
+            .filter(|&&id| Some(id) != released)
+            .map(|&id| self.get_size(agent_id, id))
+            .sum();
+        used + self.get_size(agent_id, item_id) <= capacity
+    }
+
+    // agent_id holds no more items than its cap and no more size than its capacity
+    pub(super) fn within_limits(&self, agent_id: usize) -> bool {
+        self.max_items[agent_id].is_none_or(|max_items| self.gamma[agent_id].len() <= max_items)
+            && self.agent_capacity[agent_id].is_none_or(|capacity| self.get_used_capacity(agent_id) <= capacity)
+    }
+
+    // owner, the agent of maximum price on item_id that holder has it, unless it has no room for the item; then the agent
+    // of the largest up-to-date price that has room or is holder itself. stale entries met on the way are refreshed, like
+    // in max_price_agent
+    pub(super) fn owner_with_room(&mut self, holder: Option<usize>, item_id: usize, owner: Option<usize>) -> Option<usize> {
+        match owner {
+            Some(agent_id) if Some(agent_id) != holder && !self.fits(agent_id, item_id, None) => {}
+            _ => return owner,
+        }
+        let mut passed = Vec::new();
+        let owner = loop {
+            let Some((price, Reverse(agent_id), num)) = self.item_agent[item_id].pop() else {
+                break None;
+            };
+            if num != self.num_update[agent_id] {
+                let updated_price = NotNan::new(T::from_f64(self.price(agent_id, item_id))).unwrap();
+                self.item_agent[item_id].push((updated_price, Reverse(agent_id), self.num_update[agent_id]));
+                continue;
+            }
+            passed.push((price, Reverse(agent_id), num));
+            if Some(agent_id) == holder || (!self.gamma[agent_id].contains(&item_id) && self.fits(agent_id, item_id, None)) {
+                break Some(agent_id);
+            }
+        };
+        self.item_agent[item_id].extend(passed);
+        owner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::generator::generate;
+    use crate::primal_dual::{LocalSearch, MbaError, PrimalDual, StepStatus, VerifyError};
+
+    #[test]
+    fn capacity_binds() {
+        // budgets are loose, so without capacities every agent takes its largest bids
+        let mut solver = PrimalDual::new(2, 4, 0.01);
+        solver.set_budget(0, 100.0);
+        solver.set_budget(1, 100.0);
+        solver.set_bids([
+            (0, 0, 10.0),
+            (0, 1, 9.0),
+            (0, 2, 8.0),
+            (0, 3, 7.0),
+            (1, 0, 5.0),
+            (1, 1, 4.0),
+            (1, 2, 3.0),
+            (1, 3, 2.0),
+        ]);
+        let mut unconstrained = solver.clone();
+        unconstrained.solve();
+        assert_eq!(unconstrained.get_item_assignment(), vec![Some(0); 4]);
+        assert_eq!(unconstrained.get_primal_objective_value(), 34.0);
+
+        for item_id in 0..4 {
+            solver.set_size(0, item_id, 2.0);
+            solver.set_size(1, item_id, 1.0);
+        }
+        solver.set_capacity(0, 5.0);
+        solver.set_capacity(1, 1.0);
+        solver.solve();
+
+        // agent 0 fits its two largest bids, agent 1 one more item
+        assert_eq!(solver.get_item_assignment(), vec![Some(0), Some(0), Some(1), None]);
+        assert_eq!(solver.get_primal_objective_value(), 22.0);
+        assert_eq!(solver.get_used_capacity(0), 4.0);
+        assert_eq!(solver.get_used_capacity(1), 1.0);
+        solver.verify_solution().unwrap();
+
+        // local search does not fill the unassigned item past a capacity
+        let report = solver.post_optimize(LocalSearch::FillAndMoves, 10);
+        assert_eq!(report.num_filled, 0);
+        solver.verify_solution().unwrap();
+
+        solver.gamma[1].push_back(3);
+        solver.recompute_consumption(1);
+        assert_eq!(
+            solver.verify_solution(),
+            Err(VerifyError::OverCapacity {
+                agent_id: 1,
+                used: 2.0,
+                capacity: 1.0
+            })
+        );
+    }
+
+    #[test]
+    fn random_capacity_feasible() {
+        for seed in 0..10 {
+            let instance = generate(8, 60, 0.5, (20.0, 60.0), (0.5, 10.0), seed);
+            let mut solver = PrimalDual::from_instance(&instance, 0.05);
+            for (index, &(agent_id, item_id, bid)) in instance.bids.iter().enumerate() {
+                solver.set_size(agent_id, item_id, bid / 2.0 + (index % 3) as f64);
+            }
+            for agent_id in 0..8 {
+                solver.set_capacity(agent_id, 6.0 + agent_id as f64);
+            }
+
+            // reallocation itself stays within the capacities, not only the complete solve
+            let mut stepped = solver.clone();
+            while stepped.step() == StepStatus::InProgress {
+                assert!((0..8).all(|agent_id| stepped.within_limits(agent_id)), "seed {}", seed);
+            }
+            solver.solve();
+            assert_eq!(solver.extract_solution(), stepped.extract_solution());
+            solver.verify_solution().unwrap();
+            for agent_id in 0..8 {
+                assert!(solver.get_used_capacity(agent_id) <= solver.get_capacity(agent_id).unwrap());
+            }
+            solver.post_optimize(LocalSearch::FillAndMoves, 5);
+            solver.verify_solution().unwrap();
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut solver = PrimalDual::new(2, 2, 0.1);
+        let e = solver.try_set_size(0, 1, -1.0).unwrap_err();
+        assert_eq!(
+            e,
+            MbaError::InvalidSize {
+                agent_id: 0,
+                item_id: 1,
+                size: -1.0
+            }
+        );
+        assert_eq!(e.code(), ErrorCode::InvalidSize);
+        assert_eq!(solver.try_set_size(0, 2, 1.0).unwrap_err().code(), ErrorCode::ItemIndexOutOfRange);
+        assert_eq!(solver.try_set_capacity(1, f64::INFINITY).unwrap_err().code(), ErrorCode::InvalidAgentCapacity);
+        assert_eq!(solver.try_set_capacity(2, 1.0).unwrap_err().code(), ErrorCode::AgentIndexOutOfRange);
+
+        solver.set_size(0, 1, 3.0);
+        assert_eq!(solver.get_size(0, 1), 3.0);
+        solver.set_size(0, 1, 0.0);
+        assert_eq!(solver.get_size(0, 1), 0.0);
+        solver.set_capacity(1, 2.0);
+        assert_eq!(solver.get_capacity(1), Some(2.0));
+        solver.clear_capacity(1);
+        assert_eq!(solver.get_capacity(1), None);
+
+        // exact::solve does not model capacities
+        let mut exact = PrimalDual::new(2, 2, 0.0);
+        exact.set_size(0, 1, 3.0);
+        assert_eq!(exact.try_set_capacity(0, 2.0), Err(MbaError::UnsupportedByExact { setting: "capacity" }));
+        solver.set_capacity(0, 2.0);
+        assert_eq!(solver.try_set_epsilon(0.0), Err(MbaError::UnsupportedByExact { setting: "capacity" }));
+    }
+}
@@ -0,0 +1,159 @@
+use super::{BuildError, MbaError, PrimalDual, Scalar};
+
+// an agent with its own epsilon raises its alpha in steps of that epsilon instead of the global one, e.g. a fine one
+// for the few agents with large budgets and many bids and a coarse one for the long tail. every agent then satisfies the
+// termination argument with its own epsilon, so the guarantee holds with the largest epsilon of any agent.
+// the exact solver of a global epsilon 0 ignores them
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_set_agent_epsilon fails
+    pub fn set_agent_epsilon(&mut self, agent_id: usize, epsilon: f64) {
+        self.try_set_agent_epsilon(agent_id, epsilon).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // epsilon must be in (0, 1); the next solve starts from scratch
+    pub fn try_set_agent_epsilon(&mut self, agent_id: usize, epsilon: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if !(epsilon > 0.0 && epsilon < 1.0) {
+            return Err(MbaError::Build(BuildError::InvalidEpsilon { epsilon }));
+        }
+        self.agent_epsilon[agent_id] = Some(epsilon);
+        self.mark_changed();
+        Ok(())
+    }
+
+    // back to the global epsilon
+    pub fn clear_agent_epsilon(&mut self, agent_id: usize) {
+        self.agent_epsilon[agent_id] = None;
+        self.mark_changed();
+    }
+
+    // the epsilon agent_id is solved with
+    pub fn get_agent_epsilon(&self, agent_id: usize) -> f64 {
+        self.agent_epsilon[agent_id].unwrap_or(self.epsilon)
+    }
+
+    // panics where try_set_agent_initial_alpha fails
+    pub fn set_agent_initial_alpha(&mut self, agent_id: usize, alpha: f64) {
+        self.try_set_agent_initial_alpha(agent_id, alpha).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // the first update of agent_id raises its alpha to alpha instead of following set_initial_alpha_mode, e.g. a small
+    // one for an agent known to be barely over its budget, which then keeps more of its items. alpha must be in
+    // (0, epsilon of the agent], as InitialAlpha::Fixed, since a start above epsilon would break the guarantee; an
+    // epsilon lowered below it afterwards lowers it to that epsilon. the next solve starts from scratch
+    pub fn try_set_agent_initial_alpha(&mut self, agent_id: usize, alpha: f64) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if !(alpha > 0.0 && alpha <= self.get_agent_epsilon(agent_id)) {
+            return Err(MbaError::InvalidInitialAlpha { agent_id: Some(agent_id), alpha });
+        }
+        self.agent_initial_alpha[agent_id] = Some(alpha);
+        self.mark_changed();
+        Ok(())
+    }
+
+    // back to set_initial_alpha_mode
+    pub fn clear_agent_initial_alpha(&mut self, agent_id: usize) {
+        self.agent_initial_alpha[agent_id] = None;
+        self.mark_changed();
+    }
+
+    // the epsilon of the guarantee: the global one unless an agent has its own, else the largest of any agent
+    pub(super) fn max_epsilon(&self) -> f64 {
+        if self.agent_epsilon.iter().all(Option::is_none) {
+            return self.epsilon;
+        }
+        (0..self.num_agents).map(|agent_id| self.get_agent_epsilon(agent_id)).fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::generator::generate;
+    use crate::primal_dual::{MbaError, PrimalDual, SolveEvent};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn mixed_epsilons_keep_guarantee() {
+        for seed in 0..10 {
+            let instance = generate(10, 80, 0.4, (5.0, 40.0), (0.5, 10.0), seed);
+            let mut solver = PrimalDual::from_instance(&instance, 0.2);
+            for agent_id in (0..10).step_by(3) {
+                solver.set_agent_epsilon(agent_id, 0.01);
+            }
+            solver.set_agent_epsilon(1, 0.3);
+            solver.solve();
+
+            let ratio = solver.get_approximation_ratio();
+            assert_eq!(ratio, (1.0 - solver.get_beta() / 4.0) * (1.0 - 0.3));
+            assert!(solver.get_primal_objective_value() >= ratio * solver.get_dual_objective_value() - 1e-9, "seed {}", seed);
+            assert_eq!(solver.extract_solution().epsilon, 0.3);
+            solver.extract_solution().verify().unwrap();
+        }
+    }
+
+    #[test]
+    fn same_as_global() {
+        let instance = generate(8, 60, 0.5, (10.0, 30.0), (0.5, 10.0), 311);
+        let mut global = PrimalDual::from_instance(&instance, 0.01);
+        global.solve();
+
+        let mut per_agent = PrimalDual::from_instance(&instance, 0.5);
+        for agent_id in 0..8 {
+            per_agent.set_agent_epsilon(agent_id, 0.01);
+        }
+        per_agent.solve();
+        assert_eq!(per_agent.extract_solution(), global.extract_solution());
+        assert_eq!(per_agent.get_approximation_ratio(), global.get_approximation_ratio());
+
+        // a finer epsilon for one agent leaves the others alone
+        per_agent.clear_agent_epsilon(3);
+        assert_eq!(per_agent.get_agent_epsilon(3), 0.5);
+        assert_eq!(per_agent.max_epsilon(), 0.5);
+    }
+
+    #[test]
+    fn initial_alpha() {
+        let mut solver = PrimalDual::new(2, 3, 0.1);
+        solver.set_budget(0, 4.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bids([(0, 0, 3.0), (0, 1, 3.0), (0, 2, 3.0), (1, 0, 2.0), (1, 1, 2.0), (1, 2, 2.0)]);
+        solver.set_agent_initial_alpha(0, 0.02);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        solver.set_observer(Box::new(move |event| sink.lock().unwrap().push(event)));
+        solver.solve();
+        let first = events.lock().unwrap().iter().find_map(|event| match *event {
+            SolveEvent::AlphaUpdated { agent: 0, new, .. } => Some(new),
+            _ => None,
+        });
+        assert_eq!(first, Some(0.02));
+
+        // a smaller epsilon lowers it
+        solver.set_agent_epsilon(0, 0.01);
+        events.lock().unwrap().clear();
+        solver.solve();
+        let first = events.lock().unwrap().iter().find_map(|event| match *event {
+            SolveEvent::AlphaUpdated { agent: 0, new, .. } => Some(new),
+            _ => None,
+        });
+        assert_eq!(first, Some(0.01));
+    }
+
+    #[test]
+    fn errors() {
+        let mut solver = PrimalDual::new(2, 2, 0.1);
+        for epsilon in [0.0, 1.0, -0.5, f64::NAN] {
+            assert_eq!(solver.try_set_agent_epsilon(0, epsilon).unwrap_err().code(), ErrorCode::InvalidEpsilon);
+        }
+        assert_eq!(solver.try_set_agent_epsilon(2, 0.1).unwrap_err().code(), ErrorCode::AgentIndexOutOfRange);
+
+        let e = solver.try_set_agent_initial_alpha(1, 0.2).unwrap_err();
+        assert_eq!(e, MbaError::InvalidInitialAlpha { agent_id: Some(1), alpha: 0.2 });
+        assert_eq!(e.code(), ErrorCode::InvalidInitialAlpha);
+        solver.set_agent_epsilon(1, 0.3);
+        assert!(solver.try_set_agent_initial_alpha(1, 0.2).is_ok());
+        assert!(solver.try_set_agent_initial_alpha(1, 0.0).is_err());
+    }
+}
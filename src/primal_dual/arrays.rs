@@ -0,0 +1,93 @@
+use super::{MbaError, OverbidPolicy, PrimalDual};
+use ndarray::{ArrayView1, ArrayView2};
+
+impl PrimalDual {
+    // from_ndarray_with under the default OverbidPolicy::Reject
+    pub fn from_ndarray(budgets: ArrayView1<f64>, bids: ArrayView2<f64>, epsilon: f64) -> Result<Self, MbaError> {
+        Self::from_ndarray_with(budgets, bids, epsilon, OverbidPolicy::default())
+    }
+
+    // bids[[agent_id, item_id]] = bid, 0 for no bid, with one row per budget; any layout, e.g. a transposed view, is read
+    // in row order. a bid above the budget of its agent follows policy, and a NaN, negative or infinite entry is an
+    // InvalidBid
+    pub fn from_ndarray_with(budgets: ArrayView1<f64>, bids: ArrayView2<f64>, epsilon: f64, policy: OverbidPolicy) -> Result<Self, MbaError> {
+        let (num_rows, num_items) = bids.dim();
+        if budgets.len() != num_rows {
+            return Err(MbaError::ShapeMismatch {
+                num_budgets: budgets.len(),
+                num_rows,
+            });
+        }
+
+        let mut solver = Self::try_new(num_rows, num_items, epsilon)?;
+        solver.set_overbid_policy(policy);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.try_set_budget(agent_id, budget)?;
+        }
+        let bids = bids
+            .indexed_iter()
+            .filter(|&(_, &bid)| bid != 0.0)
+            .map(|((agent_id, item_id), &bid)| (agent_id, item_id, bid));
+        solver.try_set_bids(bids)?;
+        Ok(solver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::{MbaError, OverbidPolicy, PrimalDual};
+    use crate::rng::Rng;
+    use ndarray::{array, Array1, Array2};
+
+    #[test]
+    fn same_as_set_bid() {
+        let mut rng = Rng::new(299);
+        let (num_agents, num_items) = (6, 40);
+        let budgets = Array1::from_shape_fn(num_agents, |_| 10.0 + 30.0 * rng.next_f64());
+        let bids = Array2::from_shape_fn((num_agents, num_items), |_| if rng.next_f64() < 0.4 { 0.5 + 8.0 * rng.next_f64() } else { 0.0 });
+
+        let mut manual = PrimalDual::new(num_agents, num_items, 0.01);
+        for agent_id in 0..num_agents {
+            manual.set_budget(agent_id, budgets[agent_id]);
+            for item_id in 0..num_items {
+                if bids[[agent_id, item_id]] != 0.0 {
+                    manual.set_bid(agent_id, item_id, bids[[agent_id, item_id]]);
+                }
+            }
+        }
+        let mut solver = PrimalDual::from_ndarray(budgets.view(), bids.view(), 0.01).unwrap();
+        assert_eq!(solver.num_bids(), manual.num_bids());
+
+        solver.solve();
+        manual.solve();
+        assert_eq!(solver.extract_solution(), manual.extract_solution());
+
+        let indicator = solver.assignment().to_indicator_matrix();
+        assert_eq!(indicator.dim(), (num_agents, num_items));
+        for ((agent_id, item_id), &held) in indicator.indexed_iter() {
+            assert_eq!(held == 1, solver.assignment().agent_of(item_id) == Some(agent_id));
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let budgets = array![5.0, 5.0];
+        assert_eq!(PrimalDual::from_ndarray(budgets.view(), array![[1.0, 0.0]].view(), 0.1).unwrap_err(), MbaError::ShapeMismatch { num_budgets: 2, num_rows: 1 });
+        let e = PrimalDual::from_ndarray(budgets.view(), array![[1.0, 0.0], [0.0, f64::NAN]].view(), 0.1).unwrap_err();
+        assert!(matches!(e, MbaError::InvalidBid { agent_id: 1, item_id: 1, .. }));
+        assert!(matches!(PrimalDual::from_ndarray(budgets.view(), array![[1.0], [2.0]].view(), 1.0), Err(MbaError::Build(_))));
+
+        // an overbid is dropped by default, or an error
+        let bids = array![[1.0, 6.0], [2.0, 0.0]];
+        let solver = PrimalDual::from_ndarray(budgets.view(), bids.view(), 0.1).unwrap();
+        assert_eq!(solver.num_bids(), 2);
+        let e = PrimalDual::from_ndarray_with(budgets.view(), bids.view(), 0.1, OverbidPolicy::Error).unwrap_err();
+        assert!(matches!(e, MbaError::BidExceedsBudget { agent_id: 0, item_id: 1, .. }));
+
+        // a transposed view is read by its logical indices
+        let items_by_agents = array![[1.0, 2.0], [0.0, 3.0]];
+        let solver = PrimalDual::from_ndarray(budgets.view(), items_by_agents.t(), 0.1).unwrap();
+        assert_eq!(solver.get_bid(1, 0), 2.0);
+        assert_eq!(solver.get_bid(0, 1), 0.0);
+    }
+}
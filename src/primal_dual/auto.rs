@@ -0,0 +1,134 @@
+use super::{PrimalDual, Scalar};
+use std::time::{Duration, Instant};
+
+const PROBE_EPSILON: f64 = 0.2;
+const EPSILON_LADDER: [f64; 8] = [0.1, 0.05, 0.02, 0.01, 0.005, 0.002, 0.001, 0.0005];
+
+#[derive(Clone, Debug)]
+pub struct AutoSolveReport {
+    pub probe_epsilon: f64,
+    pub probe_sweeps: usize,
+    pub probe_time: Duration,
+    pub chosen_epsilon: f64, // equals probe_epsilon when no finer epsilon fits the remaining budget
+    pub predicted_time: Duration,
+    pub sweeps: usize,
+    pub time: Duration,
+    pub completed: bool, // false if the budget ran out before all agents were paid for
+    pub ratio: f64,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // solves within time_budget, exceeding it by at most one sweep
+    //
+    // a probe with a coarse epsilon measures the cost of a sweep on this instance;
+    // the number of sweeps grows like 1 / epsilon, so the smallest epsilon whose predicted time fits the remaining budget is used for the final solve
+    //
+    // the configured epsilon is restored afterwards. the assignment of the last solve stays for reading, with the
+    // guarantee report.ratio, and the next solve starts from scratch with the configured epsilon
+    pub fn solve_auto(&mut self, time_budget: Duration) -> AutoSolveReport {
+        let epsilon = self.epsilon;
+        let report = self.solve_auto_with(time_budget);
+        self.epsilon = epsilon;
+        self.mark_changed();
+        report
+    }
+
+    fn solve_auto_with(&mut self, time_budget: Duration) -> AutoSolveReport {
+        let start = Instant::now();
+        let deadline = start + time_budget;
+
+        self.epsilon = PROBE_EPSILON;
+        self.reset_solve_state();
+        self.initialize();
+        let (probe_sweeps, probe_completed) = self.run(Some(deadline));
+        let probe_time = start.elapsed();
+
+        let mut report = AutoSolveReport {
+            probe_epsilon: PROBE_EPSILON,
+            probe_sweeps,
+            probe_time,
+            chosen_epsilon: PROBE_EPSILON,
+            predicted_time: probe_time,
+            sweeps: probe_sweeps,
+            time: probe_time,
+            completed: probe_completed,
+            ratio: self.get_approximation_ratio(),
+        };
+        if !probe_completed {
+            return report;
+        }
+
+        let time_per_sweep = probe_time.as_secs_f64() / probe_sweeps.max(1) as f64;
+        let predicted_time = |epsilon: f64| Duration::from_secs_f64(time_per_sweep * probe_sweeps.max(1) as f64 * PROBE_EPSILON / epsilon);
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let Some(&chosen_epsilon) = EPSILON_LADDER.iter().rev().find(|&&epsilon| predicted_time(epsilon) <= remaining) else {
+            return report;
+        };
+
+        let refined_start = Instant::now();
+        self.epsilon = chosen_epsilon;
+        self.reset_solve_state();
+        self.initialize();
+        let (sweeps, completed) = self.run(Some(deadline));
+
+        report.chosen_epsilon = chosen_epsilon;
+        report.predicted_time = predicted_time(chosen_epsilon);
+        report.sweeps = sweeps;
+        report.time = refined_start.elapsed();
+        report.completed = completed;
+        report.ratio = self.get_approximation_ratio();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PROBE_EPSILON;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+    use std::time::{Duration, Instant};
+
+    fn generated_solver(num_agents: usize, num_items: usize) -> PrimalDual {
+        PrimalDual::from_instance(&generate(num_agents, num_items, 0.95, (20.0, 70.0), (1.0, 18.0), 210), 0.01)
+    }
+
+    #[test]
+    fn generous_budget() {
+        let mut solver = generated_solver(50, 300);
+        let report = solver.solve_auto(Duration::from_secs(60));
+        assert!(report.completed);
+        assert!(report.chosen_epsilon < PROBE_EPSILON);
+        assert!(report.ratio >= (1.0 - solver.get_beta() / 4.0) * (1.0 - report.chosen_epsilon));
+        assert!(solver.get_primal_objective_value() >= solver.get_dual_objective_value() * report.ratio);
+    }
+
+    #[test]
+    fn tiny_budget() {
+        let mut solver = generated_solver(50, 300);
+        let start = Instant::now();
+        let report = solver.solve_auto(Duration::from_nanos(1));
+        let elapsed = start.elapsed();
+
+        assert_eq!(report.chosen_epsilon, PROBE_EPSILON);
+        assert!(!report.completed);
+        assert_eq!(report.probe_sweeps, 0);
+        // nothing but initialization ran
+        assert!(elapsed < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn restores_epsilon() {
+        for time_budget in [Duration::from_nanos(1), Duration::from_secs(60)] {
+            let mut solver = generated_solver(20, 100);
+            let mut fresh = solver.clone();
+            solver.solve_auto(time_budget);
+            assert_eq!(solver.epsilon, 0.01);
+
+            // neither the probe nor the final solve is resumed by the next solve
+            solver.solve();
+            fresh.solve();
+            assert_eq!(solver.extract_solution(), fresh.extract_solution());
+            assert_eq!(solver.get_num_reallocations(), fresh.get_num_reallocations());
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::{PrimalDual, Scalar, Stop};
+use std::sync::atomic::AtomicBool;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveOutcome {
+    // every agent is paid for, the approximation guarantee holds
+    Completed,
+    // cancel was set before every agent was paid for
+    Cancelled,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // solve that another thread can stop by setting cancel, which is read before every round of reallocation.
+    // after Cancelled the solver is as after a step: every item is held by at most one agent that bids on it, consumptions
+    // are the sums of those bids and the objectives and getters describe this partial assignment, but an agent may be
+    // left unpaid for, so the approximation guarantee does not hold. a later solve continues from there.
+    // cancel is not cleared, so a set flag cancels every further call at once
+    pub fn solve_cancellable(&mut self, cancel: &AtomicBool) -> SolveOutcome {
+        let stop = Stop {
+            cancel: Some(cancel),
+            ..Stop::default()
+        };
+        self.apply_unassignable_policy();
+        self.initialize();
+        match self.run_with(stop, |_, _| {}) {
+            (_, true) => SolveOutcome::Completed,
+            (_, false) => SolveOutcome::Cancelled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SolveOutcome;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn assert_feasible(solver: &PrimalDual) {
+        let solution = solver.extract_solution();
+        solution.verify().unwrap();
+        for (agent_id, item_ids) in solution.assignment.iter().enumerate() {
+            assert!(item_ids.iter().all(|&item_id| solver.get_bid(agent_id, item_id) > 0.0));
+            let spend: f64 = item_ids.iter().map(|&item_id| solver.get_bid(agent_id, item_id)).sum();
+            assert!((spend - solution.spends[agent_id]).abs() <= 1e-9);
+        }
+    }
+
+    #[test]
+    fn from_another_thread() {
+        let instance = generate(100, 4000, 0.5, (10.0, 200.0), (0.5, 20.0), 304);
+        let mut solver = PrimalDual::from_instance(&instance, 0.00001);
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let cancel = Arc::clone(&cancel);
+            thread::spawn(move || {
+                let outcome = solver.solve_cancellable(&cancel);
+                (solver, outcome, Instant::now())
+            })
+        };
+        thread::sleep(Duration::from_millis(20));
+        let cancelled_at = Instant::now();
+        cancel.store(true, Ordering::Relaxed);
+        let (solver, outcome, returned_at) = handle.join().unwrap();
+
+        assert_eq!(outcome, SolveOutcome::Cancelled);
+        assert!(returned_at.duration_since(cancelled_at) < Duration::from_secs(1));
+        assert_feasible(&solver);
+    }
+
+    #[test]
+    fn completed_and_resumed() {
+        let instance = generate(10, 100, 0.5, (10.0, 40.0), (0.5, 10.0), 305);
+        let mut expected = PrimalDual::from_instance(&instance, 0.01);
+        expected.solve();
+
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        let cancel = AtomicBool::new(true);
+        assert_eq!(solver.solve_cancellable(&cancel), SolveOutcome::Cancelled);
+        assert_feasible(&solver);
+
+        cancel.store(false, Ordering::Relaxed);
+        assert_eq!(solver.solve_cancellable(&cancel), SolveOutcome::Completed);
+        assert_eq!(solver.extract_solution(), expected.extract_solution());
+    }
+}
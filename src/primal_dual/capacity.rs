@@ -0,0 +1,155 @@
+use super::{MbaError, PrimalDual, Scalar};
+use ordered_float::NotNan;
+use std::cmp::Reverse;
+
+// an item of capacity c can be held by up to c distinct agents, each paying its own bid, e.g. c copies of an ad slot.
+// the solve keeps it with the agents of the c largest prices instead of the single agent of maximum price, and the
+// dual counts the c largest prices of the item, which stays a bound on the primal of the capacitated LP.
+// the (1 - beta / 4)(1 - epsilon) guarantee of get_approximation_ratio is only proven when every capacity is 1
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_set_item_capacity fails
+    pub fn set_item_capacity(&mut self, item_id: usize, capacity: usize) {
+        self.try_set_item_capacity(item_id, capacity).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // capacity must be at least 1, and exactly 1 for epsilon 0 since exact::solve assigns every item once.
+    // only before the first solve, since the assignment may already hold the item
+    pub fn try_set_item_capacity(&mut self, item_id: usize, capacity: usize) -> Result<(), MbaError> {
+        if item_id >= self.num_items {
+            return Err(MbaError::ItemIndexOutOfRange {
+                item_id,
+                num_items: self.num_items,
+            });
+        }
+        if capacity == 0 || (capacity > 1 && self.epsilon == 0.0) {
+            return Err(MbaError::InvalidCapacity { item_id, capacity });
+        }
+        if self.initialized {
+            return Err(MbaError::AlreadySolved);
+        }
+        self.item_capacity[item_id] = capacity;
+        Ok(())
+    }
+
+    pub fn get_item_capacity(&self, item_id: usize) -> usize {
+        self.item_capacity[item_id]
+    }
+
+    // agents of the capacity largest up-to-date prices on item_id, largest first; fewer if fewer agents bid on it.
+    // stale entries met on the way are refreshed, like in max_price_agent
+    pub(super) fn top_price_agents(&mut self, item_id: usize, capacity: usize) -> Vec<usize> {
+        let mut fresh = Vec::with_capacity(capacity);
+        while fresh.len() < capacity {
+            let Some((price, Reverse(agent_id), num)) = self.item_agent[item_id].pop() else {
+                break;
+            };
+            if num == self.num_update[agent_id] {
+                fresh.push((price, Reverse(agent_id), num));
+                continue;
+            }
+            let updated_price = NotNan::new(T::from_f64(self.price(agent_id, item_id))).unwrap();
+            self.item_agent[item_id].push((updated_price, Reverse(agent_id), self.num_update[agent_id]));
+        }
+        let agent_ids = fresh.iter().map(|&(_, Reverse(agent_id), _)| agent_id).collect();
+        self.item_agent[item_id].extend(fresh);
+        agent_ids
+    }
+
+    // the agent item_id of capacity above 1 should go to when holder has it: holder itself while among the top prices,
+    // otherwise the top-price agent that does not hold a copy yet and has room for it
+    pub(super) fn capacity_owner(&mut self, holder: usize, item_id: usize) -> Option<usize> {
+        let agent_ids = self.top_price_agents(item_id, self.item_capacity[item_id]);
+        if agent_ids.contains(&holder) {
+            return Some(holder);
+        }
+        agent_ids
+            .into_iter()
+            .find(|&agent_id| !self.gamma[agent_id].contains(&item_id) && self.fits(agent_id, item_id, None))
+    }
+
+    // sum of the capacity largest (1 - alpha) * bid over the active bidders of item_id
+    pub(super) fn capacity_item_price(&self, item_id: usize) -> f64 {
+        let mut prices: Vec<f64> = self.item_agents[item_id]
+            .iter()
+            .filter(|&&agent_id| self.active[agent_id])
+            .map(|&agent_id| (1.0 - self.alpha(agent_id)) * self.bid(agent_id, item_id))
+            .collect();
+        prices.sort_unstable_by(|a, b| b.total_cmp(a));
+        prices.iter().take(self.item_capacity[item_id]).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::{MbaError, PrimalDual};
+
+    // item 0 of capacity 2, bid on by all three agents; agents 0 and 1 bid the most
+    fn solver() -> PrimalDual {
+        let mut solver = PrimalDual::new(3, 2, 0.01);
+        for agent_id in 0..3 {
+            solver.set_budget(agent_id, 100.0);
+        }
+        solver.set_bid(0, 0, 30.0);
+        solver.set_bid(1, 0, 20.0);
+        solver.set_bid(2, 0, 10.0);
+        solver.set_bid(2, 1, 5.0);
+        solver.set_item_capacity(0, 2);
+        solver
+    }
+
+    #[test]
+    fn top_bidders_share_an_item() {
+        let mut solver = solver();
+        solver.solve();
+        let assignment = solver.get_assignment();
+        assert!(assignment[0].contains(&0));
+        assert!(assignment[1].contains(&0));
+        assert!(!assignment[2].contains(&0));
+        assert_eq!(solver.get_primal_objective_value(), 55.0);
+        // the two largest prices of item 0, not the largest
+        assert!(solver.get_dual_objective_value() >= 55.0);
+
+        let report = solver.verify_solution().unwrap();
+        assert_eq!(report.num_unassigned, 0);
+        solver.extract_solution().verify_with_capacities(&[2, 1]).unwrap();
+    }
+
+    #[test]
+    fn copies_move_to_top_prices() {
+        let num_agents = 8;
+        let num_items = 40;
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 30.0 + (agent_id * 17 % 23) as f64);
+        }
+        for agent_id in 0..num_agents {
+            for item_id in 0..num_items {
+                if (agent_id * 5 + item_id * 3) % 4 != 0 {
+                    solver.set_bid(agent_id, item_id, 1.0 + ((agent_id * 13 + item_id * 7) % 11) as f64);
+                }
+            }
+        }
+        for item_id in 0..num_items {
+            solver.set_item_capacity(item_id, 1 + item_id % 3);
+        }
+        solver.solve();
+
+        solver.verify_solution().unwrap();
+        let capacities: Vec<usize> = (0..num_items).map(|item_id| solver.get_item_capacity(item_id)).collect();
+        solver.extract_solution().verify_with_capacities(&capacities).unwrap();
+        assert!(solver.get_primal_objective_value() <= solver.get_dual_objective_value() * (1.0 + 1e-9));
+    }
+
+    #[test]
+    fn invalid_capacities() {
+        let mut solver = solver();
+        assert_eq!(solver.try_set_item_capacity(0, 0), Err(MbaError::InvalidCapacity { item_id: 0, capacity: 0 }));
+        assert_eq!(solver.try_set_item_capacity(2, 1), Err(MbaError::ItemIndexOutOfRange { item_id: 2, num_items: 2 }));
+        solver.solve();
+        assert_eq!(solver.try_set_item_capacity(1, 2), Err(MbaError::AlreadySolved));
+
+        let mut exact = PrimalDual::new(2, 2, 0.0);
+        assert_eq!(exact.try_set_item_capacity(0, 2), Err(MbaError::InvalidCapacity { item_id: 0, capacity: 2 }));
+        assert_eq!(exact.try_set_item_capacity(0, 1), Ok(()));
+    }
+}
@@ -0,0 +1,391 @@
+use super::{AgentOrder, InitialAlpha, MbaError, OverbidPolicy, PriceEntry, PrimalDual, RangePolicy, Scalar, UnassignablePolicy, DEFAULT_SAFE_RANGE};
+use crate::error_code::ErrorCode;
+use crate::exact;
+use crate::reduce;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::mem::size_of;
+
+// every tunable of the solver in one place; the default is the behavior of PrimalDual::new with epsilon 0.01
+//
+// with the serde feature a config reads from any serde format, missing fields take their default
+// and unknown fields are an error so that a typo does not silently fall back to the default
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, deny_unknown_fields))]
+pub struct SolverConfig {
+    pub epsilon: f64,
+    pub overbid_policy: OverbidPolicy,
+    pub unassignable_policy: UnassignablePolicy,
+    pub initial_alpha: InitialAlpha,
+    pub safe_range: (f64, f64),
+    pub range_policy: RangePolicy,
+    pub reduction_chunk_size: usize,
+    pub round_budget_fraction: f64,
+    pub agent_order: AgentOrder,
+    pub max_memory_bytes: Option<usize>, // construction and every new bid fail instead of taking more than this, see check_memory
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildError {
+    MemoryLimitExceeded { needed: usize, limit: usize },
+    InvalidEpsilon { epsilon: f64 },
+    TooLargeForExact { num_agents: usize, num_items: usize },
+    InvalidChunkSize,
+}
+
+impl BuildError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            BuildError::MemoryLimitExceeded { .. } => ErrorCode::MemoryLimitExceeded,
+            BuildError::InvalidEpsilon { .. } => ErrorCode::InvalidEpsilon,
+            BuildError::TooLargeForExact { .. } => ErrorCode::TooLargeForExact,
+            BuildError::InvalidChunkSize => ErrorCode::InvalidChunkSize,
+        }
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MemoryLimitExceeded { needed, limit } => {
+                write!(f, "solver needs {} bytes but the limit is {} bytes", needed, limit)
+            }
+            BuildError::InvalidEpsilon { epsilon } => write!(f, "epsilon {} is not in [0, 1)", epsilon),
+            BuildError::TooLargeForExact { num_agents, num_items } => write!(
+                f,
+                "epsilon 0 asks for the exact optimum, which is limited to num_agents^num_items <= {}; {} agents and {} items are too many, use a positive epsilon",
+                exact::MAX_ASSIGNMENTS,
+                num_agents,
+                num_items
+            ),
+            BuildError::InvalidChunkSize => write!(f, "reduction chunk size must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            epsilon: 0.01,
+            overbid_policy: OverbidPolicy::default(),
+            unassignable_policy: UnassignablePolicy::default(),
+            initial_alpha: InitialAlpha::default(),
+            safe_range: DEFAULT_SAFE_RANGE,
+            range_policy: RangePolicy::default(),
+            reduction_chunk_size: reduce::DEFAULT_CHUNK_SIZE,
+            round_budget_fraction: 1.0,
+            agent_order: AgentOrder::default(),
+            max_memory_bytes: None,
+        }
+    }
+}
+
+impl PrimalDual {
+    // panics where try_with_config fails
+    pub fn with_config(num_agents: usize, num_items: usize, config: SolverConfig) -> Self {
+        Self::try_with_config(num_agents, num_items, config).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // checks config.max_memory_bytes before anything is allocated, and every field as its try_ setter does
+    pub fn try_with_config(num_agents: usize, num_items: usize, config: SolverConfig) -> Result<Self, MbaError> {
+        if let Some(limit) = config.max_memory_bytes {
+            let needed = Self::construction_bytes(num_agents, num_items);
+            if needed > limit {
+                return Err(BuildError::MemoryLimitExceeded { needed, limit }.into());
+            }
+        }
+
+        let mut solver = PrimalDual::try_new(num_agents, num_items, config.epsilon)?;
+        solver.max_memory_bytes = config.max_memory_bytes;
+        solver.set_overbid_policy(config.overbid_policy);
+        solver.set_unassignable_policy(config.unassignable_policy);
+        solver.try_set_initial_alpha_mode(config.initial_alpha)?;
+        solver.try_set_safe_range(config.safe_range.0, config.safe_range.1)?;
+        solver.set_range_policy(config.range_policy);
+        solver.try_set_reduction_chunk_size(config.reduction_chunk_size)?;
+        solver.try_set_round_budget_fraction(config.round_budget_fraction)?;
+        solver.set_agent_order(config.agent_order);
+        Ok(solver)
+    }
+
+    // from_dense under config, which fails before the bids are reserved when they do not fit config.max_memory_bytes
+    pub fn try_from_dense_with_config(budgets: &[f64], bid_matrix: &[Vec<f64>], config: SolverConfig) -> Result<Self, MbaError> {
+        let num_items = bid_matrix.first().map_or(0, |row| row.len());
+        let mut solver = Self::try_with_config(budgets.len(), num_items, config)?;
+        let num_bids = bid_matrix.iter().map(|row| row.iter().filter(|&&bid| bid != 0.0).count()).sum();
+        solver.check_memory(num_bids)?;
+        solver.load_dense(budgets, bid_matrix)?;
+        Ok(solver)
+    }
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // bytes PrimalDual::new allocates, saturating at usize::MAX; bids add bid_bytes each as they are set
+    pub(super) fn construction_bytes(num_agents: usize, num_items: usize) -> usize {
+        let per_agent = 3 * size_of::<T>() // budgets, alpha, consumptions
+            + size_of::<HashMap<usize, T>>() // bid
+            + size_of::<Vec<usize>>() // agent_items
+            + size_of::<Vec<(usize, f64)>>() // original_bids
+            + size_of::<VecDeque<usize>>() // gamma
+            + size_of::<u32>() // num_update
+            + size_of::<Option<usize>>() // max_items
+            + size_of::<HashMap<usize, f64>>() // sizes
+            + 4 * size_of::<Option<f64>>() // agent_capacity, agent_epsilon, agent_initial_alpha, original_budgets
+            + 2 * size_of::<f64>() // rates, carried_spend
+            + size_of::<bool>(); // active
+        let per_item = size_of::<Vec<usize>>() // item_agents
+            + size_of::<BinaryHeap<PriceEntry<T>>>() // item_agent
+            + 2 * size_of::<Option<usize>>() // max_bid_agent, previous_owner
+            + size_of::<usize>() // item_capacity
+            + size_of::<f64>(); // reserve_price
+        num_agents.saturating_mul(per_agent).saturating_add(num_items.saturating_mul(per_item))
+    }
+
+    // bytes an accepted bid adds: its entry in the bid map with one control byte, agent_items, item_agents and the heap
+    pub(super) fn bid_bytes() -> usize {
+        size_of::<(usize, T)>() + 1 + 2 * size_of::<usize>() + size_of::<PriceEntry<T>>()
+    }
+
+    // fails when num_accepted_bids accepted bids would take the solver above max_memory_bytes
+    pub(super) fn check_memory(&self, num_accepted_bids: usize) -> Result<(), BuildError> {
+        let Some(limit) = self.max_memory_bytes else {
+            return Ok(());
+        };
+        let needed = Self::construction_bytes(self.num_agents, self.num_items).saturating_add(num_accepted_bids.saturating_mul(Self::bid_bytes()));
+        if needed > limit {
+            return Err(BuildError::MemoryLimitExceeded { needed, limit });
+        }
+        Ok(())
+    }
+
+    pub fn get_config(&self) -> SolverConfig {
+        SolverConfig {
+            epsilon: self.epsilon,
+            overbid_policy: self.overbid_policy,
+            unassignable_policy: self.unassignable_policy,
+            initial_alpha: self.initial_alpha,
+            safe_range: self.safe_range,
+            range_policy: self.range_policy,
+            reduction_chunk_size: self.reduction_chunk_size,
+            round_budget_fraction: self.round_budget_fraction,
+            agent_order: self.agent_order,
+            max_memory_bytes: self.max_memory_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildError, SolverConfig};
+    use crate::error_code::ErrorCode;
+    use crate::io::{load_into, ParseError};
+    use crate::primal_dual::{InitialAlpha, MbaError, OverbidPolicy, PrimalDual, UnassignablePolicy};
+
+    fn solve(mut solver: PrimalDual) -> crate::solution::Solution {
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [
+            (0, 0, 200.0),
+            (0, 1, 200.0),
+            (0, 2, 100.0),
+            (1, 0, 100.0),
+            (1, 1, 100.0),
+            (1, 2, 200.0),
+            (1, 3, 500.0),
+        ] {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+        solver.extract_solution()
+    }
+
+    fn custom_config() -> SolverConfig {
+        SolverConfig {
+            epsilon: 0.05,
+            overbid_policy: OverbidPolicy::Clamp,
+            unassignable_policy: UnassignablePolicy::ClampBestBid,
+            initial_alpha: InitialAlpha::Fixed(0.01),
+            round_budget_fraction: 0.9,
+            ..SolverConfig::default()
+        }
+    }
+
+    #[test]
+    fn default_matches_new() {
+        assert_eq!(PrimalDual::new(2, 4, 0.01).get_config(), SolverConfig::default());
+    }
+
+    #[test]
+    fn same_as_setters() {
+        let config = custom_config();
+
+        let mut solver = PrimalDual::new(2, 4, 0.05);
+        solver.set_overbid_policy(OverbidPolicy::Clamp);
+        solver.set_unassignable_policy(UnassignablePolicy::ClampBestBid);
+        solver.set_initial_alpha(0.01);
+        solver.set_round_budget_fraction(0.9);
+        assert_eq!(solver.get_config(), config);
+
+        assert_eq!(solve(PrimalDual::with_config(2, 4, config)), solve(solver));
+    }
+
+    #[test]
+    fn memory_limit() {
+        let config = SolverConfig {
+            max_memory_bytes: Some(1 << 30),
+            ..SolverConfig::default()
+        };
+
+        // sparse 100k x 100k with a million bids fits in 1 GB
+        let mut solver = PrimalDual::try_with_config(100_000, 100_000, config).unwrap();
+        assert_eq!(solver.get_config().max_memory_bytes, Some(1 << 30));
+        for agent_id in 0..100_000 {
+            solver.set_budget(agent_id, 100.0);
+        }
+        let bids = (0..100_000).flat_map(|agent_id| (0..10).map(move |k| (agent_id, (agent_id * 7 + k * 9_973) % 100_000, 1.0 + k as f64)));
+        assert_eq!(solver.try_set_bids(bids), Ok(()));
+
+        let small = SolverConfig {
+            max_memory_bytes: Some(1 << 20),
+            ..SolverConfig::default()
+        };
+        let Err(e) = PrimalDual::try_with_config(100_000, 100_000, small) else {
+            panic!("100k agents and 100k items fit in 1 MB");
+        };
+        let MbaError::Build(BuildError::MemoryLimitExceeded { needed, limit }) = e else {
+            panic!("unexpected {:?}", e);
+        };
+        assert_eq!(limit, 1 << 20);
+        assert_eq!(needed, PrimalDual::<f64>::construction_bytes(100_000, 100_000));
+        assert!(e.to_string().contains(&needed.to_string()));
+
+        // the same instance dense: 10^10 bids do not fit in 1 GB, while the empty solver does
+        let solver = PrimalDual::try_with_config(100_000, 100_000, config).unwrap();
+        let needed = PrimalDual::<f64>::construction_bytes(100_000, 100_000) + 10_000_000_000 * PrimalDual::<f64>::bid_bytes();
+        assert_eq!(solver.check_memory(10_000_000_000), Err(BuildError::MemoryLimitExceeded { needed, limit: 1 << 30 }));
+        assert!(needed > 100_000_000_000);
+    }
+
+    #[test]
+    fn memory_of_scalar() {
+        // a solver of f32 stores budgets, alphas, consumptions, bids and heap prices in fewer bytes; an item holds none of them
+        assert!(PrimalDual::<f32>::construction_bytes(1, 0) < PrimalDual::<f64>::construction_bytes(1, 0));
+        assert_eq!(PrimalDual::<f32>::construction_bytes(0, 1), PrimalDual::<f64>::construction_bytes(0, 1));
+        assert!(PrimalDual::<f32>::bid_bytes() < PrimalDual::<f64>::bid_bytes());
+    }
+
+    #[test]
+    fn memory_limit_bids() {
+        // room for 10 bids beyond construction
+        let limit = PrimalDual::<f64>::construction_bytes(2, 20) + 10 * PrimalDual::<f64>::bid_bytes();
+        let config = SolverConfig {
+            max_memory_bytes: Some(limit),
+            ..SolverConfig::default()
+        };
+        let exceeded = |needed| MbaError::Build(BuildError::MemoryLimitExceeded { needed, limit });
+
+        let mut solver = PrimalDual::try_with_config(2, 20, config).unwrap();
+        solver.set_budget(0, 100.0);
+        solver.set_bids((0..10).map(|item_id| (0, item_id, 1.0)));
+        let e = solver.try_set_bid(0, 10, 1.0).unwrap_err();
+        assert_eq!(e, exceeded(limit + PrimalDual::<f64>::bid_bytes()));
+        assert_eq!(e.code(), ErrorCode::MemoryLimitExceeded);
+        // replacing a bid takes no more memory, and a removed one frees its share
+        assert_eq!(solver.try_set_bid(0, 3, 2.0), Ok(()));
+        solver.remove_bid(0, 3);
+        assert_eq!(solver.try_set_bid(0, 10, 1.0), Ok(()));
+        assert!(solver.try_set_bids([(0, 11, 1.0)]).is_err());
+        assert!(matches!(load_into(&mut solver, "0,12,1.0\n".as_bytes()), Err(ParseError::Rejected { line: 1, .. })));
+
+        let mut dense = vec![vec![1.0; 20]; 2];
+        assert!(PrimalDual::try_from_dense_with_config(&[100.0, 100.0], &dense, config).is_err());
+        dense[1] = vec![0.0; 20];
+        dense[0][10..].fill(0.0);
+        let solver = PrimalDual::try_from_dense_with_config(&[100.0, 100.0], &dense, config).unwrap();
+        assert_eq!(solver.extract_solution(), PrimalDual::from_dense(&[100.0, 100.0], &dense, 0.01).extract_solution());
+    }
+
+    #[test]
+    fn chunk_size() {
+        let config = SolverConfig {
+            reduction_chunk_size: 0,
+            ..SolverConfig::default()
+        };
+        let Err(e) = PrimalDual::try_with_config(2, 4, config) else {
+            panic!("a chunk size of 0 is accepted");
+        };
+        assert_eq!(e, MbaError::Build(BuildError::InvalidChunkSize));
+        assert_eq!(e.code(), ErrorCode::InvalidChunkSize);
+
+        let mut solver = PrimalDual::new(2, 4, 0.01);
+        assert_eq!(solver.try_set_reduction_chunk_size(0), Err(BuildError::InvalidChunkSize));
+        assert_eq!(solver.get_config().reduction_chunk_size, SolverConfig::default().reduction_chunk_size);
+        assert_eq!(solver.try_set_reduction_chunk_size(1), Ok(()));
+        assert_eq!(solver.get_config().reduction_chunk_size, 1);
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let rejected = |config: SolverConfig| PrimalDual::try_with_config(2, 4, config).err().unwrap();
+        for alpha in [0.0, -0.5, 0.05, f64::NAN] {
+            let e = rejected(SolverConfig {
+                initial_alpha: InitialAlpha::Fixed(alpha),
+                epsilon: 0.01,
+                ..SolverConfig::default()
+            });
+            assert_eq!(e.code(), ErrorCode::InvalidInitialAlpha, "{}", alpha);
+        }
+        for safe_range in [(0.0, 1.0), (-1.0, 1.0), (2.0, 1.0), (f64::NAN, 1.0)] {
+            let e = rejected(SolverConfig {
+                safe_range,
+                ..SolverConfig::default()
+            });
+            assert_eq!(e.code(), ErrorCode::InvalidSafeRange, "{:?}", safe_range);
+        }
+        assert_eq!(
+            rejected(SolverConfig {
+                safe_range: (2.0, 1.0),
+                ..SolverConfig::default()
+            }),
+            MbaError::InvalidSafeRange { min_abs: 2.0, max_abs: 1.0 }
+        );
+        for fraction in [-0.1, 1.5, f64::NAN] {
+            let e = rejected(SolverConfig {
+                round_budget_fraction: fraction,
+                ..SolverConfig::default()
+            });
+            assert_eq!(e.code(), ErrorCode::InvalidRoundBudgetFraction, "{}", fraction);
+        }
+
+        let mut solver = PrimalDual::new(2, 4, 0.01);
+        assert!(solver.try_set_safe_range(1.0, 0.5).is_err());
+        assert!(solver.try_set_round_budget_fraction(2.0).is_err());
+        assert_eq!(solver.get_config(), SolverConfig::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn toml_round_trip() {
+        let config = custom_config();
+        let text = toml::to_string(&config).unwrap();
+        let restored: SolverConfig = toml::from_str(&text).unwrap();
+        assert_eq!(restored, config);
+        assert_eq!(solve(PrimalDual::with_config(2, 4, restored)), solve(PrimalDual::with_config(2, 4, config)));
+
+        let example: SolverConfig = toml::from_str(include_str!("../../examples/solver_config.toml")).unwrap();
+        assert_eq!(example.epsilon, 0.05);
+
+        let partial: SolverConfig = toml::from_str("epsilon = 0.1").unwrap();
+        assert_eq!(
+            partial,
+            SolverConfig {
+                epsilon: 0.1,
+                ..SolverConfig::default()
+            }
+        );
+
+        assert!(toml::from_str::<SolverConfig>("epsilon = 0.1\noverbid_polcy = \"clamp\"").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use super::{MbaError, PrimalDual};
+
+impl PrimalDual {
+    // bids in compressed sparse row form, as scipy.sparse.csr_matrix stores them with rows = agents and columns = items:
+    // the bids of agent_id are data[indptr[agent_id]..indptr[agent_id + 1]] on the items at the same positions of indices.
+    // indptr has one entry per budget and one more, starts at 0, never decreases and ends at the length of indices and
+    // data; an index must be below num_items, and a value must be positive, so stored zeros are an InvalidBid.
+    // the solver is sized from a pass over indices before any bid is set
+    pub fn from_csr(budgets: &[f64], num_items: usize, indptr: &[usize], indices: &[usize], data: &[f64], epsilon: f64) -> Result<Self, MbaError> {
+        let num_rows = indptr.len().saturating_sub(1);
+        if budgets.len() != num_rows {
+            return Err(MbaError::ShapeMismatch {
+                num_budgets: budgets.len(),
+                num_rows,
+            });
+        }
+        let mut previous = 0;
+        for (position, &value) in indptr.iter().enumerate() {
+            let last = position + 1 == indptr.len();
+            if (position == 0 && value != 0) || value < previous || value > indices.len() || (last && (value != indices.len() || value != data.len())) {
+                return Err(MbaError::InvalidIndptr { position, value });
+            }
+            previous = value;
+        }
+
+        let mut solver = Self::try_new(num_rows, num_items, epsilon)?;
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.try_set_budget(agent_id, budget)?;
+        }
+        let mut column_counts = vec![0; num_items];
+        for &item_id in indices.iter().filter(|&&item_id| item_id < num_items) {
+            column_counts[item_id] += 1;
+        }
+        for (item_agents, count) in solver.item_agents.iter_mut().zip(column_counts) {
+            item_agents.reserve(count);
+        }
+        for (agent_id, row) in indptr.windows(2).enumerate() {
+            solver.bid[agent_id].reserve(row[1] - row[0]);
+            solver.agent_items[agent_id].reserve(row[1] - row[0]);
+        }
+
+        let bids = indptr
+            .windows(2)
+            .enumerate()
+            .flat_map(|(agent_id, row)| (row[0]..row[1]).map(move |i| (agent_id, indices[i], data[i])));
+        solver.try_set_bids(bids)?;
+        Ok(solver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::primal_dual::{MbaError, PrimalDual};
+
+    #[test]
+    fn same_as_set_bids() {
+        let mut instance = generate(6, 30, 0.3, (10.0, 30.0), (0.5, 8.0), 300);
+        // agent 2 without bids and item 7 without bidders
+        instance.bids.retain(|&(agent_id, item_id, _)| agent_id != 2 && item_id != 7);
+        instance.bids.sort_by_key(|&(agent_id, item_id, _)| (agent_id, item_id));
+
+        let mut indptr = vec![0; instance.num_agents + 1];
+        for &(agent_id, _, _) in &instance.bids {
+            indptr[agent_id + 1] += 1;
+        }
+        for agent_id in 0..instance.num_agents {
+            indptr[agent_id + 1] += indptr[agent_id];
+        }
+        let indices: Vec<usize> = instance.bids.iter().map(|&(_, item_id, _)| item_id).collect();
+        let data: Vec<f64> = instance.bids.iter().map(|&(_, _, bid)| bid).collect();
+
+        let mut solver = PrimalDual::from_csr(&instance.budgets, instance.num_items, &indptr, &indices, &data, 0.01).unwrap();
+        let mut expected = PrimalDual::from_instance(&instance, 0.01);
+        assert_eq!(solver.num_bids(), expected.num_bids());
+        solver.solve();
+        expected.solve();
+        assert_eq!(solver.extract_solution(), expected.extract_solution());
+        assert!(solver.get_assignment()[2].is_empty());
+        assert_eq!(solver.get_item_assignment()[7], None);
+    }
+
+    #[test]
+    fn errors() {
+        let budgets = [5.0, 5.0];
+        let from_csr = |indptr: &[usize], indices: &[usize], data: &[f64]| PrimalDual::from_csr(&budgets, 3, indptr, indices, data, 0.1).map(|_| ());
+
+        assert_eq!(from_csr(&[0, 1, 2], &[0, 2], &[1.0, 2.0]), Ok(()));
+        assert_eq!(from_csr(&[0, 2], &[0, 2], &[1.0, 2.0]), Err(MbaError::ShapeMismatch { num_budgets: 2, num_rows: 1 }));
+        assert_eq!(from_csr(&[], &[], &[]), Err(MbaError::ShapeMismatch { num_budgets: 2, num_rows: 0 }));
+        assert_eq!(from_csr(&[0, 2, 1], &[0, 2], &[1.0, 2.0]), Err(MbaError::InvalidIndptr { position: 2, value: 1 }));
+        assert_eq!(from_csr(&[1, 1, 2], &[0, 2], &[1.0, 2.0]), Err(MbaError::InvalidIndptr { position: 0, value: 1 }));
+        assert_eq!(from_csr(&[0, 1, 3], &[0, 2], &[1.0, 2.0]), Err(MbaError::InvalidIndptr { position: 2, value: 3 }));
+        assert_eq!(from_csr(&[0, 1, 2], &[0, 2], &[1.0]), Err(MbaError::InvalidIndptr { position: 2, value: 2 }));
+        assert_eq!(from_csr(&[0, 1, 2], &[0, 3], &[1.0, 2.0]), Err(MbaError::ItemIndexOutOfRange { item_id: 3, num_items: 3 }));
+        assert_eq!(
+            from_csr(&[0, 1, 2], &[0, 2], &[1.0, 0.0]),
+            Err(MbaError::InvalidBid {
+                agent_id: 1,
+                item_id: 2,
+                bid: 0.0
+            })
+        );
+    }
+}
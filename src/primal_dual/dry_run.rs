@@ -0,0 +1,158 @@
+use super::{PrimalDual, Scalar};
+use ordered_float::NotNan;
+use std::cmp::Reverse;
+
+// the state initialize() would leave behind, computed without touching the solver
+#[derive(Clone, Debug, PartialEq)]
+pub struct DryRunReport {
+    pub item_owner: Vec<Option<usize>>, // item_owner[item_id] = max bid agent, or the previous owner it keeps the item with; the highest holder of a shared item
+    pub consumptions: Vec<f64>,         // consumptions[agent_id] = sum of the bids on the items it starts with
+    pub budgets: Vec<f64>,              // budgets[agent_id] = budget the solve works with
+    pub num_violating: usize,           // agents starting above U * budget
+    pub total_overspend: f64,           // sum over those agents of consumption - U * budget
+}
+
+impl DryRunReport {
+    // total_overspend relative to the total budget; the further above 0, the more sweeps a fine epsilon costs
+    pub fn overspend_fraction(&self) -> f64 {
+        let total_budget: f64 = self.budgets.iter().sum();
+        if total_budget > 0.0 {
+            self.total_overspend / total_budget
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // forecast of the initial assignment of solve() in O(bids log bids); meant to be called before solving.
+    // like initialize, an item of capacity above 1 goes to its top-price bidders and an agent without room for an item
+    // leaves it to the next bidder. items that only UnassignablePolicy::ClampBestBid would assign are left out
+    pub fn dry_run(&self) -> DryRunReport {
+        let mut item_owner = vec![None; self.num_items];
+        let mut consumptions = vec![0.0; self.num_agents];
+        let mut used = vec![0.0; self.num_agents]; // used[agent_id] = size of the items agent_id starts with
+        for (item_id, owner) in item_owner.iter_mut().enumerate() {
+            let has_room = |agent_id: usize| self.agent_capacity[agent_id].is_none_or(|capacity| used[agent_id] + self.get_size(agent_id, item_id) <= capacity);
+            let holders: Vec<usize> = match self.initial_owner(item_id) {
+                _ if self.item_capacity[item_id] > 1 => self
+                    .ranked_bidders(item_id)
+                    .into_iter()
+                    .take(self.item_capacity[item_id])
+                    .filter(|&agent_id| has_room(agent_id))
+                    .collect(),
+                Some(agent_id) if has_room(agent_id) => vec![agent_id],
+                Some(_) => self.ranked_bidders(item_id).into_iter().find(|&agent_id| has_room(agent_id)).into_iter().collect(),
+                None => Vec::new(),
+            };
+            for &agent_id in &holders {
+                consumptions[agent_id] += self.bid(agent_id, item_id);
+                used[agent_id] += self.get_size(agent_id, item_id);
+            }
+            *owner = holders.into_iter().max();
+        }
+        let budgets: Vec<f64> = (0..self.num_agents)
+            .map(|agent_id| if self.active[agent_id] { self.effective_budget(agent_id) } else { 0.0 })
+            .collect();
+
+        let mut num_violating = 0;
+        let mut total_overspend = 0.0;
+        for agent_id in 0..self.num_agents {
+            let overspend = consumptions[agent_id] - self.U(agent_id) * budgets[agent_id];
+            if self.alpha(agent_id) < 1.0 && overspend > 0.0 {
+                num_violating += 1;
+                total_overspend += overspend;
+            }
+        }
+
+        DryRunReport {
+            item_owner,
+            consumptions,
+            budgets,
+            num_violating,
+            total_overspend,
+        }
+    }
+
+    // the active bidders of item_id in the order item_agent pops them: largest price first, the lower agent id on a tie
+    fn ranked_bidders(&self, item_id: usize) -> Vec<usize> {
+        let mut ranked: Vec<(NotNan<T>, Reverse<usize>)> = self.item_agents[item_id]
+            .iter()
+            .filter(|&&agent_id| self.active[agent_id])
+            .map(|&agent_id| (NotNan::new(T::from_f64(self.price(agent_id, item_id))).unwrap(), Reverse(agent_id)))
+            .collect();
+        ranked.sort_unstable_by(|a, b| b.cmp(a));
+        ranked.into_iter().map(|(_, Reverse(agent_id))| agent_id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+
+    fn generated_solver() -> PrimalDual {
+        PrimalDual::from_instance(&generate(12, 80, 0.9, (5.0, 35.0), (1.0, 8.0), 274), 0.01)
+    }
+
+    #[test]
+    fn matches_initialize() {
+        let mut solver = generated_solver();
+        let report = solver.dry_run();
+        assert!(report.num_violating > 0);
+        assert!(report.total_overspend > 0.0);
+        assert!(report.overspend_fraction() > 0.0);
+
+        solver.initialize();
+        assert_eq!(report.consumptions, solver.consumptions);
+        assert_eq!(report.item_owner, solver.item_owner());
+        let num_violating = (0..solver.num_agents).filter(|&agent_id| !solver.is_paid_for(agent_id)).count();
+        assert_eq!(report.num_violating, num_violating);
+    }
+
+    #[test]
+    fn capacities() {
+        // both bidders hold the item of capacity 2
+        let mut solver = PrimalDual::new(2, 1, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bids([(0, 0, 5.0), (1, 0, 4.0)]);
+        solver.set_item_capacity(0, 2);
+        let report = solver.dry_run();
+        assert_eq!(report.consumptions, vec![5.0, 4.0]);
+        assert_eq!(report.item_owner, vec![Some(1)]);
+        solver.solve();
+        assert_eq!(report.consumptions, solver.consumptions);
+
+        // shared items and agents without room for every item they bid on
+        let mut solver = generated_solver();
+        for item_id in (0..80).step_by(3) {
+            solver.set_item_capacity(item_id, 2 + item_id % 2);
+        }
+        for agent_id in 0..12 {
+            for item_id in 0..80 {
+                solver.set_size(agent_id, item_id, 1.0 + ((agent_id + item_id) % 3) as f64);
+            }
+            solver.set_capacity(agent_id, 10.0 + agent_id as f64);
+        }
+        let report = solver.dry_run();
+        solver.initialize();
+        assert_eq!(report.consumptions, solver.consumptions);
+        assert_eq!(report.item_owner, solver.item_owner());
+    }
+
+    #[test]
+    fn leaves_state_untouched() {
+        let mut solver = generated_solver();
+        let before = format!("{:?}", solver.dry_run());
+        let report = solver.dry_run();
+        assert_eq!(format!("{:?}", report), before);
+        assert!(solver.consumptions.iter().all(|&consumption| consumption == 0.0));
+        assert!(solver.gamma.iter().all(|item_ids| item_ids.is_empty()));
+
+        let mut fresh = generated_solver();
+        solver.solve();
+        fresh.solve();
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+    }
+}
@@ -0,0 +1,201 @@
+use super::config::BuildError;
+use crate::error_code::ErrorCode;
+use std::fmt;
+
+// invalid input to the fallible setters; the panicking setters panic with the Display of these
+#[derive(Clone, Debug, PartialEq)]
+pub enum MbaError {
+    AgentIndexOutOfRange { agent_id: usize, num_agents: usize },
+    ItemIndexOutOfRange { item_id: usize, num_items: usize },
+    InvalidBudget { agent_id: usize, budget: f64 },                              // negative, NaN or infinite
+    InvalidBid { agent_id: usize, item_id: usize, bid: f64 },                    // not positive, NaN or infinite
+    BidExceedsBudget { agent_id: usize, item_id: usize, bid: f64, budget: f64 }, // only under OverbidPolicy::Error
+    ValueOutOfRange { name: &'static str, value: f64, safe_range: (f64, f64) },  // only under RangePolicy::Error
+    InvalidCapacity { item_id: usize, capacity: usize },                         // 0, or above 1 with epsilon 0
+    InvalidReservePrice { item_id: usize, reserve_price: f64 },                  // negative, NaN or infinite
+    ShapeMismatch { num_budgets: usize, num_rows: usize },                       // budgets and rows of a bid matrix
+    InvalidIndptr { position: usize, value: usize },                             // row pointers of from_csr
+    ConflictingOptions { reason: &'static str },                                 // options of try_solve_with
+    NotAPermutation { num_agents: usize, agent_id: Option<usize> },              // repeated or out of range agent id, None for a wrong length
+    InvalidInitialAlpha { agent_id: Option<usize>, alpha: f64 },                 // not in (0, epsilon of the agent], None for InitialAlpha::Fixed
+    InvalidSafeRange { min_abs: f64, max_abs: f64 },                             // not 0 < min_abs <= max_abs
+    InvalidRoundBudgetFraction { fraction: f64 },                                // not in [0, 1]
+    InvalidSize { agent_id: usize, item_id: usize, size: f64 },                  // negative, NaN or infinite
+    InvalidAgentCapacity { agent_id: usize, capacity: f64 },                     // negative, NaN or infinite
+    InvalidRate { agent_id: usize, rate: f64 },                                  // not positive or not finite
+    UnsupportedByExact { setting: &'static str },                                // a limit exact::solve does not model, with epsilon 0
+    AlreadySolved,
+    Build(BuildError),
+}
+
+impl MbaError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            MbaError::AgentIndexOutOfRange { .. } => ErrorCode::AgentIndexOutOfRange,
+            MbaError::ItemIndexOutOfRange { .. } => ErrorCode::ItemIndexOutOfRange,
+            MbaError::InvalidBudget { .. } => ErrorCode::InvalidBudget,
+            MbaError::InvalidBid { .. } => ErrorCode::InvalidBid,
+            MbaError::BidExceedsBudget { .. } => ErrorCode::BidExceedsBudget,
+            MbaError::ValueOutOfRange { .. } => ErrorCode::ValueOutOfRange,
+            MbaError::InvalidCapacity { .. } => ErrorCode::InvalidCapacity,
+            MbaError::InvalidReservePrice { .. } => ErrorCode::InvalidReservePrice,
+            MbaError::ShapeMismatch { .. } => ErrorCode::ShapeMismatch,
+            MbaError::InvalidIndptr { .. } => ErrorCode::InvalidIndptr,
+            MbaError::ConflictingOptions { .. } => ErrorCode::ConflictingOptions,
+            MbaError::NotAPermutation { .. } => ErrorCode::NotAPermutation,
+            MbaError::InvalidInitialAlpha { .. } => ErrorCode::InvalidInitialAlpha,
+            MbaError::InvalidSafeRange { .. } => ErrorCode::InvalidSafeRange,
+            MbaError::InvalidRoundBudgetFraction { .. } => ErrorCode::InvalidRoundBudgetFraction,
+            MbaError::InvalidSize { .. } => ErrorCode::InvalidSize,
+            MbaError::InvalidAgentCapacity { .. } => ErrorCode::InvalidAgentCapacity,
+            MbaError::InvalidRate { .. } => ErrorCode::InvalidRate,
+            MbaError::UnsupportedByExact { .. } => ErrorCode::UnsupportedByExact,
+            MbaError::AlreadySolved => ErrorCode::AlreadySolved,
+            MbaError::Build(e) => e.code(),
+        }
+    }
+}
+
+impl fmt::Display for MbaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MbaError::AgentIndexOutOfRange { agent_id, num_agents } => write!(f, "agent {} is out of range for {} agents", agent_id, num_agents),
+            MbaError::ItemIndexOutOfRange { item_id, num_items } => write!(f, "item {} is out of range for {} items", item_id, num_items),
+            MbaError::InvalidBudget { agent_id, budget } => write!(f, "budget {} of agent {} is negative or not finite", budget, agent_id),
+            MbaError::InvalidBid { agent_id, item_id, bid } => write!(f, "bid {} of agent {} on item {} is not positive or not finite", bid, agent_id, item_id),
+            MbaError::BidExceedsBudget { agent_id, item_id, bid, budget } => write!(f, "bid {} of agent {} on item {} exceeds budget {}", bid, agent_id, item_id, budget),
+            MbaError::ValueOutOfRange { name, value, safe_range } => write!(f, "{} {} is outside the safe range [{}, {}]", name, value, safe_range.0, safe_range.1),
+            MbaError::InvalidCapacity { item_id, capacity } => write!(f, "capacity {} of item {} is not supported", capacity, item_id),
+            MbaError::InvalidReservePrice { item_id, reserve_price } => write!(f, "reserve price {} of item {} is invalid", reserve_price, item_id),
+            MbaError::ShapeMismatch { num_budgets, num_rows } => write!(f, "{} budgets for a bid matrix of {} rows, expected one budget per row", num_budgets, num_rows),
+            MbaError::InvalidIndptr { position, value } => {
+                write!(f, "indptr[{}] = {} does not start at 0, decreases or does not end at the number of entries", position, value)
+            }
+            MbaError::ConflictingOptions { reason } => write!(f, "conflicting solve options: {}", reason),
+            MbaError::NotAPermutation { num_agents, agent_id: None } => write!(f, "agent order does not have {} entries", num_agents),
+            MbaError::NotAPermutation {
+                num_agents,
+                agent_id: Some(agent_id),
+            } => {
+                write!(f, "agent {} is repeated or out of range in an order of {} agents", agent_id, num_agents)
+            }
+            MbaError::InvalidInitialAlpha { agent_id: None, alpha } => write!(f, "initial alpha {} is not in (0, epsilon]", alpha),
+            MbaError::InvalidInitialAlpha { agent_id: Some(agent_id), alpha } => {
+                write!(f, "initial alpha {} of agent {} is not in (0, epsilon of the agent]", alpha, agent_id)
+            }
+            MbaError::InvalidSafeRange { min_abs, max_abs } => write!(f, "safe range [{}, {}] is empty or does not start above 0", min_abs, max_abs),
+            MbaError::InvalidRoundBudgetFraction { fraction } => write!(f, "round budget fraction {} is not in [0, 1]", fraction),
+            MbaError::InvalidSize { agent_id, item_id, size } => write!(f, "size {} of item {} for agent {} is negative or not finite", size, item_id, agent_id),
+            MbaError::InvalidAgentCapacity { agent_id, capacity } => write!(f, "capacity {} of agent {} is negative or not finite", capacity, agent_id),
+            MbaError::InvalidRate { agent_id, rate } => write!(f, "rate {} of agent {} is not positive or not finite", rate, agent_id),
+            MbaError::UnsupportedByExact { setting } => write!(f, "{} is not supported by the exact solver of epsilon 0", setting),
+            MbaError::AlreadySolved => write!(f, "the solver has already been solved"),
+            MbaError::Build(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MbaError {}
+
+impl From<BuildError> for MbaError {
+    fn from(e: BuildError) -> Self {
+        MbaError::Build(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MbaError;
+    use crate::error_code::ErrorCode;
+    use crate::primal_dual::{BuildError, OverbidPolicy, PrimalDual, RangePolicy};
+
+    fn solver() -> PrimalDual {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver
+    }
+
+    #[test]
+    fn budgets() {
+        let mut solver = solver();
+        assert_eq!(solver.try_set_budget(2, 1.0), Err(MbaError::AgentIndexOutOfRange { agent_id: 2, num_agents: 2 }));
+        assert_eq!(solver.try_set_budget(0, -1.0), Err(MbaError::InvalidBudget { agent_id: 0, budget: -1.0 }));
+        assert_eq!(
+            solver.try_set_budget(0, f64::INFINITY),
+            Err(MbaError::InvalidBudget {
+                agent_id: 0,
+                budget: f64::INFINITY
+            })
+        );
+        assert!(matches!(solver.try_set_budget(0, f64::NAN), Err(MbaError::InvalidBudget { .. })));
+        assert_eq!(solver.get_budget(0), 10.0);
+        assert_eq!(solver.try_set_budget(0, 0.0), Ok(()));
+    }
+
+    #[test]
+    fn bids() {
+        let mut solver = solver();
+        assert_eq!(solver.try_set_bid(2, 0, 1.0), Err(MbaError::AgentIndexOutOfRange { agent_id: 2, num_agents: 2 }));
+        assert_eq!(solver.try_set_bid(0, 3, 1.0), Err(MbaError::ItemIndexOutOfRange { item_id: 3, num_items: 3 }));
+        assert_eq!(
+            solver.try_set_bid(0, 0, 0.0),
+            Err(MbaError::InvalidBid {
+                agent_id: 0,
+                item_id: 0,
+                bid: 0.0
+            })
+        );
+        assert_eq!(
+            solver.try_set_bid(0, 0, -1.0),
+            Err(MbaError::InvalidBid {
+                agent_id: 0,
+                item_id: 0,
+                bid: -1.0
+            })
+        );
+        assert_eq!(
+            solver.try_set_bid(0, 0, f64::INFINITY),
+            Err(MbaError::InvalidBid {
+                agent_id: 0,
+                item_id: 0,
+                bid: f64::INFINITY
+            })
+        );
+        let e = solver.try_set_bid(0, 0, f64::NAN).unwrap_err();
+        assert_eq!(e.code(), ErrorCode::InvalidBid);
+        assert_eq!(solver.num_bids(), 0);
+
+        // rejected, not an error
+        assert_eq!(solver.try_set_bid(0, 0, 11.0), Ok(()));
+        solver.set_overbid_policy(OverbidPolicy::Error);
+        assert_eq!(
+            solver.try_set_bid(0, 0, 11.0),
+            Err(MbaError::BidExceedsBudget {
+                agent_id: 0,
+                item_id: 0,
+                bid: 11.0,
+                budget: 10.0
+            })
+        );
+        assert_eq!(solver.try_set_bid(0, 0, 5.0), Ok(()));
+        assert_eq!(solver.get_bid(0, 0), 5.0);
+    }
+
+    #[test]
+    fn safe_range() {
+        let mut solver = solver();
+        solver.set_range_policy(RangePolicy::Error);
+        let e = solver.try_set_bid(0, 0, 1e-200).unwrap_err();
+        assert_eq!(e.code(), ErrorCode::ValueOutOfRange);
+        assert!(matches!(e, MbaError::ValueOutOfRange { name: "bid", .. }));
+        assert_eq!(solver.try_set_budget(1, 1e200).unwrap_err().code(), ErrorCode::ValueOutOfRange);
+    }
+
+    #[test]
+    fn build() {
+        let e: MbaError = PrimalDual::try_new(2, 3, 1.5).err().unwrap().into();
+        assert_eq!(e, MbaError::Build(BuildError::InvalidEpsilon { epsilon: 1.5 }));
+        assert_eq!(e.code(), ErrorCode::InvalidEpsilon);
+    }
+}
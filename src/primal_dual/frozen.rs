@@ -0,0 +1,151 @@
+use super::{GapBreakdown, PrimalDual, Scalar};
+use crate::solution::Solution;
+
+// read-only result of a solve; plain data without lazily updated internals, so it is Sync and can be shared behind an Arc
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrozenSolution {
+    solution: Solution,            // assignment lists sorted by item id
+    alpha: Vec<f64>,               // alpha[agent_id]
+    item_prices: Vec<Option<f64>>, // item_prices[item_id] = (1 - alpha) * bid of the owner, None if unassigned
+    gap: GapBreakdown,
+    ratio: f64,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    pub fn freeze(self) -> FrozenSolution {
+        let mut solution = self.extract_solution();
+        solution.assignment.iter_mut().for_each(|item_ids| item_ids.sort_unstable());
+        let item_prices = solution
+            .item_owner
+            .iter()
+            .enumerate()
+            .map(|(item_id, owner)| owner.map(|agent_id| (1.0 - self.alpha(agent_id)) * self.bid(agent_id, item_id)))
+            .collect();
+
+        FrozenSolution {
+            alpha: self.alphas(),
+            item_prices,
+            gap: self.gap_attribution(),
+            ratio: self.get_approximation_ratio(),
+            solution,
+        }
+    }
+}
+
+impl FrozenSolution {
+    pub fn num_agents(&self) -> usize {
+        self.solution.num_agents()
+    }
+
+    pub fn num_items(&self) -> usize {
+        self.solution.num_items()
+    }
+
+    pub fn owner(&self, item_id: usize) -> Option<usize> {
+        self.solution.item_owner[item_id]
+    }
+
+    // items of agent_id in ascending order
+    pub fn assignment(&self, agent_id: usize) -> &[usize] {
+        &self.solution.assignment[agent_id]
+    }
+
+    pub fn spend(&self, agent_id: usize) -> f64 {
+        self.solution.spends[agent_id]
+    }
+
+    pub fn alpha(&self, agent_id: usize) -> f64 {
+        self.alpha[agent_id]
+    }
+
+    pub fn item_price(&self, item_id: usize) -> Option<f64> {
+        self.item_prices[item_id]
+    }
+
+    pub fn primal(&self) -> f64 {
+        self.solution.primal
+    }
+
+    pub fn dual(&self) -> f64 {
+        self.solution.dual
+    }
+
+    pub fn approximation_ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    pub fn gap_attribution(&self) -> &GapBreakdown {
+        &self.gap
+    }
+
+    pub fn solution(&self) -> &Solution {
+        &self.solution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrozenSolution;
+    use crate::primal_dual::PrimalDual;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn shared() {
+        assert_sync::<FrozenSolution>();
+
+        let num_agents = 20;
+        let num_items = 200;
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 20.0 + (agent_id * 11 % 30) as f64);
+        }
+        for agent_id in 0..num_agents {
+            for item_id in (agent_id % 3..num_items).step_by(3) {
+                solver.set_bid(agent_id, item_id, 1.0 + ((agent_id * 5 + item_id * 7) % 8) as f64);
+            }
+        }
+        solver.solve();
+
+        let expected = solver.extract_solution();
+        let landscapes = solver.export_landscape();
+        let gap = solver.gap_attribution();
+        let frozen = Arc::new(solver.freeze());
+
+        let handles: Vec<_> = (0..16)
+            .map(|t| {
+                let frozen = Arc::clone(&frozen);
+                let expected = expected.clone();
+                let landscapes = landscapes.clone();
+                let gap = gap.clone();
+                thread::spawn(move || {
+                    for round in 0..50 {
+                        let item_id = (t * 31 + round * 17) % num_items;
+                        let landscape = &landscapes[item_id];
+                        assert_eq!(frozen.owner(item_id), expected.item_owner[item_id]);
+                        assert_eq!(frozen.owner(item_id), landscape.winner);
+                        if let Some(winner) = landscape.winner {
+                            let bidder = landscape.bidders.iter().find(|bidder| bidder.agent_id == winner).unwrap();
+                            assert_eq!(frozen.item_price(item_id), Some(bidder.paced_price));
+                            assert_eq!(frozen.alpha(winner), bidder.alpha);
+                        }
+
+                        let agent_id = (t + round) % num_agents;
+                        let mut item_ids = expected.assignment[agent_id].clone();
+                        item_ids.sort_unstable();
+                        assert_eq!(frozen.assignment(agent_id), item_ids.as_slice());
+                        assert_eq!(frozen.spend(agent_id), expected.spends[agent_id]);
+                    }
+                    assert_eq!(frozen.primal(), expected.primal);
+                    assert_eq!(frozen.dual(), expected.dual);
+                    assert_eq!(frozen.gap_attribution(), &gap);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
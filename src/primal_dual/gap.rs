@@ -0,0 +1,168 @@
+use super::{PrimalDual, Scalar};
+use crate::reduce;
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+const DISPLAYED_AGENTS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AgentGap {
+    pub agent_id: usize,
+    pub term: f64, // budget * alpha + consumption * (1 - alpha) - min(consumption, budget)
+    pub consumption: f64,
+    pub budget: f64,
+}
+
+impl AgentGap {
+    pub fn is_over_budget(&self) -> bool {
+        self.consumption > self.budget
+    }
+}
+
+// dual - primal split into one term per agent and one term for the items
+#[derive(Clone, Debug, PartialEq)]
+pub struct GapBreakdown {
+    pub agents: Vec<AgentGap>, // agents[agent_id]; inactive agents contribute 0
+    pub items: f64,            // sum over items of the dual price minus (1 - alpha) * bid of the holders, 0 while every holder has the maximum price
+    pub total: f64,            // sum of all terms
+}
+
+impl GapBreakdown {
+    // the k agents with the largest terms, largest first
+    pub fn top(&self, k: usize) -> Vec<AgentGap> {
+        let mut agents = self.agents.clone();
+        agents.sort_by(|a, b| b.term.total_cmp(&a.term).then(a.agent_id.cmp(&b.agent_id)));
+        agents.truncate(k);
+        agents
+    }
+
+    // one row per agent
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "agent_id,term,consumption,budget,over_budget")?;
+        for agent in &self.agents {
+            writeln!(writer, "{},{:?},{:?},{:?},{}", agent.agent_id, agent.term, agent.consumption, agent.budget, agent.is_over_budget())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GapBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "gap {} over {} agents, {} from items not held at their dual price", self.total, self.agents.len(), self.items)?;
+        for agent in self.top(DISPLAYED_AGENTS) {
+            let share = if self.total > 0.0 { 100.0 * agent.term / self.total } else { 0.0 };
+            let side = if agent.is_over_budget() { "over" } else { "under" };
+            writeln!(f, "  agent {}: {} ({:.1}%), {} budget ({} of {})", agent.agent_id, agent.term, share, side, agent.consumption, agent.budget)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    pub fn gap_attribution(&self) -> GapBreakdown {
+        let agents: Vec<AgentGap> = (0..self.num_agents)
+            .map(|agent_id| {
+                let budget = self.effective_budget(agent_id);
+                let consumption = self.consumption(agent_id);
+                let term = if self.active[agent_id] {
+                    let alpha = self.alpha(agent_id);
+                    budget * alpha + consumption * (1.0 - alpha) - consumption.min(budget)
+                } else {
+                    0.0
+                };
+                AgentGap {
+                    agent_id,
+                    term,
+                    consumption,
+                    budget,
+                }
+            })
+            .collect();
+        // (1 - alpha) * bid summed over the active holders, several of them for an item of capacity above 1
+        let mut paid = vec![0.0; self.num_items];
+        for (agent_id, item_ids) in self.gamma.iter().enumerate() {
+            if !self.active[agent_id] {
+                continue;
+            }
+            for &item_id in item_ids {
+                paid[item_id] += (1.0 - self.alpha(agent_id)) * self.bid(agent_id, item_id);
+            }
+        }
+        let items = reduce::sum(self.num_items, self.reduction_chunk_size, |item_id| self.dual_item_price(item_id) - paid[item_id]);
+        let total = reduce::sum(self.num_agents, self.reduction_chunk_size, |agent_id| agents[agent_id].term) + items;
+        GapBreakdown { agents, items, total }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::read_instance;
+    use crate::primal_dual::PrimalDual;
+    use std::fs;
+    use std::io::BufReader;
+
+    fn assert_exact(solver: &PrimalDual) {
+        let breakdown = solver.gap_attribution();
+        let dual = solver.get_dual_objective_value();
+        let gap = dual - solver.get_primal_objective_value();
+        // one ulp of the dual, since the dual and the primal are each rounded on their own
+        let ulp = f64::from_bits(dual.to_bits() + 1) - dual;
+        assert!((breakdown.total - gap).abs() <= ulp, "{} != {}", breakdown.total, gap);
+    }
+
+    #[test]
+    fn sample() {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+        assert_exact(&solver);
+
+        let breakdown = solver.gap_attribution();
+        let top = breakdown.top(1);
+        assert_eq!(top.len(), 1);
+        assert!(breakdown.agents.iter().all(|agent| agent.term <= top[0].term));
+        assert!(breakdown.to_string().starts_with("gap "));
+
+        let mut buffer = Vec::new();
+        breakdown.write_csv(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn fixtures() {
+        for entry in fs::read_dir("tests/random").unwrap().flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != "in") {
+                continue;
+            }
+            let instance = read_instance(BufReader::new(fs::File::open(&path).unwrap())).unwrap();
+            for epsilon in [0.5, 0.1, 0.01] {
+                let mut solver = PrimalDual::from_instance(&instance, epsilon);
+                solver.solve();
+                assert_exact(&solver);
+                let breakdown = solver.gap_attribution();
+                assert!(breakdown.agents.iter().all(|agent| agent.term >= -1e-9), "{}", path.display());
+                assert!(breakdown.items >= 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn item_capacity() {
+        // item 0 is held by both agents, each paying its own (1 - alpha) * bid
+        let mut solver = PrimalDual::new(2, 2, 0.1);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bids([(0, 0, 4.0), (1, 0, 3.0), (1, 1, 2.0)]);
+        solver.set_item_capacity(0, 2);
+        solver.solve();
+        assert!(solver.get_assignment().iter().all(|item_ids| item_ids.contains(&0)));
+        assert_exact(&solver);
+        assert_eq!(solver.gap_attribution().items, 0.0);
+    }
+}
@@ -0,0 +1,124 @@
+use super::{PrimalDual, Scalar};
+
+// why an agent ends up without any accepted bid
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum InactiveReason {
+    NoBids,
+    // every bid exceeded the budget and was dropped by OverbidPolicy::Reject; usually bids and budget in different units
+    AllBidsRejected { max_bid: f64, budget: f64 },
+    // at least one bid was below the reserve price of its item and every other one exceeded the budget
+    BelowReservePrice,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InactiveAgent {
+    pub agent_id: usize,
+    pub reason: InactiveReason,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // agents that can never be assigned an item, by agent id; agents switched off with set_agent_active are not listed.
+    // bids added by UnassignablePolicy::ClampBestBid count as accepted, so call this after solve()
+    pub fn get_inactive_agents(&self) -> Vec<InactiveAgent> {
+        let mut max_rejected_bid: Vec<Option<f64>> = vec![None; self.num_agents];
+        for &(agent_id, _, bid) in &self.rejected_bids {
+            let max_bid = max_rejected_bid[agent_id].get_or_insert(bid);
+            *max_bid = max_bid.max(bid);
+        }
+        let mut below_reserve = vec![false; self.num_agents];
+        for &(agent_id, _, _) in &self.below_reserve_bids {
+            below_reserve[agent_id] = true;
+        }
+
+        (0..self.num_agents)
+            .filter(|&agent_id| self.active[agent_id] && self.agent_items[agent_id].is_empty())
+            .map(|agent_id| InactiveAgent {
+                agent_id,
+                reason: match max_rejected_bid[agent_id] {
+                    _ if below_reserve[agent_id] => InactiveReason::BelowReservePrice,
+                    Some(max_bid) => InactiveReason::AllBidsRejected {
+                        max_bid,
+                        budget: self.budget(agent_id),
+                    },
+                    None => InactiveReason::NoBids,
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InactiveAgent, InactiveReason};
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn cents_and_dollars() {
+        // agent 1 sent its bids in cents and its budget in dollars
+        let mut solver = PrimalDual::new(3, 3, 0.01);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        solver.set_budget(2, 100.0);
+        solver.set_bid(0, 0, 200.0);
+        solver.set_bid(0, 1, 100.0);
+        solver.set_bid(1, 0, 15000.0);
+        solver.set_bid(1, 1, 25000.0);
+        solver.set_bid(1, 2, 5000.0);
+        solver.set_agent_active(2, false);
+        solver.solve();
+
+        assert_eq!(
+            solver.get_inactive_agents(),
+            vec![InactiveAgent {
+                agent_id: 1,
+                reason: InactiveReason::AllBidsRejected { max_bid: 25000.0, budget: 400.0 },
+            }]
+        );
+    }
+
+    #[test]
+    fn no_bids() {
+        let mut solver = PrimalDual::new(2, 1, 0.01);
+        solver.set_budget(0, 1.0);
+        solver.set_budget(1, 1.0);
+        solver.set_bid(1, 0, 1.0);
+        solver.solve();
+        assert_eq!(
+            solver.get_inactive_agents(),
+            vec![InactiveAgent {
+                agent_id: 0,
+                reason: InactiveReason::NoBids,
+            }]
+        );
+    }
+
+    #[test]
+    fn below_reserve_price() {
+        // agent 0 bids below the reserve price only, agent 1 also above its budget
+        let mut solver = PrimalDual::new(3, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_budget(2, 10.0);
+        solver.set_reserve_price(0, 5.0);
+        solver.set_bid(0, 0, 2.0);
+        solver.set_bid(1, 0, 3.0);
+        solver.set_bid(1, 1, 20.0);
+        solver.set_bid(2, 0, 6.0);
+        solver.solve();
+        assert_eq!(
+            solver.get_inactive_agents(),
+            vec![
+                InactiveAgent {
+                    agent_id: 0,
+                    reason: InactiveReason::BelowReservePrice,
+                },
+                InactiveAgent {
+                    agent_id: 1,
+                    reason: InactiveReason::BelowReservePrice,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use super::{BuildError, MbaError, PrimalDual};
+use crate::solution::Solution;
+
+// every integer up to this is exactly an f64; the bids of one agent must sum to at most this
+pub const MAX_EXACT: u64 = 1 << 53;
+
+// PrimalDual for integer budgets and bids, e.g. cents.
+//
+// bids and budgets are stored as f64, which holds every integer up to MAX_EXACT. set_bid keeps the bids of each agent
+// summing to at most MAX_EXACT, so every consumption the solve adds or removes is an integer that never rounds and
+// is_paid_for compares the exact consumption against U * budget. only alpha and the prices are fractional.
+// the primal objective and the consumptions are reported as u64, summed from the assignment
+pub struct IntPrimalDual {
+    solver: PrimalDual,
+    bid_totals: Vec<u64>, // bid_totals[agent_id] = sum of the accepted bids of agent_id
+}
+
+impl IntPrimalDual {
+    // panics where try_new fails
+    pub fn new(num_agents: usize, num_items: usize, epsilon: f64) -> Self {
+        Self::try_new(num_agents, num_items, epsilon).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(num_agents: usize, num_items: usize, epsilon: f64) -> Result<Self, BuildError> {
+        Ok(IntPrimalDual {
+            solver: PrimalDual::try_new(num_agents, num_items, epsilon)?,
+            bid_totals: vec![0; num_agents],
+        })
+    }
+
+    // panics where try_set_budget fails
+    pub fn set_budget(&mut self, agent_id: usize, budget: u64) {
+        self.try_set_budget(agent_id, budget).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_set_budget(&mut self, agent_id: usize, budget: u64) -> Result<(), MbaError> {
+        if budget > MAX_EXACT {
+            return Err(out_of_range("budget", budget));
+        }
+        self.solver.try_set_budget(agent_id, budget as f64)
+    }
+
+    pub fn get_budget(&self, agent_id: usize) -> u64 {
+        self.solver.get_budget(agent_id) as u64
+    }
+
+    // panics where try_set_bid fails
+    pub fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: u64) {
+        self.try_set_bid(agent_id, item_id, bid).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // fails with MbaError::ValueOutOfRange when the accepted bids of agent_id would sum to more than MAX_EXACT
+    pub fn try_set_bid(&mut self, agent_id: usize, item_id: usize, bid: u64) -> Result<(), MbaError> {
+        self.solver.check_agent(agent_id)?;
+        let previous = self.get_bid(agent_id, item_id);
+        let others = self.bid_totals[agent_id].checked_sub(previous).expect("bid_totals holds every accepted bid");
+        match others.checked_add(bid) {
+            Some(total) if total <= MAX_EXACT => {}
+            total => return Err(out_of_range("bid total", total.unwrap_or(u64::MAX))),
+        }
+
+        self.solver.try_set_bid(agent_id, item_id, bid as f64)?;
+        // a rejected or clamped bid is not the bid that was given, and at most that one, so the sum stays within MAX_EXACT
+        self.bid_totals[agent_id] = others + self.get_bid(agent_id, item_id);
+        Ok(())
+    }
+
+    // 0 for no bid
+    pub fn get_bid(&self, agent_id: usize, item_id: usize) -> u64 {
+        self.solver.get_bid(agent_id, item_id) as u64
+    }
+
+    pub fn solve(&mut self) {
+        self.solver.solve();
+    }
+
+    // sum of the bids on the items agent_id holds, which may exceed its budget
+    pub fn get_consumption(&self, agent_id: usize) -> u64 {
+        self.solver.gamma[agent_id].iter().map(|&item_id| self.get_bid(agent_id, item_id)).sum()
+    }
+
+    // sum_a min(consumption_a, budget_a), exact
+    pub fn get_primal_objective_value(&self) -> u64 {
+        (0..self.solver.num_agents)
+            .map(|agent_id| self.get_consumption(agent_id).min(self.get_budget(agent_id)))
+            .sum()
+    }
+
+    pub fn get_dual_objective_value(&self) -> f64 {
+        self.solver.get_dual_objective_value()
+    }
+
+    pub fn get_approximation_ratio(&self) -> f64 {
+        self.solver.get_approximation_ratio()
+    }
+
+    pub fn extract_solution(&self) -> Solution {
+        self.solver.extract_solution()
+    }
+
+    // the underlying solver, for everything that has no integer counterpart
+    pub fn solver(&self) -> &PrimalDual {
+        &self.solver
+    }
+}
+
+fn out_of_range(name: &'static str, value: u64) -> MbaError {
+    MbaError::ValueOutOfRange {
+        name,
+        value: value as f64,
+        safe_range: (0.0, MAX_EXACT as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntPrimalDual, MAX_EXACT};
+    use crate::primal_dual::{MbaError, PrimalDual};
+
+    // 50 items of 1000000000000.03 dollars each; summed in f64 dollars they are 3 cents off
+    const NUM_ITEMS: usize = 50;
+    const BID_CENTS: u64 = 100_000_000_000_003;
+
+    #[test]
+    fn exact_where_f64_drifts() {
+        let exact = NUM_ITEMS as u64 * BID_CENTS;
+        // room to spare, so that the primal is the consumption rather than the budget
+        let budget = exact / 2 * 3;
+
+        let mut dollars = PrimalDual::new(1, NUM_ITEMS, 0.01);
+        dollars.set_budget(0, budget as f64 / 100.0);
+        for item_id in 0..NUM_ITEMS {
+            dollars.set_bid(0, item_id, BID_CENTS as f64 / 100.0);
+        }
+        dollars.solve();
+        let drift = (dollars.get_primal_objective_value() * 100.0 - exact as f64).abs();
+        assert!(drift >= 1.0, "{}", drift);
+
+        let mut cents = IntPrimalDual::new(1, NUM_ITEMS, 0.01);
+        cents.set_budget(0, budget);
+        for item_id in 0..NUM_ITEMS {
+            cents.set_bid(0, item_id, BID_CENTS);
+        }
+        cents.solve();
+        assert_eq!(cents.get_consumption(0), exact);
+        assert_eq!(cents.get_primal_objective_value(), exact);
+    }
+
+    #[test]
+    fn contested_items() {
+        let num_agents = 6;
+        let num_items = 200;
+        let mut solver = IntPrimalDual::new(num_agents, num_items, 0.01);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 1_000_000_007 + agent_id as u64 * 999_999_937);
+        }
+        for agent_id in 0..num_agents {
+            for item_id in (agent_id % 2..num_items).step_by(2) {
+                solver.set_bid(agent_id, item_id, 10_000_019 + ((agent_id * 31 + item_id * 17) % 97) as u64 * 1_000_003);
+            }
+        }
+        solver.solve();
+
+        let solution = solver.extract_solution();
+        for agent_id in 0..num_agents {
+            let consumption: u64 = solution.assignment[agent_id].iter().map(|&item_id| solver.get_bid(agent_id, item_id)).sum();
+            assert_eq!(solver.get_consumption(agent_id), consumption);
+            assert_eq!(solver.solver().get_consumption(agent_id), consumption as f64);
+        }
+        let primal = solver.get_primal_objective_value();
+        assert!(primal as f64 >= solver.get_dual_objective_value() * solver.get_approximation_ratio());
+    }
+
+    #[test]
+    fn bid_total_limit() {
+        let mut solver = IntPrimalDual::new(1, 3, 0.01);
+        solver.set_budget(0, MAX_EXACT);
+        solver.set_bid(0, 0, MAX_EXACT / 2);
+        solver.set_bid(0, 1, MAX_EXACT / 2);
+        assert!(matches!(solver.try_set_bid(0, 2, 1), Err(MbaError::ValueOutOfRange { name: "bid total", .. })));
+        // replacing a bid frees its share
+        solver.set_bid(0, 1, MAX_EXACT / 4);
+        assert_eq!(solver.try_set_bid(0, 2, 1), Ok(()));
+        assert!(matches!(solver.try_set_budget(0, MAX_EXACT + 1), Err(MbaError::ValueOutOfRange { name: "budget", .. })));
+
+        // a bid that overflows the sum is out of range as well, and leaves the total alone
+        assert!(matches!(solver.try_set_bid(0, 1, u64::MAX), Err(MbaError::ValueOutOfRange { name: "bid total", .. })));
+        assert_eq!(solver.get_bid(0, 1), MAX_EXACT / 4);
+        assert_eq!(solver.try_set_bid(0, 1, MAX_EXACT / 2 - 1), Ok(()));
+    }
+}
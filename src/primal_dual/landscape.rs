@@ -0,0 +1,138 @@
+use super::{PrimalDual, Scalar};
+use std::cmp::Reverse;
+use std::io;
+use std::io::Write;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bidder {
+    pub agent_id: usize,
+    pub bid: f64,
+    pub alpha: f64,
+    pub paced_price: f64, // (1 - alpha) * bid
+}
+
+// competition for one item
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemLandscape {
+    pub item_id: usize,
+    pub winner: Option<usize>,
+    pub bidders: Vec<Bidder>, // sorted by paced price descending, ties by agent id
+    pub gap: f64,             // first minus second paced price; the first price if there is a single bidder, 0 if there is none
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // landscape of every item; O(bids) overall
+    pub fn export_landscape(&self) -> Vec<ItemLandscape> {
+        let item_owner = self.item_owner();
+        (0..self.num_items).map(|item_id| self.item_landscape(item_id, item_owner[item_id])).collect()
+    }
+
+    // landscape of a single item
+    pub fn explain_item(&self, item_id: usize) -> ItemLandscape {
+        let winner = self.gamma.iter().position(|item_ids| item_ids.contains(&item_id));
+        self.item_landscape(item_id, winner)
+    }
+
+    fn item_landscape(&self, item_id: usize, winner: Option<usize>) -> ItemLandscape {
+        // item_agent holds one entry per accepted bid of the item
+        let mut agent_ids: Vec<usize> = self.item_agent[item_id].iter().map(|&(_, Reverse(agent_id), _)| agent_id).collect();
+        agent_ids.sort_unstable();
+        agent_ids.dedup();
+
+        let mut bidders: Vec<Bidder> = agent_ids
+            .into_iter()
+            .map(|agent_id| {
+                let bid = self.bid(agent_id, item_id);
+                let alpha = self.alpha(agent_id);
+                Bidder {
+                    agent_id,
+                    bid,
+                    alpha,
+                    paced_price: (1.0 - alpha) * bid,
+                }
+            })
+            .collect();
+        bidders.sort_by(|a, b| b.paced_price.total_cmp(&a.paced_price).then(a.agent_id.cmp(&b.agent_id)));
+
+        let gap = match bidders.as_slice() {
+            [] => 0.0,
+            [first] => first.paced_price,
+            [first, second, ..] => first.paced_price - second.paced_price,
+        };
+
+        ItemLandscape { item_id, winner, bidders, gap }
+    }
+}
+
+// one row per (item, bidder); items without bidders get a row with empty bidder columns
+pub fn landscape_to_csv<W: Write>(landscapes: &[ItemLandscape], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "item_id,winner,agent_id,bid,alpha,paced_price,gap")?;
+    for landscape in landscapes {
+        let winner = landscape.winner.map_or(String::new(), |agent_id| agent_id.to_string());
+        if landscape.bidders.is_empty() {
+            writeln!(writer, "{},{},,,,,{}", landscape.item_id, winner, landscape.gap)?;
+        }
+        for bidder in &landscape.bidders {
+            writeln!(writer, "{},{},{},{},{},{},{}", landscape.item_id, winner, bidder.agent_id, bidder.bid, bidder.alpha, bidder.paced_price, landscape.gap)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::landscape_to_csv;
+    use crate::primal_dual::PrimalDual;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn sample() {
+        let mut solver = PrimalDual::new(2, 4, 0.01);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+
+        let landscapes = solver.export_landscape();
+        assert_eq!(landscapes.len(), 4);
+        for landscape in &landscapes {
+            assert_eq!(landscape, &solver.explain_item(landscape.item_id));
+            assert!(landscape.bidders.windows(2).all(|pair| pair[0].paced_price >= pair[1].paced_price));
+        }
+
+        assert_eq!(landscapes[2].winner, Some(1));
+        assert_eq!(landscapes[2].bidders[0].agent_id, 1);
+        assert_eq!(landscapes[2].gap, landscapes[2].bidders[0].paced_price - landscapes[2].bidders[1].paced_price);
+        assert_eq!(landscapes[3].winner, None);
+        assert!(landscapes[3].bidders.is_empty());
+
+        let mut buffer = Vec::new();
+        landscape_to_csv(&landscapes, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 1 + 6 + 1);
+    }
+
+    #[test]
+    fn many_items() {
+        let num_agents = 10;
+        let num_items = 100_000;
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.1);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, 100_000.0);
+        }
+        for item_id in 0..num_items {
+            for k in 0..3 {
+                solver.set_bid((item_id + k * 3) % num_agents, item_id, 1.0 + ((item_id * 7 + k) % 5) as f64);
+            }
+        }
+        solver.solve();
+
+        let start = Instant::now();
+        let landscapes = solver.export_landscape();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(landscapes.iter().map(|landscape| landscape.bidders.len()).sum::<usize>(), 3 * num_items);
+    }
+}
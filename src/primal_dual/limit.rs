@@ -0,0 +1,95 @@
+use super::{PrimalDual, Scalar, Stop};
+use std::time::{Duration, Instant};
+
+// limits of solve_with_limit; None is unlimited
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SolveLimit {
+    pub time: Option<Duration>,
+    pub alpha_updates: Option<usize>, // checked between two rounds, so a round that raises alpha repeatedly can overshoot it
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveStatus {
+    // every agent is paid for, the approximation guarantee holds
+    WithinGuarantee,
+    // the solve stopped early; the assignment is consistent but carries no guarantee
+    LimitReached,
+    // the cancellation flag of solve_with stopped the solve early, as LimitReached
+    Cancelled,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // solve that stops between two rounds of reallocation once a limit is hit.
+    // gamma, consumptions and the objectives then describe the assignment at that point
+    pub fn solve_with_limit(&mut self, limit: SolveLimit) -> SolveStatus {
+        let stop = Stop {
+            deadline: limit.time.map(|time| Instant::now() + time),
+            max_alpha_updates: limit.alpha_updates.map(|num| self.num_alpha_updates + num),
+            cancel: None,
+        };
+
+        self.apply_unassignable_policy();
+        self.initialize();
+        match self.run_with(stop, |_, _| {}) {
+            (_, true) => SolveStatus::WithinGuarantee,
+            (_, false) => SolveStatus::LimitReached,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SolveLimit, SolveStatus};
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+    use std::time::Duration;
+
+    fn generated_solver() -> PrimalDual {
+        PrimalDual::from_instance(&generate(20, 200, 0.9, (10.0, 50.0), (1.0, 10.0), 218), 0.001)
+    }
+
+    fn assert_consistent(solver: &PrimalDual) {
+        let solution = solver.extract_solution();
+        assert!(solution.verify().is_ok());
+        for (agent_id, item_ids) in solution.assignment.iter().enumerate() {
+            let spend: f64 = item_ids.iter().map(|&item_id| solver.get_bid(agent_id, item_id)).sum();
+            assert!(item_ids.iter().all(|&item_id| solver.get_bid(agent_id, item_id) > 0.0));
+            assert!((spend - solution.spends[agent_id]).abs() <= 1e-9);
+        }
+    }
+
+    #[test]
+    fn alpha_updates() {
+        for num in [0, 1, 7, 50] {
+            let mut solver = generated_solver();
+            let limit = SolveLimit {
+                alpha_updates: Some(num),
+                ..SolveLimit::default()
+            };
+            assert_eq!(solver.solve_with_limit(limit), SolveStatus::LimitReached);
+            assert_consistent(&solver);
+        }
+    }
+
+    #[test]
+    fn time() {
+        let mut solver = generated_solver();
+        let limit = SolveLimit {
+            time: Some(Duration::ZERO),
+            ..SolveLimit::default()
+        };
+        assert_eq!(solver.solve_with_limit(limit), SolveStatus::LimitReached);
+        assert_consistent(&solver);
+    }
+
+    #[test]
+    fn unlimited() {
+        let mut solver = generated_solver();
+        assert_eq!(solver.solve_with_limit(SolveLimit::default()), SolveStatus::WithinGuarantee);
+        assert_consistent(&solver);
+
+        let mut fresh = generated_solver();
+        fresh.solve();
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+    }
+}
@@ -0,0 +1,229 @@
+use super::{PrimalDual, Scalar};
+
+// improvements post_optimize looks for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum LocalSearch {
+    // every unassigned item goes to the bidder it adds the most value to
+    Fill,
+    // Fill, then single items move to another bidder and two agents swap an item each while that raises the primal
+    #[default]
+    FillAndMoves,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalSearchReport {
+    pub before: f64, // primal objective before post_optimize
+    pub after: f64,
+    pub num_filled: usize,
+    pub num_moves: usize,
+    pub num_swaps: usize,
+    pub iterations: usize, // passes over the items, the last of which found nothing when below the cap
+}
+
+impl LocalSearchReport {
+    pub fn improvement(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+// changes of the primal at most this fraction of it are rounding, not improvements
+const RELATIVE_TOLERANCE: f64 = 1e-12;
+
+impl<T: Scalar> PrimalDual<T> {
+    // improves the assignment of the last solve in place by local search, for at most max_iterations passes.
+    // every accepted change strictly raises the primal and alpha is left alone, so the dual and with it
+    // primal >= ratio * dual still hold. items of capacity above 1 and inactive agents are left alone, and no agent
+    // takes an item beyond its item cap or capacity
+    pub fn post_optimize(&mut self, strategy: LocalSearch, max_iterations: usize) -> LocalSearchReport {
+        let before = self.get_primal_objective_value();
+        let mut report = LocalSearchReport {
+            before,
+            after: before,
+            num_filled: 0,
+            num_moves: 0,
+            num_swaps: 0,
+            iterations: 0,
+        };
+        let tolerance = RELATIVE_TOLERANCE * before.max(1.0);
+
+        while report.iterations < max_iterations {
+            report.iterations += 1;
+            let mut changed = false;
+            let mut item_owner = self.item_owner();
+
+            for (item_id, owner) in item_owner.iter_mut().enumerate() {
+                if owner.is_some() || self.item_capacity[item_id] > 1 {
+                    continue;
+                }
+                let best = self.item_agents[item_id]
+                    .iter()
+                    .filter(|&&agent_id| self.active[agent_id] && self.has_room(agent_id, item_id))
+                    .map(|&agent_id| (self.gain(agent_id, self.bid(agent_id, item_id)), agent_id))
+                    .filter(|&(gain, _)| gain > tolerance)
+                    .max_by(|a, b| a.0.total_cmp(&b.0).then(b.1.cmp(&a.1)));
+                if let Some((_, agent_id)) = best {
+                    self.take_item(agent_id, item_id);
+                    *owner = Some(agent_id);
+                    report.num_filled += 1;
+                    changed = true;
+                }
+            }
+
+            if strategy == LocalSearch::FillAndMoves {
+                for item_id in 0..self.num_items {
+                    let Some(holder) = item_owner[item_id] else {
+                        continue;
+                    };
+                    if self.item_capacity[item_id] > 1 || !self.active[holder] {
+                        continue;
+                    }
+                    if let Some(agent_id) = self.best_move(holder, item_id, tolerance) {
+                        self.give_item(holder, item_id);
+                        self.take_item(agent_id, item_id);
+                        item_owner[item_id] = Some(agent_id);
+                        report.num_moves += 1;
+                        changed = true;
+                    } else if let Some((agent_id, other_id)) = self.best_swap(holder, item_id, &item_owner, tolerance) {
+                        self.give_item(holder, item_id);
+                        self.give_item(agent_id, other_id);
+                        self.take_item(agent_id, item_id);
+                        self.take_item(holder, other_id);
+                        item_owner[item_id] = Some(agent_id);
+                        item_owner[other_id] = Some(holder);
+                        report.num_swaps += 1;
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        report.after = self.get_primal_objective_value();
+        report
+    }
+
+    // change of the capped value of agent_id when its consumption changes by delta
+    fn gain(&self, agent_id: usize, delta: f64) -> f64 {
+        let budget = self.effective_budget(agent_id);
+        let consumption = self.consumption(agent_id);
+        (consumption + delta).min(budget) - consumption.min(budget)
+    }
+
+    // the bidder item_id raises the primal the most for when it leaves holder
+    fn best_move(&self, holder: usize, item_id: usize, tolerance: f64) -> Option<usize> {
+        let loss = self.gain(holder, -self.bid(holder, item_id));
+        self.item_agents[item_id]
+            .iter()
+            .filter(|&&agent_id| agent_id != holder && self.active[agent_id] && self.has_room(agent_id, item_id))
+            .map(|&agent_id| (loss + self.gain(agent_id, self.bid(agent_id, item_id)), agent_id))
+            .filter(|&(delta, _)| delta > tolerance)
+            .max_by(|a, b| a.0.total_cmp(&b.0).then(b.1.cmp(&a.1)))
+            .map(|(_, agent_id)| agent_id)
+    }
+
+    // (agent_id, other_id) such that holder giving item_id to agent_id for other_id raises the primal the most
+    fn best_swap(&self, holder: usize, item_id: usize, item_owner: &[Option<usize>], tolerance: f64) -> Option<(usize, usize)> {
+        let mut best: Option<(f64, usize, usize)> = None;
+        for &agent_id in &self.item_agents[item_id] {
+            if agent_id == holder || !self.active[agent_id] {
+                continue;
+            }
+            for &other_id in &self.gamma[agent_id] {
+                if self.item_capacity[other_id] > 1 || item_owner[other_id] != Some(agent_id) {
+                    continue;
+                }
+                let received = self.bid(holder, other_id);
+                if received <= 0.0 || !self.fits(holder, other_id, Some(item_id)) || !self.fits(agent_id, item_id, Some(other_id)) {
+                    continue;
+                }
+                let delta = self.gain(holder, received - self.bid(holder, item_id)) + self.gain(agent_id, self.bid(agent_id, item_id) - self.bid(agent_id, other_id));
+                if delta > tolerance && best.is_none_or(|(best_delta, _, _)| delta > best_delta) {
+                    best = Some((delta, agent_id, other_id));
+                }
+            }
+        }
+        best.map(|(_, agent_id, other_id)| (agent_id, other_id))
+    }
+
+    fn take_item(&mut self, agent_id: usize, item_id: usize) {
+        self.gamma[agent_id].push_back(item_id);
+        let bid = T::from_f64(self.bid(agent_id, item_id));
+        self.consumptions[agent_id] += bid;
+        self.emit_move(item_id, None, Some(agent_id));
+    }
+
+    fn give_item(&mut self, agent_id: usize, item_id: usize) {
+        self.gamma[agent_id].retain(|&id| id != item_id);
+        self.recompute_consumption(agent_id);
+        self.emit_move(item_id, Some(agent_id), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalSearch;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn move_to_unused_budget() {
+        // agent 0 keeps both items, as agent 1 never outprices it on item 1, though agent 0 cannot spend more than 10
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bid(0, 0, 10.0);
+        solver.set_bid(0, 1, 10.0);
+        solver.set_bid(1, 1, 1.0);
+        solver.solve();
+        assert_eq!(solver.get_primal_objective_value(), 10.0);
+        let dual = solver.get_dual_objective_value();
+
+        let report = solver.post_optimize(LocalSearch::FillAndMoves, 10);
+        assert_eq!((report.before, report.after, report.improvement()), (10.0, 11.0, 1.0));
+        assert_eq!(report.num_moves, 1);
+        assert_eq!(solver.get_item_assignment(), vec![Some(0), Some(1)]);
+        assert_eq!(solver.get_dual_objective_value(), dual);
+        assert!(solver.get_primal_objective_value() >= solver.get_approximation_ratio() * dual);
+        solver.verify_solution().unwrap();
+    }
+
+    #[test]
+    fn fill_and_swap() {
+        // agent 0 holds item 0 and agent 1 item 1, though each bids more on the other's item; item 2 is unassigned
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 5.0);
+        solver.set_budget(1, 8.0);
+        solver.set_bids([(0, 0, 3.0), (0, 1, 5.0), (1, 0, 5.0), (1, 1, 3.0), (1, 2, 2.0)]);
+        solver.solve();
+        solver.gamma.iter_mut().for_each(|item_ids| item_ids.clear());
+        solver.gamma[0].push_back(0);
+        solver.gamma[1].push_back(1);
+        solver.consumptions = vec![3.0, 3.0];
+
+        let report = solver.post_optimize(LocalSearch::Fill, 10);
+        assert_eq!((report.num_filled, report.num_moves, report.num_swaps, report.after), (1, 0, 0, 8.0));
+
+        let report = solver.post_optimize(LocalSearch::FillAndMoves, 10);
+        assert_eq!((report.num_swaps, report.after), (1, 12.0));
+        assert_eq!(solver.get_item_assignment(), vec![Some(1), Some(0), Some(1)]);
+        solver.verify_solution().unwrap();
+    }
+
+    #[test]
+    fn keeps_the_guarantee() {
+        for seed in 0..50 {
+            let instance = generate(6, 30, 0.5, (10.0, 40.0), (1.0, 15.0), seed);
+            let mut solver = PrimalDual::from_instance(&instance, 0.05);
+            solver.solve();
+            let report = solver.post_optimize(LocalSearch::default(), 100);
+            assert!(report.improvement() >= 0.0);
+            assert!(report.iterations <= 100);
+            solver.verify_solution().unwrap();
+            assert!(solver.get_primal_objective_value() >= solver.get_approximation_ratio() * solver.get_dual_objective_value() - 1e-9);
+        }
+    }
+}
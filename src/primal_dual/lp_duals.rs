@@ -0,0 +1,98 @@
+use super::{PrimalDual, Scalar};
+use crate::instance::Instance;
+
+// dual of the LP relaxation
+//   min  sum_a budget_a * alpha_a + sum_j p_j
+//   s.t. p_j >= (1 - alpha_a) * bid_aj  for every bid
+//        alpha_a >= 0, p_j >= 0
+//
+// any alpha in [0, 1] with p_j = max_a (1 - alpha_a) * bid_aj is feasible, so objective() bounds the LP optimum and therefore the primal.
+// get_dual_objective_value is this objective, computed without materializing the prices
+#[derive(Clone, Debug, PartialEq)]
+pub struct LpDuals {
+    pub agent_duals: Vec<f64>, // agent_duals[agent_id] = alpha
+    pub item_duals: Vec<f64>,  // item_duals[item_id] = max price over bidders, 0 for unbid items; see capacity for items of capacity above 1
+}
+
+impl LpDuals {
+    pub fn objective(&self, instance: &Instance) -> f64 {
+        let agent_term: f64 = instance.budgets.iter().zip(&self.agent_duals).map(|(budget, alpha)| budget * alpha).sum();
+        agent_term + self.item_duals.iter().sum::<f64>()
+    }
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    pub fn get_lp_duals(&self) -> LpDuals {
+        LpDuals {
+            agent_duals: self.alphas(),
+            item_duals: (0..self.num_items).map(|item_id| self.dual_item_price(item_id)).collect(),
+        }
+    }
+
+    // budgets and accepted bids of the active agents
+    pub fn to_instance(&self) -> Instance {
+        let mut instance = Instance::new(self.num_agents, self.num_items);
+        instance.budgets = (0..self.num_agents).map(|agent_id| self.budget(agent_id)).collect();
+        for agent_id in (0..self.num_agents).filter(|&agent_id| self.active[agent_id]) {
+            let mut item_ids = self.agent_items[agent_id].clone();
+            item_ids.sort_unstable();
+            for item_id in item_ids {
+                instance.add_bid(agent_id, item_id, self.bid(agent_id, item_id));
+            }
+        }
+        instance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::PrimalDual;
+
+    fn solvers() -> Vec<PrimalDual> {
+        let mut sample = PrimalDual::new(2, 3, 0.01);
+        sample.set_budget(0, 300.0);
+        sample.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            sample.set_bid(agent_id, item_id, bid);
+        }
+
+        let num_agents = 8;
+        let num_items = 60;
+        let mut generated = PrimalDual::new(num_agents, num_items, 0.05);
+        for agent_id in 0..num_agents {
+            generated.set_budget(agent_id, 30.0 + (agent_id * 41 % 70) as f64);
+        }
+        for agent_id in 0..num_agents {
+            for item_id in 0..num_items {
+                let bid = ((agent_id * 29 + item_id * 7) % 17) as f64;
+                if bid > 0.0 {
+                    generated.set_bid(agent_id, item_id, bid);
+                }
+            }
+        }
+
+        vec![sample, generated]
+    }
+
+    #[test]
+    fn both_duals_bound_the_primal() {
+        for mut solver in solvers() {
+            solver.solve();
+            let primal = solver.get_primal_objective_value();
+            let dual = solver.get_dual_objective_value();
+            let lp_dual = solver.get_lp_duals().objective(&solver.to_instance());
+
+            assert!(primal <= dual + 1e-9);
+            assert!(primal <= lp_dual + 1e-9);
+            assert!((dual - lp_dual).abs() <= 1e-9 * lp_dual.max(1.0));
+        }
+    }
+
+    #[test]
+    fn before_solve() {
+        let solver = &solvers()[0];
+        let duals = solver.get_lp_duals();
+        assert_eq!(duals.agent_duals, vec![0.0, 0.0]);
+        assert_eq!(duals.item_duals, vec![200.0, 200.0, 200.0]);
+    }
+}
@@ -0,0 +1,194 @@
+use super::{MbaError, PrimalDual, Scalar};
+
+// an agent with a cap of k items keeps at most k items, e.g. one slot per advertiser and page.
+// the cap is enforced when a solve completes: an agent above it keeps its k largest bids, and every released item
+// goes to the bidder of the largest price that is below its own cap and stays paid for, or stays unassigned.
+// agents are therefore all paid for afterwards, but the dual does not account for the caps, so
+// get_approximation_ratio is only proven when no cap releases an item.
+// exact::solve does not model caps, so they are rejected while epsilon is 0
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_set_max_items fails
+    pub fn set_max_items(&mut self, agent_id: usize, max_items: usize) {
+        self.try_set_max_items(agent_id, max_items).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_set_max_items(&mut self, agent_id: usize, max_items: usize) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if self.epsilon == 0.0 {
+            return Err(MbaError::UnsupportedByExact { setting: "max_items" });
+        }
+        self.max_items[agent_id] = Some(max_items);
+        self.mark_changed();
+        Ok(())
+    }
+
+    // None for no cap, the default
+    pub fn get_max_items(&self, agent_id: usize) -> Option<usize> {
+        self.max_items[agent_id]
+    }
+
+    pub fn clear_max_items(&mut self, agent_id: usize) {
+        self.max_items[agent_id] = None;
+        self.mark_changed();
+    }
+
+    // whether agent_id can take item_id on top of its items, within its cap and its capacity
+    pub(super) fn has_room(&self, agent_id: usize, item_id: usize) -> bool {
+        self.max_items[agent_id].is_none_or(|max_items| self.gamma[agent_id].len() < max_items) && self.fits(agent_id, item_id, None)
+    }
+
+    // enforces the item caps; returns the number of items released by an agent above its cap. such an agent keeps its
+    // items in the order of decreasing bids as long as they fit. reallocation already keeps every agent within its
+    // capacity, and a released item only goes to an agent with room for it
+    pub(super) fn enforce_agent_limits(&mut self) -> usize {
+        if self.max_items.iter().all(Option::is_none) {
+            return 0;
+        }
+
+        let mut released = Vec::new();
+        for agent_id in 0..self.num_agents {
+            if self.within_limits(agent_id) {
+                continue;
+            }
+            let mut item_ids: Vec<usize> = self.gamma[agent_id].drain(..).collect();
+            item_ids.sort_by(|&a, &b| self.bid(agent_id, b).total_cmp(&self.bid(agent_id, a)).then(a.cmp(&b)));
+            for item_id in item_ids {
+                if self.has_room(agent_id, item_id) {
+                    self.gamma[agent_id].push_back(item_id);
+                } else {
+                    released.push((item_id, agent_id));
+                    self.emit_move(item_id, Some(agent_id), None);
+                }
+            }
+            self.recompute_consumption(agent_id);
+        }
+
+        // the items of the largest prices find a new bidder first
+        let num_released = released.len();
+        released.sort_by(|&(a, _), &(b, _)| self.dual_item_price(b).total_cmp(&self.dual_item_price(a)).then(a.cmp(&b)));
+        for (item_id, holder) in released {
+            let mut bidders: Vec<usize> = self.item_agents[item_id]
+                .iter()
+                .copied()
+                .filter(|&agent_id| agent_id != holder && self.active[agent_id] && !self.gamma[agent_id].contains(&item_id))
+                .collect();
+            bidders.sort_by(|&a, &b| self.price(b, item_id).total_cmp(&self.price(a, item_id)).then(a.cmp(&b)));
+            let recipient = bidders.into_iter().find(|&agent_id| {
+                self.has_room(agent_id, item_id) && self.consumption(agent_id) + self.bid(agent_id, item_id) <= self.U(agent_id) * self.effective_budget(agent_id)
+            });
+            if let Some(agent_id) = recipient {
+                self.gamma[agent_id].push_back(item_id);
+                let bid = T::from_f64(self.bid(agent_id, item_id));
+                self.consumptions[agent_id] += bid;
+                self.emit_move(item_id, None, Some(agent_id));
+            }
+        }
+        self.num_item_moves += num_released;
+        num_released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::primal_dual::{MbaError, PrimalDual, VerifyError};
+
+    // maximum weight of a matching of agents to items by brute force
+    fn max_matching(bids: &[Vec<f64>], agent_id: usize, taken: &mut Vec<bool>) -> f64 {
+        if agent_id == bids.len() {
+            return 0.0;
+        }
+        let mut best = max_matching(bids, agent_id + 1, taken);
+        for item_id in 0..taken.len() {
+            if !taken[item_id] && bids[agent_id][item_id] > 0.0 {
+                taken[item_id] = true;
+                best = best.max(bids[agent_id][item_id] + max_matching(bids, agent_id + 1, taken));
+                taken[item_id] = false;
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn one_item_each() {
+        for seed in 0..20u64 {
+            let num_agents = 4;
+            let num_items = 6;
+            let bids: Vec<Vec<f64>> = (0..num_agents)
+                .map(|agent_id| {
+                    (0..num_items)
+                        .map(|item_id| ((agent_id as u64 * 7 + item_id as u64 * 13 + seed * 5) % 10) as f64)
+                        .collect()
+                })
+                .collect();
+            let mut solver = PrimalDual::new(num_agents, num_items, 0.01);
+            for (agent_id, row) in bids.iter().enumerate() {
+                solver.set_budget(agent_id, 1000.0);
+                solver.set_max_items(agent_id, 1);
+                for (item_id, &bid) in row.iter().enumerate() {
+                    if bid > 0.0 {
+                        solver.set_bid(agent_id, item_id, bid);
+                    }
+                }
+            }
+            solver.solve();
+
+            solver.verify_solution().unwrap();
+            assert!(solver.get_assignment().iter().all(|item_ids| item_ids.len() <= 1));
+            let optimum = max_matching(&bids, 0, &mut vec![false; num_items]);
+            let primal = solver.get_primal_objective_value();
+            assert!(primal <= optimum + 1e-9 && primal >= optimum / 2.0, "{} {}", primal, optimum);
+        }
+    }
+
+    #[test]
+    fn released_to_the_next_price() {
+        let mut solver = PrimalDual::new(3, 3, 0.01);
+        for agent_id in 0..3 {
+            solver.set_budget(agent_id, 100.0);
+        }
+        solver.set_bids([(0, 0, 10.0), (0, 1, 9.0), (0, 2, 8.0), (1, 1, 5.0), (2, 1, 4.0), (2, 2, 1.0)]);
+        solver.set_max_items(0, 1);
+        solver.set_max_items(1, 0);
+        assert_eq!(solver.get_max_items(2), None);
+        solver.solve();
+
+        // agent 0 keeps its largest bid; agent 1 outprices agent 2 on item 1 but has no room
+        assert_eq!(solver.get_item_assignment(), vec![Some(0), Some(2), Some(2)]);
+        assert_eq!(solver.get_primal_objective_value(), 15.0);
+        assert_eq!(solver.try_set_max_items(3, 1), Err(MbaError::AgentIndexOutOfRange { agent_id: 3, num_agents: 3 }));
+
+        solver.gamma[1].push_back(1);
+        solver.gamma[2].retain(|&item_id| item_id != 1);
+        solver.consumptions[1] = 5.0;
+        solver.consumptions[2] = 1.0;
+        assert_eq!(
+            solver.verify_solution(),
+            Err(VerifyError::TooManyItems {
+                agent_id: 1,
+                num_items: 1,
+                max_items: 0
+            })
+        );
+    }
+
+    #[test]
+    fn rejected_by_exact() {
+        // exact::solve ignores caps: it gives both items to agent 0, and a cap of 1 applied afterwards leaves 5 where 5 + 4 is optimal
+        let mut solver = PrimalDual::new(2, 2, 0.0);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 100.0);
+        solver.set_bids([(0, 0, 5.0), (0, 1, 5.0), (1, 0, 4.0)]);
+        let e = solver.try_set_max_items(0, 1).unwrap_err();
+        assert_eq!(e, MbaError::UnsupportedByExact { setting: "max_items" });
+        assert_eq!(e.code(), ErrorCode::UnsupportedByExact);
+
+        solver.set_epsilon(0.01);
+        solver.set_max_items(0, 1);
+        assert_eq!(solver.try_set_epsilon(0.0), Err(MbaError::UnsupportedByExact { setting: "max_items" }));
+        solver.clear_max_items(0);
+        solver.set_epsilon(0.0);
+        solver.solve();
+        assert_eq!(solver.get_primal_objective_value(), 10.0);
+    }
+}
@@ -0,0 +1,152 @@
+use super::{PrimalDual, Scalar};
+
+// a change made by a solve, for watching the primal-dual dynamics. replaying the ItemReassigned events of a solver in
+// order from an empty assignment gives its assignment
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveEvent {
+    // from None: the item was unassigned; to None: it is dropped. price is that of to, 0 when to is None
+    ItemReassigned {
+        item: usize,
+        from: Option<usize>,
+        to: Option<usize>,
+        price: f64,
+    },
+    AlphaUpdated {
+        agent: usize,
+        old: f64,
+        new: f64,
+    },
+    // after a round of reallocation that left the agent paid for
+    AgentPaidFor {
+        agent: usize,
+    },
+}
+
+type Callback = Box<dyn FnMut(SolveEvent) + Send + Sync>;
+
+// a clone of the solver starts without an observer, since a callback cannot be cloned
+#[derive(Default)]
+pub(super) struct Observer(Option<Callback>);
+
+impl Clone for Observer {
+    fn clone(&self) -> Self {
+        Observer(None)
+    }
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // observer is called with every SolveEvent of solve and its variants, step, local search and the item caps,
+    // including the initial placement of the items, and the release of every item when a solve starts from scratch
+    pub fn set_observer(&mut self, observer: Box<dyn FnMut(SolveEvent) + Send + Sync>) {
+        self.observer = Observer(Some(observer));
+    }
+
+    pub fn clear_observer(&mut self) {
+        self.observer = Observer(None);
+    }
+
+    pub fn has_observer(&self) -> bool {
+        self.observer.0.is_some()
+    }
+
+    // event is only built when an observer is set
+    #[inline]
+    pub(super) fn emit<F: FnOnce(&Self) -> SolveEvent>(&mut self, event: F) {
+        if let Some(mut observer) = self.observer.0.take() {
+            observer(event(self));
+            self.observer.0 = Some(observer);
+        }
+    }
+
+    // an event per held item, before gamma is cleared
+    pub(super) fn emit_release_all(&mut self) {
+        if !self.has_observer() {
+            return;
+        }
+        for agent_id in 0..self.num_agents {
+            for i in 0..self.gamma[agent_id].len() {
+                let item_id = self.gamma[agent_id][i];
+                self.emit_move(item_id, Some(agent_id), None);
+            }
+        }
+    }
+
+    pub(super) fn emit_move(&mut self, item: usize, from: Option<usize>, to: Option<usize>) {
+        self.emit(|solver| SolveEvent::ItemReassigned {
+            item,
+            from,
+            to,
+            price: to.map_or(0.0, |agent_id| solver.price(agent_id, item)),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SolveEvent;
+    use crate::generator::generate;
+    use crate::primal_dual::{LocalSearch, PrimalDual};
+    use std::sync::{Arc, Mutex};
+
+    fn recorded(solver: &mut PrimalDual) -> Arc<Mutex<Vec<SolveEvent>>> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        solver.set_observer(Box::new(move |event| sink.lock().unwrap().push(event)));
+        events
+    }
+
+    // the owner of every item after the ItemReassigned events, checking that each starts where the previous one ended
+    fn replay(events: &[SolveEvent], num_items: usize) -> Vec<Option<usize>> {
+        let mut owners = vec![None; num_items];
+        for event in events {
+            if let SolveEvent::ItemReassigned { item, from, to, .. } = *event {
+                assert_eq!(owners[item], from, "{:?}", event);
+                owners[item] = to;
+            }
+        }
+        owners
+    }
+
+    #[test]
+    fn replayed() {
+        let instance = generate(10, 120, 0.4, (10.0, 40.0), (0.5, 10.0), 305);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        let events = recorded(&mut solver);
+        solver.solve();
+
+        let events = events.lock().unwrap();
+        assert_eq!(replay(&events, instance.num_items), solver.get_item_assignment());
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, SolveEvent::ItemReassigned { from: Some(_), to: Some(_), .. })));
+        assert!(events.iter().any(|event| matches!(event, SolveEvent::AgentPaidFor { .. })));
+        let mut alphas = vec![0.0; instance.num_agents];
+        for event in events.iter() {
+            if let SolveEvent::AlphaUpdated { agent, old, new } = *event {
+                assert_eq!(alphas[agent], old);
+                assert!(new > old);
+                alphas[agent] = new;
+            }
+        }
+        assert_eq!(alphas, (0..instance.num_agents).map(|agent_id| solver.get_alpha(agent_id)).collect::<Vec<f64>>());
+
+        // a clone has no observer
+        assert!(!solver.clone().has_observer());
+    }
+
+    #[test]
+    fn restarted_and_capped() {
+        let instance = generate(6, 60, 0.5, (10.0, 30.0), (0.5, 10.0), 306);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        let events = recorded(&mut solver);
+        solver.solve();
+        solver.set_budget(0, 3.0);
+        solver.set_max_items(1, 2);
+        solver.solve();
+        solver.set_bid(2, 5, 1.0);
+        solver.solve_warm();
+        solver.post_optimize(LocalSearch::FillAndMoves, 10);
+
+        assert_eq!(replay(&events.lock().unwrap(), instance.num_items), solver.get_item_assignment());
+    }
+}
@@ -0,0 +1,414 @@
+use super::{check_permutation, AgentOrder, LocalSearch, LocalSearchReport, MbaError, PrimalDual, Scalar, SolveStats, SolveStatus, Stop};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// options of solve_with, built from the default, which solves exactly like solve:
+//
+//   let options = SolveOptions::new().time_limit(Duration::from_secs(1)).collect_stats(true);
+//   let result = solver.solve_with(&options);
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolveOptions<'a> {
+    normalize: bool,
+    time_limit: Option<Duration>,
+    max_alpha_updates: Option<usize>, // checked between two rounds, so a round that raises alpha repeatedly can overshoot it
+    cancel: Option<&'a AtomicBool>,
+    agent_order: Option<AgentOrder>, // None keeps the order of set_agent_order
+    agent_permutation: Option<&'a [usize]>,
+    local_search: Option<(LocalSearch, usize)>,
+    collect_stats: bool,
+}
+
+// what solve_with did
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolveResult {
+    pub status: SolveStatus,
+    pub stats: Option<SolveStats>, // Some with collect_stats
+    pub local_search: Option<LocalSearchReport>,
+    pub primal: f64, // primal objective after the local search
+}
+
+impl<'a> SolveOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // divide budgets and bids by the largest budget for the solve and restore them afterwards, see normalized
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    pub fn time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    // alpha updates of this call, on top of those of earlier calls that the solve continues from
+    pub fn max_alpha_updates(mut self, max_alpha_updates: usize) -> Self {
+        self.max_alpha_updates = Some(max_alpha_updates);
+        self
+    }
+
+    // read before every round of reallocation, see solve_cancellable
+    pub fn cancel(mut self, cancel: &'a AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    // order of the sweeps of this call; the solver keeps its own order for later calls
+    pub fn agent_order(mut self, agent_order: AgentOrder) -> Self {
+        self.agent_order = Some(agent_order);
+        self
+    }
+
+    // AgentOrder::Custom with permutation for this call, see set_agent_permutation
+    pub fn agent_permutation(mut self, permutation: &'a [usize]) -> Self {
+        self.agent_permutation = Some(permutation);
+        self
+    }
+
+    // post_optimize(strategy, max_iterations) after the solve, also after a solve stopped early
+    pub fn local_search(mut self, strategy: LocalSearch, max_iterations: usize) -> Self {
+        self.local_search = Some((strategy, max_iterations));
+        self
+    }
+
+    // stats cost a copy of the update counters per call, so they are off by default
+    pub fn collect_stats(mut self, collect_stats: bool) -> Self {
+        self.collect_stats = collect_stats;
+        self
+    }
+
+    fn stops_early(&self) -> bool {
+        self.time_limit.is_some() || self.max_alpha_updates.is_some() || self.cancel.is_some()
+    }
+
+    // epsilon 0 solves by enumeration, which has no rounds to stop between and no sweeps to order
+    fn validate(&self, epsilon: f64, num_agents: usize) -> Result<(), MbaError> {
+        if epsilon == 0.0 && self.stops_early() {
+            return Err(MbaError::ConflictingOptions {
+                reason: "the exact solver of epsilon 0 cannot stop early",
+            });
+        }
+        if self.agent_order.is_some() && self.agent_permutation.is_some() {
+            return Err(MbaError::ConflictingOptions {
+                reason: "an agent order and an agent permutation were both given",
+            });
+        }
+        if let Some(permutation) = self.agent_permutation {
+            check_permutation(permutation, num_agents)?;
+        }
+        if epsilon == 0.0 && (self.agent_order.is_some() || self.agent_permutation.is_some()) {
+            return Err(MbaError::ConflictingOptions {
+                reason: "the exact solver of epsilon 0 has no agent order",
+            });
+        }
+        if self.local_search.is_some_and(|(_, max_iterations)| max_iterations == 0) {
+            return Err(MbaError::ConflictingOptions {
+                reason: "local search needs at least one iteration",
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_solve_with fails
+    pub fn solve_with(&mut self, options: &SolveOptions<'_>) -> SolveResult {
+        self.try_solve_with(options).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // solve under options, which fails before anything is solved when they conflict. a solve stopped early is left as
+    // solve_with_limit leaves it, and the next solve continues from there
+    pub fn try_solve_with(&mut self, options: &SolveOptions<'_>) -> Result<SolveResult, MbaError> {
+        options.validate(self.epsilon, self.num_agents)?;
+
+        let start = Instant::now();
+        let deadline = options.time_limit.map(|time| start + time);
+        let agent_order = self.agent_order;
+        let mut agent_permutation = None; // that of the solver while options replace it
+        if let Some(permutation) = options.agent_permutation {
+            if agent_order != AgentOrder::Custom || self.agent_permutation != permutation {
+                agent_permutation = Some(std::mem::replace(&mut self.agent_permutation, permutation.to_vec()));
+                self.set_agent_order(AgentOrder::Custom);
+            }
+        } else if let Some(order) = options.agent_order.filter(|&order| order != agent_order) {
+            self.set_agent_order(order);
+        }
+
+        self.apply_unassignable_policy();
+        let (sweeps, done, counters) = self.normalized(options.normalize, |solver| {
+            solver.initialize();
+            // after initialize, which may reset the counters
+            let counters = options.collect_stats.then(|| solver.counters());
+            let stop = Stop {
+                deadline,
+                max_alpha_updates: options.max_alpha_updates.map(|num| solver.num_alpha_updates + num),
+                cancel: options.cancel,
+            };
+            let (sweeps, done) = solver.run_with(stop, |_, _| {});
+            (sweeps, done, counters)
+        });
+        self.agent_order = agent_order;
+        if let Some(permutation) = agent_permutation {
+            self.agent_permutation = permutation;
+        }
+
+        let stats = counters.map(|counters| self.stats_since(counters, sweeps, start));
+        let local_search = options.local_search.map(|(strategy, max_iterations)| self.post_optimize(strategy, max_iterations));
+        let status = match options.cancel {
+            _ if done => SolveStatus::WithinGuarantee,
+            Some(cancel) if cancel.load(Ordering::Relaxed) => SolveStatus::Cancelled,
+            _ => SolveStatus::LimitReached,
+        };
+        Ok(SolveResult {
+            status,
+            stats,
+            local_search,
+            primal: self.get_primal_objective_value(),
+        })
+    }
+
+    // solve on budgets, bids, carried spend and switch penalty divided by the largest budget, which keeps prices and the
+    // paid-for check near 1 when the instance lives around 1e9 or 1e-9. the assignment and alphas are those of solving
+    // the divided instance; budgets and bids are then restored bit for bit and consumptions recomputed from them, so
+    // every getter and objective reports the original units. observer events carry prices of the divided instance
+    fn normalized<R, F: FnOnce(&mut Self) -> R>(&mut self, normalize: bool, solve: F) -> R {
+        if !normalize {
+            return solve(self);
+        }
+        let scale = self.budgets.iter().fold(0.0f64, |max_budget, &budget| max_budget.max(budget.as_f64()));
+        if !(scale > 0.0 && scale.is_finite()) {
+            return solve(self);
+        }
+
+        let budgets = self.budgets.clone();
+        let bids = self.bid.clone();
+        let carried_spend = self.carried_spend.clone();
+        let switch_penalty = self.switch_penalty;
+
+        self.budgets.iter_mut().for_each(|budget| *budget = T::from_f64(budget.as_f64() / scale));
+        for bid in self.bid.iter_mut().flat_map(|bids| bids.values_mut()) {
+            *bid = T::from_f64(bid.as_f64() / scale);
+        }
+        self.carried_spend.iter_mut().for_each(|spend| *spend /= scale);
+        self.switch_penalty /= scale;
+        self.rescaled();
+
+        let result = solve(self);
+
+        self.budgets = budgets;
+        self.bid = bids;
+        self.carried_spend = carried_spend;
+        self.switch_penalty = switch_penalty;
+        self.rescaled();
+        result
+    }
+
+    // consumptions and heap prices after budgets and bids changed units
+    fn rescaled(&mut self) {
+        for agent_id in 0..self.num_agents {
+            self.recompute_consumption(agent_id);
+        }
+        self.rebuild_bid_index();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SolveOptions, SolveResult};
+    use crate::error_code::ErrorCode;
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::primal_dual::{AgentOrder, LocalSearch, MbaError, PrimalDual, SolveStatus};
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
+
+    // budgets around 1e9 and a long tail of bids around 1e-3 next to bids around 1e8
+    fn pathological() -> Instance {
+        let mut instance = generate(8, 80, 0.5, (1.0, 4.0), (0.5, 3.0), 306);
+        for budget in &mut instance.budgets {
+            *budget *= 1e9;
+        }
+        for (i, (_, item_id, bid)) in instance.bids.iter_mut().enumerate() {
+            *bid *= if *item_id % 3 == 0 { 1e-3 } else { 1e8 } * if i % 2 == 0 { 1.0 } else { 1.7 };
+        }
+        instance
+    }
+
+    fn generated_solver(epsilon: f64) -> PrimalDual {
+        PrimalDual::from_instance(&generate(15, 150, 0.4, (10.0, 40.0), (0.5, 8.0), 307), epsilon)
+    }
+
+    #[test]
+    fn normalize_same_as_prescaled() {
+        let instance = pathological();
+        let scale = instance.budgets.iter().fold(0.0f64, |max_budget, &budget| max_budget.max(budget));
+        let mut prescaled = instance.clone();
+        prescaled.budgets.iter_mut().for_each(|budget| *budget /= scale);
+        prescaled.bids.iter_mut().for_each(|(_, _, bid)| *bid /= scale);
+
+        let normalize = SolveOptions::new().normalize(true);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        solver.solve_with(&normalize);
+        let mut expected = PrimalDual::from_instance(&prescaled, 0.01);
+        expected.solve();
+
+        assert_eq!(solver.get_assignment(), expected.get_assignment());
+        for agent_id in 0..instance.num_agents {
+            assert_eq!(solver.get_alpha(agent_id), expected.get_alpha(agent_id));
+            assert_eq!(solver.get_budget(agent_id), instance.budgets[agent_id]);
+        }
+        for &(agent_id, item_id, bid) in &instance.bids {
+            assert_eq!(solver.get_bid(agent_id, item_id), bid);
+        }
+        let primal = solver.get_primal_objective_value();
+        assert!((primal - expected.get_primal_objective_value() * scale).abs() <= 1e-9 * primal);
+        assert!(primal >= solver.get_approximation_ratio() * solver.get_dual_objective_value() * (1.0 - 1e-9));
+        assert!(solver.extract_solution().verify().is_ok());
+
+        // the bids of instance, in any order, normalize to the same assignment
+        let mut reversed = instance.clone();
+        reversed.bids.reverse();
+        let mut reversed_solver = PrimalDual::from_instance(&reversed, 0.01);
+        reversed_solver.solve_with(&normalize);
+        assert_eq!(reversed_solver.get_assignment(), solver.get_assignment());
+
+        // a repeated normalized solve keeps its result, and warm solves continue in the original units
+        let solution = solver.extract_solution();
+        solver.solve_with(&normalize);
+        assert_eq!(solver.extract_solution(), solution);
+        solver.set_bid(0, 1, 2.0);
+        solver.solve_warm();
+        assert!(solver.extract_solution().verify().is_ok());
+
+        // nothing to scale by
+        let mut empty = PrimalDual::new(2, 2, 0.01);
+        assert_eq!(empty.solve_with(&normalize).primal, 0.0);
+    }
+
+    #[test]
+    fn default_is_solve() {
+        let mut solver = generated_solver(0.01);
+        let result = solver.solve_with(&SolveOptions::default());
+        let mut expected = generated_solver(0.01);
+        expected.solve();
+        assert_eq!(solver.extract_solution(), expected.extract_solution());
+        assert_eq!(
+            result,
+            SolveResult {
+                status: SolveStatus::WithinGuarantee,
+                stats: None,
+                local_search: None,
+                primal: expected.get_primal_objective_value(),
+            }
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut solver = generated_solver(0.001);
+        let result = solver.solve_with(&SolveOptions::new().max_alpha_updates(5));
+        assert_eq!(result.status, SolveStatus::LimitReached);
+        assert!(solver.extract_solution().verify().is_ok());
+
+        let mut solver = generated_solver(0.001);
+        let result = solver.solve_with(&SolveOptions::new().time_limit(Duration::ZERO));
+        assert_eq!(result.status, SolveStatus::LimitReached);
+
+        let cancel = AtomicBool::new(true);
+        let mut solver = generated_solver(0.001);
+        let result = solver.solve_with(&SolveOptions::new().cancel(&cancel).time_limit(Duration::from_secs(60)));
+        assert_eq!(result.status, SolveStatus::Cancelled);
+        // continues to the result of an unlimited solve
+        let result = solver.solve_with(&SolveOptions::new().time_limit(Duration::from_secs(60)));
+        assert_eq!(result.status, SolveStatus::WithinGuarantee);
+        let mut expected = generated_solver(0.001);
+        expected.solve();
+        assert_eq!(solver.extract_solution(), expected.extract_solution());
+    }
+
+    #[test]
+    fn agent_order() {
+        let options = SolveOptions::new().agent_order(AgentOrder::MostBidsFirst);
+        let mut solver = generated_solver(0.01);
+        solver.solve_with(&options);
+        let mut expected = generated_solver(0.01);
+        expected.set_agent_order(AgentOrder::MostBidsFirst);
+        expected.solve();
+        assert_eq!(solver.extract_solution(), expected.extract_solution());
+        // for this call only
+        assert_eq!(solver.get_config().agent_order, AgentOrder::IndexOrder);
+    }
+
+    #[test]
+    fn stats_and_local_search() {
+        let mut solver = generated_solver(0.01);
+        let options = SolveOptions::new().collect_stats(true).local_search(LocalSearch::FillAndMoves, 10);
+        let result = solver.solve_with(&options);
+
+        let mut expected = generated_solver(0.01);
+        let stats = expected.solve_with_stats();
+        let report = expected.post_optimize(LocalSearch::FillAndMoves, 10);
+        let result_stats = result.stats.unwrap();
+        assert_eq!((result_stats.sweeps, &result_stats.alpha_updates), (stats.sweeps, &stats.alpha_updates));
+        assert_eq!(result_stats.item_moves, stats.item_moves);
+        assert_eq!(result.local_search, Some(report));
+        assert_eq!(result.primal, report.after);
+        assert_eq!(result.primal, solver.get_primal_objective_value());
+    }
+
+    #[test]
+    fn combined() {
+        let instance = pathological();
+        let cancel = AtomicBool::new(false);
+        let options = SolveOptions::new()
+            .normalize(true)
+            .agent_order(AgentOrder::LargestOverspendFirst)
+            .cancel(&cancel)
+            .time_limit(Duration::from_secs(60))
+            .collect_stats(true)
+            .local_search(LocalSearch::Fill, 5);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        let result = solver.solve_with(&options);
+        assert_eq!(result.status, SolveStatus::WithinGuarantee);
+        assert!(result.stats.unwrap().sweeps > 0);
+        assert!(result.local_search.unwrap().improvement() >= 0.0);
+        assert!(solver.extract_solution().verify().is_ok());
+
+        let limited = SolveOptions::new().normalize(true).max_alpha_updates(3).collect_stats(true);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        let result = solver.solve_with(&limited);
+        if result.status == SolveStatus::LimitReached {
+            assert!(result.stats.unwrap().alpha_updates.iter().sum::<usize>() >= 3);
+        }
+        assert_eq!(solver.get_budget(0), instance.budgets[0]);
+        assert!(solver.extract_solution().verify().is_ok());
+    }
+
+    #[test]
+    fn conflicts() {
+        let cancel = AtomicBool::new(false);
+        let mut exact = PrimalDual::new(2, 3, 0.0);
+        for options in [
+            SolveOptions::new().time_limit(Duration::from_secs(1)),
+            SolveOptions::new().max_alpha_updates(10),
+            SolveOptions::new().cancel(&cancel),
+            SolveOptions::new().agent_order(AgentOrder::MostBidsFirst),
+        ] {
+            let e = exact.try_solve_with(&options).unwrap_err();
+            assert_eq!(e.code(), ErrorCode::ConflictingOptions);
+        }
+        assert!(exact.try_solve_with(&SolveOptions::new().normalize(true).collect_stats(true)).is_ok());
+
+        let mut solver = generated_solver(0.01);
+        assert_eq!(
+            solver.try_solve_with(&SolveOptions::new().local_search(LocalSearch::Fill, 0)),
+            Err(MbaError::ConflictingOptions {
+                reason: "local search needs at least one iteration"
+            })
+        );
+        // nothing was solved
+        assert_eq!(solver.get_primal_objective_value(), 0.0);
+    }
+}
@@ -0,0 +1,91 @@
+use super::{PrimalDual, Scalar};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// the passes over items whose items are independent of each other, run on the rayon global pool with the rayon feature.
+// every item gets the owner the sequential pass would give it, so the solve does not depend on the number of threads
+#[cfg(feature = "rayon")]
+pub(super) const MIN_ITEMS: usize = 1 << 12;
+// items per rayon task
+#[cfg(feature = "rayon")]
+const CHUNK_LEN: usize = 1 << 8;
+
+impl<T: Scalar> PrimalDual<T> {
+    // owners[item_id] = initial_owner(item_id) for every item
+    pub(super) fn initial_owners(&self, owners: &mut Vec<Option<usize>>) {
+        owners.clear();
+        #[cfg(feature = "rayon")]
+        if self.num_items >= self.parallel_min_items {
+            (0..self.num_items)
+                .into_par_iter()
+                .with_min_len(CHUNK_LEN)
+                .map(|item_id| self.initial_owner(item_id))
+                .collect_into_vec(owners);
+            return;
+        }
+        owners.extend((0..self.num_items).map(|item_id| self.initial_owner(item_id)));
+    }
+
+    // refreshes the stale heap entries of all items agent_id holds at once and sets owners[i] to the agent of maximum price
+    // for gamma[agent_id][i], None for items of capacity above 1. false, leaving owners alone, when agent_id holds too few
+    // items to be worth it or without the rayon feature
+    #[cfg(feature = "rayon")]
+    pub(super) fn prefetch_max_price_agents(&mut self, agent_id: usize, owners: &mut Vec<Option<usize>>) -> bool {
+        if self.gamma[agent_id].len() < self.parallel_min_items {
+            return false;
+        }
+
+        // the heaps leave item_agent, so that they are refreshed in parallel while the prices are read from self
+        let (gamma, item_agent) = (&self.gamma, &mut self.item_agent);
+        let mut heaps: Vec<_> = gamma[agent_id].iter().map(|&item_id| (item_id, std::mem::take(&mut item_agent[item_id]))).collect();
+        let solver = &*self;
+        heaps
+            .par_iter_mut()
+            .with_min_len(CHUNK_LEN)
+            .map(|(item_id, heap)| {
+                if solver.item_capacity[*item_id] > 1 {
+                    None
+                } else {
+                    solver.refresh_top(*item_id, heap)
+                }
+            })
+            .collect_into_vec(owners);
+        for (item_id, heap) in heaps {
+            self.item_agent[item_id] = heap;
+        }
+        true
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    pub(super) fn prefetch_max_price_agents(&mut self, _agent_id: usize, _owners: &mut Vec<Option<usize>>) -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use crate::generator::{generate_with, GeneratorConfig};
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn same_as_sequential() {
+        for seed in 0..4 {
+            // every agent holds hundreds of items, with items of equal bids to exercise the tie-breaking
+            let config = GeneratorConfig {
+                max_beta: Some(0.05),
+                ..GeneratorConfig::new(12, 3000, 0.3, (200.0, 2000.0), (1.0, 5.0), seed)
+            };
+            let instance = generate_with(&config);
+
+            let mut solutions = Vec::new();
+            for parallel_min_items in [usize::MAX, 16] {
+                let mut solver = PrimalDual::from_instance(&instance, 0.01);
+                solver.parallel_min_items = parallel_min_items;
+                solver.solve();
+                solver.verify_solution().unwrap();
+                solutions.push((solver.extract_solution(), solver.get_num_reallocations()));
+            }
+            assert_eq!(solutions[0], solutions[1], "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use super::{PrimalDual, Scalar};
+
+// one round of an unpaid agent, in three phases
+//   1. classify: find the agent of maximum price for every item the agent holds, see capacity for items held by several agents
+//      and agent_capacity for agents without room
+//   2. transfer: hand the items with another maximum-price agent over, in order, until the agent is paid for;
+//      an item without any valid bidder left is dropped
+//   3. raise alpha: if the agent is still unpaid, or drop items once alpha is at its cap
+//
+// alphas only change in phase 3, so classifying all items up front sees the same prices as classifying them one by one
+pub(super) struct Classification {
+    pub(super) owners: Vec<Option<usize>>, // owners[i] = agent of maximum price for gamma[agent_id][i], None if nobody bids on it
+    pub(super) all_unique: bool,           // every item is retained and the agent is its only bidder
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    pub(super) fn reallocate(&mut self, agent_id: usize) {
+        let classification = self.classify(agent_id);
+        let scan_completed = self.apply_transfers(agent_id, &classification.owners);
+        self.raise_alpha(agent_id, scan_completed && classification.all_unique);
+        self.owners_scratch = classification.owners;
+        self.num_reallocations += 1;
+    }
+
+    // owners reuses owners_scratch, so once it has grown to the largest gamma no round allocates
+    pub(super) fn classify(&mut self, agent_id: usize) -> Classification {
+        let mut owners = std::mem::take(&mut self.owners_scratch);
+        owners.clear();
+        let prefetched = self.prefetch_max_price_agents(agent_id, &mut owners);
+        let mut all_unique = true;
+        for i in 0..self.gamma[agent_id].len() {
+            let item_id = self.gamma[agent_id][i];
+            let max_agent_id = if self.item_capacity[item_id] > 1 {
+                self.capacity_owner(agent_id, item_id)
+            } else if prefetched {
+                self.owner_with_room(Some(agent_id), item_id, owners[i])
+            } else {
+                let owner = self.max_price_agent(item_id);
+                self.owner_with_room(Some(agent_id), item_id, owner)
+            };
+            all_unique &= max_agent_id == Some(agent_id) && self.item_agent[item_id].len() == 1;
+            if prefetched {
+                owners[i] = max_agent_id;
+            } else {
+                owners.push(max_agent_id);
+            }
+        }
+        Classification { owners, all_unique }
+    }
+
+    // returns false if the agent became paid for before every stolen item was handed over.
+    // the consumption of agent_id is recomputed once the pass stops
+    // retained items move to the back of gamma, items after the stopping point stay in front in their order
+    pub(super) fn apply_transfers(&mut self, agent_id: usize, owners: &[Option<usize>]) -> bool {
+        for &max_agent_id in owners {
+            let item_id = self.gamma[agent_id].pop_front().unwrap();
+            // an earlier transfer of the round may have filled max_agent_id up
+            let max_agent_id = self.owner_with_room(Some(agent_id), item_id, max_agent_id);
+
+            // item_id is rightly allocated
+            if max_agent_id == Some(agent_id) {
+                self.gamma[agent_id].push_back(item_id);
+                continue;
+            }
+
+            // erase item_id from agent_id
+            self.num_item_moves += 1;
+            let bid = T::from_f64(self.bid(agent_id, item_id));
+            self.consumptions[agent_id] -= bid;
+
+            // insert item_id to max_agent_id
+            if let Some(max_agent_id) = max_agent_id {
+                self.gamma[max_agent_id].push_back(item_id);
+                let bid = T::from_f64(self.bid(max_agent_id, item_id));
+                self.consumptions[max_agent_id] += bid;
+            }
+            self.emit_move(item_id, Some(agent_id), max_agent_id);
+
+            if self.is_paid_for(agent_id) {
+                self.recompute_consumption(agent_id);
+                return false;
+            }
+        }
+        self.recompute_consumption(agent_id);
+        true
+    }
+
+    // when nobody else bids on the agent's items, no transfer can help, so alpha rises until the agent is paid for.
+    // alpha at max_alpha cannot rise any more, so the agent drops items instead
+    pub(super) fn raise_alpha(&mut self, agent_id: usize, all_unique: bool) {
+        if all_unique {
+            self.num_unique_raises += 1;
+            for _ in 0..self.num_items {
+                if self.is_paid_for(agent_id) || self.alpha(agent_id) >= self.max_alpha() {
+                    break;
+                }
+                self.update_alpha(agent_id);
+            }
+        }
+
+        if self.is_paid_for(agent_id) {
+            return;
+        }
+        if self.alpha(agent_id) >= self.max_alpha() {
+            self.force_drop(agent_id);
+        } else {
+            self.update_alpha(agent_id);
+        }
+    }
+
+    // items leave the front of gamma unassigned until the agent is paid for; an agent left without items is paid for exactly
+    pub(super) fn force_drop(&mut self, agent_id: usize) {
+        while !self.is_paid_for(agent_id) {
+            let Some(item_id) = self.gamma[agent_id].pop_front() else {
+                break;
+            };
+            let bid = T::from_f64(self.bid(agent_id, item_id));
+            self.consumptions[agent_id] -= bid;
+            self.num_item_moves += 1;
+            self.num_forced_drops += 1;
+            self.emit_move(item_id, Some(agent_id), None);
+        }
+        self.recompute_consumption(agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::PrimalDual;
+
+    // agent 0 holds items 0, 1, 2 after initialization; agent 1 also bids on items 1 and 2
+    fn initialized_solver() -> PrimalDual {
+        let mut solver = PrimalDual::new(2, 3, 0.1);
+        solver.set_budget(0, 100.0);
+        solver.set_budget(1, 100.0);
+        solver.set_bid(0, 0, 60.0);
+        solver.set_bid(0, 1, 50.0);
+        solver.set_bid(0, 2, 50.0);
+        solver.set_bid(1, 1, 49.0);
+        solver.set_bid(1, 2, 48.0);
+        solver.initialize();
+        solver
+    }
+
+    #[test]
+    fn classify() {
+        let mut solver = initialized_solver();
+        let classification = solver.classify(0);
+        assert_eq!(classification.owners, vec![Some(0), Some(0), Some(0)]);
+        assert!(!classification.all_unique);
+
+        solver.update_alpha(0);
+        let classification = solver.classify(0);
+        assert_eq!(classification.owners, vec![Some(0), Some(1), Some(1)]);
+        assert!(!classification.all_unique);
+    }
+
+    #[test]
+    fn apply_transfers_stops_when_paid() {
+        let mut solver = initialized_solver();
+        solver.update_alpha(0);
+
+        // moving item 1 brings agent 0 from 160 down to 110, within U * budget
+        assert!(!solver.apply_transfers(0, &[Some(0), Some(1), Some(1)]));
+        assert_eq!(solver.gamma[0], [2, 0]);
+        assert_eq!(solver.gamma[1], [1]);
+        assert_eq!(solver.consumptions[0], 110.0);
+        assert_eq!(solver.consumptions[1], 49.0);
+    }
+
+    #[test]
+    fn apply_transfers_completes() {
+        let mut solver = initialized_solver();
+        assert!(solver.apply_transfers(0, &[Some(0), Some(0), Some(0)]));
+        assert_eq!(solver.gamma[0], [0, 1, 2]);
+        assert_eq!(solver.consumptions[0], 160.0);
+    }
+
+    #[test]
+    fn drops_items_without_bidders() {
+        let mut solver = initialized_solver();
+        solver.item_agent[1].clear();
+        let classification = solver.classify(0);
+        assert_eq!(classification.owners, vec![Some(0), None, Some(0)]);
+
+        // dropping item 1 brings agent 0 down to 110, within U * budget
+        assert!(!solver.apply_transfers(0, &classification.owners));
+        assert_eq!(solver.gamma[0], [2, 0]);
+        assert_eq!(solver.consumptions[0], 110.0);
+    }
+
+    #[test]
+    fn raise_alpha_for_unique_items() {
+        let mut solver = PrimalDual::new(1, 2, 0.1);
+        solver.set_budget(0, 10.0);
+        solver.set_bid(0, 0, 10.0);
+        solver.set_bid(0, 1, 10.0);
+        solver.initialize();
+
+        let classification = solver.classify(0);
+        assert!(classification.all_unique);
+        // alpha rises once per item held and once more
+        solver.raise_alpha(0, classification.all_unique);
+        assert_eq!(solver.num_update[0], 3);
+        assert!(!solver.is_paid_for(0));
+    }
+
+    #[test]
+    fn raise_alpha_once_otherwise() {
+        let mut solver = initialized_solver();
+        solver.raise_alpha(0, false);
+        assert_eq!(solver.alpha[0], 0.1);
+        assert_eq!(solver.num_update[0], 1);
+    }
+
+    #[test]
+    fn drops_items_at_max_alpha() {
+        let mut solver = PrimalDual::new(1, 3, 0.1);
+        solver.set_budget(0, 10.0);
+        for item_id in 0..3 {
+            solver.set_bid(0, item_id, 10.0);
+        }
+        solver.initialize();
+        solver.update_alpha(0);
+        solver.alpha[0] = solver.max_alpha();
+        // U is finite, though large, at max_alpha
+        assert!(solver.U(0).is_finite());
+
+        solver.consumptions[0] = solver.U(0) * 10.0 * 2.0;
+        solver.raise_alpha(0, false);
+        assert_eq!(solver.alpha[0], solver.max_alpha());
+        assert!(solver.is_paid_for(0));
+        assert!(solver.gamma[0].is_empty());
+        assert_eq!(solver.consumptions[0], 0.0);
+        assert_eq!(solver.num_forced_drops, 3);
+    }
+
+    #[test]
+    fn tiny_budgets_terminate() {
+        let mut solver = PrimalDual::new(1, 1, 0.01);
+        solver.set_budget(0, 1e-9);
+        solver.set_bid(0, 0, 1e-9 * 0.9);
+        let stats = solver.solve_with_stats();
+        assert_eq!(stats.forced_drops, 0);
+        assert_eq!(solver.get_primal_objective_value(), 1e-9 * 0.9);
+
+        // the items sum to 90000 budgets, which alpha covers without dropping any
+        let num_items = 100_000;
+        let mut solver = PrimalDual::new(1, num_items, 0.01);
+        solver.set_budget(0, 1e-9);
+        solver.set_bids((0..num_items).map(|item_id| (0, item_id, 1e-9 * 0.9)));
+        let stats = solver.solve_with_stats();
+        assert!(stats.reallocations <= 2, "{:?}", stats.reallocations);
+        assert_eq!(stats.forced_drops, 0);
+        solver.verify_solution().unwrap();
+        assert_eq!(solver.get_primal_objective_value(), 1e-9);
+    }
+}
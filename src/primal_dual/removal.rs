@@ -0,0 +1,192 @@
+use super::{MbaError, PrimalDual, Scalar};
+use std::cmp::Reverse;
+
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_remove_bid fails
+    pub fn remove_bid(&mut self, agent_id: usize, item_id: usize) {
+        self.try_remove_bid(agent_id, item_id).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // undoes every set_bid of agent_id on item_id, accepted, rejected or below the reserve price; a pair without a bid is left alone.
+    // only before the first solve, since the assignment may already hold the item
+    pub fn try_remove_bid(&mut self, agent_id: usize, item_id: usize) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if item_id >= self.num_items {
+            return Err(MbaError::ItemIndexOutOfRange {
+                item_id,
+                num_items: self.num_items,
+            });
+        }
+        if self.initialized {
+            return Err(MbaError::AlreadySolved);
+        }
+
+        self.rejected_bids.retain(|&(id, item, _)| (id, item) != (agent_id, item_id));
+        self.below_reserve_bids.retain(|&(id, item, _)| (id, item) != (agent_id, item_id));
+        self.original_bids[agent_id].retain(|&(item, _)| item != item_id);
+        self.drop_accepted_bid(agent_id, item_id);
+        Ok(())
+    }
+
+    // panics where try_clear_agent fails
+    pub fn clear_agent(&mut self, agent_id: usize) {
+        self.try_clear_agent(agent_id).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // removes every bid of agent_id; its budget stays
+    pub fn try_clear_agent(&mut self, agent_id: usize) -> Result<(), MbaError> {
+        self.check_agent(agent_id)?;
+        if self.initialized {
+            return Err(MbaError::AlreadySolved);
+        }
+
+        self.rejected_bids.retain(|&(id, _, _)| id != agent_id);
+        self.below_reserve_bids.retain(|&(id, _, _)| id != agent_id);
+        self.original_bids[agent_id].clear();
+        self.num_accepted_bids -= self.bid[agent_id].len();
+        self.bid[agent_id].clear();
+        for item_id in std::mem::take(&mut self.agent_items[agent_id]) {
+            self.item_agents[item_id].retain(|&id| id != agent_id);
+            self.unindex_bid(agent_id, item_id);
+        }
+        self.recompute_beta();
+        Ok(())
+    }
+
+    // forgets the accepted bid of agent_id on item_id, if there is one
+    pub(super) fn drop_accepted_bid(&mut self, agent_id: usize, item_id: usize) {
+        let Some(bid) = self.bid[agent_id].remove(&item_id) else {
+            return;
+        };
+        self.num_accepted_bids -= 1;
+        self.agent_items[agent_id].retain(|&item| item != item_id);
+        self.item_agents[item_id].retain(|&id| id != agent_id);
+        self.unindex_bid(agent_id, item_id);
+        if bid.as_f64() / self.budget(agent_id) >= self.beta {
+            self.recompute_beta();
+        }
+    }
+
+    // drops the heap entries of agent_id on item_id and picks the max bid agent among the remaining bidders
+    pub(super) fn unindex_bid(&mut self, agent_id: usize, item_id: usize) {
+        self.item_agent[item_id].retain(|&(_, Reverse(id), _)| id != agent_id);
+        self.max_bid_agent[item_id] = self.item_agents[item_id]
+            .iter()
+            .filter(|&&id| self.active[id])
+            .max_by(|&&a, &&b| self.bid(a, item_id).total_cmp(&self.bid(b, item_id)).then(b.cmp(&a)))
+            .copied();
+    }
+
+    // beta of the remaining accepted bids of the active agents, as set_bid would have grown it
+    pub(super) fn recompute_beta(&mut self) {
+        let (bid, budgets, active) = (&self.bid, &self.budgets, &self.active);
+        self.beta = (0..self.num_agents)
+            .filter(|&agent_id| active[agent_id])
+            .flat_map(|agent_id| bid[agent_id].values().map(move |&bid| (bid.as_f64() / budgets[agent_id].as_f64()).min(1.0)))
+            .fold(0.0, f64::max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::{MbaError, PrimalDual};
+
+    const NUM_AGENTS: usize = 8;
+    const NUM_ITEMS: usize = 60;
+
+    fn budget(agent_id: usize) -> f64 {
+        20.0 + (agent_id * 17 % 30) as f64
+    }
+
+    fn bids() -> Vec<(usize, usize, f64)> {
+        let mut bids = Vec::new();
+        for agent_id in 0..NUM_AGENTS {
+            for item_id in 0..NUM_ITEMS {
+                if (agent_id + item_id) % 3 != 0 {
+                    bids.push((agent_id, item_id, 1.0 + ((agent_id * 7 + item_id * 5) % 11) as f64));
+                }
+            }
+        }
+        bids
+    }
+
+    fn solver_with(bids: &[(usize, usize, f64)]) -> PrimalDual {
+        let mut solver = PrimalDual::new(NUM_AGENTS, NUM_ITEMS, 0.01);
+        for agent_id in 0..NUM_AGENTS {
+            solver.set_budget(agent_id, budget(agent_id));
+        }
+        for &(agent_id, item_id, bid) in bids {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver
+    }
+
+    #[test]
+    fn remove_bids() {
+        let bids = bids();
+        let removed = |&(agent_id, item_id, _): &(usize, usize, f64)| (agent_id * 3 + item_id) % 7 == 0;
+
+        let mut solver = solver_with(&bids);
+        // the largest bid relative to its budget, so beta has to shrink
+        solver.set_bid(0, 0, budget(0));
+        solver.remove_bid(0, 0);
+        for bid in bids.iter().filter(|bid| removed(bid)) {
+            solver.remove_bid(bid.0, bid.1);
+        }
+
+        let kept: Vec<_> = bids.iter().copied().filter(|bid| !removed(bid)).collect();
+        let mut fresh = solver_with(&kept);
+        assert_eq!(solver.beta, fresh.beta);
+        for item_id in 0..NUM_ITEMS {
+            assert_eq!(solver.get_max_bidder(item_id), fresh.get_max_bidder(item_id));
+        }
+
+        solver.solve();
+        fresh.solve();
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+    }
+
+    #[test]
+    fn clear_agent() {
+        let bids = bids();
+        let mut solver = solver_with(&bids);
+        // rejected, so get_inactive_agents would report it
+        solver.set_bid(3, 0, 1000.0);
+        solver.clear_agent(3);
+
+        let kept: Vec<_> = bids.iter().copied().filter(|&(agent_id, _, _)| agent_id != 3).collect();
+        let mut fresh = solver_with(&kept);
+        assert_eq!(solver.get_inactive_agents(), fresh.get_inactive_agents());
+
+        solver.solve();
+        fresh.solve();
+        assert_eq!(solver.extract_solution(), fresh.extract_solution());
+        assert!(solver.get_assignment()[3].is_empty());
+    }
+
+    #[test]
+    fn only_before_solve() {
+        let mut solver = solver_with(&bids());
+        assert_eq!(
+            solver.try_remove_bid(0, NUM_ITEMS),
+            Err(MbaError::ItemIndexOutOfRange {
+                item_id: NUM_ITEMS,
+                num_items: NUM_ITEMS
+            })
+        );
+        assert_eq!(
+            solver.try_clear_agent(NUM_AGENTS),
+            Err(MbaError::AgentIndexOutOfRange {
+                agent_id: NUM_AGENTS,
+                num_agents: NUM_AGENTS
+            })
+        );
+        // no bid on the pair
+        assert_eq!(solver.try_remove_bid(0, 0), Ok(()));
+
+        solver.solve();
+        assert_eq!(solver.try_remove_bid(0, 1), Err(MbaError::AlreadySolved));
+        assert_eq!(solver.try_clear_agent(0), Err(MbaError::AlreadySolved));
+        assert!(solver.get_bid(0, 1) > 0.0);
+    }
+}
@@ -0,0 +1,114 @@
+use super::{MbaError, PrimalDual, Scalar};
+
+// an item is only sold for at least its reserve price: a bid below it, after OverbidPolicy, is recorded but takes no part
+// in the solve, like a bid rejected by OverbidPolicy::Reject. a changed reserve price refilters the recorded bids
+impl<T: Scalar> PrimalDual<T> {
+    // panics where try_set_reserve_price fails
+    pub fn set_reserve_price(&mut self, item_id: usize, reserve_price: f64) {
+        self.try_set_reserve_price(item_id, reserve_price).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // reserve_price must be finite and at least 0, the default. accepted bids below it are disabled and disabled bids
+    // at least it are accepted again, both in the order they were set. only before the first solve, since the assignment
+    // may already hold the item
+    pub fn try_set_reserve_price(&mut self, item_id: usize, reserve_price: f64) -> Result<(), MbaError> {
+        if item_id >= self.num_items {
+            return Err(MbaError::ItemIndexOutOfRange {
+                item_id,
+                num_items: self.num_items,
+            });
+        }
+        if !(reserve_price >= 0.0 && reserve_price.is_finite()) {
+            return Err(MbaError::InvalidReservePrice { item_id, reserve_price });
+        }
+        if self.initialized {
+            return Err(MbaError::AlreadySolved);
+        }
+        self.reserve_price[item_id] = reserve_price;
+
+        let disabled: Vec<usize> = self.item_agents[item_id]
+            .iter()
+            .copied()
+            .filter(|&agent_id| self.bid(agent_id, item_id) < reserve_price)
+            .collect();
+        for agent_id in disabled {
+            let bid = self.bid(agent_id, item_id);
+            self.drop_accepted_bid(agent_id, item_id);
+            self.below_reserve_bids.push((agent_id, item_id, bid));
+        }
+
+        let readmitted: Vec<(usize, f64)> = self
+            .below_reserve_bids
+            .iter()
+            .filter(|&&(_, item, bid)| item == item_id && bid >= reserve_price)
+            .map(|&(agent_id, _, bid)| (agent_id, bid))
+            .collect();
+        self.below_reserve_bids.retain(|&(_, item, bid)| item != item_id || bid < reserve_price);
+        for (agent_id, bid) in readmitted {
+            self.accept_bid(agent_id, item_id, T::from_f64(bid), true)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_reserve_price(&self, item_id: usize) -> f64 {
+        self.reserve_price[item_id]
+    }
+
+    // (agent_id, item_id, bid) of the bids disabled by a reserve price, in the order they were disabled
+    pub fn get_below_reserve_bids(&self) -> &[(usize, usize, f64)] {
+        &self.below_reserve_bids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::{MbaError, PrimalDual, UnassignedReason};
+
+    #[test]
+    fn only_bid_below_reserve() {
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_reserve_price(1, 5.0);
+        solver.set_bid(0, 0, 3.0);
+        solver.set_bid(1, 1, 4.0);
+        assert_eq!(solver.num_bids(), 1);
+        assert_eq!(solver.get_max_bidder(1), None);
+        solver.solve();
+
+        assert_eq!(solver.get_item_assignment(), vec![Some(0), None]);
+        assert_eq!(solver.get_unassigned_items(), vec![(1, UnassignedReason::BelowReservePrice)]);
+        assert_eq!(solver.get_below_reserve_bids(), &[(1, 1, 4.0)]);
+        solver.verify_solution().unwrap();
+    }
+
+    #[test]
+    fn raised_after_set_bid() {
+        // agent 0 wins item 0 without a reserve price; at 6 only agent 1 is left
+        let mut solver = PrimalDual::new(2, 1, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bid(0, 0, 8.0);
+        solver.set_bid(1, 0, 6.0);
+        assert_eq!(solver.get_max_bidder(0), Some(0));
+        let mut raised = solver.clone();
+
+        solver.solve();
+        assert_eq!(solver.get_item_assignment(), vec![Some(0)]);
+
+        raised.set_reserve_price(0, 9.0);
+        assert_eq!(raised.get_max_bidder(0), None);
+        raised.set_reserve_price(0, 6.0);
+        assert_eq!(raised.get_max_bidder(0), Some(0));
+        raised.set_bid(0, 0, 5.0);
+        assert_eq!(raised.get_max_bidder(0), Some(1));
+        assert_eq!(raised.get_reserve_price(0), 6.0);
+        raised.solve();
+        assert_eq!(raised.get_item_assignment(), vec![Some(1)]);
+        assert_eq!(raised.get_primal_objective_value(), 6.0);
+
+        assert_eq!(raised.try_set_reserve_price(0, 1.0), Err(MbaError::AlreadySolved));
+        assert_eq!(raised.try_set_reserve_price(1, 1.0), Err(MbaError::ItemIndexOutOfRange { item_id: 1, num_items: 1 }));
+        assert!(matches!(PrimalDual::new(1, 1, 0.1).try_set_reserve_price(0, -1.0), Err(MbaError::InvalidReservePrice { .. })));
+    }
+}
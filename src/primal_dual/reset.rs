@@ -0,0 +1,151 @@
+use super::{exact, BuildError, PrimalDual, Scalar};
+
+// clearing instead of dropping keeps the capacity of the bid maps, heaps and deques, so a solver reused over many
+// instances of one shape stops allocating once it has seen the largest of them
+impl<T: Scalar> PrimalDual<T> {
+    // back to the state of new: no budgets, bids, rates, capacities, item caps, sizes, agent epsilons, reserve prices, inactive agents, previous owners, carried spend or range warnings,
+    // and nothing solved. epsilon and the settings (policies, safe range, agent order, limits) are kept
+    pub fn reset(&mut self) {
+        self.budgets.fill(T::zero());
+        self.bid.iter_mut().for_each(|bids| bids.clear());
+        self.num_accepted_bids = 0;
+        self.agent_items.iter_mut().for_each(|item_ids| item_ids.clear());
+        self.item_agents.iter_mut().for_each(|agent_ids| agent_ids.clear());
+        self.item_agent.iter_mut().for_each(|heap| heap.clear());
+        self.max_bid_agent.fill(None);
+        self.item_capacity.fill(1);
+        self.max_items.fill(None);
+        self.sizes.iter_mut().for_each(|sizes| sizes.clear());
+        self.agent_capacity.fill(None);
+        self.agent_epsilon.fill(None);
+        self.agent_initial_alpha.fill(None);
+        self.reserve_price.fill(0.0);
+        self.beta = 0.0;
+        self.rejected_bids.clear();
+        self.below_reserve_bids.clear();
+        self.range_warnings.clear();
+
+        self.rates.fill(1.0);
+        self.original_budgets.fill(None);
+        self.original_bids.iter_mut().for_each(|bids| bids.clear());
+        self.previous_owner.fill(None);
+        self.active.fill(true);
+        self.carried_spend.fill(0.0);
+        self.visit_order.clear();
+
+        self.reset_solve_state();
+    }
+
+    // panics where try_reset_with_size fails
+    pub fn reset_with_size(&mut self, num_agents: usize, num_items: usize) {
+        self.try_reset_with_size(num_agents, num_items).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    // reset for num_agents agents and num_items items, with the same limit on the size as try_new for epsilon 0
+    pub fn try_reset_with_size(&mut self, num_agents: usize, num_items: usize) -> Result<(), BuildError> {
+        if self.epsilon == 0.0 && !exact::fits_shape(num_agents, num_items) {
+            return Err(BuildError::TooLargeForExact { num_agents, num_items });
+        }
+        if let Some(limit) = self.max_memory_bytes {
+            let needed = Self::construction_bytes(num_agents, num_items);
+            if needed > limit {
+                return Err(BuildError::MemoryLimitExceeded { needed, limit });
+            }
+        }
+        self.num_agents = num_agents;
+        self.num_items = num_items;
+
+        self.budgets.resize(num_agents, T::zero());
+        self.bid.resize_with(num_agents, Default::default);
+        self.agent_items.resize_with(num_agents, Vec::new);
+        self.alpha.resize(num_agents, T::zero());
+        self.consumptions.resize(num_agents, T::zero());
+        self.gamma.resize_with(num_agents, Default::default);
+        self.num_update.resize(num_agents, 0);
+        self.rates.resize(num_agents, 1.0);
+        self.original_budgets.resize(num_agents, None);
+        self.original_bids.resize_with(num_agents, Vec::new);
+        self.active.resize(num_agents, true);
+        self.carried_spend.resize(num_agents, 0.0);
+        self.max_items.resize(num_agents, None);
+        self.sizes.resize_with(num_agents, Default::default);
+        self.agent_capacity.resize(num_agents, None);
+        self.agent_epsilon.resize(num_agents, None);
+        self.agent_initial_alpha.resize(num_agents, None);
+
+        self.item_agents.resize_with(num_items, Vec::new);
+        self.item_agent.resize_with(num_items, Default::default);
+        self.max_bid_agent.resize(num_items, None);
+        self.item_capacity.resize(num_items, 1);
+        self.reserve_price.resize(num_items, 0.0);
+        self.previous_owner.resize(num_items, None);
+
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::instance::Instance;
+    use crate::primal_dual::{BuildError, PrimalDual};
+
+    fn load(solver: &mut PrimalDual, instance: &Instance) {
+        for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        solver.set_bids(instance.bids.iter().copied());
+    }
+
+    fn assert_same(solver: &PrimalDual, fresh: &PrimalDual) {
+        assert_eq!(solver.get_assignment(), fresh.get_assignment());
+        assert_eq!(solver.get_primal_objective_value(), fresh.get_primal_objective_value());
+        assert_eq!(solver.get_dual_objective_value(), fresh.get_dual_objective_value());
+        assert_eq!(solver.get_lp_duals(), fresh.get_lp_duals());
+        assert_eq!(solver.get_approximation_ratio(), fresh.get_approximation_ratio());
+    }
+
+    #[test]
+    fn matches_a_fresh_solver() {
+        let first = generate(6, 40, 0.6, (20.0, 60.0), (1.0, 15.0), 1);
+        let second = generate(6, 40, 0.4, (10.0, 80.0), (1.0, 20.0), 2);
+
+        let mut solver = PrimalDual::new(6, 40, 0.05);
+        load(&mut solver, &first);
+        solver.set_item_capacity(3, 2);
+        solver.solve();
+        let capacity = solver.bid[0].capacity();
+
+        solver.reset();
+        assert_eq!(solver.get_primal_objective_value(), 0.0);
+        assert_eq!(solver.get_item_capacity(3), 1);
+        assert!(solver.bid[0].capacity() >= capacity);
+        load(&mut solver, &second);
+        solver.solve();
+
+        let mut fresh = PrimalDual::from_instance(&second, 0.05);
+        fresh.solve();
+        assert_same(&solver, &fresh);
+    }
+
+    #[test]
+    fn resized() {
+        let mut solver = PrimalDual::from_instance(&generate(6, 40, 0.6, (20.0, 60.0), (1.0, 15.0), 3), 0.05);
+        solver.solve();
+        for (num_agents, num_items, seed) in [(3, 10, 4), (9, 70, 5)] {
+            let instance = generate(num_agents, num_items, 0.5, (20.0, 60.0), (1.0, 15.0), seed);
+            solver.reset_with_size(num_agents, num_items);
+            load(&mut solver, &instance);
+            solver.solve();
+
+            let mut fresh = PrimalDual::from_instance(&instance, 0.05);
+            fresh.solve();
+            assert_same(&solver, &fresh);
+        }
+
+        let mut exact = PrimalDual::new(2, 2, 0.0);
+        assert!(matches!(exact.try_reset_with_size(10, 100), Err(BuildError::TooLargeForExact { num_agents: 10, num_items: 100 })));
+        assert!(matches!(exact.try_reset_with_size(30_000, 30_000), Err(BuildError::TooLargeForExact { .. })));
+    }
+}
@@ -0,0 +1,169 @@
+use super::{AgentOrder, PrimalDual, Scalar};
+use crate::rng::RngConfig;
+
+// what solve_best_of tried
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestartReport {
+    pub orders: Vec<AgentOrder>, // orders[run] = AgentOrder::Random of run
+    pub primals: Vec<f64>,       // primals[run] = primal objective of run
+    pub best: usize,             // run the solver keeps, the first with the largest primal
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // num_runs cold solves of clones of the solver, each visiting the agents in a random order drawn from seed, keeping
+    // the result of the largest primal objective. every run carries the approximation guarantee, so the best one does.
+    // the solver keeps its agent order, and a later solve without changes keeps the kept result. the observer sees the
+    // earlier assignment released and the kept one placed, not the runs
+    pub fn solve_best_of(&mut self, num_runs: usize, seed: u64) -> RestartReport {
+        assert!(num_runs > 0, "solve_best_of needs at least one run");
+
+        let mut rng = RngConfig::new(seed).stream("restarts");
+        let mut report = RestartReport {
+            orders: Vec::with_capacity(num_runs),
+            primals: Vec::with_capacity(num_runs),
+            best: 0,
+        };
+        let mut best: Option<Self> = None;
+        for run in 0..num_runs {
+            let order = AgentOrder::Random(rng.next_u64());
+            let mut solver = self.clone();
+            solver.set_agent_order(order);
+            solver.reset_solve_state();
+            solver.solve();
+
+            let primal = solver.get_primal_objective_value();
+            if best.is_none() || primal > report.primals[report.best] {
+                report.best = run;
+                best = Some(solver);
+            }
+            report.orders.push(order);
+            report.primals.push(primal);
+        }
+
+        let mut best = best.expect("at least one run");
+        best.agent_order = self.agent_order;
+        self.emit_release_all();
+        best.observer = std::mem::take(&mut self.observer);
+        *self = best;
+        for agent_id in 0..self.num_agents {
+            for i in 0..self.gamma[agent_id].len() {
+                let item_id = self.gamma[agent_id][i];
+                self.emit_move(item_id, None, Some(agent_id));
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::primal_dual::{AgentOrder, MbaError, PrimalDual, SolveEvent, SolveOptions};
+    use std::sync::{Arc, Mutex};
+
+    // index order ends at 15, the reverse order at 16
+    fn order_sensitive() -> PrimalDual {
+        let mut solver = PrimalDual::new(3, 4, 0.1);
+        for (agent_id, budget) in [6.0, 7.0, 4.0].into_iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        solver.set_bids([
+            (0, 0, 2.0),
+            (0, 1, 4.0),
+            (0, 2, 5.0),
+            (0, 3, 5.0),
+            (1, 0, 7.0),
+            (1, 1, 6.0),
+            (1, 3, 4.0),
+            (2, 0, 2.0),
+            (2, 1, 3.0),
+            (2, 2, 3.0),
+        ]);
+        solver
+    }
+
+    #[test]
+    fn orders_differ() {
+        let mut solver = order_sensitive();
+        solver.solve();
+        assert_eq!(solver.get_primal_objective_value(), 15.0);
+
+        let mut reversed = order_sensitive();
+        reversed.set_agent_permutation(vec![2, 1, 0]);
+        reversed.solve();
+        assert_eq!(reversed.get_primal_objective_value(), 16.0);
+
+        // the same for one call of solve_with, after which the solver is back to its own order
+        let mut solver = order_sensitive();
+        let result = solver.solve_with(&SolveOptions::new().agent_permutation(&[2, 1, 0]));
+        assert_eq!(result.primal, 16.0);
+        assert_eq!(solver.get_config().agent_order, AgentOrder::IndexOrder);
+
+        // by budget is the permutation [1, 0, 2]
+        let mut by_budget = order_sensitive();
+        by_budget.set_agent_order(AgentOrder::LargestBudgetFirst);
+        by_budget.solve();
+        let mut permuted = order_sensitive();
+        permuted.set_agent_permutation(vec![1, 0, 2]);
+        permuted.solve();
+        assert_eq!(by_budget.extract_solution(), permuted.extract_solution());
+    }
+
+    #[test]
+    fn best_of() {
+        let mut solver = order_sensitive();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        solver.set_observer(Box::new(move |event| sink.lock().unwrap().push(event)));
+        let report = solver.solve_best_of(8, 308);
+
+        assert_eq!(report.primals.len(), 8);
+        let max = report.primals.iter().copied().fold(f64::MIN, f64::max);
+        assert_eq!(report.primals[report.best], max);
+        assert!(report.primals[..report.best].iter().all(|&primal| primal < max));
+        assert!(max >= 16.0);
+        assert_eq!(solver.get_primal_objective_value(), max);
+        assert!(solver.extract_solution().verify().is_ok());
+        assert_eq!(solver.get_config().agent_order, AgentOrder::IndexOrder);
+        assert!(solver.has_observer());
+
+        // every run is reproducible on its own
+        for (&order, &primal) in report.orders.iter().zip(&report.primals) {
+            let mut run = order_sensitive();
+            run.set_agent_order(order);
+            run.solve();
+            assert_eq!(run.get_primal_objective_value(), primal);
+        }
+        assert_eq!(order_sensitive().solve_best_of(8, 308), report);
+
+        // the observer saw the kept assignment placed
+        let placed = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| matches!(event, SolveEvent::ItemReassigned { from: None, .. }))
+            .count();
+        assert_eq!(placed, solver.get_assignment().iter().map(|item_ids| item_ids.len()).sum::<usize>());
+    }
+
+    #[test]
+    fn invalid_permutations() {
+        let mut solver = order_sensitive();
+        let e = solver.try_set_agent_permutation(vec![0, 1]).unwrap_err();
+        assert_eq!(e, MbaError::NotAPermutation { num_agents: 3, agent_id: None });
+        assert_eq!(e.code(), ErrorCode::NotAPermutation);
+        assert_eq!(
+            solver.try_set_agent_permutation(vec![0, 2, 0]),
+            Err(MbaError::NotAPermutation {
+                num_agents: 3,
+                agent_id: Some(0)
+            })
+        );
+        assert!(solver.try_set_agent_permutation(vec![0, 1, 3]).is_err());
+        assert_eq!(solver.get_config().agent_order, AgentOrder::IndexOrder);
+
+        assert!(matches!(solver.try_solve_with(&SolveOptions::new().agent_permutation(&[1, 1, 0])), Err(MbaError::NotAPermutation { agent_id: Some(1), .. })));
+        let both = SolveOptions::new().agent_permutation(&[1, 2, 0]).agent_order(AgentOrder::MostBidsFirst);
+        assert_eq!(solver.try_solve_with(&both).unwrap_err().code(), ErrorCode::ConflictingOptions);
+    }
+}
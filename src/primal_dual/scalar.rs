@@ -0,0 +1,31 @@
+use num_traits::float::FloatCore;
+use num_traits::Float;
+use std::fmt::Debug;
+use std::ops::{AddAssign, SubAssign};
+
+// type PrimalDual stores budgets, bids, alphas, consumptions and heap prices in.
+// arithmetic that mixes these values, such as prices and objectives, is done in f64 and rounded back to the scalar when stored
+pub trait Scalar: Float + FloatCore + AddAssign + SubAssign + Debug + Default + Send + Sync + 'static {
+    fn from_f64(value: f64) -> Self;
+    fn as_f64(self) -> f64;
+}
+
+impl Scalar for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
@@ -0,0 +1,103 @@
+use super::{MbaError, PrimalDual, Scalar};
+
+// a spend at least this fraction below the effective budget leaves the budget slack
+const TIGHT_TOLERANCE: f64 = 1e-9;
+
+// how much one agent's budget is worth after a solve; see sensitivity
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgentSensitivity {
+    pub agent_id: usize,
+    pub alpha: f64,
+    pub tight: bool, // the agent spends its whole effective budget
+    pub spend: f64,  // sum of the bids on its items, which may exceed the budget
+    pub effective_budget: f64,
+    pub marginal_value: f64, // estimated objective gained per unit of extra budget
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // one entry per agent. alpha is the dual price of the agent's budget: raising the budget by delta raises the dual bound
+    // by alpha * delta, so marginal_value = alpha estimates what the primal gains per unit, 0 for inactive agents.
+    // it is an estimate only: the primal is within the approximation ratio of the dual, not equal to it, and large
+    // changes move the alphas; resolve_with_budget_delta gives the exact difference at the cost of a solve
+    pub fn sensitivity(&self) -> Vec<AgentSensitivity> {
+        (0..self.num_agents)
+            .map(|agent_id| {
+                let effective_budget = self.effective_budget(agent_id);
+                let spend = self.consumption(agent_id);
+                AgentSensitivity {
+                    agent_id,
+                    alpha: self.alpha(agent_id),
+                    tight: self.active[agent_id] && spend >= effective_budget * (1.0 - TIGHT_TOLERANCE),
+                    spend,
+                    effective_budget,
+                    marginal_value: if self.active[agent_id] { self.alpha(agent_id) } else { 0.0 },
+                }
+            })
+            .collect()
+    }
+
+    // panics where try_resolve_with_budget_delta fails
+    pub fn resolve_with_budget_delta(&self, agent_id: usize, delta: f64) -> f64 {
+        self.try_resolve_with_budget_delta(agent_id, delta).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // primal of a fresh solve of a copy whose agent_id has delta more budget, minus the primal of a fresh solve of this
+    // instance; the solver itself is left alone. two solves, as a budget change needs a solve from scratch
+    pub fn try_resolve_with_budget_delta(&self, agent_id: usize, delta: f64) -> Result<f64, MbaError> {
+        self.check_agent(agent_id)?;
+        let mut base = self.clone();
+        base.reset_solve_state();
+        let mut perturbed = base.clone();
+        perturbed.try_set_budget(agent_id, T::from_f64(self.budget(agent_id) + delta))?;
+
+        base.solve();
+        perturbed.solve();
+        Ok(perturbed.get_primal_objective_value() - base.get_primal_objective_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::primal_dual::{MbaError, PrimalDual};
+
+    #[test]
+    fn constrained_agent() {
+        // agent 0 wants 30 with a budget of 10, agent 1 has more budget than bids
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 100.0);
+        solver.set_bids([(0, 0, 10.0), (0, 1, 10.0), (0, 2, 10.0), (1, 2, 1.0)]);
+        solver.solve();
+
+        let sensitivity = solver.sensitivity();
+        assert!(sensitivity[0].tight && sensitivity[0].alpha > 0.0);
+        assert_eq!(sensitivity[0].marginal_value, sensitivity[0].alpha);
+        assert!(!sensitivity[1].tight);
+        assert_eq!(sensitivity[1].marginal_value, 0.0);
+        assert_eq!(sensitivity[1].effective_budget, 100.0);
+
+        assert_eq!(solver.resolve_with_budget_delta(0, 10.0), 10.0);
+        assert_eq!(solver.resolve_with_budget_delta(1, 10.0), 0.0);
+        assert_eq!(solver.get_budget(0), 10.0);
+        assert_eq!(solver.try_resolve_with_budget_delta(0, -20.0), Err(MbaError::InvalidBudget { agent_id: 0, budget: -10.0 }));
+    }
+
+    #[test]
+    fn estimate_against_resolve() {
+        // the primal moves by at most alpha * delta plus the gap to the dual
+        let instance = generate(6, 60, 0.5, (20.0, 60.0), (1.0, 10.0), 292);
+        let mut solver = PrimalDual::from_instance(&instance, 0.01);
+        solver.solve();
+        let gap = solver.get_dual_objective_value() - solver.get_primal_objective_value();
+        for entry in solver.sensitivity() {
+            let delta = 0.1 * entry.effective_budget;
+            let difference = solver.resolve_with_budget_delta(entry.agent_id, delta);
+            assert!(difference <= entry.marginal_value * delta + gap + 1e-9, "{:?} {}", entry, difference);
+            if !entry.tight {
+                assert!(entry.spend < entry.effective_budget);
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use super::{PrimalDual, Scalar};
+use std::time::{Duration, Instant};
+
+// what one call of solve_with_stats did
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolveStats {
+    pub sweeps: usize,
+    pub alpha_updates: Vec<usize>, // alpha_updates[agent_id] = times alpha of agent_id was raised
+    pub reallocations: usize,      // rounds of reallocation
+    pub item_moves: usize,         // items handed to another agent or dropped
+    pub unique_raises: usize,      // rounds in which none of the agent's items had another bidder, so alpha rose until it was paid for
+    pub forced_drops: usize,       // items dropped because alpha of their holder reached its cap, also counted in item_moves
+    pub num_switches: usize,       // items whose owner differs from the previous solution of solve_with_stability, else 0
+    pub beta: f64,
+    pub time: Duration,
+}
+
+// counters of the solver when a solve starts, see stats_since
+pub(super) struct Counters {
+    num_update: Vec<u32>,
+    reallocations: usize,
+    item_moves: usize,
+    unique_raises: usize,
+    forced_drops: usize,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // solve returning what the algorithm did; the counts cover this call only
+    pub fn solve_with_stats(&mut self) -> SolveStats {
+        let start = Instant::now();
+        self.apply_unassignable_policy();
+        self.initialize();
+        // after initialize, which may reset the counters
+        let counters = self.counters();
+        let (sweeps, _) = self.run(None);
+        self.stats_since(counters, sweeps, start)
+    }
+
+    pub(super) fn counters(&self) -> Counters {
+        Counters {
+            num_update: self.num_update.clone(),
+            reallocations: self.num_reallocations,
+            item_moves: self.num_item_moves,
+            unique_raises: self.num_unique_raises,
+            forced_drops: self.num_forced_drops,
+        }
+    }
+
+    // what the solve did since counters were taken, in sweeps sweeps started at start
+    pub(super) fn stats_since(&self, counters: Counters, sweeps: usize, start: Instant) -> SolveStats {
+        SolveStats {
+            sweeps,
+            alpha_updates: self
+                .num_update
+                .iter()
+                .zip(&counters.num_update)
+                .map(|(&after, &before)| (after - before) as usize)
+                .collect(),
+            reallocations: self.num_reallocations - counters.reallocations,
+            item_moves: self.num_item_moves - counters.item_moves,
+            unique_raises: self.num_unique_raises - counters.unique_raises,
+            forced_drops: self.num_forced_drops - counters.forced_drops,
+            num_switches: self.num_switches(),
+            beta: self.beta,
+            time: start.elapsed(),
+        }
+    }
+
+    // items held by another agent than previous_owner names, 0 outside solve_with_stability where it names none
+    fn num_switches(&self) -> usize {
+        let item_owner = self.item_owner();
+        (0..self.num_items)
+            .filter(|&item_id| self.previous_owner[item_id].is_some() && self.previous_owner[item_id] != item_owner[item_id])
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+
+    fn generated_solver() -> PrimalDual {
+        PrimalDual::from_instance(&generate(15, 120, 0.9, (10.0, 50.0), (1.0, 10.0), 207), 0.01)
+    }
+
+    #[test]
+    fn counts() {
+        let mut solver = generated_solver();
+        let stats = solver.solve_with_stats();
+
+        assert_eq!(stats.alpha_updates, solver.num_update.iter().map(|&num| num as usize).collect::<Vec<_>>());
+        assert_eq!(stats.alpha_updates.iter().sum::<usize>(), solver.num_alpha_updates);
+        assert_eq!(stats.reallocations, solver.get_num_reallocations());
+        assert!(stats.sweeps > 0 && stats.reallocations > 0 && stats.item_moves > 0);
+        assert_eq!(stats.beta, solver.beta);
+
+        let mut plain = generated_solver();
+        plain.solve();
+        assert_eq!(plain.get_primal_objective_value(), solver.get_primal_objective_value());
+    }
+
+    #[test]
+    fn unique_raises() {
+        // agent 0 is the only bidder on its items and overspends
+        let mut solver = PrimalDual::new(2, 3, 0.1);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bid(0, 0, 8.0);
+        solver.set_bid(0, 1, 8.0);
+        solver.set_bid(1, 2, 5.0);
+        let stats = solver.solve_with_stats();
+        assert!(stats.unique_raises > 0);
+        assert_eq!(stats.item_moves, 0);
+        assert_eq!(stats.alpha_updates[1], 0);
+    }
+}
@@ -0,0 +1,145 @@
+use super::{PrimalDual, Scalar, SolveEvent};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepStatus {
+    // a step did some work; gamma, consumptions and the objectives describe the assignment so far
+    InProgress,
+    // every agent is paid for, the approximation guarantee holds
+    Done,
+}
+
+// position in the sweep in progress
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Sweep {
+    position: usize, // index into visit_order of the next agent to check
+    clean: bool,     // no agent of the sweep so far needed a reallocation
+}
+
+// what advance did
+pub(super) enum Progress {
+    Reallocated,
+    SweepEnded, // a sweep without a reallocation is Done instead
+    Done,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // one bounded unit of solve for an event loop: the first step initializes, every further step reallocates for one
+    // unpaid agent, and the step that finds all of them paid for returns Done. solve is these steps run to Done, with the
+    // same result. between two steps the solver is consistent, so the objectives and the assignment can be read; a
+    // setter in between makes the next step start over, as the next solve would.
+    // a step also checks the paid agents up to the next unpaid one, so it costs at most a reallocation and two sweeps of checks
+    pub fn step(&mut self) -> StepStatus {
+        if !self.stepping || self.changed || !self.new_bid_items.is_empty() {
+            self.apply_unassignable_policy();
+            self.initialize();
+            self.stepping = true;
+            self.sweep = None;
+            return StepStatus::InProgress;
+        }
+        if self.epsilon == 0.0 {
+            self.assign_exact();
+            self.stepping = false;
+            return StepStatus::Done;
+        }
+        loop {
+            match self.advance() {
+                Progress::Reallocated => return StepStatus::InProgress,
+                Progress::SweepEnded => {}
+                Progress::Done => {
+                    self.enforce_agent_limits();
+                    self.stepping = false;
+                    return StepStatus::Done;
+                }
+            }
+        }
+    }
+
+    // a reallocation for the next unpaid agent of the sweep in progress, starting a sweep if there is none, or the end of
+    // the sweep when every agent left is paid for
+    pub(super) fn advance(&mut self) -> Progress {
+        let mut sweep = match self.sweep {
+            Some(sweep) => sweep,
+            None => {
+                self.update_visit_order();
+                Sweep { position: 0, clean: true }
+            }
+        };
+        while sweep.position < self.num_agents {
+            let agent_id = self.visit_order[sweep.position];
+            if !self.is_paid_for(agent_id) {
+                sweep.clean = false;
+                self.sweep = Some(sweep);
+                self.reallocate(agent_id);
+                if self.is_paid_for(agent_id) {
+                    self.emit(|_| SolveEvent::AgentPaidFor { agent: agent_id });
+                }
+                return Progress::Reallocated;
+            }
+            sweep.position += 1;
+        }
+        self.sweep = None;
+        if sweep.clean {
+            Progress::Done
+        } else {
+            Progress::SweepEnded
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StepStatus;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn same_as_solve() {
+        let instance = generate(12, 150, 0.4, (10.0, 40.0), (0.5, 10.0), 303);
+        let mut solved = PrimalDual::from_instance(&instance, 0.005);
+        solved.solve();
+
+        let mut stepped = PrimalDual::from_instance(&instance, 0.005);
+        let mut num_steps = 0;
+        while stepped.step() == StepStatus::InProgress {
+            num_steps += 1;
+            let primal = stepped.get_primal_objective_value();
+            assert!(primal >= 0.0 && primal <= stepped.get_dual_objective_value() + 1e-9);
+            stepped.extract_solution().verify().unwrap();
+        }
+        assert!(num_steps > 10);
+        assert_eq!(stepped.extract_solution(), solved.extract_solution());
+        assert_eq!(stepped.get_num_reallocations(), solved.get_num_reallocations());
+
+        // a solved solver is done after one more check
+        assert_eq!(stepped.step(), StepStatus::InProgress);
+        assert_eq!(stepped.step(), StepStatus::Done);
+        assert_eq!(stepped.extract_solution(), solved.extract_solution());
+    }
+
+    #[test]
+    fn setter_between_steps() {
+        let instance = generate(8, 80, 0.5, (10.0, 30.0), (0.5, 10.0), 304);
+        let mut stepped = PrimalDual::from_instance(&instance, 0.01);
+        for _ in 0..20 {
+            stepped.step();
+        }
+        stepped.set_budget(0, 5.0);
+        while stepped.step() == StepStatus::InProgress {}
+
+        let mut fresh = PrimalDual::from_instance(&instance, 0.01);
+        fresh.set_budget(0, 5.0);
+        fresh.solve();
+        assert_eq!(stepped.extract_solution(), fresh.extract_solution());
+    }
+
+    #[test]
+    fn exact() {
+        let mut solver = PrimalDual::new(2, 2, 0.0);
+        solver.set_budget(0, 3.0);
+        solver.set_budget(1, 3.0);
+        solver.set_bids([(0, 0, 2.0), (0, 1, 2.0), (1, 1, 1.0)]);
+        assert_eq!(solver.step(), StepStatus::InProgress);
+        assert_eq!(solver.step(), StepStatus::Done);
+        assert_eq!(solver.get_primal_objective_value(), 3.0);
+    }
+}
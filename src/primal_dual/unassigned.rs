@@ -0,0 +1,83 @@
+use super::{PrimalDual, Scalar};
+
+// why an item ends up held by nobody
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum UnassignedReason {
+    NoBids,
+    // every bid exceeded the budget of its bidder and was dropped by OverbidPolicy::Reject
+    AllBidsExceededBudget,
+    // every bid was below the reserve price of the item
+    BelowReservePrice,
+    // the item has accepted bids, but the solve left it with nobody, e.g. because its bidders are inactive
+    DroppedDuringSolve,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // items held by nobody, by item id. bids added by UnassignablePolicy::ClampBestBid count as accepted, so call this after solve()
+    pub fn get_unassigned_items(&self) -> Vec<(usize, UnassignedReason)> {
+        let mut rejected = vec![false; self.num_items];
+        for &(_, item_id, _) in &self.rejected_bids {
+            rejected[item_id] = true;
+        }
+
+        let mut below_reserve = vec![false; self.num_items];
+        for &(_, item_id, _) in &self.below_reserve_bids {
+            below_reserve[item_id] = true;
+        }
+
+        let item_owner = self.item_owner();
+        (0..self.num_items)
+            .filter(|&item_id| item_owner[item_id].is_none())
+            .map(|item_id| {
+                let reason = if !self.item_agents[item_id].is_empty() {
+                    UnassignedReason::DroppedDuringSolve
+                } else if below_reserve[item_id] {
+                    UnassignedReason::BelowReservePrice
+                } else if rejected[item_id] {
+                    UnassignedReason::AllBidsExceededBudget
+                } else {
+                    UnassignedReason::NoBids
+                };
+                (item_id, reason)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnassignedReason;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn reasons() {
+        // item 0 is contested, item 1 has no bids, item 2 only a bid above the budget,
+        // items 3 and 4 stay with agent 1 until it is deactivated and cannot afford them any more
+        let mut solver = PrimalDual::new(2, 5, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 20.0);
+        solver.set_bid(0, 0, 5.0);
+        solver.set_bid(1, 0, 4.0);
+        solver.set_bid(0, 2, 11.0);
+        solver.set_bid(1, 3, 10.0);
+        solver.set_bid(1, 4, 10.0);
+        solver.solve();
+        assert_eq!(solver.get_unassigned_items(), vec![(1, UnassignedReason::NoBids), (2, UnassignedReason::AllBidsExceededBudget)]);
+
+        solver.set_agent_active(1, false);
+        solver.set_budget(1, 5.0);
+        solver.solve();
+        solver.verify_solution().unwrap();
+        assert_eq!(
+            solver.get_unassigned_items(),
+            vec![
+                (1, UnassignedReason::NoBids),
+                (2, UnassignedReason::AllBidsExceededBudget),
+                (3, UnassignedReason::DroppedDuringSolve),
+                (4, UnassignedReason::DroppedDuringSolve),
+            ]
+        );
+        assert_eq!(solver.get_item_assignment()[0], Some(0));
+    }
+}
@@ -0,0 +1,89 @@
+use super::{PrimalDual, Scalar};
+
+// an instance is uniform when every active agent with an accepted bid has the same positive effective budget and no
+// accepted bid exceeds it, i.e. beta <= 1. the a priori bound (1 - beta / 4)(1 - epsilon) cannot be improved for such
+// instances, as the instance showing the integrality gap 3/4 of the LP already has equal budgets; get_certified_ratio
+// gives the guarantee of the assignment at hand instead, for these and every other instance
+impl<T: Scalar> PrimalDual<T> {
+    // whether the current budgets and bids form a uniform instance, see above
+    pub fn is_uniform_instance(&self) -> bool {
+        let mut uniform_budget = None;
+        for agent_id in (0..self.num_agents).filter(|&agent_id| self.active[agent_id] && !self.bid[agent_id].is_empty()) {
+            let budget = self.effective_budget(agent_id);
+            if budget <= 0.0 || uniform_budget.is_some_and(|uniform_budget| uniform_budget != budget) {
+                return false;
+            }
+            if self.bid[agent_id].values().any(|&bid| bid.as_f64() > budget) {
+                return false;
+            }
+            uniform_budget = Some(budget);
+        }
+        uniform_budget.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+    use crate::rng::RngConfig;
+
+    // budgets all equal to budget, bids at most the budget
+    fn uniform(num_agents: usize, num_items: usize, budget: f64, seed: u64) -> PrimalDual {
+        let mut rng = RngConfig::new(seed).stream("uniform");
+        let mut solver = PrimalDual::new(num_agents, num_items, 0.05);
+        for agent_id in 0..num_agents {
+            solver.set_budget(agent_id, budget);
+            for item_id in 0..num_items {
+                if rng.next_f64() < 0.5 {
+                    solver.set_bid(agent_id, item_id, rng.range_f64(0.1, budget));
+                }
+            }
+        }
+        solver
+    }
+
+    #[test]
+    fn ratio_at_least_generic() {
+        for seed in 0..20 {
+            let mut solver = uniform(6, 40, 10.0, seed);
+            assert!(solver.is_uniform_instance());
+            let generic = solver.get_approximation_ratio();
+            solver.solve();
+            assert_eq!(generic, (1.0 - solver.beta / 4.0) * 0.95);
+
+            let ratio = solver.get_certified_ratio();
+            assert!(ratio >= generic, "seed {}: {} {}", seed, ratio, generic);
+            assert!(ratio <= 1.0);
+            assert!(solver.get_primal_objective_value() >= ratio * solver.get_dual_objective_value(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn detection() {
+        let mut solver = uniform(3, 10, 5.0, 309);
+        assert!(solver.is_uniform_instance());
+
+        // an agent without bids does not count
+        for item_id in 0..10 {
+            solver.remove_bid(2, item_id);
+        }
+        solver.set_budget(2, 7.0);
+        assert!(solver.is_uniform_instance());
+
+        solver.set_bid(2, 0, 1.0);
+        assert!(!solver.is_uniform_instance());
+        solver.solve();
+        assert_eq!(solver.get_approximation_ratio(), (1.0 - solver.beta / 4.0) * 0.95);
+
+        // a budget lowered below a bid breaks beta <= 1
+        let mut solver = uniform(2, 10, 5.0, 310);
+        solver.set_budget(0, 0.05);
+        solver.set_budget(1, 0.05);
+        assert!(!solver.is_uniform_instance());
+
+        assert!(!PrimalDual::new(2, 3, 0.1).is_uniform_instance());
+        let instance = generate(4, 20, 0.5, (10.0, 20.0), (0.5, 5.0), 309);
+        assert!(!PrimalDual::from_instance(&instance, 0.1).is_uniform_instance());
+    }
+}
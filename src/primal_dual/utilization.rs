@@ -0,0 +1,215 @@
+use super::{PrimalDual, Scalar};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UtilizationStatus {
+    // the last attempt reached the target
+    Achieved,
+    // every attempt stayed below the target
+    Exhausted,
+    // even spending every bid cannot reach the target; no attempt was made
+    Infeasible,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UtilizationAttempt {
+    pub epsilon: f64,
+    pub utilization: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UtilizationOutcome {
+    pub target: f64,
+    pub upper_bound: f64, // sum_a min(budget_a, sum of bids of a) / total budget, no assignment does better
+    pub attempts: Vec<UtilizationAttempt>,
+    pub status: UtilizationStatus,
+}
+
+// spend of one agent; see agent_spends
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AgentSpend {
+    pub agent_id: usize,
+    pub consumption: f64,
+    pub remaining_budget: f64,
+    pub value: f64,
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // sum of the bids on the items agent_id holds, which may exceed its budget
+    pub fn get_consumption(&self, agent_id: usize) -> f64 {
+        self.consumption(agent_id)
+    }
+
+    // effective budget not yet consumed, at least 0
+    pub fn get_remaining_budget(&self, agent_id: usize) -> f64 {
+        (self.effective_budget(agent_id) - self.consumption(agent_id)).max(0.0)
+    }
+
+    // contribution of agent_id to the primal objective: min(consumption, effective budget), 0 for inactive agents
+    pub fn get_agent_value(&self, agent_id: usize) -> f64 {
+        if !self.active[agent_id] {
+            return 0.0;
+        }
+        self.consumption(agent_id).min(self.effective_budget(agent_id))
+    }
+
+    pub fn agent_spends(&self) -> impl Iterator<Item = AgentSpend> + '_ {
+        (0..self.num_agents).map(move |agent_id| AgentSpend {
+            agent_id,
+            consumption: self.get_consumption(agent_id),
+            remaining_budget: self.get_remaining_budget(agent_id),
+            value: self.get_agent_value(agent_id),
+        })
+    }
+
+    // spend counted up to the budget of each active agent; equals the primal objective
+    pub fn get_total_spend(&self) -> f64 {
+        self.get_primal_objective_value()
+    }
+
+    pub fn get_total_budget(&self) -> f64 {
+        (0..self.num_agents)
+            .filter(|&agent_id| self.active[agent_id])
+            .map(|agent_id| self.effective_budget(agent_id))
+            .sum()
+    }
+
+    // solves until get_total_spend() / get_total_budget() reaches target, halving epsilon after every attempt that falls short
+    //
+    // the solver is left with the solution and epsilon of the last attempt
+    pub fn solve_to_utilization(&mut self, target: f64, max_attempts: usize) -> UtilizationOutcome {
+        let total_budget = self.get_total_budget();
+        let upper_bound = if total_budget > 0.0 {
+            let reachable: f64 = (0..self.num_agents)
+                .filter(|&agent_id| self.active[agent_id])
+                .map(|agent_id| {
+                    let volume: f64 = self.bids_of_agent(agent_id).map(|(_, _, bid)| bid).sum();
+                    volume.min(self.effective_budget(agent_id))
+                })
+                .sum();
+            reachable / total_budget
+        } else {
+            1.0
+        };
+
+        let mut outcome = UtilizationOutcome {
+            target,
+            upper_bound,
+            attempts: Vec::new(),
+            status: UtilizationStatus::Infeasible,
+        };
+        if upper_bound < target {
+            return outcome;
+        }
+
+        outcome.status = UtilizationStatus::Exhausted;
+        for attempt in 0..max_attempts {
+            if attempt > 0 {
+                self.epsilon /= 2.0;
+            }
+            self.reset_solve_state();
+            self.solve();
+
+            let utilization = if total_budget > 0.0 { self.get_total_spend() / total_budget } else { 1.0 };
+            outcome.attempts.push(UtilizationAttempt {
+                epsilon: self.epsilon,
+                utilization,
+            });
+            if utilization >= target {
+                outcome.status = UtilizationStatus::Achieved;
+                break;
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UtilizationStatus;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+
+    fn generated_solver(bid_scale: f64) -> PrimalDual {
+        PrimalDual::from_instance(&generate(10, 60, 0.33, (20.0, 30.0), (bid_scale, bid_scale * 7.0), 223), 0.2)
+    }
+
+    #[test]
+    fn achievable() {
+        let mut solver = generated_solver(1.0);
+        let outcome = solver.solve_to_utilization(0.9, 5);
+        assert_eq!(outcome.status, UtilizationStatus::Achieved);
+        assert!(outcome.upper_bound >= 0.9);
+
+        let last = outcome.attempts.last().unwrap();
+        assert!(last.utilization >= 0.9);
+        assert_eq!(last.epsilon, solver.epsilon);
+        assert!(outcome
+            .attempts
+            .windows(2)
+            .all(|pair| pair[1].epsilon == pair[0].epsilon / 2.0 && pair[0].utilization < 0.9));
+        assert_eq!(solver.get_total_spend() / solver.get_total_budget(), last.utilization);
+    }
+
+    #[test]
+    fn after_halving() {
+        // epsilon 0.4 and 0.2 fall short, 0.1 reaches every budget
+        let instance = generate(10, 60, 0.3, (20.0, 30.0), (1.0, 7.0), 7);
+        let mut solver = PrimalDual::from_instance(&instance, 0.4);
+        let outcome = solver.solve_to_utilization(0.995, 5);
+        assert_eq!(outcome.status, UtilizationStatus::Achieved);
+        let epsilons: Vec<f64> = outcome.attempts.iter().map(|attempt| attempt.epsilon).collect();
+        assert_eq!(epsilons, vec![0.4, 0.2, 0.1]);
+        assert!(outcome
+            .attempts
+            .windows(2)
+            .all(|pair| pair[1].epsilon == pair[0].epsilon / 2.0 && pair[0].utilization < 0.995));
+        assert!(outcome.attempts[2].utilization >= 0.995);
+        assert_eq!(solver.epsilon, 0.1);
+    }
+
+    #[test]
+    fn exhausted() {
+        // every bid could be spent, but no attempt spends all of them
+        let instance = generate(10, 60, 0.3, (20.0, 30.0), (1.0, 7.0), 1);
+        let mut solver = PrimalDual::from_instance(&instance, 0.4);
+        let outcome = solver.solve_to_utilization(1.0, 4);
+        assert_eq!(outcome.status, UtilizationStatus::Exhausted);
+        assert_eq!(outcome.upper_bound, 1.0);
+        assert_eq!(outcome.attempts.len(), 4);
+        assert!(outcome.attempts.iter().all(|attempt| attempt.utilization < 1.0));
+        assert_eq!(solver.epsilon, 0.05);
+        assert_eq!(solver.get_total_spend() / solver.get_total_budget(), outcome.attempts[3].utilization);
+    }
+
+    #[test]
+    fn unachievable() {
+        // every agent bids less than a third of its budget in total
+        let mut solver = generated_solver(0.01);
+        let outcome = solver.solve_to_utilization(0.92, 5);
+        assert_eq!(outcome.status, UtilizationStatus::Infeasible);
+        assert!(outcome.upper_bound < 0.92);
+        assert!(outcome.attempts.is_empty());
+    }
+
+    #[test]
+    fn agent_spends() {
+        let mut solver = generated_solver(2.0);
+        solver.set_agent_active(9, false);
+        solver.solve();
+
+        let spends: Vec<_> = solver.agent_spends().collect();
+        assert_eq!(spends.len(), 10);
+        let assignment = solver.assignment();
+        for spend in &spends {
+            let budget = solver.get_effective_budget(spend.agent_id);
+            assert_eq!(spend.consumption, assignment.spend(spend.agent_id));
+            assert_eq!(spend.remaining_budget, (budget - spend.consumption).max(0.0));
+            assert!(spend.value <= budget && spend.value <= spend.consumption);
+        }
+        assert!(spends.iter().any(|spend| spend.consumption > solver.get_effective_budget(spend.agent_id)));
+        assert_eq!(spends[9].value, 0.0);
+
+        let total: f64 = spends.iter().map(|spend| spend.value).sum();
+        assert!((total - solver.get_primal_objective_value()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,244 @@
+use super::{PrimalDual, Scalar};
+use crate::error_code::ErrorCode;
+use num_traits::Float;
+use std::fmt;
+
+// consumptions and the primal may drift from the bids by this fraction of their magnitude,
+// or by this many machine epsilons of the scalar type when that is larger
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+const SCALAR_EPSILONS: f64 = 1e3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolutionReport {
+    pub spends: Vec<f64>,     // spends[agent_id] = sum of the bids on the items in gamma[agent_id]
+    pub overspends: Vec<f64>, // overspends[agent_id] = spend - budget if positive, else 0
+    pub num_unassigned: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerifyError {
+    ItemOutOfRange { agent_id: usize, item_id: usize },
+    DuplicateItem { item_id: usize, agent_ids: (usize, usize) }, // held twice by one agent, or by two with capacity 1
+    CapacityExceeded { item_id: usize, capacity: usize },        // held by more than capacity agents
+    MissingBid { agent_id: usize, item_id: usize },              // the agent holds an item without a positive accepted bid on it
+    TooManyItems { agent_id: usize, num_items: usize, max_items: usize },
+    OverCapacity { agent_id: usize, used: f64, capacity: f64 }, // the sizes of its items exceed the capacity of the agent
+    ConsumptionDrift { agent_id: usize, recorded: f64, recomputed: f64 },
+    PrimalMismatch { reported: f64, recomputed: f64 },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::ItemOutOfRange { agent_id, item_id } => write!(f, "agent {} holds item {}, which is out of range", agent_id, item_id),
+            VerifyError::DuplicateItem { item_id, agent_ids } => write!(f, "item {} is held by both agent {} and agent {}", item_id, agent_ids.0, agent_ids.1),
+            VerifyError::CapacityExceeded { item_id, capacity } => write!(f, "item {} is held by more than {} agents", item_id, capacity),
+            VerifyError::MissingBid { agent_id, item_id } => write!(f, "agent {} holds item {} without a positive bid on it", agent_id, item_id),
+            VerifyError::TooManyItems { agent_id, num_items, max_items } => {
+                write!(f, "agent {} holds {} items but may hold at most {}", agent_id, num_items, max_items)
+            }
+            VerifyError::OverCapacity { agent_id, used, capacity } => write!(f, "agent {} uses {} of its capacity {}", agent_id, used, capacity),
+            VerifyError::ConsumptionDrift { agent_id, recorded, recomputed } => {
+                write!(f, "consumption of agent {} is {} but its bids sum to {}", agent_id, recorded, recomputed)
+            }
+            VerifyError::PrimalMismatch { reported, recomputed } => write!(f, "primal objective is {} but the spends sum to {}", reported, recomputed),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl VerifyError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            VerifyError::ItemOutOfRange { .. } => ErrorCode::ItemIndexOutOfRange,
+            VerifyError::DuplicateItem { .. } => ErrorCode::ItemAssignedTwice,
+            VerifyError::CapacityExceeded { .. } => ErrorCode::CapacityExceeded,
+            VerifyError::MissingBid { .. } => ErrorCode::MissingBid,
+            VerifyError::TooManyItems { .. } => ErrorCode::TooManyItems,
+            VerifyError::OverCapacity { .. } => ErrorCode::OverCapacity,
+            VerifyError::ConsumptionDrift { .. } => ErrorCode::ConsumptionDrift,
+            VerifyError::PrimalMismatch { .. } => ErrorCode::PrimalMismatch,
+        }
+    }
+}
+
+fn within_tolerance<T: Scalar>(a: f64, b: f64) -> bool {
+    let tolerance = RELATIVE_TOLERANCE.max(SCALAR_EPSILONS * <T as Float>::epsilon().as_f64());
+    (a - b).abs() <= tolerance * a.abs().max(b.abs()).max(1.0)
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // checks that gamma is an assignment of items to bidders within the item capacities, item caps and agent capacities, and that consumptions and the primal agree with it
+    pub fn verify_solution(&self) -> Result<SolutionReport, VerifyError> {
+        // owner[item_id] = last agent seen holding item_id, num_holders[item_id] = number of agents holding it
+        let mut owner: Vec<Option<usize>> = vec![None; self.num_items];
+        let mut num_holders = vec![0; self.num_items];
+        let mut spends = vec![0.0; self.num_agents];
+        for (agent_id, item_ids) in self.gamma.iter().enumerate() {
+            for &item_id in item_ids {
+                if item_id >= self.num_items {
+                    return Err(VerifyError::ItemOutOfRange { agent_id, item_id });
+                }
+                if let Some(other_id) = owner[item_id] {
+                    if other_id == agent_id || self.item_capacity[item_id] == 1 {
+                        return Err(VerifyError::DuplicateItem {
+                            item_id,
+                            agent_ids: (other_id, agent_id),
+                        });
+                    }
+                    if num_holders[item_id] == self.item_capacity[item_id] {
+                        return Err(VerifyError::CapacityExceeded {
+                            item_id,
+                            capacity: self.item_capacity[item_id],
+                        });
+                    }
+                }
+                owner[item_id] = Some(agent_id);
+                num_holders[item_id] += 1;
+
+                let bid = self.bid(agent_id, item_id);
+                if bid <= 0.0 {
+                    return Err(VerifyError::MissingBid { agent_id, item_id });
+                }
+                spends[agent_id] += bid;
+            }
+        }
+
+        for (agent_id, item_ids) in self.gamma.iter().enumerate() {
+            if let Some(max_items) = self.max_items[agent_id].filter(|&max_items| item_ids.len() > max_items) {
+                return Err(VerifyError::TooManyItems {
+                    agent_id,
+                    num_items: item_ids.len(),
+                    max_items,
+                });
+            }
+            if let Some(capacity) = self.agent_capacity[agent_id] {
+                let used = self.get_used_capacity(agent_id);
+                if used > capacity {
+                    return Err(VerifyError::OverCapacity { agent_id, used, capacity });
+                }
+            }
+        }
+
+        for (agent_id, &recomputed) in spends.iter().enumerate() {
+            let recorded = self.consumption(agent_id);
+            if !within_tolerance::<T>(recorded, recomputed) {
+                return Err(VerifyError::ConsumptionDrift { agent_id, recorded, recomputed });
+            }
+        }
+
+        // over every agent that holds items, so items left with an agent deactivated after the solve show up
+        let recomputed: f64 = (0..self.num_agents).map(|agent_id| spends[agent_id].min(self.effective_budget(agent_id))).sum();
+        let reported = self.get_primal_objective_value();
+        if !within_tolerance::<T>(reported, recomputed) {
+            return Err(VerifyError::PrimalMismatch { reported, recomputed });
+        }
+
+        let overspends = (0..self.num_agents)
+            .map(|agent_id| (spends[agent_id] - self.effective_budget(agent_id)).max(0.0))
+            .collect();
+        Ok(SolutionReport {
+            spends,
+            overspends,
+            num_unassigned: owner.iter().filter(|owner| owner.is_none()).count(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerifyError;
+    use crate::primal_dual::PrimalDual;
+
+    // item 3 has no bids and stays unassigned
+    fn solved() -> PrimalDual {
+        let mut solver = PrimalDual::new(2, 4, 0.01);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            solver.set_bid(agent_id, item_id, bid);
+        }
+        solver.solve();
+        solver
+    }
+
+    #[test]
+    fn consistent() {
+        let solver = solved();
+        let report = solver.verify_solution().unwrap();
+        assert_eq!(report.num_unassigned, 1);
+        for agent_id in 0..2 {
+            assert_eq!(report.spends[agent_id], solver.consumptions[agent_id]);
+            assert_eq!(report.overspends[agent_id], (solver.consumptions[agent_id] - solver.get_budget(agent_id)).max(0.0));
+        }
+        assert!(report.overspends.iter().any(|&overspend| overspend > 0.0));
+
+        let unsolved = PrimalDual::new(2, 4, 0.01);
+        let report = unsolved.verify_solution().unwrap();
+        assert_eq!(report.num_unassigned, 4);
+        assert_eq!(report.spends, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn tolerates_rounding() {
+        let mut solver = solved();
+        solver.consumptions[0] *= 1.0 + 1e-12;
+        assert!(solver.verify_solution().is_ok());
+    }
+
+    #[test]
+    fn item_out_of_range() {
+        let mut solver = solved();
+        solver.gamma[1].push_back(7);
+        assert_eq!(solver.verify_solution(), Err(VerifyError::ItemOutOfRange { agent_id: 1, item_id: 7 }));
+    }
+
+    #[test]
+    fn duplicate_item() {
+        let mut solver = solved();
+        let item_id = solver.gamma[0][0];
+        solver.gamma[1].push_back(item_id);
+        solver.consumptions[1] += solver.bid(1, item_id);
+        assert_eq!(solver.verify_solution(), Err(VerifyError::DuplicateItem { item_id, agent_ids: (0, 1) }));
+    }
+
+    #[test]
+    fn missing_bid() {
+        let mut solver = solved();
+        solver.gamma[0].push_back(3);
+        assert_eq!(solver.verify_solution(), Err(VerifyError::MissingBid { agent_id: 0, item_id: 3 }));
+    }
+
+    #[test]
+    fn consumption_drift() {
+        let mut solver = solved();
+        let recomputed = solver.consumptions[1];
+        solver.consumptions[1] += 1e-3;
+        assert_eq!(
+            solver.verify_solution(),
+            Err(VerifyError::ConsumptionDrift {
+                agent_id: 1,
+                recorded: recomputed + 1e-3,
+                recomputed
+            })
+        );
+    }
+
+    #[test]
+    fn moved_item_without_consumptions() {
+        let mut solver = solved();
+        let item_id = solver.gamma[1].pop_back().unwrap();
+        solver.gamma[0].push_back(item_id);
+        assert!(matches!(solver.verify_solution(), Err(VerifyError::ConsumptionDrift { agent_id: 0, .. })));
+    }
+
+    #[test]
+    fn primal_mismatch() {
+        let mut solver = solved();
+        // the primal leaves out inactive agents, but agent 1 still holds its items
+        solver.set_agent_active(1, false);
+        let error = solver.verify_solution().unwrap_err();
+        assert!(matches!(error, VerifyError::PrimalMismatch { .. }), "{}", error);
+    }
+}
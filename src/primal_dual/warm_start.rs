@@ -0,0 +1,145 @@
+use super::{PrimalDual, Scalar};
+
+impl<T: Scalar> PrimalDual<T> {
+    // the starting point of solve_warm after an earlier solve on this solver: alphas and gamma stay, and every item bid on since
+    // then moves to an agent of maximum price, as initialize would have placed it had the bid been there from the start.
+    // consumptions are recomputed from gamma, since set_bid may have raised a bid on an item that is held
+    pub(super) fn reinitialize(&mut self) {
+        let mut item_ids = std::mem::take(&mut self.new_bid_items);
+        item_ids.sort_unstable();
+        item_ids.dedup();
+
+        let holders = self.item_owner();
+        // items of capacity above 1 leave all their holders and go to the agents of the largest prices
+        let mut shared = vec![false; self.num_items];
+        for &item_id in &item_ids {
+            shared[item_id] = self.item_capacity[item_id] > 1;
+        }
+        if shared.contains(&true) {
+            for agent_id in 0..self.num_agents {
+                if self.has_observer() {
+                    for item_id in self.gamma[agent_id].clone().into_iter().filter(|&item_id| shared[item_id]) {
+                        self.emit_move(item_id, Some(agent_id), None);
+                    }
+                }
+                self.gamma[agent_id].retain(|&item_id| !shared[item_id]);
+            }
+        }
+
+        for &item_id in &item_ids {
+            if shared[item_id] {
+                for agent_id in self.top_price_agents(item_id, self.item_capacity[item_id]) {
+                    if !self.fits(agent_id, item_id, None) {
+                        continue;
+                    }
+                    self.gamma[agent_id].push_back(item_id);
+                    self.emit_move(item_id, None, Some(agent_id));
+                }
+                continue;
+            }
+            let owner = self.max_price_agent(item_id);
+            let owner = self.owner_with_room(holders[item_id], item_id, owner);
+            if owner == holders[item_id] {
+                continue;
+            }
+            if let Some(holder) = holders[item_id] {
+                self.gamma[holder].retain(|&id| id != item_id);
+            }
+            if let Some(agent_id) = owner {
+                self.gamma[agent_id].push_back(item_id);
+            }
+            self.emit_move(item_id, holders[item_id], owner);
+        }
+
+        for agent_id in 0..self.num_agents {
+            self.recompute_consumption(agent_id);
+        }
+        item_ids.clear();
+        self.new_bid_items = item_ids;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primal_dual::PrimalDual;
+
+    const NUM_AGENTS: usize = 20;
+    const NUM_ITEMS: usize = 300;
+
+    // bids of generated_bids(round) are new in round
+    fn generated_bids(round: usize) -> Vec<(usize, usize, f64)> {
+        let mut bids = Vec::new();
+        for agent_id in 0..NUM_AGENTS {
+            for item_id in 0..NUM_ITEMS {
+                if (agent_id * 7 + item_id * 3) % 5 == round {
+                    bids.push((agent_id, item_id, 1.0 + ((agent_id * 13 + item_id * 11) % 17) as f64));
+                }
+            }
+        }
+        bids
+    }
+
+    fn solver_with_rounds(rounds: &[usize]) -> PrimalDual {
+        let mut solver = PrimalDual::new(NUM_AGENTS, NUM_ITEMS, 0.01);
+        for agent_id in 0..NUM_AGENTS {
+            solver.set_budget(agent_id, 40.0 + (agent_id * 23 % 60) as f64);
+        }
+        for &round in rounds {
+            for (agent_id, item_id, bid) in generated_bids(round) {
+                solver.set_bid(agent_id, item_id, bid);
+            }
+        }
+        solver
+    }
+
+    #[test]
+    fn new_bids() {
+        let mut warm = solver_with_rounds(&[0, 1]);
+        warm.solve();
+        for round in 2..5 {
+            for (agent_id, item_id, bid) in generated_bids(round) {
+                warm.set_bid(agent_id, item_id, bid);
+            }
+            warm.solve_warm();
+            warm.verify_solution().unwrap();
+
+            let rounds: Vec<usize> = (0..=round).collect();
+            let mut cold = solver_with_rounds(&rounds);
+            cold.solve();
+
+            let warm_primal = warm.get_primal_objective_value();
+            let cold_primal = cold.get_primal_objective_value();
+            assert!(warm_primal >= warm.get_dual_objective_value() * warm.get_approximation_ratio());
+            // both are within the guarantee of the same optimum
+            assert!(warm_primal >= cold_primal * cold.get_approximation_ratio(), "{} {}", warm_primal, cold_primal);
+            assert!(cold_primal >= warm_primal * warm.get_approximation_ratio(), "{} {}", warm_primal, cold_primal);
+        }
+    }
+
+    #[test]
+    fn unchanged_instance() {
+        let mut solver = solver_with_rounds(&[0, 1, 2]);
+        solver.solve();
+        let solution = solver.extract_solution();
+
+        let stats = solver.solve_with_stats();
+        assert_eq!(solver.extract_solution(), solution);
+        assert_eq!(stats.sweeps, 1);
+        assert_eq!(stats.reallocations, 0);
+    }
+
+    #[test]
+    fn raised_bid_on_held_item() {
+        let mut solver = PrimalDual::new(2, 2, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bid(0, 0, 3.0);
+        solver.set_bid(1, 1, 3.0);
+        solver.solve();
+
+        solver.set_bid(0, 0, 5.0);
+        solver.solve_warm();
+        assert_eq!(solver.get_primal_objective_value(), 8.0);
+        solver.verify_solution().unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+use super::{PriceEntry, PrimalDual, Scalar, Stop};
+use std::io;
+use std::io::Write;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+const NUM_HOTSPOTS: usize = 5;
+
+#[derive(Clone, Copy, Debug)]
+pub struct WatchdogConfig {
+    pub soft_threshold: Duration,     // no snapshot is written before this much time has passed
+    pub every_sweeps: usize,          // after the threshold, a snapshot is written every every_sweeps sweeps
+    pub hard_limit: Option<Duration>, // the solve stops between two rounds of reallocation once this much time has passed
+}
+
+impl<T: Scalar> PrimalDual<T> {
+    // solve writing a one-line diagnostic snapshot to writer when the solve runs long
+    //
+    // snapshot: sweep, elapsed_ms, unpaid, hotspots (agent:overspend of the 5 agents furthest above U * budget), primal, dual, memory_bytes.
+    // between snapshots nothing is allocated.
+    // returns whether all agents are paid for, which is false only when the hard limit was hit
+    pub fn solve_with_watchdog<W: Write>(&mut self, config: WatchdogConfig, writer: &mut W) -> io::Result<bool> {
+        debug_assert!(config.every_sweeps > 0);
+
+        let start = Instant::now();
+        let deadline = config.hard_limit.map(|limit| start + limit);
+        let mut result = Ok(());
+        let mut last_dump = 0;
+
+        self.apply_unassignable_policy();
+        self.initialize();
+        let (_, completed) = self.run_with(Stop::at(deadline), |solver, num_sweeps| {
+            let elapsed = start.elapsed();
+            if result.is_err() || elapsed < config.soft_threshold || num_sweeps - last_dump < config.every_sweeps {
+                return;
+            }
+            last_dump = num_sweeps;
+            result = solver.write_snapshot(writer, num_sweeps, elapsed);
+        });
+
+        result.map(|_| completed)
+    }
+
+    // approximate number of bytes held by the solver: what check_memory counts, with the heaps at their capacity, which
+    // outgrows the bids as raised prices push new entries, and the items held in gamma
+    pub fn estimate_memory_bytes(&self) -> usize {
+        let num_heap_entries: usize = self.item_agent.iter().map(|heap| heap.capacity()).sum();
+        let num_gamma_entries: usize = self.gamma.iter().map(|item_ids| item_ids.capacity()).sum();
+        // bid_bytes counts one heap entry per bid, the heaps are counted whole
+        let bid_bytes = Self::bid_bytes() - size_of::<PriceEntry<T>>();
+        Self::construction_bytes(self.num_agents, self.num_items)
+            + self.num_accepted_bids * bid_bytes
+            + num_heap_entries * size_of::<PriceEntry<T>>()
+            + num_gamma_entries * size_of::<usize>()
+    }
+
+    fn write_snapshot<W: Write>(&self, writer: &mut W, num_sweeps: usize, elapsed: Duration) -> io::Result<()> {
+        let mut num_unpaid = 0;
+        let mut hotspots = [(0.0f64, usize::MAX); NUM_HOTSPOTS]; // sorted by overspend descending
+        for agent_id in 0..self.num_agents {
+            if self.is_paid_for(agent_id) {
+                continue;
+            }
+            num_unpaid += 1;
+
+            let overspend = self.consumption(agent_id) - self.U(agent_id) * self.effective_budget(agent_id);
+            if let Some(position) = hotspots.iter().position(|&(value, id)| id == usize::MAX || overspend > value) {
+                hotspots[position..].rotate_right(1);
+                hotspots[position] = (overspend, agent_id);
+            }
+        }
+
+        write!(writer, "sweep={} elapsed_ms={} unpaid={} hotspots=", num_sweeps, elapsed.as_millis(), num_unpaid)?;
+        for (i, &(overspend, agent_id)) in hotspots.iter().take_while(|&&(_, id)| id != usize::MAX).enumerate() {
+            write!(writer, "{}{}:{}", if i == 0 { "" } else { "," }, agent_id, overspend)?;
+        }
+        writeln!(writer, " primal={} dual={} memory_bytes={}", self.get_primal_objective_value(), self.get_dual_objective_value(), self.estimate_memory_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WatchdogConfig;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn generated_solver() -> PrimalDual {
+        PrimalDual::from_instance(&generate(20, 200, 0.9, (10.0, 50.0), (1.0, 10.0), 229), 0.01)
+    }
+
+    #[test]
+    fn dumps() {
+        let mut solver = generated_solver();
+        let config = WatchdogConfig {
+            soft_threshold: Duration::ZERO,
+            every_sweeps: 1,
+            hard_limit: None,
+        };
+        let mut buffer = Vec::new();
+        assert!(solver.solve_with_watchdog(config, &mut buffer).unwrap());
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines.len() >= 2);
+
+        let mut previous_sweep = 0;
+        for line in lines {
+            let fields: HashMap<&str, &str> = line.split(' ').map(|field| field.split_once('=').unwrap()).collect();
+            let sweep: usize = fields["sweep"].parse().unwrap();
+            let unpaid: usize = fields["unpaid"].parse().unwrap();
+            let primal: f64 = fields["primal"].parse().unwrap();
+            let dual: f64 = fields["dual"].parse().unwrap();
+            let hotspots: Vec<f64> = fields["hotspots"]
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.split_once(':').unwrap().1.parse().unwrap())
+                .collect();
+
+            assert!(sweep > previous_sweep);
+            previous_sweep = sweep;
+            assert_eq!(hotspots.len(), unpaid.min(5));
+            assert!(hotspots.iter().all(|&overspend| overspend > 0.0));
+            assert!(hotspots.windows(2).all(|pair| pair[0] >= pair[1]));
+            assert!(primal <= dual + 1e-9);
+            assert!(fields["memory_bytes"].parse::<usize>().unwrap() >= 20 * 180 * 8);
+        }
+        // the last sweep finds every agent paid for
+        assert!(output.lines().last().unwrap().contains("unpaid=0"));
+    }
+
+    #[test]
+    fn memory_estimate() {
+        let instance = generate(20, 200, 0.9, (10.0, 50.0), (1.0, 10.0), 229);
+        let solver = PrimalDual::from_instance(&instance, 0.01);
+        let num_bids: usize = solver.agent_items.iter().map(Vec::len).sum();
+        assert!(solver.estimate_memory_bytes() >= PrimalDual::<f64>::construction_bytes(20, 200) + num_bids * PrimalDual::<f64>::bid_bytes());
+
+        // the heap entries and the scalars of a solver of f32 are smaller
+        let narrow = PrimalDual::<f32>::from_instance_scalar(&instance, 0.01);
+        assert!(narrow.estimate_memory_bytes() < solver.estimate_memory_bytes());
+    }
+
+    #[test]
+    fn quiet_before_threshold() {
+        let mut solver = generated_solver();
+        let config = WatchdogConfig {
+            soft_threshold: Duration::from_secs(3600),
+            every_sweeps: 1,
+            hard_limit: None,
+        };
+        let mut buffer = Vec::new();
+        assert!(solver.solve_with_watchdog(config, &mut buffer).unwrap());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn hard_limit() {
+        let mut solver = generated_solver();
+        let config = WatchdogConfig {
+            soft_threshold: Duration::ZERO,
+            every_sweeps: 1,
+            hard_limit: Some(Duration::ZERO),
+        };
+        let mut buffer = Vec::new();
+        assert!(!solver.solve_with_watchdog(config, &mut buffer).unwrap());
+    }
+}
@@ -0,0 +1,198 @@
+// python bindings, built as an extension module by maturin with the extension-module feature (see pyproject.toml):
+//
+//   from maximum_budgeted_allocation import PrimalDual
+//   solver = PrimalDual(2, 3, 0.01)
+//   solver.set_budget(0, 10.0)
+//   solver.set_bids([(0, 0, 4.0), (0, 1, 3.0)])           # or set_bid_arrays(agent_ids, item_ids, bids) with numpy arrays
+//   solver.solve()
+//   solver.assignment()                                    # {0: [0, 1], 1: []}
+//
+// invalid input raises IndexError for an index out of range, RuntimeError for a setter that is only allowed before the
+// first solve and ValueError otherwise, with the message of the MbaError
+use crate::error_code::ErrorCode;
+use crate::primal_dual::{self, MbaError};
+use pyo3::exceptions::{PyIndexError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, VecDeque};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExceptionKind {
+    Index,
+    Value,
+    Runtime,
+}
+
+fn exception_kind(code: ErrorCode) -> ExceptionKind {
+    match code {
+        ErrorCode::AgentIndexOutOfRange | ErrorCode::ItemIndexOutOfRange => ExceptionKind::Index,
+        ErrorCode::AlreadySolved => ExceptionKind::Runtime,
+        _ => ExceptionKind::Value,
+    }
+}
+
+fn to_py_err(error: MbaError) -> PyErr {
+    let message = error.to_string();
+    match exception_kind(error.code()) {
+        ExceptionKind::Index => PyIndexError::new_err(message),
+        ExceptionKind::Value => PyValueError::new_err(message),
+        ExceptionKind::Runtime => PyRuntimeError::new_err(message),
+    }
+}
+
+// the bids of three parallel columns, which must have the same length
+fn zip_columns(agent_ids: Vec<usize>, item_ids: Vec<usize>, bids: Vec<f64>) -> Result<Vec<(usize, usize, f64)>, String> {
+    if agent_ids.len() != item_ids.len() || agent_ids.len() != bids.len() {
+        return Err(format!("agent_ids, item_ids and bids have lengths {}, {} and {}", agent_ids.len(), item_ids.len(), bids.len()));
+    }
+    Ok(agent_ids
+        .into_iter()
+        .zip(item_ids)
+        .zip(bids)
+        .map(|((agent_id, item_id), bid)| (agent_id, item_id, bid))
+        .collect())
+}
+
+// agent_id -> its items in the order they were assigned, with an entry for every agent
+fn assignment_map(gamma: &[VecDeque<usize>]) -> BTreeMap<usize, Vec<usize>> {
+    gamma
+        .iter()
+        .enumerate()
+        .map(|(agent_id, item_ids)| (agent_id, item_ids.iter().copied().collect()))
+        .collect()
+}
+
+#[pyclass(name = "PrimalDual", module = "maximum_budgeted_allocation")]
+pub struct PyPrimalDual {
+    solver: primal_dual::PrimalDual,
+}
+
+#[pymethods]
+impl PyPrimalDual {
+    #[new]
+    fn new(num_agents: usize, num_items: usize, epsilon: f64) -> PyResult<Self> {
+        let solver = primal_dual::PrimalDual::try_new(num_agents, num_items, epsilon).map_err(|e| to_py_err(e.into()))?;
+        Ok(PyPrimalDual { solver })
+    }
+
+    #[getter]
+    fn num_agents(&self) -> usize {
+        self.solver.num_agents()
+    }
+
+    #[getter]
+    fn num_items(&self) -> usize {
+        self.solver.num_items()
+    }
+
+    fn set_budget(&mut self, agent_id: usize, budget: f64) -> PyResult<()> {
+        self.solver.try_set_budget(agent_id, budget).map_err(to_py_err)
+    }
+
+    fn get_budget(&self, agent_id: usize) -> PyResult<f64> {
+        if agent_id >= self.solver.num_agents() {
+            return Err(to_py_err(MbaError::AgentIndexOutOfRange {
+                agent_id,
+                num_agents: self.solver.num_agents(),
+            }));
+        }
+        Ok(self.solver.get_budget(agent_id))
+    }
+
+    fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: f64) -> PyResult<()> {
+        self.solver.try_set_bid(agent_id, item_id, bid).map_err(to_py_err)
+    }
+
+    // a sequence of (agent_id, item_id, bid) triples
+    fn set_bids(&mut self, bids: Vec<(usize, usize, f64)>) -> PyResult<()> {
+        self.solver.try_set_bids(bids).map_err(to_py_err)
+    }
+
+    // three parallel sequences, e.g. numpy arrays of integers and floats
+    fn set_bid_arrays(&mut self, agent_ids: Vec<usize>, item_ids: Vec<usize>, bids: Vec<f64>) -> PyResult<()> {
+        let bids = zip_columns(agent_ids, item_ids, bids).map_err(PyValueError::new_err)?;
+        self.solver.try_set_bids(bids).map_err(to_py_err)
+    }
+
+    // releases the gil, the solver is only touched through this object
+    fn solve(&mut self, py: Python<'_>) {
+        py.allow_threads(|| self.solver.solve());
+    }
+
+    #[getter]
+    fn primal_objective_value(&self) -> f64 {
+        self.solver.get_primal_objective_value()
+    }
+
+    #[getter]
+    fn dual_objective_value(&self) -> f64 {
+        self.solver.get_dual_objective_value()
+    }
+
+    #[getter]
+    fn approximation_ratio(&self) -> f64 {
+        self.solver.get_approximation_ratio()
+    }
+
+    fn assignment(&self) -> BTreeMap<usize, Vec<usize>> {
+        assignment_map(self.solver.get_assignment())
+    }
+
+    // agent of each item, None if it is unassigned
+    fn item_assignment(&self) -> Vec<Option<usize>> {
+        self.solver.get_item_assignment()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PrimalDual(num_agents={}, num_items={})", self.solver.num_agents(), self.solver.num_items())
+    }
+}
+
+#[pymodule]
+fn maximum_budgeted_allocation(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyPrimalDual>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assignment_map, exception_kind, zip_columns, ExceptionKind};
+    use crate::error_code::ErrorCode;
+    use crate::primal_dual::{MbaError, PrimalDual};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn columns() {
+        assert_eq!(zip_columns(vec![0, 1], vec![2, 3], vec![1.5, 2.5]), Ok(vec![(0, 2, 1.5), (1, 3, 2.5)]));
+        assert_eq!(zip_columns(vec![], vec![], vec![]), Ok(vec![]));
+        assert_eq!(zip_columns(vec![0, 1], vec![2], vec![1.5, 2.5]), Err("agent_ids, item_ids and bids have lengths 2, 1 and 2".to_string()));
+        assert!(zip_columns(vec![0], vec![2], vec![]).is_err());
+    }
+
+    #[test]
+    fn assignment() {
+        let mut solver = PrimalDual::new(3, 3, 0.01);
+        solver.set_budget(0, 10.0);
+        solver.set_budget(1, 10.0);
+        solver.set_bids([(0, 0, 4.0), (0, 2, 3.0), (1, 1, 2.0)]);
+        solver.solve();
+
+        let map = assignment_map(solver.get_assignment());
+        assert_eq!(map, BTreeMap::from([(0, vec![0, 2]), (1, vec![1]), (2, vec![])]));
+    }
+
+    #[test]
+    fn exceptions() {
+        let error = MbaError::ItemIndexOutOfRange { item_id: 3, num_items: 3 };
+        assert_eq!(exception_kind(error.code()), ExceptionKind::Index);
+        assert_eq!(exception_kind(MbaError::AlreadySolved.code()), ExceptionKind::Runtime);
+        assert_eq!(exception_kind(ErrorCode::AgentIndexOutOfRange), ExceptionKind::Index);
+        for code in [
+            ErrorCode::InvalidEpsilon,
+            ErrorCode::InvalidBudget,
+            ErrorCode::InvalidBid,
+            ErrorCode::BidExceedsBudget,
+            ErrorCode::TooLargeForExact,
+        ] {
+            assert_eq!(exception_kind(code), ExceptionKind::Value);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// deterministic summation
+//
+// values are split into fixed chunks, each chunk is summed left to right, and chunk sums are combined pairwise in a fixed tree,
+// so the result only depends on the chunk size and not on the number of threads or their scheduling
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 14;
+
+pub(crate) fn sum_serial<F: Fn(usize) -> f64>(n: usize, chunk_size: usize, f: F) -> f64 {
+    let chunk_sums: Vec<f64> = (0..n.div_ceil(chunk_size)).map(|chunk| chunk_sum(n, chunk_size, chunk, &f)).collect();
+    pairwise(&chunk_sums)
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn sum_parallel<F: Fn(usize) -> f64 + Sync>(n: usize, chunk_size: usize, f: F) -> f64 {
+    let chunk_sums: Vec<f64> = (0..n.div_ceil(chunk_size)).into_par_iter().map(|chunk| chunk_sum(n, chunk_size, chunk, &f)).collect();
+    pairwise(&chunk_sums)
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn sum<F: Fn(usize) -> f64 + Sync>(n: usize, chunk_size: usize, f: F) -> f64 {
+    if n <= chunk_size {
+        return sum_serial(n, chunk_size, f);
+    }
+    sum_parallel(n, chunk_size, f)
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn sum<F: Fn(usize) -> f64>(n: usize, chunk_size: usize, f: F) -> f64 {
+    sum_serial(n, chunk_size, f)
+}
+
+fn chunk_sum<F: Fn(usize) -> f64>(n: usize, chunk_size: usize, chunk: usize, f: &F) -> f64 {
+    let begin = chunk * chunk_size;
+    let end = (begin + chunk_size).min(n);
+    (begin..end).map(f).sum()
+}
+
+fn pairwise(values: &[f64]) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        1 => values[0],
+        len => pairwise(&values[..len / 2]) + pairwise(&values[len / 2..]),
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::{sum_parallel, sum_serial};
+
+    #[test]
+    fn bit_identical() {
+        // xorshift so the values have no structure that would make every order agree
+        let mut state: u64 = 88172645463325252;
+        for _ in 0..100 {
+            let n = (state % 100_000) as usize + 1;
+            let values: Vec<f64> = (0..n)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state % 1_000_000) as f64 / 7.0
+                })
+                .collect();
+
+            for chunk_size in [1, 64, 4096] {
+                let serial = sum_serial(n, chunk_size, |i| values[i]);
+                let parallel = sum_parallel(n, chunk_size, |i| values[i]);
+                assert_eq!(serial.to_bits(), parallel.to_bits());
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// one seed for a whole experiment
+//
+// every randomized component asks for its own stream with a fixed tag;
+// the stream seed is splitmix64(seed ^ fnv1a(tag)), so components never share a sequence
+// and adding a component does not shift the numbers drawn by the others
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RngConfig {
+    pub seed: u64,
+}
+
+impl RngConfig {
+    pub fn new(seed: u64) -> Self {
+        RngConfig { seed }
+    }
+
+    // non-reproducible seed
+    pub fn from_entropy() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos()));
+        RngConfig { seed: hasher.finish() }
+    }
+
+    pub fn stream(&self, tag: &str) -> Rng {
+        Rng::new(splitmix64(self.seed ^ fnv1a(tag)))
+    }
+}
+
+// splitmix64 generator
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        splitmix64(self.state)
+    }
+
+    // uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // uniform in [low, high)
+    pub fn range_f64(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    // uniform in [low, high)
+    pub fn range_usize(&mut self, low: usize, high: usize) -> usize {
+        debug_assert!(low < high);
+        low + (self.next_u64() % (high - low) as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.range_usize(0, i + 1));
+        }
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn fnv1a(tag: &str) -> u64 {
+    tag.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RngConfig;
+
+    fn draw(config: RngConfig, tag: &str) -> Vec<u64> {
+        let mut rng = config.stream(tag);
+        (0..16).map(|_| rng.next_u64()).collect()
+    }
+
+    #[test]
+    fn reproducible() {
+        assert_eq!(draw(RngConfig::new(722), "generator"), draw(RngConfig::new(722), "generator"));
+        assert_ne!(draw(RngConfig::new(722), "generator"), draw(RngConfig::new(723), "generator"));
+        assert_ne!(draw(RngConfig::new(722), "generator"), draw(RngConfig::new(722), "shuffle"));
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = RngConfig::new(1).stream("ranges");
+        for _ in 0..1000 {
+            let value = rng.range_f64(2.0, 3.0);
+            assert!((2.0..3.0).contains(&value));
+            assert!(rng.range_usize(5, 8) < 8);
+        }
+
+        let mut values: Vec<usize> = (0..100).collect();
+        rng.shuffle(&mut values);
+        values.sort();
+        assert_eq!(values, (0..100).collect::<Vec<usize>>());
+    }
+}
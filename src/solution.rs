@@ -0,0 +1,172 @@
+use crate::error_code::ErrorCode;
+use std::io;
+use std::io::Write;
+
+// result of a solve, independent of the solver that produced it
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution {
+    pub assignment: Vec<Vec<usize>>,    // assignment[agent_id] = [item_id, ...]
+    pub item_owner: Vec<Option<usize>>, // item_owner[item_id] = agent_id, the highest one for an item held by several agents
+    pub spends: Vec<f64>,               // spends[agent_id] = sum of bids on assigned items
+    pub primal: f64,
+    pub dual: f64,
+    pub epsilon: f64,
+    pub beta: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SolutionError {
+    ItemAssignedTwice { item_id: usize },
+    ItemOutOfRange { item_id: usize },
+    OwnerMismatch { item_id: usize },
+    NegativeSpend { agent_id: usize },
+    PrimalExceedsDual,
+}
+
+impl SolutionError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SolutionError::ItemAssignedTwice { .. } => ErrorCode::ItemAssignedTwice,
+            SolutionError::ItemOutOfRange { .. } => ErrorCode::ItemIndexOutOfRange,
+            SolutionError::OwnerMismatch { .. } => ErrorCode::ItemOwnerMismatch,
+            SolutionError::NegativeSpend { .. } => ErrorCode::NegativeSpend,
+            SolutionError::PrimalExceedsDual => ErrorCode::PrimalExceedsDual,
+        }
+    }
+}
+
+impl Solution {
+    pub fn num_agents(&self) -> usize {
+        self.assignment.len()
+    }
+
+    pub fn num_items(&self) -> usize {
+        self.item_owner.len()
+    }
+
+    // that of the solver the solution comes from: 1 for epsilon 0, which is the exact optimum
+    pub fn get_approximation_ratio(&self) -> f64 {
+        if self.epsilon == 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.beta / 4.0) * (1.0 - self.epsilon)
+    }
+
+    // checks that assignment, item_owner and the objectives agree with each other
+    pub fn verify(&self) -> Result<(), SolutionError> {
+        self.verify_with_capacities(&vec![1; self.num_items()])
+    }
+
+    // verify for items that may be held by up to capacities[item_id] distinct agents; item_owner then names the highest of them
+    pub fn verify_with_capacities(&self, capacities: &[usize]) -> Result<(), SolutionError> {
+        debug_assert_eq!(capacities.len(), self.num_items());
+        let mut owner = vec![None; self.num_items()];
+        let mut num_holders = vec![0; self.num_items()];
+        for (agent_id, item_ids) in self.assignment.iter().enumerate() {
+            for &item_id in item_ids {
+                if item_id >= self.num_items() {
+                    return Err(SolutionError::ItemOutOfRange { item_id });
+                }
+                if owner[item_id] == Some(agent_id) || num_holders[item_id] == capacities[item_id] {
+                    return Err(SolutionError::ItemAssignedTwice { item_id });
+                }
+                owner[item_id] = Some(agent_id);
+                num_holders[item_id] += 1;
+            }
+        }
+
+        if let Some(item_id) = (0..self.num_items()).find(|&item_id| owner[item_id] != self.item_owner[item_id]) {
+            return Err(SolutionError::OwnerMismatch { item_id });
+        }
+
+        if let Some(agent_id) = self.spends.iter().position(|&spend| spend < 0.0) {
+            return Err(SolutionError::NegativeSpend { agent_id });
+        }
+
+        if self.primal > self.dual * (1.0 + 1e-9) {
+            return Err(SolutionError::PrimalExceedsDual);
+        }
+
+        Ok(())
+    }
+
+    // writes one "agent_id,item_id" line per assigned item
+    pub fn write_assignment<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "agent_id,item_id")?;
+        for (agent_id, item_ids) in self.assignment.iter().enumerate() {
+            for item_id in item_ids {
+                writeln!(writer, "{},{}", agent_id, item_id)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Solution, SolutionError};
+    use crate::primal_dual::PrimalDual;
+
+    fn solve_sample() -> Solution {
+        let mut solver = PrimalDual::new(2, 3, 0.01);
+
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+
+        solver.set_bid(0, 0, 200.0);
+        solver.set_bid(0, 1, 200.0);
+        solver.set_bid(0, 2, 100.0);
+
+        solver.set_bid(1, 0, 100.0);
+        solver.set_bid(1, 1, 100.0);
+        solver.set_bid(1, 2, 200.0);
+
+        solver.solve();
+        solver.extract_solution()
+    }
+
+    #[test]
+    fn extract() {
+        let solution = solve_sample();
+        assert_eq!(solution.primal, 500.0);
+        assert!(solution.primal >= solution.dual * solution.get_approximation_ratio());
+        assert_eq!(solution.verify(), Ok(()));
+
+        let mut buffer = Vec::new();
+        solution.write_assignment(&mut buffer).unwrap();
+        let csv = String::from_utf8(buffer).unwrap();
+        assert_eq!(csv.lines().count(), 1 + solution.item_owner.iter().flatten().count());
+    }
+
+    #[test]
+    fn exact_ratio() {
+        let mut solver = PrimalDual::new(2, 3, 0.0);
+        solver.set_budget(0, 300.0);
+        solver.set_budget(1, 400.0);
+        solver.set_agent_epsilon(1, 0.1);
+        solver.set_bids([(0, 0, 200.0), (0, 1, 200.0), (1, 1, 100.0), (1, 2, 200.0)]);
+        solver.solve();
+        let solution = solver.extract_solution();
+        assert_eq!(solution.epsilon, 0.0);
+        assert_eq!(solution.get_approximation_ratio(), 1.0);
+        assert_eq!(solution.get_approximation_ratio(), solver.get_approximation_ratio());
+    }
+
+    #[test]
+    fn verify_corrupted() {
+        let mut solution = solve_sample();
+        let item_id = solution.assignment[0][0];
+        solution.assignment[1].push(item_id);
+        assert_eq!(solution.verify(), Err(SolutionError::ItemAssignedTwice { item_id }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let solution = solve_sample();
+        let json = serde_json::to_string(&solution).unwrap();
+        let restored: Solution = serde_json::from_str(&json).unwrap();
+        assert_eq!(solution, restored);
+    }
+}
@@ -0,0 +1,166 @@
+// common interface of the algorithms, so that callers can pick one at run time through Box<dyn MbaSolver>
+use crate::assignment::Assignment;
+use crate::error_code::ErrorCode;
+use crate::greedy::Greedy;
+use crate::primal_dual::{BuildError, PrimalDual, Scalar};
+use crate::solution::Solution;
+use std::fmt;
+
+pub trait MbaSolver {
+    fn set_budget(&mut self, agent_id: usize, budget: f64);
+    fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: f64);
+    fn solve(&mut self) -> Solution;
+    // items held by every agent after the last solve
+    fn assignment(&self) -> Assignment;
+    // primal objective of the last solve
+    fn objective(&self) -> f64;
+}
+
+impl<T: Scalar> MbaSolver for PrimalDual<T> {
+    fn set_budget(&mut self, agent_id: usize, budget: f64) {
+        PrimalDual::set_budget(self, agent_id, T::from_f64(budget));
+    }
+
+    fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: f64) {
+        PrimalDual::set_bid(self, agent_id, item_id, T::from_f64(bid));
+    }
+
+    fn solve(&mut self) -> Solution {
+        PrimalDual::solve(self);
+        self.extract_solution()
+    }
+
+    fn assignment(&self) -> Assignment {
+        PrimalDual::assignment(self)
+    }
+
+    fn objective(&self) -> f64 {
+        self.get_primal_objective_value()
+    }
+}
+
+impl MbaSolver for Greedy {
+    fn set_budget(&mut self, agent_id: usize, budget: f64) {
+        Greedy::set_budget(self, agent_id, budget);
+    }
+
+    fn set_bid(&mut self, agent_id: usize, item_id: usize, bid: f64) {
+        Greedy::set_bid(self, agent_id, item_id, bid);
+    }
+
+    fn solve(&mut self) -> Solution {
+        Greedy::solve(self);
+        self.extract_solution()
+    }
+
+    fn assignment(&self) -> Assignment {
+        Greedy::assignment(self)
+    }
+
+    fn objective(&self) -> f64 {
+        self.get_primal_objective_value()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpecError {
+    UnknownSolver { name: String },
+    InvalidEpsilon { text: String },
+    Build(BuildError),
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecError::UnknownSolver { name } => write!(f, "unknown solver {:?}, expected primal_dual, primal_dual_f32 or greedy", name),
+            SpecError::InvalidEpsilon { text } => write!(f, "epsilon {:?} is not a number", text),
+            SpecError::Build(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+impl SpecError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SpecError::UnknownSolver { .. } => ErrorCode::UnknownSolver,
+            SpecError::InvalidEpsilon { .. } => ErrorCode::InvalidEpsilon,
+            SpecError::Build(e) => e.code(),
+        }
+    }
+}
+
+// solver named by spec: "greedy", or "primal_dual" / "primal_dual_f32" optionally followed by ":epsilon", e.g. "primal_dual:0.05".
+// epsilon defaults to 0.01
+pub fn from_spec(spec: &str, num_agents: usize, num_items: usize) -> Result<Box<dyn MbaSolver>, SpecError> {
+    let (name, epsilon) = match spec.split_once(':') {
+        Some((name, text)) => (name, text.trim().parse().map_err(|_| SpecError::InvalidEpsilon { text: text.to_string() })?),
+        None => (spec, 0.01),
+    };
+    match name.trim() {
+        "primal_dual" => Ok(Box::new(PrimalDual::try_new(num_agents, num_items, epsilon).map_err(SpecError::Build)?)),
+        "primal_dual_f32" => Ok(Box::new(PrimalDual::<f32>::try_new_scalar(num_agents, num_items, epsilon).map_err(SpecError::Build)?)),
+        "greedy" => Ok(Box::new(Greedy::new(num_agents, num_items))),
+        name => Err(SpecError::UnknownSolver { name: name.to_string() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_spec, MbaSolver, SpecError};
+    use crate::exact;
+    use crate::rng::RngConfig;
+
+    #[test]
+    fn same_instance_through_dyn() {
+        let mut rng = RngConfig::new(271).stream("same_instance_through_dyn");
+        let num_agents = 4;
+        let num_items = 9;
+        let budgets: Vec<f64> = (0..num_agents).map(|_| rng.range_f64(10.0, 40.0)).collect();
+        let mut bids = vec![vec![0.0; num_items]; num_agents];
+        for (agent_id, row) in bids.iter_mut().enumerate() {
+            for bid in row.iter_mut() {
+                if rng.next_f64() < 0.7 {
+                    *bid = rng.range_f64(1.0, 15.0).min(budgets[agent_id]);
+                }
+            }
+        }
+        let (optimum, _) = exact::solve(&budgets, &bids).unwrap();
+
+        let mut solvers: Vec<Box<dyn MbaSolver>> = ["primal_dual:0.01", "primal_dual_f32", "greedy"]
+            .iter()
+            .map(|spec| from_spec(spec, num_agents, num_items).unwrap())
+            .collect();
+        let mut objectives = Vec::new();
+        for solver in &mut solvers {
+            for (agent_id, &budget) in budgets.iter().enumerate() {
+                solver.set_budget(agent_id, budget);
+            }
+            for (agent_id, row) in bids.iter().enumerate() {
+                for (item_id, &bid) in row.iter().enumerate().filter(|&(_, &bid)| bid > 0.0) {
+                    solver.set_bid(agent_id, item_id, bid);
+                }
+            }
+
+            let solution = solver.solve();
+            assert_eq!(solution.primal, solver.objective());
+            // f32 sums its consumptions in f32
+            assert!((solver.assignment().total_value() - solver.objective()).abs() <= 1e-6 * optimum);
+            objectives.push(solver.objective());
+        }
+
+        // primal-dual within its guarantee, greedy within 1/2
+        assert!(objectives[0] >= optimum * 0.99 * 0.75 - 1e-9);
+        assert!(objectives[1] >= optimum * 0.99 * 0.75 * (1.0 - 1e-4));
+        assert!(objectives[2] >= optimum / 2.0 - 1e-9);
+        assert!(objectives.iter().all(|&objective| objective <= optimum * (1.0 + 1e-6)));
+    }
+
+    #[test]
+    fn invalid_specs() {
+        assert!(matches!(from_spec("simplex", 1, 1), Err(SpecError::UnknownSolver { .. })));
+        assert!(matches!(from_spec("primal_dual:small", 1, 1), Err(SpecError::InvalidEpsilon { .. })));
+        assert!(matches!(from_spec("primal_dual:1.5", 1, 1), Err(SpecError::Build(_))));
+    }
+}
@@ -0,0 +1,109 @@
+// several epsilons on one instance, keeping the best assignment found.
+// the solver is built once and cloned per epsilon; with the rayon feature the solves run in parallel
+use crate::assignment::Assignment;
+use crate::instance::Instance;
+use crate::primal_dual::PrimalDual;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepRun {
+    pub epsilon: f64,
+    pub primal: f64,
+    pub dual: f64,
+    pub ratio: f64, // guaranteed approximation ratio of the run
+    pub time: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepResult {
+    pub runs: Vec<SweepRun>, // in the order of the epsilons
+    pub best: usize,         // index into runs of the largest primal, the first of equal ones
+    pub best_assignment: Assignment,
+}
+
+impl SweepResult {
+    pub fn best_run(&self) -> &SweepRun {
+        &self.runs[self.best]
+    }
+
+    // smallest dual over the runs, each of which bounds the optimum
+    pub fn best_dual(&self) -> f64 {
+        self.runs.iter().map(|run| run.dual).fold(f64::INFINITY, f64::min)
+    }
+}
+
+// panics for no epsilons or one PrimalDual::new rejects
+pub fn solve_multi(instance: &Instance, epsilons: &[f64]) -> SweepResult {
+    assert!(!epsilons.is_empty(), "solve_multi needs at least one epsilon");
+    let built = PrimalDual::from_instance(instance, epsilons[0]);
+    let solve = |&epsilon: &f64| {
+        let mut solver = built.clone();
+        solver.set_epsilon(epsilon);
+        let start = Instant::now();
+        solver.solve();
+        let time = start.elapsed();
+        let run = SweepRun {
+            epsilon,
+            primal: solver.get_primal_objective_value(),
+            dual: solver.get_dual_objective_value(),
+            ratio: solver.get_approximation_ratio(),
+            time,
+        };
+        (run, solver.assignment())
+    };
+
+    #[cfg(feature = "rayon")]
+    let results: Vec<(SweepRun, Assignment)> = epsilons.par_iter().map(solve).collect();
+    #[cfg(not(feature = "rayon"))]
+    let results: Vec<(SweepRun, Assignment)> = epsilons.iter().map(solve).collect();
+
+    let mut best = 0;
+    for (index, (run, _)) in results.iter().enumerate() {
+        if run.primal > results[best].0.primal {
+            best = index;
+        }
+    }
+    let (runs, mut assignments): (Vec<SweepRun>, Vec<Assignment>) = results.into_iter().unzip();
+    SweepResult {
+        runs,
+        best,
+        best_assignment: assignments.swap_remove(best),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve_multi;
+    use crate::generator::generate;
+    use crate::primal_dual::PrimalDual;
+
+    #[test]
+    fn best_of_the_runs() {
+        let epsilons = [0.1, 0.05, 0.01, 0.001];
+        for seed in 0..5 {
+            let instance = generate(8, 80, 0.5, (20.0, 80.0), (1.0, 20.0), seed);
+            let result = solve_multi(&instance, &epsilons);
+
+            assert_eq!(result.runs.iter().map(|run| run.epsilon).collect::<Vec<_>>(), epsilons);
+            let max = result.runs.iter().map(|run| run.primal).fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(result.best_run().primal, max);
+            assert_eq!(result.best_assignment.total_value(), max);
+            assert!(max <= result.best_dual() + 1e-9);
+
+            for run in &result.runs {
+                let mut solver = PrimalDual::from_instance(&instance, run.epsilon);
+                solver.solve();
+                assert_eq!(run.primal, solver.get_primal_objective_value());
+                assert_eq!(run.ratio, solver.get_approximation_ratio());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one epsilon")]
+    fn no_epsilons() {
+        solve_multi(&generate(2, 2, 1.0, (1.0, 2.0), (1.0, 2.0), 0), &[]);
+    }
+}
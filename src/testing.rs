@@ -0,0 +1,230 @@
+// differential testing of the solvers of the crate against each other
+//
+// neither greedy nor exact::solve shares code with PrimalDual, so that a bug in one does not hide in the other
+use crate::exact;
+use crate::greedy::Greedy;
+use crate::instance::Instance;
+use crate::primal_dual::{OverbidPolicy, PrimalDual};
+use crate::solution::Solution;
+use std::fmt;
+
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Clone, Debug)]
+pub struct DifferentialReport {
+    pub instance: Instance,
+    pub epsilon: f64,
+    pub primal_dual: Solution,
+    pub greedy: Solution,
+    pub exact: Option<Solution>,
+    pub violations: Vec<String>,
+}
+
+impl DifferentialReport {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// runs every solver on instance and checks
+//   exact >= primal-dual >= guarantee * exact
+//   exact >= greedy >= exact / 2
+//   every solution passes Solution::verify
+//   every solver leaves exactly the items without bids unassigned
+//
+// PrimalDual and greedy run with OverbidPolicy::Clamp, which does not change the value of any assignment
+pub fn differential(instance: &Instance, epsilon: f64) -> DifferentialReport {
+    let bid = bid_matrix(instance);
+
+    let mut solver = PrimalDual::new(instance.num_agents, instance.num_items, epsilon);
+    solver.set_overbid_policy(OverbidPolicy::Clamp);
+    for (agent_id, &budget) in instance.budgets.iter().enumerate() {
+        solver.set_budget(agent_id, budget);
+    }
+    for &(agent_id, item_id, value) in &instance.bids {
+        solver.set_bid(agent_id, item_id, value);
+    }
+    solver.solve();
+    let primal_dual = solver.extract_solution();
+
+    let mut greedy = Greedy::from_instance(instance);
+    greedy.set_overbid_policy(OverbidPolicy::Clamp);
+    greedy.solve();
+    let greedy = greedy.extract_solution();
+    let exact = exact::solve(&instance.budgets, &bid).map(|(_, item_owner)| to_solution(&instance.budgets, &bid, item_owner));
+
+    let mut violations = Vec::new();
+    for (name, solution) in [("primal-dual", Some(&primal_dual)), ("greedy", Some(&greedy)), ("exact", exact.as_ref())] {
+        let Some(solution) = solution else {
+            continue;
+        };
+        if let Err(e) = solution.verify() {
+            violations.push(format!("{} solution fails verification: {:?}", name, e));
+        }
+        let unassigned: Vec<usize> = (0..instance.num_items).filter(|&item_id| solution.item_owner[item_id].is_none()).collect();
+        let unassignable: Vec<usize> = (0..instance.num_items).filter(|&item_id| bid.iter().all(|bids| bids[item_id] == 0.0)).collect();
+        if unassigned != unassignable {
+            violations.push(format!("{} leaves items {:?} unassigned, expected {:?}", name, unassigned, unassignable));
+        }
+    }
+
+    if let Some(exact) = &exact {
+        let optimum = exact.primal;
+        let slack = TOLERANCE * optimum.max(1.0);
+        if primal_dual.primal > optimum + slack {
+            violations.push(format!("primal-dual {} exceeds the optimum {}", primal_dual.primal, optimum));
+        }
+        let guarantee = primal_dual.get_approximation_ratio();
+        if primal_dual.primal < guarantee * optimum - slack {
+            violations.push(format!("primal-dual {} is below {} * optimum {}", primal_dual.primal, guarantee, optimum));
+        }
+        if greedy.primal > optimum + slack {
+            violations.push(format!("greedy {} exceeds the optimum {}", greedy.primal, optimum));
+        }
+        if greedy.primal < optimum / 2.0 - slack {
+            violations.push(format!("greedy {} is below half of the optimum {}", greedy.primal, optimum));
+        }
+    }
+
+    DifferentialReport {
+        instance: instance.clone(),
+        epsilon,
+        primal_dual,
+        greedy,
+        exact,
+        violations,
+    }
+}
+
+// violations followed by the instance with every value divided by the same power of two,
+// which hides the magnitudes of the original data without changing any comparison the solvers make
+impl fmt::Display for DifferentialReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "violation: {}", violation)?;
+        }
+
+        let largest = self
+            .instance
+            .budgets
+            .iter()
+            .chain(self.instance.bids.iter().map(|(_, _, bid)| bid))
+            .fold(0.0f64, |a, &b| a.max(b));
+        let scale = if largest > 0.0 { 2f64.powi(-(largest.log2().ceil() as i32)) } else { 1.0 };
+
+        writeln!(f, "epsilon = {:?}", self.epsilon)?;
+        writeln!(f, "num_agents = {}, num_items = {}", self.instance.num_agents, self.instance.num_items)?;
+        writeln!(f, "budgets = {:?}", self.instance.budgets.iter().map(|budget| budget * scale).collect::<Vec<f64>>())?;
+        writeln!(
+            f,
+            "bids = {:?}",
+            self.instance
+                .bids
+                .iter()
+                .map(|&(agent_id, item_id, bid)| (agent_id, item_id, bid * scale))
+                .collect::<Vec<_>>()
+        )
+    }
+}
+
+// bid[agent_id][item_id], a later bid on the same pair replaces the earlier one as in PrimalDual::set_bid
+fn bid_matrix(instance: &Instance) -> Vec<Vec<f64>> {
+    let mut bid = vec![vec![0.0; instance.num_items]; instance.num_agents];
+    for &(agent_id, item_id, value) in &instance.bids {
+        bid[agent_id][item_id] = value;
+    }
+    bid
+}
+
+fn value(budgets: &[f64], bid: &[Vec<f64>], item_owner: &[Option<usize>]) -> (f64, Vec<f64>) {
+    let mut spends = vec![0.0; budgets.len()];
+    for (item_id, owner) in item_owner.iter().enumerate() {
+        if let &Some(agent_id) = owner {
+            spends[agent_id] += bid[agent_id][item_id];
+        }
+    }
+    let value = spends.iter().zip(budgets).map(|(spend, budget)| spend.min(*budget)).sum();
+    (value, spends)
+}
+
+fn to_solution(budgets: &[f64], bid: &[Vec<f64>], item_owner: Vec<Option<usize>>) -> Solution {
+    let (primal, spends) = value(budgets, bid, &item_owner);
+    let mut assignment = vec![Vec::new(); budgets.len()];
+    for (item_id, owner) in item_owner.iter().enumerate() {
+        if let &Some(agent_id) = owner {
+            assignment[agent_id].push(item_id);
+        }
+    }
+
+    // sum_a min(budget_a, sum_j bid_aj) bounds every assignment
+    let dual = budgets.iter().zip(bid).map(|(budget, bids)| budget.min(bids.iter().sum())).sum();
+    let beta = bid
+        .iter()
+        .zip(budgets)
+        .flat_map(|(bids, &budget)| bids.iter().map(move |&value| if value > 0.0 { (value / budget).min(1.0) } else { 0.0 }))
+        .fold(0.0, f64::max);
+
+    Solution {
+        assignment,
+        item_owner,
+        spends,
+        primal,
+        dual,
+        epsilon: 0.0,
+        beta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::differential;
+    use crate::instance::Instance;
+    use proptest::prelude::*;
+
+    fn instance_strategy() -> impl Strategy<Value = Instance> {
+        (1usize..5, 1usize..7).prop_flat_map(|(num_agents, num_items)| {
+            (proptest::collection::vec(1.0f64..100.0, num_agents), proptest::collection::vec((0..num_agents, 0..num_items, 1.0f64..60.0), 0..num_agents * num_items + 1))
+                .prop_map(move |(budgets, bids)| Instance {
+                    num_agents,
+                    num_items,
+                    budgets,
+                    bids,
+                })
+        })
+    }
+
+    #[test]
+    fn sample() {
+        let mut instance = Instance::new(2, 4);
+        instance.set_budget(0, 300.0);
+        instance.set_budget(1, 400.0);
+        for (agent_id, item_id, bid) in [(0, 0, 200.0), (0, 1, 200.0), (0, 2, 100.0), (1, 0, 100.0), (1, 1, 100.0), (1, 2, 200.0)] {
+            instance.add_bid(agent_id, item_id, bid);
+        }
+
+        let report = differential(&instance, 0.01);
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.exact.unwrap().primal, 500.0);
+    }
+
+    #[test]
+    fn report_hides_magnitudes() {
+        let mut instance = Instance::new(1, 1);
+        instance.set_budget(0, 3.0e6);
+        instance.add_bid(0, 0, 1.5e6);
+        let mut report = differential(&instance, 0.1);
+        report.violations.push("injected".to_string());
+
+        let text = report.to_string();
+        assert!(text.starts_with("violation: injected"));
+        assert!(!text.contains("3000000") && !text.contains("1500000"));
+    }
+
+    proptest! {
+        #[test]
+        fn random_instances(instance in instance_strategy(), epsilon in 0.01f64..0.5) {
+            let report = differential(&instance, epsilon);
+            prop_assert!(report.is_ok(), "{}", report);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use maximum_budgeted_allocation::error_code::ErrorCode;
+use maximum_budgeted_allocation::io::{read_instance, write_instance_binary};
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn mba(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mba")).args(args).output().unwrap()
+}
+
+// value of "name: value" in the output of mba
+fn field(stdout: &str, name: &str) -> f64 {
+    let prefix = format!("{}: ", name);
+    let line = stdout
+        .lines()
+        .find(|line| line.starts_with(&prefix))
+        .unwrap_or_else(|| panic!("no {} in {}", name, stdout));
+    line[prefix.len()..].parse().unwrap()
+}
+
+#[test]
+fn random_instances() {
+    let directory = Path::new("tests/random");
+    let mut num_files = 0;
+    for entry in fs::read_dir(directory).unwrap().flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "in") {
+            continue;
+        }
+        num_files += 1;
+
+        let output = mba(&[path.to_str().unwrap(), "--epsilon", "0.05"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let (primal, dual, ratio) = (field(&stdout, "primal"), field(&stdout, "dual"), field(&stdout, "ratio"));
+        let optimum: f64 = fs::read_to_string(path.with_extension("out")).unwrap().trim().parse().unwrap();
+        assert!(primal >= optimum * ratio, "{}: {} {}", path.display(), primal, optimum);
+        assert!(primal <= optimum && optimum <= dual * (1.0 + 1e-9), "{}: {} {} {}", path.display(), primal, optimum, dual);
+    }
+    assert!(num_files > 0);
+}
+
+#[test]
+fn assignment() {
+    let output_path = std::env::temp_dir().join(format!("mba_cli_assignment_{}.csv", std::process::id()));
+    let output = mba(&["tests/random/1.in", "--print-assignment", "--output", output_path.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let written = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(&output_path).unwrap();
+    assert!(written.starts_with("agent_id,item_id\n"));
+    assert!(written.lines().count() > 1);
+    assert!(stdout.ends_with(&written));
+}
+
+#[test]
+fn errors() {
+    let path = std::env::temp_dir().join(format!("mba_cli_malformed_{}.in", std::process::id()));
+    fs::write(&path, "2,3\n10,20\n0,5,1\n").unwrap();
+    let output = mba(&[path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(ErrorCode::ItemIndexOutOfRange.value()));
+    assert!(stderr.starts_with("error: ") && stderr.contains("line 3"), "{}", stderr);
+    assert!(!stderr.contains("panicked"));
+
+    for (args, code) in [
+        (&["tests/random/0.in", "--epsilon", "2"][..], ErrorCode::InvalidEpsilon.value()),
+        (&["tests/random/0.in", "--epsilon"], 2),
+        (&["tests/random/0.in", "--unknown"], 2),
+        (&["does_not_exist.in"], ErrorCode::Io.value()),
+        (&[], 2),
+    ] {
+        let output = mba(args);
+        assert_eq!(output.status.code(), Some(code), "{:?}", args);
+        assert!(String::from_utf8(output.stderr).unwrap().starts_with("error: "));
+    }
+}
+
+#[test]
+fn binary_instance() {
+    let instance = read_instance(BufReader::new(fs::File::open("tests/random/1.in").unwrap())).unwrap();
+    let path = std::env::temp_dir().join(format!("mba_cli_binary_{}.bin", std::process::id()));
+    write_instance_binary(&instance, fs::File::create(&path).unwrap()).unwrap();
+    let binary = mba(&[path.to_str().unwrap(), "--print-assignment"]);
+    fs::remove_file(&path).unwrap();
+    assert!(binary.status.success(), "{}", String::from_utf8_lossy(&binary.stderr));
+    assert_eq!(binary.stdout, mba(&["tests/random/1.in", "--print-assignment"]).stdout);
+}
+
+#[test]
+fn inactive_agents() {
+    // agent 1 bids on nothing
+    let path = std::env::temp_dir().join(format!("mba_cli_inactive_{}.in", std::process::id()));
+    fs::write(&path, "2,1\n10,10\n0,0,5\n").unwrap();
+    let warned = mba(&[path.to_str().unwrap()]);
+    let quiet = mba(&[path.to_str().unwrap(), "--inactive-warning-fraction", "0.5"]);
+    fs::remove_file(&path).unwrap();
+    assert!(warned.status.success() && quiet.status.success());
+    assert!(String::from_utf8(warned.stderr).unwrap().starts_with("WARNING: 1 of 2 agents have no accepted bid"));
+    assert!(quiet.stderr.is_empty());
+}
+
+#[cfg(feature = "config")]
+#[test]
+fn config() {
+    let output = mba(&["tests/random/1.in", "--config", "examples/solver_config.toml"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(field(&stdout, "ratio"), field(&String::from_utf8(mba(&["tests/random/1.in", "--epsilon", "0.05"]).stdout).unwrap(), "ratio"));
+
+    // --epsilon takes precedence over the file
+    let output = mba(&["tests/random/1.in", "--config", "examples/solver_config.toml", "--epsilon", "0.2"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(field(&stdout, "ratio"), field(&String::from_utf8(mba(&["tests/random/1.in", "--epsilon", "0.2"]).stdout).unwrap(), "ratio"));
+
+    let output = mba(&["tests/random/1.in", "--config", "does_not_exist.toml"]);
+    assert_eq!(output.status.code(), Some(ErrorCode::Io.value()));
+}
@@ -0,0 +1,41 @@
+// frozen files of every released format version; they must load forever
+use maximum_budgeted_allocation::io::{detect_format, read_instance_binary, read_solution_binary, FormatKind};
+use std::fs::File;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+#[test]
+fn instance_v1() {
+    let path = fixture("instance_v1.mbai");
+    assert_eq!(detect_format(&path).unwrap(), FormatKind::Instance { version: 1 });
+
+    let instance = read_instance_binary(File::open(&path).unwrap()).unwrap();
+    assert_eq!(instance.num_agents, 2);
+    assert_eq!(instance.num_items, 4);
+    assert_eq!(instance.budgets, vec![300.0, 400.0]);
+    assert_eq!(instance.bids.len(), 6);
+    assert_eq!(instance.bids[5], (1, 2, 200.0));
+}
+
+#[test]
+fn solution_v1() {
+    let path = fixture("solution_v1.mbas");
+    assert_eq!(detect_format(&path).unwrap(), FormatKind::Solution { version: 1 });
+
+    let solution = read_solution_binary(File::open(&path).unwrap()).unwrap();
+    assert_eq!(solution.assignment, vec![vec![0, 1], vec![2]]);
+    assert_eq!(solution.item_owner, vec![Some(0), Some(0), Some(1), None]);
+    assert_eq!(solution.spends, vec![400.0, 200.0]);
+    assert_eq!(solution.primal, 500.0);
+    assert_eq!(solution.epsilon, 0.01);
+    assert_eq!(solution.verify(), Ok(()));
+}
+
+#[test]
+fn text_and_unknown() {
+    assert_eq!(detect_format(fixture("../../examples/solver_config.toml")).unwrap(), FormatKind::Text);
+    assert_eq!(detect_format(fixture("../../LICENSE")).unwrap(), FormatKind::Text);
+}
@@ -0,0 +1,84 @@
+// every instance up to 3 agents x 4 items with bids in {0, 1, 2, 3} (0 is no bid) and budgets in {2, 4},
+// solved by PrimalDual and by exact::solve
+use maximum_budgeted_allocation::exact;
+use maximum_budgeted_allocation::primal_dual::PrimalDual;
+
+const BIDS: [f64; 4] = [0.0, 1.0, 2.0, 3.0];
+const BUDGETS: [f64; 2] = [2.0, 4.0];
+const EPSILON: f64 = 0.01;
+
+fn check(budgets: &[f64], bids: &[Vec<f64>]) {
+    let num_agents = budgets.len();
+    let num_items = bids[0].len();
+    let solve = || {
+        let mut solver = PrimalDual::new(num_agents, num_items, EPSILON);
+        for (agent_id, &budget) in budgets.iter().enumerate() {
+            solver.set_budget(agent_id, budget);
+        }
+        for (agent_id, row) in bids.iter().enumerate() {
+            for (item_id, &bid) in row.iter().enumerate().filter(|&(_, &bid)| bid > 0.0) {
+                solver.set_bid(agent_id, item_id, bid);
+            }
+        }
+        solver.solve();
+        (solver.extract_solution(), solver.get_approximation_ratio())
+    };
+
+    let (solution, ratio) = solve();
+    assert_eq!(solution.verify(), Ok(()), "budgets {:?} bids {:?}", budgets, bids);
+    assert_eq!(solve().0, solution, "nondeterministic on budgets {:?} bids {:?}", budgets, bids);
+
+    // PrimalDual rejects bids above the budget
+    let accepted: Vec<Vec<f64>> = bids
+        .iter()
+        .zip(budgets)
+        .map(|(row, &budget)| row.iter().map(|&bid| if bid <= budget { bid } else { 0.0 }).collect())
+        .collect();
+    let (optimum, _) = exact::solve(budgets, &accepted).unwrap();
+    assert!(solution.primal <= optimum + 1e-9, "budgets {:?} bids {:?}", budgets, bids);
+    assert!(solution.primal >= ratio * optimum - 1e-9, "primal {} < {} * {} on budgets {:?} bids {:?}", solution.primal, ratio, optimum, budgets, bids);
+}
+
+fn enumerate(num_agents: usize, num_items: usize) {
+    let num_cells = num_agents * num_items;
+    for budget_mask in 0..BUDGETS.len().pow(num_agents as u32) {
+        let budgets: Vec<f64> = (0..num_agents)
+            .map(|agent_id| BUDGETS[budget_mask / BUDGETS.len().pow(agent_id as u32) % BUDGETS.len()])
+            .collect();
+        for code in 0..BIDS.len().pow(num_cells as u32) {
+            let bids: Vec<Vec<f64>> = (0..num_agents)
+                .map(|agent_id| {
+                    (0..num_items)
+                        .map(|item_id| BIDS[code / BIDS.len().pow((agent_id * num_items + item_id) as u32) % BIDS.len()])
+                        .collect()
+                })
+                .collect();
+            check(&budgets, &bids);
+        }
+    }
+}
+
+// every shape with at most 6 cells
+#[test]
+fn small_shapes() {
+    for num_agents in 1..=3 {
+        for num_items in 1..=4 {
+            if num_agents * num_items <= 6 {
+                enumerate(num_agents, num_items);
+            }
+        }
+    }
+}
+
+// about 1.4e8 instances; run with --release -- --ignored
+#[test]
+#[ignore]
+fn all_shapes() {
+    for num_agents in 1..=3 {
+        for num_items in 1..=4 {
+            if num_agents * num_items > 6 {
+                enumerate(num_agents, num_items);
+            }
+        }
+    }
+}
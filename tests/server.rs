@@ -0,0 +1,211 @@
+#![cfg(feature = "server")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+struct ServerProcess {
+    child: Child,
+    address: String,
+}
+
+impl ServerProcess {
+    fn spawn(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_mba-server"))
+            .args(["--port", "0"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut address = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        ServerProcess {
+            child,
+            address: address.trim().to_string(),
+        }
+    }
+
+    // returns (status code, body)
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", method, path, self.address, body.len(), body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or("").to_string();
+        (status, body)
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+const SAMPLE: &str = r#"{
+    "epsilon": 0.01,
+    "num_items": 3,
+    "budgets": [300.0, 400.0],
+    "bids": [[0, 0, 200.0], [0, 1, 200.0], [0, 2, 100.0], [1, 0, 100.0], [1, 1, 100.0], [1, 2, 200.0]]
+}"#;
+
+#[test]
+fn healthz() {
+    let server = ServerProcess::spawn(&[]);
+    let (status, _) = server.request("GET", "/healthz", "");
+    assert_eq!(status, 200);
+}
+
+#[test]
+fn solve_and_verify() {
+    let server = ServerProcess::spawn(&[]);
+
+    let (status, body) = server.request("POST", "/solve", SAMPLE);
+    assert_eq!(status, 200);
+    let solution: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(solution["primal"].as_f64().unwrap(), 500.0);
+    assert!(solution["primal"].as_f64().unwrap() >= solution["dual"].as_f64().unwrap() * solution["ratio"].as_f64().unwrap());
+
+    let assignment = &solution["assignment"];
+    let mut item_owner = vec![None; 3];
+    for (agent_id, item_ids) in assignment.as_array().unwrap().iter().enumerate() {
+        for item_id in item_ids.as_array().unwrap() {
+            item_owner[item_id.as_u64().unwrap() as usize] = Some(agent_id);
+        }
+    }
+    let verify_request = serde_json::json!({
+        "assignment": assignment,
+        "item_owner": item_owner,
+        "spends": [0.0, 0.0],
+        "primal": solution["primal"],
+        "dual": solution["dual"],
+        "epsilon": 0.01,
+        "beta": 0.5,
+    });
+    let (status, body) = server.request("POST", "/verify", &verify_request.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["ok"], true);
+}
+
+#[test]
+fn exact() {
+    let server = ServerProcess::spawn(&[]);
+    let (status, body) = server.request("POST", "/solve", &SAMPLE.replace("0.01", "0.0"));
+    assert_eq!(status, 200);
+    let solution: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(solution["primal"].as_f64().unwrap(), 500.0);
+    assert_eq!(solution["ratio"].as_f64().unwrap(), 1.0);
+}
+
+#[test]
+fn body_limit() {
+    let server = ServerProcess::spawn(&["--max-body-bytes", "16"]);
+    let (status, _) = server.request("POST", "/solve", SAMPLE);
+    assert_eq!(status, 413);
+}
+
+#[test]
+fn malformed_instance() {
+    let server = ServerProcess::spawn(&[]);
+    let (status, _) = server.request("POST", "/solve", r#"{"epsilon": 0.01}"#);
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn invalid_instance() {
+    let server = ServerProcess::spawn(&[]);
+    for (instance, code) in [
+        (r#"{"num_items": 1, "budgets": [-1.0], "bids": []}"#, 30),
+        (r#"{"num_items": 1, "budgets": [1.0], "bids": [[0, 1, 1.0]]}"#, 21),
+        (r#"{"num_items": 1, "budgets": [1.0], "bids": [[1, 0, 1.0]]}"#, 20),
+        (r#"{"epsilon": 1.5, "num_items": 1, "budgets": [1.0], "bids": []}"#, 10),
+    ] {
+        let (status, body) = server.request("POST", "/solve", instance);
+        assert_eq!(status, 400, "{}", instance);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["code"], code, "{}", instance);
+    }
+}
+
+#[test]
+fn deadline() {
+    let server = ServerProcess::spawn(&["--deadline-ms", "0"]);
+
+    let num_agents = 200;
+    let num_items = 500;
+    let bids: Vec<(usize, usize, f64)> = (0..num_agents)
+        .flat_map(|agent_id| (0..num_items).map(move |item_id| (agent_id, item_id, 1.0 + ((agent_id * 7 + item_id * 13) % 10) as f64)))
+        .collect();
+    let instance = serde_json::json!({
+        "epsilon": 0.001,
+        "num_items": num_items,
+        "budgets": vec![50.0; num_agents],
+        "bids": bids,
+    });
+
+    let (status, body) = server.request("POST", "/solve", &instance.to_string());
+    assert_eq!(status, 503);
+    let partial: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(partial["num_bids"], num_agents * num_items);
+
+    // the cancelled solve still reports the assignment it reached
+    let assignment = partial["partial"]["assignment"].as_array().unwrap();
+    assert_eq!(assignment.len(), num_agents);
+    assert!(partial["partial"]["primal"].as_f64().unwrap() <= partial["partial"]["dual"].as_f64().unwrap());
+}
+
+#[test]
+fn config_file() {
+    let server = ServerProcess::spawn(&["--config", concat!(env!("CARGO_MANIFEST_DIR"), "/examples/solver_config.toml")]);
+    let instance = r#"{"num_items": 3, "budgets": [300.0, 400.0], "bids": [[0, 0, 200.0], [0, 1, 200.0], [0, 2, 100.0], [1, 0, 100.0], [1, 1, 100.0], [1, 2, 200.0]]}"#;
+    let (status, body) = server.request("POST", "/solve", instance);
+    assert_eq!(status, 200);
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["primal"].as_f64().unwrap(), 500.0);
+}
+
+#[test]
+fn inactive_agents() {
+    let server = ServerProcess::spawn(&["--inactive-warning-fraction", "0.5"]);
+    let instance = r#"{"num_items": 2, "budgets": [300.0, 4.0], "bids": [[0, 0, 200.0], [1, 0, 150.0], [1, 1, 250.0]]}"#;
+    let (status, body) = server.request("POST", "/solve", instance);
+    assert_eq!(status, 200);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["inactive_agents"], serde_json::json!([{"agent_id": 1, "reason": {"all_bids_rejected": {"max_bid": 250.0, "budget": 4.0}}}]));
+}
+
+#[test]
+fn oversized_instance() {
+    let server = ServerProcess::spawn(&["--max-memory-bytes", "1000000"]);
+    for (instance, status, code) in [
+        (r#"{"num_items": 100000000000, "budgets": [1.0], "bids": []}"#, 413, 50),
+        (r#"{"num_items": 100000, "budgets": [1.0, 1.0], "bids": []}"#, 413, 50),
+        (r#"{"epsilon": 0.0, "num_items": 30, "budgets": [1.0, 1.0, 1.0], "bids": []}"#, 400, 51),
+    ] {
+        let (actual, body) = server.request("POST", "/solve", instance);
+        assert_eq!(actual, status, "{}", instance);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["code"], code, "{}", instance);
+    }
+    assert_eq!(server.request("GET", "/healthz", "").0, 200);
+}
+
+#[test]
+fn healthz_during_solve() {
+    let server = ServerProcess::spawn(&[]);
+
+    // a solve whose body is still on its way keeps its request busy; the padding keeps the server from buffering the
+    // whole body before it hands the request on
+    let body = format!("{}{}", SAMPLE, " ".repeat(1 << 16));
+    let mut solving = TcpStream::connect(&server.address).unwrap();
+    let (head, rest) = body.split_at(SAMPLE.len() / 2);
+    write!(solving, "POST /solve HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", server.address, body.len(), head).unwrap();
+    solving.flush().unwrap();
+
+    assert_eq!(server.request("GET", "/healthz", "").0, 200);
+
+    solving.write_all(rest.as_bytes()).unwrap();
+    let mut response = String::new();
+    solving.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+}